@@ -18,7 +18,13 @@ class RS3DataCollector:
         c.execute('''CREATE TABLE IF NOT EXISTS items (
                      id INTEGER PRIMARY KEY,
                      name TEXT,
-                     ge_limit INTEGER)''')
+                     ge_limit INTEGER,
+                     high_alch INTEGER)''')
+
+        # Databases created before high_alch was collected
+        columns = [row[1] for row in c.execute("PRAGMA table_info(items)")]
+        if 'high_alch' not in columns:
+            c.execute("ALTER TABLE items ADD COLUMN high_alch INTEGER")
 
         # Table for Price History (Link to Item, Date, Price, Volume)
         # Using a composite primary key to prevent duplicate entries for the same day
@@ -56,11 +62,16 @@ class RS3DataCollector:
                 if not isinstance(item, dict): continue
                 if 'price' not in item: continue
 
-                items_to_update.append((int(item_id), item['name'], item.get('limit', 10000)))
+                items_to_update.append((int(item_id), item['name'], item.get('limit', 10000), item.get('highalch')))
                 history_to_insert.append((int(item_id), today_str, int(item['price']), item.get('volume', 0)))
 
-            c.executemany("INSERT OR REPLACE INTO items VALUES (?,?,?)", items_to_update)
-            c.executemany("INSERT OR IGNORE INTO history VALUES (?,?,?,?)", history_to_insert)
+            c.executemany("INSERT OR REPLACE INTO items VALUES (?,?,?,?)", items_to_update)
+            # Manual prices added in the analyzer only stand in until the real one arrives
+            columns = [row[1] for row in c.execute("PRAGMA table_info(history)")]
+            if 'manual' in columns:
+                c.executemany("DELETE FROM history WHERE item_id = ? AND record_date = ? AND manual = 1",
+                              [(item_id, day) for item_id, day, _, _ in history_to_insert])
+            c.executemany("INSERT OR IGNORE INTO history (item_id, record_date, price, volume) VALUES (?,?,?,?)", history_to_insert)
             conn.commit()
             
             # --- SUCCESS LOGGING ---
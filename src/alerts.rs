@@ -0,0 +1,162 @@
+use crate::baskets::{self, Baskets};
+use crate::model::{Gp, Holding, ItemStats};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlertKind {
+    HoldingDrop,
+    TargetHit,
+    BasketCheap,
+    Rule,  // A user alert rule matched
+}
+
+#[derive(Debug, Clone)]
+pub struct Alert {
+    pub kind: AlertKind,
+    pub item_id: Option<i32>,  // None for baskets, which only have a name
+    pub item: String,
+    pub message: String,
+}
+
+impl Alert {
+    // One-line form used by every outgoing notification channel
+    pub fn text(&self) -> String {
+        let icon = match self.kind {
+            AlertKind::HoldingDrop => "📉",
+            AlertKind::TargetHit => "🎯",
+            AlertKind::BasketCheap => "🧺",
+            AlertKind::Rule => "📣",
+        };
+        format!("{} {} {}", icon, self.item, self.message)
+    }
+
+    fn key(&self) -> (AlertKind, Option<i32>, String) {
+        (self.kind, self.item_id, self.item.clone())
+    }
+}
+
+// How long a standing alert waits before it's sent again
+pub const NOTIFY_COOLDOWN_SECS: u64 = 24 * 3_600;
+
+// When each standing alert last went out. These alerts are rebuilt after every
+// scan, so without this one that stays true would be sent after every rescan.
+#[derive(Debug, Default)]
+pub struct Notified {
+    sent: HashMap<(AlertKind, Option<i32>, String), u64>,  // -> Unix seconds
+}
+
+impl Notified {
+    // The alerts to send now - new ones, and ones last sent a cooldown ago -
+    // marked as sent. Alerts that have cleared are forgotten, so they go out
+    // again as soon as they come back.
+    pub fn due<'a>(&mut self, alerts: &'a [Alert], now: u64) -> Vec<&'a Alert> {
+        self.sent.retain(|key, _| alerts.iter().any(|a| a.key() == *key));
+        alerts
+            .iter()
+            .filter(|a| {
+                let due = self.sent.get(&a.key()).is_none_or(|&t| now >= t + NOTIFY_COOLDOWN_SECS);
+                if due {
+                    self.sent.insert(a.key(), now);
+                }
+                due
+            })
+            .collect()
+    }
+}
+
+// Protects existing positions: fires when a held item falls `drop_pct` below
+// its cost basis or reaches the user's target sell price.
+pub fn holding_alerts(holdings: &[Holding], prices: &HashMap<i32, f64>, drop_pct: f64) -> Vec<Alert> {
+    let mut alerts = Vec::new();
+
+    for h in holdings {
+        let Some(&price) = prices.get(&h.item_id) else { continue };
+
+        if h.cost_basis > Gp::ZERO {
+            let cost = h.cost_basis.as_f64();
+            let change_pct = (price - cost) / cost * 100.0;
+            if change_pct <= -drop_pct {
+                alerts.push(Alert {
+                    kind: AlertKind::HoldingDrop,
+                    item_id: Some(h.item_id),
+                    item: h.name.clone(),
+                    message: format!("down {:.1}% from your cost of {} gp (now {:.0} gp)", -change_pct, h.cost_basis.0, price),
+                });
+            }
+        }
+
+        if let Some(target) = h.target_sell {
+            if price >= target.as_f64() {
+                alerts.push(Alert {
+                    kind: AlertKind::TargetHit,
+                    item_id: Some(h.item_id),
+                    item: h.name.clone(),
+                    message: format!("reached your target of {} gp (now {:.0} gp)", target.0, price),
+                });
+            }
+        }
+    }
+
+    alerts
+}
+
+// Fires for each basket whose total cost is down in the cheapest part of its history
+pub fn basket_alerts(baskets: &Baskets, stats: &[ItemStats]) -> Vec<Alert> {
+    baskets.baskets
+        .iter()
+        .filter_map(|b| {
+            let value = baskets::value(b, stats);
+            value.is_cheap(b).then(|| Alert {
+                kind: AlertKind::BasketCheap,
+                item_id: None,
+                item: b.name.clone(),
+                message: format!(
+                    "costs {:.0} gp, cheaper than {:.0}% of its {} tracked days",
+                    value.total,
+                    100.0 - value.percentile.unwrap_or_default(),
+                    value.history.len()
+                ),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn held(qty: i64) -> Holding {
+        Holding { item_id: 1050, name: "Santa hat".into(), qty, cost_basis: Gp(40_000), target_sell: None }
+    }
+
+    #[test]
+    fn standing_holding_alerts_wait_out_the_cooldown() {
+        let mut notified = Notified::default();
+        let low = HashMap::from([(1050, 35_000.0)]);
+        let recovered = HashMap::from([(1050, 40_000.0)]);
+        let scan = |prices: &HashMap<i32, f64>| holding_alerts(&[held(2)], prices, 10.0);
+        let now = 1_700_000_000;
+
+        let first = notified.due(&scan(&low), now).len();
+        let next_scan = notified.due(&scan(&low), now + 3_600).len();
+        let next_day = notified.due(&scan(&low), now + NOTIFY_COOLDOWN_SECS).len();
+        notified.due(&scan(&recovered), now + NOTIFY_COOLDOWN_SECS + 3_600);
+        let fell_again = notified.due(&scan(&low), now + NOTIFY_COOLDOWN_SECS + 7_200).len();
+
+        assert_eq!((first, next_scan, next_day, fell_again), (1, 0, 1, 1));
+    }
+
+    #[test]
+    fn cheap_baskets_are_tracked_by_name() {
+        let cheap = |name: &str| Alert { kind: AlertKind::BasketCheap, item_id: None, item: name.into(), message: String::new() };
+        let mut notified = Notified::default();
+        let now = 1_700_000_000;
+
+        let first = notified.due(&[cheap("Barrows")], now).len();
+        let scan = [cheap("Barrows"), cheap("Herbs")];
+        let both = notified.due(&scan, now + 3_600);
+
+        assert_eq!(first, 1);
+        assert_eq!(both.iter().map(|a| a.item.as_str()).collect::<Vec<_>>(), ["Herbs"]);
+    }
+}
@@ -0,0 +1,69 @@
+// Scan results for an Alt1 Toolkit overlay. The overlay reads the GE
+// interface, and when the player hovers an item it looks the item up here
+// and draws the analyzer's buy/sell targets next to it.
+//
+// The file is rewritten after every scan. Alt1 apps are web pages, so serve
+// the folder it's written to over HTTP (e.g. `python -m http.server`) and
+// have the overlay poll it. The schema is versioned; fields are only ever
+// added within a version.
+//
+//   {
+//     "schema": "rs3-analyzer/alt1-targets",
+//     "version": 1,
+//     "generated_at": 1767225600,      // Unix seconds
+//     "data_date": "2026-01-01",       // Latest price snapshot the scan used
+//     "items": {
+//       "4151": {
+//         "name": "Abyssal whip",
+//         "buy": 1180000,              // Whole gp, ready for the GE price box
+//         "sell": 1320000,
+//         "qty": 10,                   // Suggested quantity for the current budget
+//         "profit_each": 113600,       // After tax (and slippage, when modelled)
+//         "roi": 9.6,                  // Percent
+//         "tier": "GREEN",             // DIAMOND, GOLD, GREEN, NORMAL or CRASH
+//         "score": 74
+//       }
+//     },
+//     "by_name": { "abyssal whip": 4151 }   // Lowercase name -> id, for OCR'd names
+//   }
+use crate::model::Gp;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+pub const SCHEMA: &str = "rs3-analyzer/alt1-targets";
+pub const VERSION: u32 = 1;
+
+#[derive(Serialize)]
+pub struct Target {
+    pub name: String,
+    pub buy: Gp,
+    pub sell: Gp,
+    pub qty: i32,
+    pub profit_each: Gp,
+    pub roi: f64,
+    pub tier: String,
+    pub score: i32,
+}
+
+#[derive(Serialize)]
+pub struct Targets {
+    pub schema: &'static str,
+    pub version: u32,
+    pub generated_at: u64,
+    pub data_date: String,
+    pub items: BTreeMap<i32, Target>,
+    pub by_name: BTreeMap<String, i32>,
+}
+
+impl Targets {
+    pub fn new(generated_at: u64, data_date: String, targets: impl IntoIterator<Item = (i32, Target)>) -> Self {
+        let items: BTreeMap<i32, Target> = targets.into_iter().collect();
+        let by_name = items.iter().map(|(id, t)| (t.name.to_lowercase(), *id)).collect();
+        Targets { schema: SCHEMA, version: VERSION, generated_at, data_date, items, by_name }
+    }
+
+    pub fn write(&self, path: &str) -> std::io::Result<()> {
+        let json = serde_json::to_string(self).map_err(std::io::Error::other)?;
+        std::fs::write(path, json)
+    }
+}
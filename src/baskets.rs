@@ -0,0 +1,115 @@
+// Baskets: a fixed shopping list (an armour set and weapon, 100 of each
+// ingredient for a batch of potions) priced as a whole, so its cost can be
+// tracked over time and bought when it's cheap against its own history.
+use crate::game::Game;
+use crate::model::ItemStats;
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+
+const BASKETS_PATH: &str = "baskets.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BasketItem {
+    pub item_id: i32,
+    pub name: String,
+    pub qty: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Basket {
+    pub name: String,
+    pub items: Vec<BasketItem>,
+    pub cheap_percentile: f64,  // Alert when today's cost is in this bottom % of its history
+}
+
+impl Basket {
+    pub fn new(name: String) -> Self {
+        Basket { name, items: vec![], cheap_percentile: 10.0 }
+    }
+
+    // Adds to the quantity if the item is already in the basket
+    pub fn add(&mut self, item_id: i32, name: String, qty: i64) {
+        match self.items.iter_mut().find(|i| i.item_id == item_id) {
+            Some(existing) => existing.qty += qty,
+            None => self.items.push(BasketItem { item_id, name, qty }),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Baskets {
+    pub baskets: Vec<Basket>,
+    #[serde(skip)]
+    path: String,  // Per-game file it was loaded from
+}
+
+impl Baskets {
+    pub fn load(game: Game) -> Self {
+        let path = game.file(BASKETS_PATH);
+        let mut loaded: Baskets = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default();
+        loaded.path = path;
+        loaded
+    }
+
+    pub fn save(&self) {
+        if let Ok(json) = serde_json::to_string_pretty(self) {
+            let _ = std::fs::write(&self.path, json);
+        }
+    }
+}
+
+pub struct BasketValue {
+    pub total: f64,  // At the latest prices
+    pub missing: Vec<String>,  // Items with no price in the scan
+    pub history: Vec<(i64, f64)>,  // (day, total) on days every item has a price
+    pub change_7d_pct: Option<f64>,
+    pub percentile: Option<f64>,  // Share of past days that were cheaper than today, 0-100
+}
+
+impl BasketValue {
+    pub fn is_cheap(&self, basket: &Basket) -> bool {
+        self.missing.is_empty() && self.percentile.is_some_and(|p| p <= basket.cheap_percentile)
+    }
+}
+
+pub fn value(basket: &Basket, stats: &[ItemStats]) -> BasketValue {
+    let by_id: HashMap<i32, &ItemStats> = stats.iter().map(|s| (s.item_id, s)).collect();
+
+    let mut total = 0.0;
+    let mut missing = Vec::new();
+    let mut daily: Option<HashMap<i64, f64>> = None;
+    for item in &basket.items {
+        let Some(s) = by_id.get(&item.item_id) else {
+            missing.push(item.name.clone());
+            continue;
+        };
+        total += s.current_price * item.qty as f64;
+
+        // Keep only the days every item so far was priced on
+        let prices: HashMap<i64, f64> = s.history.iter().copied().collect();
+        daily = Some(match daily {
+            None => prices.iter().map(|(day, p)| (*day, p * item.qty as f64)).collect(),
+            Some(sums) => sums
+                .into_iter()
+                .filter_map(|(day, sum)| Some((day, sum + prices.get(&day)? * item.qty as f64)))
+                .collect(),
+        });
+    }
+
+    let mut history: Vec<(i64, f64)> = daily.unwrap_or_default().into_iter().collect();
+    history.sort_by_key(|(day, _)| *day);
+
+    let change_7d_pct = history.last().and_then(|&(last_day, now)| {
+        let (_, then) = history.iter().rev().find(|(day, _)| *day <= last_day - 7)?;
+        (*then > 0.0).then(|| (now - then) / then * 100.0)
+    });
+    let percentile = (history.len() >= 2 && missing.is_empty()).then(|| {
+        let cheaper = history.iter().filter(|(_, v)| *v < total).count();
+        cheaper as f64 / history.len() as f64 * 100.0
+    });
+
+    BasketValue { total, missing, history, change_7d_pct, percentile }
+}
@@ -0,0 +1,142 @@
+// Everything the user has configured, as one portable JSON file
+use crate::config::AppConfig;
+use crate::hooks::HookConfig;
+use crate::journal::Trade;
+use crate::model::Holding;
+use crate::watchlist::WatchedItem;
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+
+// 2: favorites keyed by item ID instead of name
+// 3: the blacklist
+pub const BUNDLE_VERSION: u32 = 3;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StateBundle {
+    pub version: u32,
+    pub favorite_ids: Vec<i32>,
+    pub favorites: HashMap<String, bool>,  // Version 1 only, migrated on import
+    pub pinned: Vec<i32>,
+    pub blacklist: Vec<i32>,  // Version 3 on; older bundles leave the local one alone
+    pub watchlist: Vec<WatchedItem>,
+    pub config: AppConfig,
+    pub holdings: Vec<Holding>,
+    pub journal: Vec<Trade>,
+}
+
+// Bundles get shared, so credentials stay out of them, along with the hook
+// settings an import ignores anyway (a webhook URL is often a secret itself)
+pub fn without_secrets(mut config: AppConfig) -> AppConfig {
+    config.email.password.clear();
+    config.telegram.bot_token.clear();
+    config.post_scan = HookConfig::default();
+    config.alt1_path.clear();
+    config
+}
+
+// What an imported config can't change: credentials, and anything that runs
+// a command or sends data somewhere. A bundle from someone else could
+// otherwise set the post-scan hook to any shell command, or point the digest
+// (and its SMTP login) at their own server. Those keep their local values.
+pub fn keep_local(mut incoming: AppConfig, local: &AppConfig) -> AppConfig {
+    incoming.email = local.email.clone();
+    incoming.telegram = local.telegram.clone();
+    incoming.post_scan = local.post_scan.clone();
+    incoming.alt1_path = local.alt1_path.clone();
+    incoming
+}
+
+pub fn export(path: &str, bundle: &StateBundle) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(bundle).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())
+}
+
+pub fn import(path: &str) -> Result<StateBundle, String> {
+    let data = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let bundle: StateBundle = serde_json::from_str(&data).map_err(|e| e.to_string())?;
+    if bundle.version > BUNDLE_VERSION {
+        return Err(format!("bundle version {} is newer than this app supports", bundle.version));
+    }
+    Ok(bundle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Gp;
+
+    fn with_secrets() -> AppConfig {
+        let mut config = AppConfig { bank_size: 250_000_000.0, ..Default::default() };
+        config.email.password = "hunter2".into();
+        config.telegram.bot_token = "123:abc".into();
+        config.post_scan = HookConfig { command: "notify-send".into(), url: "https://example.com/hook".into() };
+        config.alt1_path = "C:\\alt1".into();
+        config
+    }
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("rs3_analyzer_bundle_{}_{}.json", name, std::process::id()))
+    }
+
+    #[test]
+    fn bundles_round_trip_without_secrets() {
+        let bundle = StateBundle {
+            version: BUNDLE_VERSION,
+            favorite_ids: vec![1050, 1038],
+            pinned: vec![1050],
+            blacklist: vec![995],
+            watchlist: vec![WatchedItem { item_id: 1038, name: "Red partyhat".into() }],
+            config: without_secrets(with_secrets()),
+            holdings: vec![Holding { item_id: 1050, name: "Santa hat".into(), qty: 2, cost_basis: Gp(40_000), target_sell: Some(Gp(45_000)) }],
+            journal: vec![Trade { item: "Santa hat".into(), qty: 2, buy: Gp(40_000), sell: Gp(44_000), timestamp: 1_700_000_000 }],
+            ..Default::default()
+        };
+        let path = temp_path("round_trip");
+        export(path.to_str().unwrap(), &bundle).unwrap();
+        let imported = import(path.to_str().unwrap());
+        let _ = std::fs::remove_file(&path);
+        let imported = imported.unwrap();
+
+        assert_eq!(serde_json::to_value(&imported).unwrap(), serde_json::to_value(&bundle).unwrap());
+        assert!(imported.config.email.password.is_empty());
+        assert!(imported.config.telegram.bot_token.is_empty());
+        assert!(!imported.config.post_scan.is_set());
+        assert!(imported.config.alt1_path.is_empty());
+        assert_eq!(imported.config.bank_size, 250_000_000.0);
+    }
+
+    #[test]
+    fn imports_keep_the_local_credentials_and_hooks() {
+        let local = with_secrets();
+        let mut incoming = AppConfig { bank_size: 1_000_000.0, ..Default::default() };
+        incoming.email.password = "theirs".into();
+        incoming.post_scan.command = "rm -rf ~".into();
+
+        let merged = keep_local(incoming, &local);
+
+        assert_eq!(merged.bank_size, 1_000_000.0);
+        assert_eq!(merged.email, local.email);
+        assert_eq!(merged.telegram, local.telegram);
+        assert_eq!(merged.post_scan, local.post_scan);
+        assert_eq!(merged.alt1_path, local.alt1_path);
+    }
+
+    #[test]
+    fn older_bundles_load_and_newer_ones_are_refused() {
+        let path = temp_path("versions");
+        std::fs::write(&path, r#"{"version": 1, "favorites": {"Santa hat": true}, "pinned": [1050]}"#).unwrap();
+        let old = import(path.to_str().unwrap());
+        std::fs::write(&path, format!(r#"{{"version": {}}}"#, BUNDLE_VERSION + 1)).unwrap();
+        let newer = import(path.to_str().unwrap());
+        let _ = std::fs::remove_file(&path);
+        let old = old.unwrap();
+
+        assert_eq!(old.version, 1);
+        assert_eq!(old.favorites.get("Santa hat"), Some(&true));
+        assert!(old.favorite_ids.is_empty());
+        assert!(old.blacklist.is_empty());
+        assert_eq!(old.pinned, [1050]);
+        assert!(newer.is_err());
+    }
+}
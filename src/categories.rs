@@ -0,0 +1,57 @@
+// Item categories inferred from names - the items table doesn't carry any.
+// First matching rule wins, so more specific keywords come first.
+
+const RULES: &[(&str, &[&str])] = &[
+    ("Rares", &["partyhat", "h'ween", "christmas cracker", "santa hat", "disk of returning", "pumpkin"]),
+    ("Runes", &[" rune", "rune essence", "pure essence"]),
+    ("Seeds", &[" seed", "sapling"]),
+    ("Potions", &["potion", "(1)", "(2)", "(3)", "(4)", "(6)", "flask", "brew", "overload", "antifire", "antipoison"]),
+    ("Herbs", &["grimy", "clean ", "guam", "marrentill", "tarromin", "harralander", "ranarr", "toadflax", "irit",
+                "avantoe", "kwuarm", "snapdragon", "cadantine", "lantadyme", "dwarf weed", "torstol", "fellstalk",
+                "arbuck", "spirit weed", "wergali", "bloodweed"]),
+    ("Logs", &["logs", "plank"]),
+    ("Ores & Bars", &[" ore", " bar", "coal", "stone spirit", "concentrated"]),
+    ("Hides & Leather", &["hide", "leather"]),
+    ("Food", &["raw ", "cooked", "shark", "rocktail", "sailfish", "manta ray", "pie", "pizza", "stew", "cake"]),
+    ("Weapons", &["sword", "scimitar", "dagger", "mace", "whip", "bow", "crossbow", "staff", "wand", "claw",
+                  "halberd", "maul", "spear", "rapier", "hasta", "bolt", "arrow", "dart", "knife"]),
+    ("Armour", &["helm", "body", "legs", "platebody", "platelegs", "chaps", "boots", "gloves", "gauntlets",
+                 "shield", "defender", "hood", "robe", "cape", "coif", "vambraces", "kiteshield", "tassets", "cuirass"]),
+];
+
+pub fn category(name: &str) -> &'static str {
+    let lower = format!(" {}", name.to_lowercase());
+    RULES
+        .iter()
+        .find(|(_, keywords)| keywords.iter().any(|k| lower.contains(k)))
+        .map(|(category, _)| *category)
+        .unwrap_or("Other")
+}
+
+// Skilling supplies by the skill that consumes them. Curated separately from
+// the categories above because secondaries, bones and the like would otherwise
+// land in "Other".
+const SKILL_GROUPS: &[(&str, &[&str])] = &[
+    ("Herblore", &["grimy", "clean ", "guam", "marrentill", "tarromin", "harralander", "ranarr", "toadflax", "irit",
+                   "avantoe", "kwuarm", "snapdragon", "cadantine", "lantadyme", "dwarf weed", "torstol", "fellstalk",
+                   "arbuck", "spirit weed", "wergali", "bloodweed", "eye of newt", "limpwurt", "red spiders' eggs",
+                   "white berries", "snape grass", "wine of zamorak", "dragon scale dust", "potato cactus",
+                   "mort myre fungus", "jangerberries", "crushed nest", "unicorn horn dust", "goat horn dust",
+                   "vial of water", "phoenix feather", "primal extract"]),
+    ("Woodcutting & Fletching", &["logs", "bowstring", "arrow shaft", "feather", "headless arrow"]),
+    ("Construction", &["plank"]),
+    ("Mining & Smithing", &[" ore", " bar", "coal", "stone spirit", "concentrated"]),
+    ("Farming", &[" seed", "sapling", "compost"]),
+    ("Runecrafting", &["rune essence", "pure essence", "talisman"]),
+    ("Crafting", &["hide", "leather", "uncut ", "flax", "molten glass", "sandstone", "soft clay"]),
+    ("Prayer", &["bones", "ashes"]),
+    ("Cooking", &["raw "]),
+];
+
+pub fn skill_group(name: &str) -> Option<&'static str> {
+    let lower = format!(" {}", name.to_lowercase());
+    SKILL_GROUPS
+        .iter()
+        .find(|(_, keywords)| keywords.iter().any(|k| lower.contains(k)))
+        .map(|(skill, _)| *skill)
+}
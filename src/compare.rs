@@ -0,0 +1,166 @@
+// Side-by-side evaluation of two scoring setups: how each ranks the current
+// market, and how each one's picks would have fared over recent history.
+use crate::flips::{analyze, net_profit, Horizon, PriceRounding, ScoreWeights, Tax};
+use crate::model::{FlipResult, Gp, ItemSnapshot, ItemStats};
+use crate::stats::{build_stats, day_number};
+use crate::strategy::Strategies;
+use std::collections::HashMap;
+
+// Only moves within either side's top ranks are worth listing
+const TOP_RANKS: usize = 100;
+
+// One side of the comparison: weights plus an optional script on top
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+    pub label: String,
+    pub weights: ScoreWeights,
+    pub horizon: Horizon,
+    pub script: Option<String>,
+    pub tax: Tax,
+    pub rounding: PriceRounding,
+    pub manual_weight: usize,
+}
+
+// How a variant's top picks played out over the held-back days
+#[derive(Debug, Clone, Default)]
+pub struct Backtest {
+    pub picks: usize,
+    pub filled: usize,  // Price dipped to the buy price
+    pub completed: usize,  // ...and later reached the sell price
+    pub invested: Gp,
+    pub profit: Gp,  // Completed flips only, after tax
+}
+
+impl Backtest {
+    pub fn roi(&self) -> f64 {
+        if self.invested > Gp::ZERO { self.profit.as_f64() / self.invested.as_f64() * 100.0 } else { 0.0 }
+    }
+}
+
+pub struct RankChange {
+    pub name: String,
+    pub rank_a: Option<usize>,  // 1-based; None when the variant drops the item
+    pub rank_b: Option<usize>,
+}
+
+pub struct Comparison {
+    pub changes: Vec<RankChange>,  // Biggest movers first
+    pub backtest_a: Backtest,
+    pub backtest_b: Backtest,
+    pub holdout_days: i64,
+}
+
+fn score(variant: &Variant, strategies: &Strategies, stats: &ItemStats) -> Option<FlipResult> {
+    let mut flip = analyze(stats, &variant.tax, &variant.weights, variant.horizon, variant.rounding);
+    if let Some(strategy) = variant.script.as_deref().and_then(|name| strategies.get(name)) {
+        // Script errors drop the item rather than aborting the comparison
+        if !strategies.apply(strategy, stats, &mut flip).unwrap_or(false) {
+            return None;
+        }
+    }
+    (flip.profit > Gp::ZERO).then_some(flip)
+}
+
+// Items ordered best first, as (stats index, flip)
+fn ranking(variant: &Variant, strategies: &Strategies, stats: &[ItemStats]) -> Vec<(usize, FlipResult)> {
+    let mut ranked: Vec<(usize, FlipResult)> = stats
+        .iter()
+        .enumerate()
+        .filter_map(|(i, s)| score(variant, strategies, s).map(|f| (i, f)))
+        .collect();
+    ranked.sort_by(|a, b| b.1.score.cmp(&a.1.score).then(stats[a.0].name.cmp(&stats[b.0].name)));
+    ranked
+}
+
+// Scores on everything before the last `holdout_days`, then walks the
+// held-back prices: a pick fills once the price is at or under its buy
+// price, and completes if it later reaches the sell price.
+fn backtest(
+    variant: &Variant,
+    strategies: &Strategies,
+    snaps: &[ItemSnapshot],
+    holdout_days: i64,
+    top_n: usize,
+    budget: f64,
+) -> Backtest {
+    let last_day = snaps.iter().filter_map(|s| day_number(&s.record_date)).max().unwrap_or(0);
+    let cutoff = last_day - holdout_days;
+
+    let (train, test): (Vec<ItemSnapshot>, Vec<ItemSnapshot>) = snaps
+        .iter()
+        .cloned()
+        .partition(|s| day_number(&s.record_date).is_some_and(|d| d <= cutoff));
+
+    let mut future: HashMap<i32, Vec<f64>> = HashMap::new();
+    for s in &test {
+        future.entry(s.item_id).or_default().push(s.price.as_f64());
+    }
+
+    let stats = build_stats(&train, variant.manual_weight);
+    let picks: Vec<(usize, FlipResult)> = ranking(variant, strategies, &stats).into_iter().take(top_n).collect();
+    let per_pick = budget / picks.len().max(1) as f64;
+
+    let mut result = Backtest { picks: picks.len(), ..Backtest::default() };
+    for (i, flip) in &picks {
+        let s = &stats[*i];
+        let Some(prices) = future.get(&s.item_id) else { continue };
+        let (buy, sell) = (flip.buy.as_f64(), flip.sell.as_f64());
+        let Some(fill_at) = prices.iter().position(|&p| p <= buy) else { continue };
+
+        let qty = ((per_pick / buy.max(1.0)) as i64).min(s.ge_limit.max(1) as i64);
+        result.filled += 1;
+        result.invested += flip.buy * qty;
+        if prices[fill_at..].iter().any(|&p| p >= sell) {
+            result.completed += 1;
+            result.profit += net_profit(&s.name, flip.buy, flip.sell, &variant.tax) * qty;
+        }
+    }
+    result
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn compare(
+    a: &Variant,
+    b: &Variant,
+    strategies: &Strategies,
+    snaps: &[ItemSnapshot],
+    stats: &[ItemStats],
+    holdout_days: i64,
+    top_n: usize,
+    budget: f64,
+) -> Comparison {
+    let ranks = |variant: &Variant| -> HashMap<i32, usize> {
+        ranking(variant, strategies, stats)
+            .iter()
+            .enumerate()
+            .map(|(rank, (i, _))| (stats[*i].item_id, rank + 1))
+            .collect()
+    };
+    let (ranks_a, ranks_b) = (ranks(a), ranks(b));
+
+    let mut changes: Vec<RankChange> = stats
+        .iter()
+        .filter_map(|s| {
+            let (rank_a, rank_b) = (ranks_a.get(&s.item_id).copied(), ranks_b.get(&s.item_id).copied());
+            let top = rank_a.is_some_and(|r| r <= TOP_RANKS) || rank_b.is_some_and(|r| r <= TOP_RANKS);
+            top.then(|| RankChange {
+                name: s.name.clone(),
+                rank_a,
+                rank_b,
+            })
+        })
+        .collect();
+
+    // Largest moves first; an item only one side ranks counts as a move off the end
+    let worst = stats.len() + 1;
+    let shift = |c: &RankChange| c.rank_a.unwrap_or(worst).abs_diff(c.rank_b.unwrap_or(worst));
+    let best = |c: &RankChange| c.rank_a.unwrap_or(worst).min(c.rank_b.unwrap_or(worst));
+    changes.sort_by(|x, y| shift(y).cmp(&shift(x)).then(best(x).cmp(&best(y))));
+
+    Comparison {
+        changes,
+        backtest_a: backtest(a, strategies, snaps, holdout_days, top_n, budget),
+        backtest_b: backtest(b, strategies, snaps, holdout_days, top_n, budget),
+        holdout_days,
+    }
+}
@@ -0,0 +1,249 @@
+use crate::email::EmailConfig;
+use crate::flips::{Horizon, PriceRounding, ScoreWeights};
+use crate::game::Game;
+use crate::hooks::HookConfig;
+use crate::palette::Palette;
+use crate::schedule::{QuietHours, ScanSchedule};
+use crate::snapshot_stream;
+use crate::telegram::TelegramConfig;
+use serde::{Serialize, Deserialize};
+
+const CONFIG_PATH: &str = "config.json";
+
+// Height of the results table rows
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum RowDensity {
+    #[default]
+    Comfortable,
+    Compact,  // Shorter rows, smaller text, secondary columns folded into the name tooltip
+}
+
+// Picked during first-run setup; seeds the scoring weights and budget
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum RiskTolerance {
+    Cautious,
+    #[default]
+    Balanced,
+    Aggressive,
+}
+
+impl RiskTolerance {
+    pub fn weights(self) -> ScoreWeights {
+        match self {
+            RiskTolerance::Cautious => ScoreWeights { roi: 0.8, volume: 1.5, volatility: 0.5, risk_penalty: 1.5, ..ScoreWeights::default() },
+            RiskTolerance::Balanced => ScoreWeights::default(),
+            RiskTolerance::Aggressive => ScoreWeights { roi: 1.3, profit: 1.3, volume: 0.8, volatility: 1.5, risk_penalty: 0.6, ..ScoreWeights::default() },
+        }
+    }
+
+    // Share of the bank suggested as the flipping budget
+    pub fn budget_share(self) -> f64 {
+        match self {
+            RiskTolerance::Cautious => 0.25,
+            RiskTolerance::Balanced => 0.5,
+            RiskTolerance::Aggressive => 0.8,
+        }
+    }
+}
+
+// What profit figures are shown in
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Currency {
+    #[default]
+    Gp,
+    Bonds,  // Converted at the latest Bond price
+    Usd,  // Bonds times what a bond costs in real money
+}
+
+// How the bank is split between the tabs, so each one plans with its own
+// share instead of the whole bank. Whatever flips and investments don't take
+// is held back for alching.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Allocation {
+    pub enabled: bool,
+    pub flips_pct: f64,
+    pub investments_pct: f64,
+}
+
+impl Default for Allocation {
+    fn default() -> Self {
+        Allocation { enabled: false, flips_pct: 60.0, investments_pct: 30.0 }
+    }
+}
+
+impl Allocation {
+    pub fn alch_pct(&self) -> f64 {
+        (100.0 - self.flips_pct - self.investments_pct).max(0.0)
+    }
+
+    pub fn flips(&self, bank: f64) -> f64 {
+        bank * self.flips_pct / 100.0
+    }
+
+    pub fn investments(&self, bank: f64) -> f64 {
+        bank * self.investments_pct / 100.0
+    }
+
+    pub fn alchs(&self, bank: f64) -> f64 {
+        bank * self.alch_pct() / 100.0
+    }
+
+    // Keeps the shares summing to at most 100%, trimming the one not being edited
+    pub fn clamp(&mut self, flips_edited: bool) {
+        self.flips_pct = self.flips_pct.clamp(0.0, 100.0);
+        self.investments_pct = self.investments_pct.clamp(0.0, 100.0);
+        if self.flips_pct + self.investments_pct > 100.0 {
+            if flips_edited {
+                self.investments_pct = 100.0 - self.flips_pct;
+            } else {
+                self.flips_pct = 100.0 - self.investments_pct;
+            }
+        }
+    }
+}
+
+// Old snapshots are compacted so the database stays small without losing the
+// long-term trend: one row per day past `daily_after_days`, one per week past
+// `weekly_after_days`. Scans only read the last 90 days, so both stay above that.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Retention {
+    pub enabled: bool,
+    pub daily_after_days: u32,
+    pub weekly_after_days: u32,
+    pub last_run: u64,  // Unix seconds
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Retention { enabled: false, daily_after_days: 120, weekly_after_days: 400, last_run: 0 }
+    }
+}
+
+impl Retention {
+    pub const MIN_DAYS: u32 = 90;
+
+    // Runs at most once a day
+    pub fn is_due(&self, now: u64) -> bool {
+        self.enabled && now.saturating_sub(self.last_run) >= 86_400
+    }
+}
+
+// Where the window was left, restored on the next launch
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowState {
+    pub size: Option<[f32; 2]>,
+    pub position: Option<[f32; 2]>,
+    pub maximized: bool,
+    pub side_panel_width: Option<f32>,
+    pub tab: String,
+}
+
+// User settings persisted between sessions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    pub daily_profit_goal: f64,
+    pub holding_drop_alert_pct: f64,  // Alert when a held item falls this far below cost basis
+    pub new_item_days: u32,  // Items first seen within this many days count as new
+    pub dead_item_days: i64,  // Items with no snapshot for this long are dead
+    pub include_dead_items: bool,
+    pub row_density: RowDensity,
+    pub currency: Currency,
+    pub palette: Palette,  // Colours for gain/loss, trend and tier indicators
+    pub usd_per_bond: f64,
+    pub beginner_mode: bool,
+    pub merge_variants: bool,  // One row per variant family (charged/uncharged, doses...) instead of each listing
+    pub game: Game,  // RS3 or Old School; picks the database, tax rules and saved lists
+    pub ironman_mode: bool,  // Replace flip recommendations with plain price and volume data
+    pub budget: f64,  // Budget the app starts with
+    pub max_drawdown_pct: f64,  // Worst-case plan loss allowed, as % of budget; 0 = no limit
+    pub model_slippage: bool,  // Take expected slippage off profit in thin markets
+    pub realistic_profit: bool,  // Scale profit by the share of the spread history says was capturable
+    pub timeframe_columns: [bool; 3],  // ROI/trend/volatility column groups shown, per stats::TIMEFRAMES
+    pub bank_size: f64,
+    pub allocation: Allocation,
+    pub risk_tolerance: RiskTolerance,
+    pub weights: ScoreWeights,
+    pub horizon: Horizon,
+    pub rounding: PriceRounding,  // Steps the recommended offer prices are rounded to
+    pub manual_weight: usize,  // Times each manual price point counts in the recent window
+    pub strategy: Option<String>,  // Script in strategies/ applied after the built-in scoring
+    pub schedule: ScanSchedule,
+    pub quiet_hours: QuietHours,
+    pub close_to_tray: bool,  // Closing the window hides it to the tray and keeps scanning
+    pub telegram: TelegramConfig,
+    pub email: EmailConfig,
+    pub post_scan: HookConfig,
+    pub profile: String,  // Whose trade journal is active; empty = the main one
+    pub alt1_path: String,  // Where to write targets for the Alt1 overlay after each scan; empty = off
+    pub retention: Retention,
+    pub scan_batch_size: usize,  // Snapshots read per batch during a scan; smaller keeps the progress bar moving
+    pub window: WindowState,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        AppConfig {
+            daily_profit_goal: 10_000_000.0,
+            holding_drop_alert_pct: 10.0,
+            new_item_days: 14,
+            dead_item_days: 14,
+            include_dead_items: false,
+            row_density: RowDensity::Comfortable,
+            currency: Currency::Gp,
+            palette: Palette::default(),
+            usd_per_bond: 7.99,
+            beginner_mode: false,
+            game: Game::Rs3,
+            ironman_mode: false,
+            merge_variants: false,
+            budget: 50_000_000.0,
+            max_drawdown_pct: 0.0,
+            model_slippage: true,
+            realistic_profit: false,
+            timeframe_columns: [false; 3],
+            bank_size: 0.0,
+            allocation: Allocation::default(),
+            risk_tolerance: RiskTolerance::Balanced,
+            weights: ScoreWeights::default(),
+            horizon: Horizon::default(),
+            rounding: PriceRounding::default(),
+            manual_weight: 1,
+            strategy: None,
+            schedule: ScanSchedule::default(),
+            quiet_hours: QuietHours::default(),
+            close_to_tray: false,
+            telegram: TelegramConfig::default(),
+            email: EmailConfig::default(),
+            post_scan: HookConfig::default(),
+            profile: String::new(),
+            alt1_path: String::new(),
+            retention: Retention::default(),
+            scan_batch_size: snapshot_stream::DEFAULT_BATCH_SIZE,
+            window: WindowState::default(),
+        }
+    }
+}
+
+impl AppConfig {
+    // No config file means this is the first launch
+    pub fn exists() -> bool {
+        std::path::Path::new(CONFIG_PATH).exists()
+    }
+
+    pub fn load() -> Self {
+        match std::fs::read_to_string(CONFIG_PATH) {
+            Ok(data) => serde_json::from_str(&data).unwrap_or_default(),
+            Err(_) => AppConfig::default(),
+        }
+    }
+
+    pub fn save(&self) {
+        if let Ok(json) = serde_json::to_string_pretty(self) {
+            let _ = std::fs::write(CONFIG_PATH, json);
+        }
+    }
+}
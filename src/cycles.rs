@@ -0,0 +1,116 @@
+// Dominant cycle detection: the lag at which a detrended price series best
+// matches itself, and where in that cycle today sits. Plenty of items dip
+// and recover on a weekly rhythm (reset day, weekend play).
+
+const MIN_PERIOD: usize = 3;
+const MAX_PERIOD: usize = 45;
+const MIN_CYCLES: usize = 3;  // The series has to cover the period this many times
+const MIN_STRENGTH: f64 = 0.3;  // Autocorrelation at the period
+
+#[derive(Debug, Clone, Copy)]
+pub struct Cycle {
+    pub period: usize,  // Days
+    pub strength: f64,  // Autocorrelation at `period`, 0..1
+    pub days_to_low: usize,  // 0 = the usual low is today
+    pub days_to_high: usize,
+    pub amplitude_pct: f64,  // Average high-to-low swing as a % of the price
+}
+
+impl Cycle {
+    pub fn advice(&self) -> String {
+        match (self.days_to_low, self.days_to_high) {
+            (0, _) => format!("In the usual low now - buy, then sell in ~{} days", self.days_to_high),
+            (_, 0) => format!("At the usual high now - sell, next low in ~{} days", self.days_to_low),
+            (low, high) if low < high => format!("Buy in ~{} days, sell ~{} days after that", low, high - low),
+            (low, high) => format!("Sell in ~{} days, then buy back ~{} days after that", high, low - high),
+        }
+    }
+}
+
+// One price per calendar day, carrying the last price over gaps
+fn daily(history: &[(i64, f64)]) -> Vec<f64> {
+    let Some(&(first, _)) = history.first() else { return vec![] };
+    let mut out = Vec::new();
+    let mut last = 0.0;
+    let mut it = history.iter().peekable();
+    for day in first..=history[history.len() - 1].0 {
+        while let Some(&&(d, p)) = it.peek() {
+            if d > day {
+                break;
+            }
+            last = p;
+            it.next();
+        }
+        out.push(last);
+    }
+    out
+}
+
+// Residuals from a straight-line fit, so a trend doesn't read as one long cycle
+fn detrend(prices: &[f64]) -> Vec<f64> {
+    let n = prices.len() as f64;
+    let mean_x = (n - 1.0) / 2.0;
+    let mean_y = prices.iter().sum::<f64>() / n;
+    let (mut cov, mut var) = (0.0, 0.0);
+    for (i, p) in prices.iter().enumerate() {
+        let dx = i as f64 - mean_x;
+        cov += dx * (p - mean_y);
+        var += dx * dx;
+    }
+    let slope = if var > 0.0 { cov / var } else { 0.0 };
+    prices.iter().enumerate().map(|(i, p)| p - (mean_y + slope * (i as f64 - mean_x))).collect()
+}
+
+fn autocorrelation(series: &[f64], lag: usize) -> f64 {
+    let variance: f64 = series.iter().map(|v| v * v).sum();
+    if variance <= 0.0 {
+        return 0.0;
+    }
+    series.iter().zip(&series[lag..]).map(|(a, b)| a * b).sum::<f64>() / variance
+}
+
+// The strongest repeating period in `history` ((day, price), oldest first),
+// or None when nothing repeats clearly enough
+pub fn dominant_cycle(history: &[(i64, f64)]) -> Option<Cycle> {
+    let prices = daily(history);
+    let max_period = MAX_PERIOD.min(prices.len() / MIN_CYCLES);
+    if max_period < MIN_PERIOD {
+        return None;
+    }
+    let residuals = detrend(&prices);
+
+    // Take the best local peak, not just the largest value - short lags
+    // always correlate well with their neighbours
+    let acf: Vec<f64> = (0..=max_period + 1).map(|lag| autocorrelation(&residuals, lag)).collect();
+    let (period, strength) = (MIN_PERIOD..=max_period)
+        .filter(|&lag| acf[lag] >= acf[lag - 1] && acf[lag] >= acf[lag + 1])
+        .map(|lag| (lag, acf[lag]))
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+    if strength < MIN_STRENGTH {
+        return None;
+    }
+
+    // Average residual at each position in the cycle, lined up so today is the last position
+    let mut sums = vec![0.0; period];
+    let mut counts = vec![0usize; period];
+    let offset = period - 1 - (residuals.len() - 1) % period;
+    for (i, r) in residuals.iter().enumerate() {
+        sums[(i + offset) % period] += r;
+        counts[(i + offset) % period] += 1;
+    }
+    let profile: Vec<f64> = sums.iter().zip(&counts).map(|(s, c)| s / (*c).max(1) as f64).collect();
+    let low = (0..period).min_by(|&a, &b| profile[a].total_cmp(&profile[b]))?;
+    let high = (0..period).max_by(|&a, &b| profile[a].total_cmp(&profile[b]))?;
+
+    // Today is position period - 1, so the next visit to position p is p + 1 days out
+    let days_until = |pos: usize| (pos + 1) % period;
+    let mean_price = prices.iter().sum::<f64>() / prices.len() as f64;
+
+    Some(Cycle {
+        period,
+        strength,
+        days_to_low: days_until(low),
+        days_to_high: days_until(high),
+        amplitude_pct: if mean_price > 0.0 { (profile[high] - profile[low]) / mean_price * 100.0 } else { 0.0 },
+    })
+}
@@ -0,0 +1,64 @@
+// Thins a long price series down to what a chart can show. Largest-Triangle-
+// Three-Buckets keeps the first and last points, splits the rest into equal
+// buckets and from each keeps the point that makes the biggest triangle with
+// the previous pick and the next bucket's average - so spikes and dips
+// survive where plain every-nth sampling would skip right over them.
+
+// Points per chart line; about one per pixel of a wide detail chart
+pub const CHART_POINTS: usize = 800;
+
+// `points` must be sorted by x. Series already under `threshold` come back as is.
+pub fn lttb(points: &[[f64; 2]], threshold: usize) -> Vec<[f64; 2]> {
+    if threshold < 3 || points.len() <= threshold {
+        return points.to_vec();
+    }
+
+    let bucket = (points.len() - 2) as f64 / (threshold - 2) as f64;
+    let mut kept = Vec::with_capacity(threshold);
+    kept.push(points[0]);
+
+    let mut previous = points[0];
+    for i in 0..threshold - 2 {
+        let start = (i as f64 * bucket) as usize + 1;
+        let end = ((i + 1) as f64 * bucket) as usize + 1;
+
+        // Average of the next bucket; the final one reaches the last point
+        let next_end = (((i + 2) as f64 * bucket) as usize + 1).clamp(end + 1, points.len());
+        let next = &points[end..next_end];
+        let avg_x = next.iter().map(|p| p[0]).sum::<f64>() / next.len() as f64;
+        let avg_y = next.iter().map(|p| p[1]).sum::<f64>() / next.len() as f64;
+
+        let area = |p: &[f64; 2]| {
+            ((previous[0] - avg_x) * (p[1] - previous[1]) - (previous[0] - p[0]) * (avg_y - previous[1])).abs()
+        };
+        let best = points[start..end]
+            .iter()
+            .max_by(|a, b| area(a).total_cmp(&area(b)))
+            .copied()
+            .unwrap_or(previous);
+
+        kept.push(best);
+        previous = best;
+    }
+
+    kept.push(points[points.len() - 1]);
+    kept
+}
+
+// Bars for a series too long to draw one per point: consecutive points
+// averaged in equal-sized buckets, as (centre x, mean, width in x). Short
+// series come back as a bar per point, one unit wide.
+pub fn bucket_means(points: &[[f64; 2]], buckets: usize) -> Vec<(f64, f64, f64)> {
+    if buckets == 0 || points.len() <= buckets {
+        return points.iter().map(|p| (p[0], p[1], 1.0)).collect();
+    }
+
+    points
+        .chunks(points.len().div_ceil(buckets))
+        .map(|chunk| {
+            let (first, last) = (chunk[0][0], chunk[chunk.len() - 1][0]);
+            let mean = chunk.iter().map(|p| p[1]).sum::<f64>() / chunk.len() as f64;
+            ((first + last) / 2.0, mean, last - first + 1.0)
+        })
+        .collect()
+}
@@ -0,0 +1,103 @@
+// Optional daily email digest sent over SMTP. The body is the same HTML the
+// report export writes, trimmed to the top flips plus alerts and P&L.
+use serde::{Serialize, Deserialize};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmailConfig {
+    pub enabled: bool,
+    pub smtp_host: String,
+    pub smtp_port: u16,  // 465 uses implicit TLS, anything else STARTTLS
+    pub username: String,
+    pub password: String,  // Stored in config.json as-is; use an app password
+    pub from: String,
+    pub to: String,
+    pub send_hour: u32,  // First scan at or after this UTC hour sends the digest
+    pub last_sent_day: i64,
+}
+
+impl Default for EmailConfig {
+    fn default() -> Self {
+        EmailConfig {
+            enabled: false,
+            smtp_host: String::new(),
+            smtp_port: 587,
+            username: String::new(),
+            password: String::new(),
+            from: String::new(),
+            to: String::new(),
+            send_hour: 8,
+            last_sent_day: 0,
+        }
+    }
+}
+
+impl EmailConfig {
+    pub fn is_due(&self, now: u64) -> bool {
+        let today = (now / 86_400) as i64;
+        self.enabled && self.last_sent_day != today && (now % 86_400) / 3_600 >= self.send_hour as u64
+    }
+}
+
+// Sends in the background; the outcome shows up in `status`
+#[derive(Default)]
+pub struct Mailer {
+    status: Arc<Mutex<Option<String>>>,
+}
+
+impl Mailer {
+    pub fn status(&self) -> Option<String> {
+        self.status.lock().unwrap().clone()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn send(&self, config: &EmailConfig, subject: String, html: String) {
+        let config = config.clone();
+        let status = self.status.clone();
+        *status.lock().unwrap() = Some("Sending...".into());
+        std::thread::spawn(move || {
+            let result = match send_blocking(&config, subject, html) {
+                Ok(()) => format!("Digest sent to {}", config.to),
+                Err(e) => format!("Couldn't send the digest: {}", e),
+            };
+            *status.lock().unwrap() = Some(result);
+        });
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn send(&self, _config: &EmailConfig, _subject: String, _html: String) {
+        *self.status.lock().unwrap() = Some("Email is not available in the web build".into());
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn send_blocking(config: &EmailConfig, subject: String, html: String) -> Result<(), String> {
+    use lettre::message::header::ContentType;
+    use lettre::transport::smtp::authentication::Credentials;
+    use lettre::{Message, SmtpTransport, Transport};
+
+    let message = Message::builder()
+        .from(config.from.parse().map_err(|e| format!("bad sender address: {}", e))?)
+        .to(config.to.parse().map_err(|e| format!("bad recipient address: {}", e))?)
+        .subject(subject)
+        .header(ContentType::TEXT_HTML)
+        .body(html)
+        .map_err(|e| e.to_string())?;
+
+    let host = config.smtp_host.trim();
+    let builder = if config.smtp_port == 465 {
+        SmtpTransport::relay(host)
+    } else {
+        SmtpTransport::starttls_relay(host)
+    }
+    .map_err(|e| e.to_string())?;
+
+    builder
+        .port(config.smtp_port)
+        .credentials(Credentials::new(config.username.clone(), config.password.clone()))
+        .build()
+        .send(&message)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,125 @@
+// Exit advice for held items the scanner rates CRASH: sell into the drop, or
+// wait for the price to come back? Answered from the item's own past drops
+// of at least the same depth and how long they took to recover, if at all.
+use crate::flips::{after_tax, Tax};
+use crate::model::ItemStats;
+
+const LOOKBACK: usize = 14;  // Records the pre-drop reference price is the median of
+const MIN_DROP: f64 = 0.05;  // Shallower dips than this aren't treated as drops
+const MAX_WAIT_DAYS: i64 = 30;  // A drop still down after this long counts as not recovered
+const MIN_EPISODES: usize = 3;
+const HOLD_MARGIN: f64 = 0.02;  // Waiting has to beat selling by this much to tie the gp up
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExitCall {
+    Hold,
+    Sell,
+    Unknown,  // Too few comparable drops in the history to say
+}
+
+impl ExitCall {
+    pub fn label(self) -> &'static str {
+        match self {
+            ExitCall::Hold => "Hold for recovery",
+            ExitCall::Sell => "Cut losses now",
+            ExitCall::Unknown => "Not enough history",
+        }
+    }
+}
+
+// Per-item figures; the caller scales by the holding's quantity
+#[derive(Debug, Clone)]
+pub struct ExitAdvice {
+    pub drop_pct: f64,  // How far the price sits below its recent reference
+    pub episodes: usize,  // Past drops at least as deep
+    pub recovered: usize,  // ...that got back to their reference within MAX_WAIT_DAYS
+    pub median_recovery_days: Option<f64>,
+    pub sell_now: f64,  // Net per item after tax at today's price
+    pub hold_value: f64,  // Expected net per item from waiting up to MAX_WAIT_DAYS
+    pub call: ExitCall,
+}
+
+impl ExitAdvice {
+    pub fn recovery_rate(&self) -> f64 {
+        if self.episodes == 0 { 0.0 } else { self.recovered as f64 / self.episodes as f64 }
+    }
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(|a, b| a.total_cmp(b));
+    values[values.len() / 2]
+}
+
+// Median of the LOOKBACK records before `i`
+fn reference(history: &[(i64, f64)], i: usize) -> Option<f64> {
+    if i < LOOKBACK {
+        return None;
+    }
+    let mut window: Vec<f64> = history[i - LOOKBACK..i].iter().map(|(_, p)| *p).collect();
+    Some(median(&mut window))
+}
+
+pub fn advise(stats: &ItemStats, tax: &Tax) -> Option<ExitAdvice> {
+    let history = &stats.history;
+    let last = history.len().checked_sub(1)?;
+    let current = stats.current_price;
+    let drop = 1.0 - current / reference(history, last)?;
+    let threshold = drop.max(MIN_DROP);
+
+    // Replay each earlier drop of at least this depth: it either climbs back to
+    // its reference (sold there) or is sold at whatever it's worth after the wait
+    let mut exits = Vec::new();
+    let mut recovery_days = Vec::new();
+    let mut i = LOOKBACK;
+    while i < last {
+        let (day, price) = history[i];
+        let Some(target) = reference(history, i) else { break };
+        if price > target * (1.0 - threshold) {
+            i += 1;
+            continue;
+        }
+
+        let window_end = history[i..].iter().position(|(d, _)| *d - day > MAX_WAIT_DAYS).map(|n| i + n);
+        let recovered_at = history[i + 1..window_end.unwrap_or(history.len())]
+            .iter()
+            .position(|(_, p)| *p >= target)
+            .map(|n| i + 1 + n);
+
+        match (recovered_at, window_end) {
+            (Some(j), _) => {
+                recovery_days.push((history[j].0 - day) as f64);
+                exits.push(target / price);
+                i = j + 1;
+            }
+            (None, Some(end)) => {
+                exits.push(history[end - 1].1 / price);
+                i = end;
+            }
+            // Still inside its wait when the data runs out, so no verdict yet
+            (None, None) => break,
+        }
+    }
+
+    let sell_now = after_tax(&stats.name, current, tax);
+    let episodes = exits.len();
+    let hold_price = if episodes > 0 { current * exits.iter().sum::<f64>() / episodes as f64 } else { current };
+    let hold_value = after_tax(&stats.name, hold_price, tax);
+
+    let call = if episodes < MIN_EPISODES {
+        ExitCall::Unknown
+    } else if hold_value > sell_now * (1.0 + HOLD_MARGIN) {
+        ExitCall::Hold
+    } else {
+        ExitCall::Sell
+    };
+
+    Some(ExitAdvice {
+        drop_pct: drop.max(0.0) * 100.0,
+        episodes,
+        recovered: recovery_days.len(),
+        median_recovery_days: (!recovery_days.is_empty()).then(|| median(&mut recovery_days)),
+        sell_now,
+        hold_value,
+        call,
+    })
+}
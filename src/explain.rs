@@ -0,0 +1,125 @@
+// Plain-language summary of why an item is (or isn't) a flip, built from the
+// same numbers the table shows: "Place a buy offer at 1.2M: the price sits
+// below its 30-day Q10, volume is 3× average and the trend is flat."
+use crate::format::format_gp;
+use crate::model::{Drawdown, Timeframe};
+use crate::regime::Regime;
+
+// Reasons given per summary; past this it stops reading like a sentence
+const MAX_REASONS: usize = 3;
+
+// Within this much (%) above the buy price an offer is worth placing now
+const NEAR_BUY_PCT: f64 = 3.0;
+
+// A 30-day move smaller than this (%) reads as flat
+const FLAT_PCT: f64 = 3.0;
+
+// Drawdowns worth a warning (%)
+const DEEP_DRAWDOWN_PCT: f64 = 15.0;
+
+pub struct Facts<'a> {
+    pub price: Option<f64>,  // Latest price
+    pub buy: f64,
+    pub sell: f64,
+    pub tier: &'a str,
+    pub roi: f64,
+    pub cycle_days: Option<f64>,
+    pub volume_ratio: Option<f64>,  // Recent volume against the item's average
+    pub month: Option<&'a Timeframe>,  // The 30-day window
+    pub regime: Option<Regime>,
+    pub dump: bool,
+    pub low_confidence: Option<&'a str>,
+    pub drawdown: Option<Drawdown>,
+}
+
+pub fn summary(f: &Facts) -> String {
+    if f.tier == "CRASH" {
+        return format!(
+            "Avoid for now: even selling at {} wouldn't cover a {} buy after tax.",
+            format_gp(f.sell),
+            format_gp(f.buy)
+        );
+    }
+
+    let action = match f.price {
+        Some(price) if price <= f.buy => format!("Buy at {} now", format_gp(f.buy)),
+        Some(price) if price <= f.buy * (1.0 + NEAR_BUY_PCT / 100.0) => format!("Place a buy offer at {}", format_gp(f.buy)),
+        Some(price) if f.buy > 0.0 => format!(
+            "Wait for a dip to {} ({:.0}% under the current price)",
+            format_gp(f.buy),
+            (price - f.buy) / price * 100.0
+        ),
+        _ => format!("Buy at {}", format_gp(f.buy)),
+    };
+
+    let mut reasons = Vec::new();
+    if let (Some(price), Some(month)) = (f.price, f.month) {
+        if price <= month.q10 {
+            reasons.push("the price sits below its 30-day Q10".to_string());
+        } else if price >= month.q90 {
+            reasons.push("the price is near its 30-day high".to_string());
+        }
+    }
+    if f.dump {
+        reasons.push("it's a post-promo dump that usually recovers".to_string());
+    }
+    match f.volume_ratio {
+        Some(ratio) if ratio >= 1.5 => reasons.push(format!("volume is {:.1}× average", ratio)),
+        Some(ratio) if ratio <= 0.5 => reasons.push("volume is well below average".to_string()),
+        _ => {}
+    }
+    if let Some(month) = f.month {
+        if month.change_pct.abs() < FLAT_PCT {
+            reasons.push("the trend is flat".to_string());
+        } else {
+            let direction = if month.change_pct > 0.0 { "up" } else { "down" };
+            reasons.push(format!("it's {} {:.0}% over 30 days", direction, month.change_pct.abs()));
+        }
+    }
+    if f.regime == Some(Regime::Cyclical) {
+        reasons.push("the price has been cycling steadily".to_string());
+    }
+    reasons.truncate(MAX_REASONS);
+
+    let mut text = action;
+    if !reasons.is_empty() {
+        text.push_str(" because ");
+        text.push_str(&join_reasons(&reasons));
+    }
+    text.push('.');
+
+    text.push_str(&format!(" Selling at {} makes {:.1}% after tax", format_gp(f.sell), f.roi));
+    match f.cycle_days {
+        Some(days) => text.push_str(&format!(", usually within {:.0} days.", days.max(1.0))),
+        None => text.push('.'),
+    }
+
+    if let Some(d) = f.drawdown.filter(|d| d.max_pct >= DEEP_DRAWDOWN_PCT) {
+        text.push_str(&format!(" It has fallen {:.0}% below that buy price before", d.max_pct));
+        match d.recovery_days {
+            Some(days) => text.push_str(&format!(" and took {} days to recover.", days)),
+            None => text.push_str(" and hasn't recovered yet."),
+        }
+    }
+    if let Some(reason) = f.low_confidence {
+        text.push_str(&format!(" Low confidence - {}.", lowercase_first(reason)));
+    }
+    text
+}
+
+// "a, b and c"
+fn join_reasons(reasons: &[String]) -> String {
+    match reasons {
+        [] => String::new(),
+        [only] => only.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+fn lowercase_first(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
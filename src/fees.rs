@@ -0,0 +1,76 @@
+// Fee schedules beyond the built-in RS3 and Old School GE taxes - a private
+// server's rates, or a tax change the code hasn't caught up with yet. They
+// live in fees.json (one per game) so they can be edited by hand:
+//
+//   { "name": "My server", "fee": { "kind": "tiered", "cap": 10000000,
+//     "bands": [{ "from": 0, "rate": 0.01 }, { "from": 1000000, "rate": 0.03 }] },
+//     "categories": { "Runes": { "kind": "flat", "rate": 0.0 } },
+//     "exempt": ["Bond"] }
+//
+// `kind` is "flat" (rate), "capped" (rate, cap) or "tiered" (bands, optional cap).
+use crate::flips::Tax;
+use crate::game::Game;
+use serde::{Serialize, Deserialize};
+
+pub const FEES_PATH: &str = "fees.json";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Fees {
+    pub selected: Option<String>,  // Schedule in use by name; None = the game's own GE tax
+    pub schedules: Vec<Tax>,
+    #[serde(skip)]
+    path: String,
+    #[serde(skip)]
+    pub error: Option<String>,  // Why the file didn't parse; it's left alone until fixed
+}
+
+impl Fees {
+    pub fn load(game: Game) -> Self {
+        let path = game.file(FEES_PATH);
+        let mut loaded = match std::fs::read_to_string(&path) {
+            Ok(data) => serde_json::from_str(&data).unwrap_or_else(|e| Fees {
+                error: Some(format!("{}: {}", path, e)),
+                ..Fees::default()
+            }),
+            Err(_) => Fees::default(),
+        };
+        loaded.path = path;
+        loaded
+    }
+
+    // Never over a file that failed to parse, so a half-done edit isn't lost
+    pub fn save(&self) {
+        if self.error.is_some() {
+            return;
+        }
+        if let Ok(json) = serde_json::to_string_pretty(self) {
+            let _ = std::fs::write(&self.path, json);
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    // Falls back to the game's tax when the selected schedule has been
+    // removed from the file
+    pub fn active(&self, game: Game) -> Tax {
+        self.selected
+            .as_deref()
+            .and_then(|name| self.schedules.iter().find(|t| t.name == name))
+            .cloned()
+            .unwrap_or_else(|| game.tax())
+    }
+
+    // Adds a copy of `from` under an unused name, ready to edit in the file.
+    // Returns the new name.
+    pub fn add_copy(&mut self, from: &Tax) -> String {
+        let name = (1..)
+            .map(|n| if n == 1 { "Custom".to_string() } else { format!("Custom {}", n) })
+            .find(|name| !self.schedules.iter().any(|t| &t.name == name))
+            .unwrap_or_default();
+        self.schedules.push(Tax { name: name.clone(), ..from.clone() });
+        name
+    }
+}
@@ -1,7 +1,499 @@
+use crate::categories;
 use crate::model::ItemStats;
-use crate::model::FlipResult;
+use crate::model::{FlipResult, Gp};
+use crate::regime::Regime;
+use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
 
-pub fn analyze(stats: &ItemStats, tax: f64) -> FlipResult {
+// How much of one item's sale price goes to the GE
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FeeModel {
+    Flat { rate: f64 },
+    Capped { rate: f64, cap: f64 },
+    // The rate of the highest band the price reaches, applied to the whole price
+    Tiered { bands: Vec<FeeBand>, cap: Option<f64> },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeeBand {
+    pub from: f64,  // Prices at or above this pay `rate`
+    pub rate: f64,
+}
+
+impl FeeModel {
+    pub fn rate_at(&self, price: f64) -> f64 {
+        match self {
+            FeeModel::Flat { rate } | FeeModel::Capped { rate, .. } => *rate,
+            FeeModel::Tiered { bands, .. } => bands
+                .iter()
+                .filter(|b| price >= b.from)
+                .max_by(|a, b| a.from.total_cmp(&b.from))
+                .map_or(0.0, |b| b.rate),
+        }
+    }
+
+    pub fn cap(&self) -> Option<f64> {
+        match self {
+            FeeModel::Flat { .. } => None,
+            FeeModel::Capped { cap, .. } => Some(*cap),
+            FeeModel::Tiered { cap, .. } => *cap,
+        }
+    }
+
+    // "2% capped at 5.00M", "1% from 0, 2% from 10.0K"
+    pub fn describe(&self) -> String {
+        let pct = |rate: f64| format!("{}%", rate * 100.0);
+        let text = match self {
+            FeeModel::Flat { rate } | FeeModel::Capped { rate, .. } => pct(*rate),
+            FeeModel::Tiered { bands, .. } => bands
+                .iter()
+                .map(|b| format!("{} from {}", pct(b.rate), crate::format::format_gp(b.from)))
+                .collect::<Vec<_>>()
+                .join(", "),
+        };
+        match self.cap() {
+            Some(cap) => format!("{} capped at {}", text, crate::format::format_gp(cap)),
+            None => text,
+        }
+    }
+}
+
+// A game's (or server's) Grand Exchange tax on the sell side. Built-in for
+// RS3 and Old School; anything else goes in fees.json (see fees.rs).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tax {
+    pub name: String,
+    pub fee: FeeModel,
+    #[serde(default)]
+    pub categories: BTreeMap<String, FeeModel>,  // Overrides keyed by categories::category
+    #[serde(default)]
+    pub exempt: Vec<String>,  // Items the GE doesn't tax at all
+}
+
+impl Tax {
+    // RS3: 2%, so anything selling above 250M pays a flat 5M
+    pub fn rs3() -> Tax {
+        Tax {
+            name: "RS3 GE tax".to_string(),
+            fee: FeeModel::Capped { rate: 0.02, cap: 5_000_000.0 },
+            categories: BTreeMap::new(),
+            exempt: vec!["Bond".to_string()],
+        }
+    }
+
+    // Old School: the same rate and cap, but bonds and a list of basic
+    // skilling tools are exempt
+    pub fn osrs() -> Tax {
+        Tax {
+            name: "Old School GE tax".to_string(),
+            fee: FeeModel::Capped { rate: 0.02, cap: 5_000_000.0 },
+            categories: BTreeMap::new(),
+            exempt: [
+                "Old school bond", "Chisel", "Gardening trowel", "Glassblowing pipe", "Hammer", "Needle",
+                "Pestle and mortar", "Rake", "Saw", "Secateurs", "Seed dibber", "Shears", "Spade", "Watering can(0)",
+            ]
+            .map(String::from)
+            .to_vec(),
+        }
+    }
+
+    pub fn is_exempt(&self, name: &str) -> bool {
+        self.exempt.iter().any(|n| n.eq_ignore_ascii_case(name))
+    }
+
+    pub fn fee_for(&self, name: &str) -> &FeeModel {
+        // Working out the category costs a lowercase copy of the name; skip it when nothing overrides
+        if self.categories.is_empty() {
+            return &self.fee;
+        }
+        self.categories.get(categories::category(name)).unwrap_or(&self.fee)
+    }
+
+    pub fn describe(&self) -> String {
+        let mut text = self.fee.describe();
+        if !self.categories.is_empty() {
+            text.push_str(&format!(", {} category overrides", self.categories.len()));
+        }
+        if !self.exempt.is_empty() {
+            text.push_str(&format!(", {} exempt items", self.exempt.len()));
+        }
+        text
+    }
+}
+
+// Tax paid per item sold at `price`. Rounded down, so items under 50gp are
+// effectively tax-free too.
+pub fn sale_tax(name: &str, price: Gp, tax: &Tax) -> Gp {
+    if tax.is_exempt(name) {
+        return Gp::ZERO;
+    }
+    let fee = tax.fee_for(name);
+    let price = price.as_f64();
+    Gp::from_f64((price * fee.rate_at(price)).floor().clamp(0.0, fee.cap().unwrap_or(f64::INFINITY)))
+}
+
+// What selling at an estimated price leaves after tax, for quantiles and
+// simulated exits that aren't whole gp
+pub fn after_tax(name: &str, price: f64, tax: &Tax) -> f64 {
+    price - sale_tax(name, Gp::from_f64(price), tax).as_f64()
+}
+
+// Per-item profit after tax in whole gp. The tax is already floored, so this
+// stays exact however many items it's later multiplied by.
+pub fn net_profit(name: &str, buy: Gp, sell: Gp, tax: &Tax) -> Gp {
+    sell - sale_tax(name, sell, tax) - buy
+}
+
+// How easily an item's offers fill, from its average daily volume
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Liquidity {
+    High,
+    Medium,
+    Low,
+    Thin,
+}
+
+impl Liquidity {
+    pub fn of(avg_volume: f64) -> Self {
+        match avg_volume {
+            v if v >= 100_000.0 => Liquidity::High,
+            v if v >= 10_000.0 => Liquidity::Medium,
+            v if v >= 1_000.0 => Liquidity::Low,
+            _ => Liquidity::Thin,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Liquidity::High => "High",
+            Liquidity::Medium => "Medium",
+            Liquidity::Low => "Low",
+            Liquidity::Thin => "Thin",
+        }
+    }
+
+    // Typical price give-up per side when the order is small next to volume
+    fn base_slippage(self) -> f64 {
+        match self {
+            Liquidity::High => 0.0,
+            Liquidity::Medium => 0.005,
+            Liquidity::Low => 0.02,
+            Liquidity::Thin => 0.04,
+        }
+    }
+}
+
+// Expected slippage per side as a fraction: thin markets fill a few percent
+// worse than Q10/Q90, and more so the bigger the order is against daily volume.
+pub fn slippage(avg_volume: f64, qty: i32) -> f64 {
+    let base = Liquidity::of(avg_volume).base_slippage();
+    let order_share = if avg_volume > 0.0 { qty.max(0) as f64 / avg_volume } else { 1.0 };
+    (base * (1.0 + order_share)).min(0.10)
+}
+
+// Per-item profit and ROI after paying `slip` more on the buy and getting
+// `slip` less on the sell
+pub fn slipped_profit(name: &str, buy: Gp, sell: Gp, slip: f64, tax: &Tax) -> (Gp, f64) {
+    let buy = Gp::from_f64(buy.as_f64() * (1.0 + slip));
+    let sell = Gp::from_f64(sell.as_f64() * (1.0 - slip));
+    let net = net_profit(name, buy, sell, tax);
+    let roi = if buy > Gp::ZERO { net.as_f64() / buy.as_f64() * 100.0 } else { 0.0 };
+    (net, roi)
+}
+
+// The guide price only moves so far in a single daily update, so a target a
+// long way from the current price takes several updates to reach. The cap is
+// the item's own 90th-percentile day-over-day move, kept within GE-like bounds.
+const MIN_DAILY_CAP: f64 = 0.01;
+const MAX_DAILY_CAP: f64 = 0.10;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Reach {
+    pub daily_cap: f64,  // Largest typical move per update, as a fraction
+    pub buy_days: u32,   // Updates for the price to fall from current to the buy target
+    pub sell_days: u32,  // Updates to climb from the buy target to the sell target
+}
+
+impl Reach {
+    // Either leg needs more than one day's worth of movement
+    pub fn is_slow(&self) -> bool {
+        self.buy_days > 1 || self.sell_days > 1
+    }
+
+    pub fn days(&self) -> u32 {
+        self.buy_days + self.sell_days
+    }
+}
+
+pub fn daily_move_cap(history: &[(i64, f64)]) -> f64 {
+    let mut moves: Vec<f64> = history
+        .windows(2)
+        .filter(|w| w[1].0 - w[0].0 == 1 && w[0].1 > 0.0)
+        .map(|w| (w[1].1 / w[0].1 - 1.0).abs())
+        .collect();
+    if moves.is_empty() {
+        return MAX_DAILY_CAP;
+    }
+    moves.sort_by(|a, b| a.total_cmp(b));
+    quantile(&moves, 0.9).clamp(MIN_DAILY_CAP, MAX_DAILY_CAP)
+}
+
+// Whole updates needed to move from `from` to `to` at `cap` per update
+fn updates_to(from: f64, to: f64, cap: f64) -> u32 {
+    if from <= 0.0 || to <= 0.0 {
+        return 0;
+    }
+    let distance = (to / from).ln().abs();
+    (distance / (1.0 + cap).ln()).ceil() as u32
+}
+
+pub fn reach(stats: &ItemStats, buy: f64, sell: f64) -> Reach {
+    let cap = daily_move_cap(&stats.history);
+    Reach {
+        daily_cap: cap,
+        buy_days: if buy < stats.current_price { updates_to(stats.current_price, buy, cap) } else { 0 },
+        sell_days: updates_to(buy, sell, cap),
+    }
+}
+
+// Per-item loss if the flip has to be dumped at a bad (5th percentile) price.
+// The lower of the full-history and recent P5 is used so a crashing item's
+// new, lower range counts.
+pub fn worst_case_loss(stats: &ItemStats, buy: f64, tax: &Tax) -> f64 {
+    let p5 = if stats.recent_prices.is_empty() {
+        quantile(&stats.prices, 0.05)
+    } else {
+        quantile(&stats.prices, 0.05).min(quantile(&stats.recent_prices, 0.05))
+    };
+    (buy - after_tax(&stats.name, p5, tax)).max(0.0)
+}
+
+// Lowest whole-gp sell price that gets `buy` back after tax
+pub fn break_even_sell(name: &str, buy: f64, tax: &Tax) -> f64 {
+    let buy = buy.ceil();
+    if tax.is_exempt(name) {
+        return buy;
+    }
+
+    // Within one fee band what's left after tax never falls as the price
+    // rises, so each band is searched on its own and the lowest band with an
+    // answer wins. Only a tier boundary can make a higher price net less.
+    let fee = tax.fee_for(name);
+    let net = |sell: f64| after_tax(name, sell, tax);
+    let mut starts = vec![buy];
+    if let FeeModel::Tiered { bands, .. } = fee {
+        starts.extend(bands.iter().map(|b| b.from.ceil()).filter(|&from| from > buy));
+        starts.sort_by(f64::total_cmp);
+        starts.dedup();
+    }
+
+    for (i, &start) in starts.iter().enumerate() {
+        let end = match starts.get(i + 1) {
+            Some(next) => next - 1.0,
+            // Past the last boundary this always nets at least `buy`
+            None => (buy / (1.0 - fee.rate_at(start).min(0.99))).ceil().min(buy + fee.cap().unwrap_or(f64::INFINITY)).max(start),
+        };
+        if net(end) < buy {
+            continue;
+        }
+        let (mut low, mut high) = (start, end);
+        while low < high {
+            let mid = ((low + high) / 2.0).floor();
+            if net(mid) >= buy {
+                high = mid;
+            } else {
+                low = mid + 1.0;
+            }
+        }
+        return low;
+    }
+    buy
+}
+
+// Ordering of tiers from worst to best, for comparing recommendations between scans
+pub fn tier_rank(tier: &str) -> i32 {
+    match tier {
+        "CRASH" => 0,
+        "NORMAL" => 1,
+        "GREEN" => 2,
+        "GOLD" => 3,
+        "DIAMOND" => 4,
+        _ => 1,
+    }
+}
+
+// Below either of these a tier rests on too little data to take at face value
+pub const MIN_CONFIDENT_POINTS: usize = 20;
+pub const MIN_CONFIDENT_COVERAGE: f64 = 50.0;  // % of stats::COVERAGE_DAYS with a snapshot
+
+// One tier down for a rating built on sparse data, with the reason. NORMAL and
+// CRASH stay put - there's nothing to overstate.
+pub fn confidence_demotion(tier: &str, data_points: usize, coverage_pct: f64) -> Option<(String, String)> {
+    let lower = match tier {
+        "DIAMOND" => "GOLD",
+        "GOLD" => "GREEN",
+        "GREEN" => "NORMAL",
+        _ => return None,
+    };
+
+    let mut reasons = Vec::new();
+    if data_points < MIN_CONFIDENT_POINTS {
+        reasons.push(format!("only {} data points", data_points));
+    }
+    if coverage_pct < MIN_CONFIDENT_COVERAGE {
+        reasons.push(format!("prices on {:.0}% of recent days", coverage_pct));
+    }
+    if reasons.is_empty() {
+        return None;
+    }
+    Some((lower.to_string(), format!("Lowered from {}: {}", tier, reasons.join(" and "))))
+}
+
+// Multipliers on the score components. All 1.0 reproduces the original scoring.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoreWeights {
+    pub roi: f64,
+    pub volume: f64,
+    pub profit: f64,
+    pub volatility: f64,
+    pub reliability: f64,
+    pub trend: f64,
+    pub risk_penalty: f64,  // Scales the spread, outlier and crash penalties
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        ScoreWeights {
+            roi: 1.0,
+            volume: 1.0,
+            profit: 1.0,
+            volatility: 1.0,
+            reliability: 1.0,
+            trend: 1.0,
+            risk_penalty: 1.0,
+        }
+    }
+}
+
+// Offer prices rounded to steps a person would actually type: about three
+// significant figures (1 gp under 10k, 1k for millions) when Fine, one fewer
+// when Coarse. Buys round up and sells round down, so rounding never makes a
+// flip look better than the raw quantiles.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum PriceRounding {
+    Off,
+    #[default]
+    Fine,
+    Coarse,
+}
+
+impl PriceRounding {
+    pub const ALL: [PriceRounding; 3] = [PriceRounding::Off, PriceRounding::Fine, PriceRounding::Coarse];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PriceRounding::Off => "Exact",
+            PriceRounding::Fine => "Fine",
+            PriceRounding::Coarse => "Coarse",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            PriceRounding::Off => "Offer at the exact quantile prices, e.g. 1,283,467",
+            PriceRounding::Fine => "Nearest 1k for millions, 100 for hundreds of thousands... e.g. 1,284,000",
+            PriceRounding::Coarse => "Nearest 10k for millions, 1k for hundreds of thousands... e.g. 1,290,000",
+        }
+    }
+
+    fn step(self, price: i64) -> i64 {
+        let digits = price.max(1).ilog10() as i32;
+        let extra = match self {
+            PriceRounding::Off => return 1,
+            PriceRounding::Fine => 3,
+            PriceRounding::Coarse => 2,
+        };
+        10_i64.pow((digits - extra).max(0) as u32)
+    }
+
+    pub fn buy(self, price: Gp) -> Gp {
+        let step = self.step(price.0);
+        Gp((price.0 + step - 1).div_euclid(step) * step)
+    }
+
+    pub fn sell(self, price: Gp) -> Gp {
+        let step = self.step(price.0);
+        Gp(price.0.div_euclid(step) * step)
+    }
+}
+
+// How long the user plans to hold a flip. Shorter horizons look at less
+// history with tighter targets; longer ones lean more on the trend. Records
+// are daily, one per item per day, and a scan only loads the last 90 days, so
+// Intraday means the shortest daily window and Long at most those 90 days.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Horizon {
+    Intraday,
+    Short,
+    #[default]
+    Medium,  // The original analysis
+    Long,
+}
+
+impl Horizon {
+    pub const ALL: [Horizon; 4] = [Horizon::Intraday, Horizon::Short, Horizon::Medium, Horizon::Long];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Horizon::Intraday => "Intraday",
+            Horizon::Short => "Short",
+            Horizon::Medium => "Medium",
+            Horizon::Long => "Long",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Horizon::Intraday => "Hours: last 7 daily records (there's no intraday data), Q30/Q70 targets, trend mostly ignored",
+            Horizon::Short => "A few days: last 14 records, Q20/Q80 targets",
+            Horizon::Medium => "About a week or two: crash-aware window, Q10/Q90 targets",
+            Horizon::Long => "Weeks: every record a scan loads (the last 90 days), Q5/Q95 targets, trend weighted double",
+        }
+    }
+
+    // Most recent records to analyse; None keeps the crash-aware window
+    fn window(self) -> Option<usize> {
+        match self {
+            Horizon::Intraday => Some(7),
+            Horizon::Short => Some(14),
+            Horizon::Medium => None,
+            Horizon::Long => Some(usize::MAX),  // Bounded by the loader's 90-day window
+        }
+    }
+
+    // Buy and sell percentiles; None keeps the crash-aware Q10/Q90 (Q15/Q85)
+    fn targets(self) -> Option<(f64, f64)> {
+        match self {
+            Horizon::Intraday => Some((0.30, 0.70)),
+            Horizon::Short => Some((0.20, 0.80)),
+            Horizon::Medium => None,
+            Horizon::Long => Some((0.05, 0.95)),
+        }
+    }
+
+    fn trend_weight(self) -> f64 {
+        match self {
+            Horizon::Intraday => 0.25,
+            Horizon::Short => 0.75,
+            Horizon::Medium => 1.0,
+            Horizon::Long => 2.0,
+        }
+    }
+}
+
+pub fn analyze(stats: &ItemStats, tax: &Tax, weights: &ScoreWeights, horizon: Horizon, rounding: PriceRounding) -> FlipResult {
     if stats.prices.is_empty() {
         return FlipResult::empty();
     }
@@ -47,7 +539,10 @@ pub fn analyze(stats: &ItemStats, tax: f64) -> FlipResult {
     let use_recent = (price_crashed || price_spiked) && stats.recent_prices.len() >= 10;
     let use_filtered = !use_recent && !stats.filtered_prices.is_empty() && stats.outliers_removed > 0;
     
-    let analysis_prices = if use_recent {
+    let analysis_prices = if let Some(n) = horizon.window() {
+        let skip = stats.history.len().saturating_sub(n);
+        stats.history[skip..].iter().map(|(_, p)| *p).collect()
+    } else if use_recent {
         stats.recent_prices.clone()
     } else if use_filtered {
         stats.filtered_prices.clone()
@@ -56,7 +551,7 @@ pub fn analyze(stats: &ItemStats, tax: f64) -> FlipResult {
     };
     
     let mut prices = analysis_prices;
-    prices.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    prices.sort_by(|a, b| a.total_cmp(b));
 
     let q05 = quantile(&prices, 0.05);
     let q10 = quantile(&prices, 0.10);
@@ -69,22 +564,24 @@ pub fn analyze(stats: &ItemStats, tax: f64) -> FlipResult {
     let q95 = quantile(&prices, 0.95);
 
     // Use more conservative percentiles for crashed/spiked items
-    let (buy, sell) = if use_recent {
+    let (buy, sell) = if let Some((lo, hi)) = horizon.targets() {
+        (Gp::from_f64(quantile(&prices, lo)), Gp::from_f64(quantile(&prices, hi)))
+    } else if use_recent {
         // For crashed items, use tighter range (Q15-Q85) to avoid old extremes
-        (q15.round() as i32, q85.round() as i32)
+        (Gp::from_f64(q15), Gp::from_f64(q85))
     } else {
         // Normal items use standard Q10-Q90
-        (q10.round() as i32, q90.round() as i32)
+        (Gp::from_f64(q10), Gp::from_f64(q90))
     };
+    let (buy, sell) = (rounding.buy(buy), rounding.sell(sell));
 
     let price_range = q90 - q10;
     let volatility = if q50 > 0.0 { (price_range / q50) * 100.0 } else { 0.0 };
 
-    let gross = (sell - buy) as f64;
-    let tax_loss = sell as f64 * tax;
-    let net = gross - tax_loss;
+    let profit = net_profit(&stats.name, buy, sell, tax);
+    let net = profit.as_f64();
 
-    let roi = if buy > 0 { (net / buy as f64) * 100.0 } else { 0.0 };
+    let roi = if buy > Gp::ZERO { (net / buy.as_f64()) * 100.0 } else { 0.0 };
 
     // Keep tier calculation normal - don't force CRASH for volatile items
     let tier = if net < 0.0 {
@@ -102,7 +599,7 @@ pub fn analyze(stats: &ItemStats, tax: f64) -> FlipResult {
     let roi_score = (roi * 2.0).max(i32::MIN as f64).min(i32::MAX as f64) as i32;
     
     let volume_score = if stats.avg_volume > 0.0 {
-        (stats.avg_volume.log10() * 15.0).max(0.0).min(100.0) as i32
+        (stats.avg_volume.log10() * 15.0).clamp(0.0, 100.0) as i32
     } else {
         0
     };
@@ -113,12 +610,14 @@ pub fn analyze(stats: &ItemStats, tax: f64) -> FlipResult {
         ((net / 100_000.0).max(-50.0)) as i32
     };
     
-    // Volatility bonus - items with price swings are better for flipping
-    let volatility_score = (volatility.min(100.0) / 2.0) as i32;
+    // Volatility bonus - items with price swings are better for flipping, as
+    // long as the swings repeat. Chaotic ones count against the item instead.
+    let regime_factor = Regime::of(&stats.history).map_or(1.0, Regime::volatility_factor);
+    let volatility_score = (volatility.min(100.0) / 2.0 * regime_factor) as i32;
     
     let reliability_score = ((stats.data_points as f64 / 5.0).min(10.0)) as i32;
     
-    let spread_penalty = if price_range < (buy as f64 * 0.02) {
+    let spread_penalty = if price_range < (buy.as_f64() * 0.02) {
         -20
     } else {
         0
@@ -151,15 +650,17 @@ pub fn analyze(stats: &ItemStats, tax: f64) -> FlipResult {
         0
     };
     
-    let score = roi_score
-        .saturating_add(volume_score)
-        .saturating_add(profit_score)
-        .saturating_add(volatility_score)
-        .saturating_add(reliability_score)
-        .saturating_add(spread_penalty)
-        .saturating_add(trend_score)
-        .saturating_add(outlier_penalty)
-        .saturating_add(crash_penalty);
+    let weighted = |score: i32, weight: f64| (score as f64 * weight).round() as i32;
+
+    let score = weighted(roi_score, weights.roi)
+        .saturating_add(weighted(volume_score, weights.volume))
+        .saturating_add(weighted(profit_score, weights.profit))
+        .saturating_add(weighted(volatility_score, weights.volatility))
+        .saturating_add(weighted(reliability_score, weights.reliability))
+        .saturating_add(weighted(spread_penalty, weights.risk_penalty))
+        .saturating_add(weighted(trend_score, weights.trend * horizon.trend_weight()))
+        .saturating_add(weighted(outlier_penalty, weights.risk_penalty))
+        .saturating_add(weighted(crash_penalty, weights.risk_penalty));
 
     let mut analysis_notes = String::new();
     
@@ -183,15 +684,14 @@ pub fn analyze(stats: &ItemStats, tax: f64) -> FlipResult {
         tier,
         buy,
         sell,
-        qty: 1,
-        profit: net.round() as i32,
+        profit,
         roi,
         avg_volume: stats.avg_volume,
         notes: format!(
             "{}Vol:{:.0}% | Spread:{}gp | Q5-Q95:{:.0}-{:.0} | Data:{}pts",
             analysis_notes,
             volatility,
-            (sell - buy),
+            (sell - buy).0,
             q05.round(),
             q95.round(),
             stats.data_points
@@ -199,10 +699,178 @@ pub fn analyze(stats: &ItemStats, tax: f64) -> FlipResult {
     }
 }
 
-fn quantile(v: &Vec<f64>, q: f64) -> f64 {
+// How hard a flip is to pull off, for steering newer players
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Difficulty {
+    Easy,
+    Moderate,
+    Hard,
+    Expert,
+}
+
+impl Difficulty {
+    pub fn label(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Moderate => "Moderate",
+            Difficulty::Hard => "Hard",
+            Difficulty::Expert => "Expert",
+        }
+    }
+}
+
+// Scores volume, buy limit, volatility and whether the spread is still there
+// recently. Returns the rating with a plain-language reason per factor.
+pub fn difficulty(stats: &ItemStats, flip: &FlipResult) -> (Difficulty, Vec<String>) {
+    let mut points = 0;
+    let mut reasons = Vec::new();
+
+    let (vol_points, vol_note) = match stats.avg_volume {
+        v if v >= 100_000.0 => (0, "very liquid - offers fill fast"),
+        v if v >= 10_000.0 => (1, "decent volume"),
+        v if v >= 1_000.0 => (2, "thin volume - offers may take hours to fill"),
+        _ => (3, "very thin volume - offers may take days to fill"),
+    };
+    points += vol_points;
+    reasons.push(format!("{:.0}/day: {}", stats.avg_volume, vol_note));
+
+    if stats.ge_limit > 0 && stats.ge_limit <= 10 {
+        points += 2;
+        reasons.push(format!("buy limit of {} per 4h caps how much you can flip", stats.ge_limit));
+    } else if stats.ge_limit > 0 && stats.ge_limit <= 100 {
+        points += 1;
+        reasons.push(format!("modest buy limit ({} per 4h)", stats.ge_limit));
+    }
+
+    let volatility = if stats.q50 > 0.0 { (stats.q90 - stats.q10) / stats.q50 * 100.0 } else { 0.0 };
+    if volatility > 50.0 {
+        points += 2;
+        reasons.push(format!("price swings {:.0}% - easy to get caught holding", volatility));
+    } else if volatility > 20.0 {
+        points += 1;
+        reasons.push(format!("price swings {:.0}%", volatility));
+    }
+
+    // Is the recommended spread still showing up in the recent records?
+    let spread = (flip.sell - flip.buy).as_f64();
+    if spread > 0.0 && !stats.recent_prices.is_empty() {
+        let recent_spread = quantile(&stats.recent_prices, 0.90) - quantile(&stats.recent_prices, 0.10);
+        let persistence = recent_spread / spread;
+        if persistence < 0.5 {
+            points += 2;
+            reasons.push(format!("spread has narrowed to {:.0}% of its usual width lately", persistence * 100.0));
+        } else if persistence < 0.8 {
+            points += 1;
+            reasons.push("spread is a little narrower than usual lately".to_string());
+        }
+    }
+
+    if flip.roi < 3.0 {
+        points += 1;
+        reasons.push(format!("thin margin ({:.1}% after tax) leaves no room for error", flip.roi));
+    }
+
+    let level = match points {
+        0..=1 => Difficulty::Easy,
+        2..=3 => Difficulty::Moderate,
+        4..=5 => Difficulty::Hard,
+        _ => Difficulty::Expert,
+    };
+    (level, reasons)
+}
+
+fn quantile(v: &[f64], q: f64) -> f64 {
     if v.is_empty() {
         return 0.0;
     }
     let idx = ((v.len() - 1) as f64 * q).round() as usize;
     v[idx]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::ItemSnapshot;
+    use crate::stats;
+
+    // Zero prices make ROI a 0/0; scoring and the sorts inside must still finish
+    #[test]
+    fn analyze_survives_zero_prices() {
+        let snaps: Vec<ItemSnapshot> = (0..30)
+            .map(|day| ItemSnapshot {
+                item_id: 1,
+                name: "Broken thing".into(),
+                ge_limit: 100,
+                record_date: format!("2026-01-{:02}", day + 1),
+                price: Gp(if day % 3 == 0 { 0 } else { 1 }),
+                volume: if day % 2 == 0 { 0 } else { 10 },
+                manual: false,
+            })
+            .collect();
+        let stats = stats::build_stats(&snaps, 1);
+        let mut flips: Vec<FlipResult> = [Horizon::Intraday, Horizon::Short, Horizon::Medium, Horizon::Long]
+            .into_iter()
+            .map(|horizon| analyze(&stats[0], &Tax::rs3(), &Default::default(), horizon, Default::default()))
+            .collect();
+        flips.push(FlipResult { roi: f64::NAN, ..flips[0].clone() });
+        flips.sort_by(|a, b| b.roi.total_cmp(&a.roi));
+        assert!(flips[0].roi.is_nan());  // total_cmp puts a positive NaN above every number
+    }
+
+    #[test]
+    fn tax_is_capped_above_250m() {
+        let tax = Tax::rs3();
+        assert_eq!(sale_tax("Abyssal whip", Gp(300_000_000), &tax), Gp(5_000_000));
+        assert_eq!(sale_tax("Abyssal whip", Gp(2_000_000_000), &tax), Gp(5_000_000));
+        assert_eq!(sale_tax("Abyssal whip", Gp(250_000_000), &tax), Gp(5_000_000));
+        assert_eq!(sale_tax("Abyssal whip", Gp(249_999_950), &tax), Gp(4_999_999));
+    }
+
+    #[test]
+    fn bonds_are_tax_free() {
+        assert_eq!(sale_tax("Bond", Gp(300_000_000), &Tax::rs3()), Gp::ZERO);
+        assert_eq!(sale_tax("Old school bond", Gp(10_000_000), &Tax::osrs()), Gp::ZERO);
+        assert_eq!(break_even_sell("Bond", 300_000_000.0, &Tax::rs3()), 300_000_000.0);
+    }
+
+    #[test]
+    fn cheap_items_pay_nothing() {
+        let tax = Tax::rs3();
+        assert_eq!(sale_tax("Feather", Gp(1), &tax), Gp::ZERO);
+        assert_eq!(sale_tax("Feather", Gp(49), &tax), Gp::ZERO);
+        assert_eq!(sale_tax("Feather", Gp(50), &tax), Gp(1));
+    }
+
+    #[test]
+    fn profit_totals_are_exact() {
+        let tax = Tax::rs3();
+        // 2% of 1.1M is 22,000
+        assert_eq!(net_profit("Abyssal whip", Gp(1_000_000), Gp(1_100_000), &tax) * 25_000, Gp(1_950_000_000));
+        // 2% of 1,337 floors to 26
+        assert_eq!(net_profit("Nature rune", Gp(1_234), Gp(1_337), &tax) * 1_000_000, Gp(77_000_000));
+        // Capped at 5M
+        assert_eq!(net_profit("Christmas cracker", Gp(400_000_000), Gp(420_000_000), &tax) * 2, Gp(30_000_000));
+        // Exempt
+        assert_eq!(net_profit("Bond", Gp(100_000_000), Gp(110_000_000), &tax) * 3, Gp(30_000_000));
+    }
+
+    #[test]
+    fn break_even_at_the_cap_boundary() {
+        let tax = Tax::rs3();
+        let net = |sell: f64| after_tax("Abyssal whip", sell, &tax);
+
+        // Just under the cap the floored tax is a gp short of 5M, so 1gp below
+        // 250M already nets 245M
+        assert_eq!(break_even_sell("Abyssal whip", 245_000_000.0, &tax), 249_999_999.0);
+        assert_eq!(break_even_sell("Abyssal whip", 245_000_001.0, &tax), 250_000_001.0);
+        // Past the cap the tax is a flat 5M
+        assert_eq!(break_even_sell("Abyssal whip", 300_000_000.0, &tax), 305_000_000.0);
+        assert_eq!(break_even_sell("Abyssal whip", 1_000.0, &tax), 1_020.0);
+
+        for buy in [244_999_999.0, 245_000_001.0, 246_000_000.0] {
+            let sell = break_even_sell("Abyssal whip", buy, &tax);
+            assert!(net(sell) >= buy, "{} doesn't get {} back", sell, buy);
+            assert!(net(sell - 1.0) < buy, "{} isn't the lowest for {}", sell, buy);
+        }
+    }
+}
@@ -0,0 +1,47 @@
+// Value floors: the least an item is worth even if nobody buys it back on the GE.
+//
+// High-alching turns any item into coins for the cost of a nature rune (fire
+// staff assumed), and disassembling it yields Invention components. A buy
+// offer under the better of the two can always be unwound without a loss.
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+
+const COMPONENTS_PATH: &str = "components.json";
+pub const NATURE_RUNE: &str = "Nature rune";
+
+// Per-item gp value of what disassembly gives back, keyed by item name.
+// Components aren't traded on the GE, so these are kept by hand.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ComponentValues {
+    pub items: HashMap<String, f64>,
+}
+
+impl ComponentValues {
+    pub fn load() -> Self {
+        match std::fs::read_to_string(COMPONENTS_PATH) {
+            Ok(data) => serde_json::from_str(&data).unwrap_or_default(),
+            Err(_) => ComponentValues::default(),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<f64> {
+        self.items.get(name).copied().filter(|v| *v > 0.0)
+    }
+}
+
+// max(high alch - rune cost, component value), or None when neither is known
+pub fn value_floor(high_alch: Option<f64>, rune_cost: f64, component: Option<f64>) -> Option<f64> {
+    let alch = high_alch.filter(|v| *v > 0.0).map(|v| v - rune_cost);
+    match (alch, component) {
+        (Some(a), Some(c)) => Some(a.max(c)),
+        (a, c) => a.or(c),
+    }
+    .filter(|v| *v > 0.0)
+}
+
+// How far the floor sits above the buy price, as a percentage of it.
+// Positive means the buy is under the floor and the flip is near-riskless.
+pub fn floor_margin_pct(floor: f64, buy: f64) -> f64 {
+    if buy <= 0.0 { return 0.0; }
+    (floor - buy) / buy * 100.0
+}
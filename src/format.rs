@@ -0,0 +1,49 @@
+// Number formatting shared by the UI and text exports
+use crate::config::Currency;
+use crate::model::Gp;
+use std::fmt;
+
+pub fn format_gp(value: f64) -> String {
+    if value >= 1_000_000_000.0 {
+        format!("{:.2}B", value / 1_000_000_000.0)
+    } else if value >= 1_000_000.0 {
+        format!("{:.2}M", value / 1_000_000.0)
+    } else if value >= 1_000.0 {
+        format!("{:.1}K", value / 1_000.0)
+    } else {
+        format!("{:.0}", value)
+    }
+}
+
+// Same short form as format_gp, signed
+impl fmt::Display for Gp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        write!(f, "{}{}", sign, format_gp(self.0.unsigned_abs() as f64))
+    }
+}
+
+// Shows gp amounts in the currency picked in settings. Falls back to gp
+// until a scan has supplied a bond price.
+#[derive(Debug, Clone, Copy)]
+pub struct Money {
+    pub currency: Currency,
+    pub bond_price: Option<f64>,
+    pub usd_per_bond: f64,
+}
+
+impl Money {
+    pub fn format(&self, gp: f64) -> String {
+        let sign = if gp < 0.0 { "-" } else { "" };
+        match (self.currency, self.bond_price) {
+            (Currency::Bonds, Some(bond)) if bond > 0.0 => format!("{}{:.2} bonds", sign, gp.abs() / bond),
+            (Currency::Usd, Some(bond)) if bond > 0.0 => format!("{}${:.2}", sign, gp.abs() / bond * self.usd_per_bond),
+            _ => format!("{}{}", sign, format_gp(gp.abs())),
+        }
+    }
+}
+
+// Whole-gp price with no separators or suffixes, ready to paste into a GE offer
+pub fn offer_price(value: f64) -> String {
+    format!("{:.0}", value.round())
+}
@@ -0,0 +1,72 @@
+// Which game's Grand Exchange is being analyzed. Everything past loading -
+// stats, flips, the UI - is shared; what differs is where the prices live,
+// the tax rules, and which wiki item links point at.
+use crate::flips::Tax;
+use crate::loader;
+use serde::{Serialize, Deserialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Game {
+    #[default]
+    Rs3,
+    Osrs,
+}
+
+impl Game {
+    pub const ALL: [Game; 2] = [Game::Rs3, Game::Osrs];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Game::Rs3 => "RuneScape 3",
+            Game::Osrs => "Old School",
+        }
+    }
+
+    pub fn short_label(self) -> &'static str {
+        match self {
+            Game::Rs3 => "RS3",
+            Game::Osrs => "OSRS",
+        }
+    }
+
+    // Each game keeps its own database so histories never mix
+    pub fn db_path(self) -> &'static str {
+        match self {
+            Game::Rs3 => loader::DB_PATH,
+            Game::Osrs => "osrs_market.db",
+        }
+    }
+
+    // The GE's own tax; fees.json can swap in another schedule
+    pub fn tax(self) -> Tax {
+        match self {
+            Game::Rs3 => Tax::rs3(),
+            Game::Osrs => Tax::osrs(),
+        }
+    }
+
+    // Priced in bonds when profits are shown in bonds
+    pub fn bond_name(self) -> &'static str {
+        match self {
+            Game::Rs3 => "Bond",
+            Game::Osrs => "Old school bond",
+        }
+    }
+
+    pub fn wiki_url(self, name: &str) -> String {
+        let host = match self {
+            Game::Rs3 => "runescape.wiki",
+            Game::Osrs => "oldschool.runescape.wiki",
+        };
+        format!("https://{}/w/{}", host, name.replace(' ', "_"))
+    }
+
+    // Per-game copy of a saved list (favorites, watchlist, ...). RS3 keeps the
+    // original file names so existing lists carry over.
+    pub fn file(self, name: &str) -> String {
+        match self {
+            Game::Rs3 => name.to_string(),
+            Game::Osrs => format!("osrs_{}", name),
+        }
+    }
+}
@@ -0,0 +1,137 @@
+// Turns pasted Grand Exchange history into journal trades. The text can come
+// from OCR of the in-game history screen or be typed by hand, one offer per
+// line, e.g.
+//
+//   Bought 1,000 x Blood rune for 351K
+//   Sold 1,000 x Blood rune for 412,000 gp
+//   Sold 25 Prayer potion (4) @ 10.5k each
+//
+// "for" is the offer's total (as the GE history shows it); "@", "at" or a
+// trailing "each"/"ea" mark a per-item price. Buys and sells of the same item
+// are then paired up into completed flips.
+use crate::journal::Trade;
+use crate::model::Gp;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+#[derive(Debug, Clone)]
+pub struct GeLine {
+    pub side: Side,
+    pub item: String,
+    pub qty: i64,
+    pub price_each: f64,
+}
+
+pub struct Import {
+    pub trades: Vec<Trade>,
+    pub open: Vec<(String, i64)>,  // Bought but not (yet) sold, or sold with no matching buy (negative)
+    pub skipped: Vec<String>,  // Lines that didn't parse
+}
+
+// "1,234", "351K", "1.2m", "5b", "12,345gp"
+pub fn parse_gp(text: &str) -> Option<f64> {
+    let text = text.trim().trim_end_matches("gp").trim_end_matches("coins").trim().replace(',', "");
+    let (number, scale) = match text.chars().last()?.to_ascii_lowercase() {
+        'k' => (&text[..text.len() - 1], 1e3),
+        'm' => (&text[..text.len() - 1], 1e6),
+        'b' => (&text[..text.len() - 1], 1e9),
+        _ => (text.as_str(), 1.0),
+    };
+    let value: f64 = number.trim().parse().ok()?;
+    (value >= 0.0).then_some(value * scale)
+}
+
+pub fn parse_line(line: &str) -> Option<GeLine> {
+    let line = line.trim();
+    let (first, rest) = line.split_once(char::is_whitespace)?;
+    let side = match first.to_ascii_lowercase().as_str() {
+        "bought" | "buy" | "b" => Side::Buy,
+        "sold" | "sell" | "s" => Side::Sell,
+        _ => return None,
+    };
+
+    // Quantity, with or without an "x" after it
+    let rest = rest.trim_start();
+    let (qty_text, rest) = rest.split_once(char::is_whitespace)?;
+    let qty: i64 = qty_text.trim_end_matches(['x', 'X']).replace(',', "").parse().ok()?;
+    let rest = rest.trim_start();
+    let rest = rest.strip_prefix("x ").or_else(|| rest.strip_prefix("X ")).unwrap_or(rest);
+
+    // Item name runs up to the price marker
+    let lower = rest.to_ascii_lowercase();
+    let (item, price_text, per_item) = [(" for ", false), (" @ ", true), (" at ", true)]
+        .iter()
+        .find_map(|(marker, per_item)| {
+            let at = lower.rfind(marker)?;
+            Some((&rest[..at], &rest[at + marker.len()..], *per_item))
+        })?;
+
+    let price_lower = price_text.trim().to_ascii_lowercase();
+    let (price_text, each) = match price_lower.strip_suffix("each").or_else(|| price_lower.strip_suffix("ea")) {
+        Some(p) => (p.to_string(), true),
+        None => (price_lower, false),
+    };
+    let price = parse_gp(&price_text)?;
+    if qty <= 0 || item.trim().is_empty() {
+        return None;
+    }
+
+    Some(GeLine {
+        side,
+        item: item.trim().to_string(),
+        qty,
+        price_each: if per_item || each { price } else { price / qty as f64 },
+    })
+}
+
+// Buys and sells of each item are averaged and matched up; whatever doesn't
+// match is reported as still open
+pub fn import(text: &str, timestamp: u64) -> Import {
+    let mut skipped = Vec::new();
+    // Keyed by lowercase name: (display name, bought qty, bought gp, sold qty, sold gp)
+    let mut totals: HashMap<String, (String, i64, f64, i64, f64)> = HashMap::new();
+    let mut order = Vec::new();
+
+    for line in text.lines().filter(|l| !l.trim().is_empty()) {
+        let Some(ge) = parse_line(line) else {
+            skipped.push(line.trim().to_string());
+            continue;
+        };
+        let key = ge.item.to_lowercase();
+        let entry = totals.entry(key.clone()).or_insert_with(|| {
+            order.push(key);
+            (ge.item.clone(), 0, 0.0, 0, 0.0)
+        });
+        let gp = ge.price_each * ge.qty as f64;
+        match ge.side {
+            Side::Buy => { entry.1 += ge.qty; entry.2 += gp; }
+            Side::Sell => { entry.3 += ge.qty; entry.4 += gp; }
+        }
+    }
+
+    let mut trades = Vec::new();
+    let mut open = Vec::new();
+    for key in order {
+        let (name, bought, bought_gp, sold, sold_gp) = &totals[&key];
+        let matched = (*bought).min(*sold);
+        if matched > 0 {
+            trades.push(Trade {
+                item: name.clone(),
+                qty: matched.min(i32::MAX as i64) as i32,
+                buy: Gp::from_f64(bought_gp / *bought as f64),
+                sell: Gp::from_f64(sold_gp / *sold as f64),
+                timestamp,
+            });
+        }
+        if bought != sold {
+            open.push((name.clone(), bought - sold));
+        }
+    }
+
+    Import { trades, open, skipped }
+}
@@ -0,0 +1,86 @@
+// Plain-language explanations of every metric, in game terms. Shared by the
+// table tooltips, beginner help and the exported reports.
+
+pub struct Term {
+    pub key: &'static str,
+    pub name: &'static str,
+    pub text: &'static str,
+}
+
+pub const TERMS: &[Term] = &[
+    // Columns
+    Term { key: "score", name: "Score", text: "Overall flip rating - the sum of the score components below. Higher is better; negative means avoid." },
+    Term { key: "score_pct", name: "Score percentile", text: "Where the score ranks in this scan, 0-100: 90 beats 90% of the items scanned. Raw scores have no fixed range, so this is the one to compare between scans; the raw score is shown beside it." },
+    Term { key: "tier", name: "Tier", text: "Score bucket: Diamond > Gold > Good > Normal. Crash means the flip loses money after tax." },
+    Term { key: "trend", name: "Trend", text: "Slope of the price over the history window. ↑ means the item has been getting more expensive, ↓ cheaper." },
+    Term { key: "difficulty", name: "Difficulty", text: "How hard the flip is to pull off: volume, buy limit, volatility and whether the spread is holding up. Start with Easy ones." },
+    Term { key: "risk", name: "Risk", text: "Warnings from the price history: crashing, recently crashed or spiked, or with outlier prices removed." },
+    Term { key: "buy", name: "Buy Price", text: "Suggested buy offer: the Q10 price (Q15 when the market has recently moved), so offers fill on normal dips." },
+    Term { key: "sell", name: "Sell Price", text: "Suggested sell offer: the Q90 price (Q85 when the market has recently moved)." },
+    Term { key: "break_even", name: "Break-even", text: "Lowest sell price that gets the buy price back after GE tax. The gap to the sell price is your safety margin." },
+    Term { key: "qty", name: "Qty", text: "How many to buy: what the budget affords, capped by the 4-hour GE buy limit." },
+    Term { key: "volume", name: "Avg Vol/Day", text: "Average number traded per day. Higher volume means offers fill faster." },
+    Term { key: "total_cost", name: "Total Cost", text: "Buy price times quantity - the gp tied up in the offer." },
+    Term { key: "profit", name: "Profit", text: "What the whole quantity makes after GE tax (2% capped at 5M per item by default, bonds exempt; see GE Tax in the side panel) and expected slippage." },
+    Term { key: "roi", name: "ROI after tax", text: "Profit per item as a percentage of the buy price, after GE tax." },
+    Term { key: "cycle", name: "Cycle", text: "Typical days from the price touching the buy zone to it next touching the sell zone, and the GP/hr that implies." },
+
+    Term { key: "floor", name: "Floor", text: "What the item is worth without the GE: high alch minus a nature rune, or its Invention components. The margin is how far that sits above the buy price - positive (🛡) means the flip can't really lose." },
+    Term { key: "reach", name: "Price Cap", text: "The guide price only moves a few percent per daily update. ⏳ marks targets more than one update away, and the cycle estimate never assumes faster than that." },
+    Term { key: "coverage", name: "Data Coverage", text: "How many of the last 90 days have a price snapshot. A recommendation built on a handful of scattered days is much less certain than one with a full history." },
+    Term { key: "regime", name: "Volatility Regime", text: "How the price has been moving lately: stable (➖), cycling around a steady level (🔁), trending one way (↗) or swinging chaotically (🌀). Cycles earn a bigger volatility bonus in the score; chaotic swings count against it." },
+    Term { key: "capture", name: "Capture Rate", text: "How much of the buy-to-sell spread the price history actually offered: for every day the price touched the buy target, how far it climbed towards the sell target within two weeks. Realistic profit scales profit and ROI by it." },
+    Term { key: "timeframes", name: "Timeframes", text: "ROI, price change and volatility over just the last 7, 30 or 90 days. ⇅ marks items where the short and long windows disagree, like a dip in a long uptrend - often the best entries, sometimes the start of a turn." },
+    Term { key: "drawdown", name: "Max Drawdown", text: "The furthest the price has fallen below the buy price after touching it, and how many days it took to climb back - the worst a buyer at the recommended price would have sat through." },
+    Term { key: "slippage", name: "Slippage", text: "Thin markets rarely fill exactly at Q10/Q90. Low-volume items assume a buy a few percent higher and a sell a few percent lower, more so for big orders next to daily volume." },
+
+    // Statistics
+    Term { key: "q05", name: "Q5 / Q95", text: "The prices 5% and 95% of days traded below - the range the price keeps to apart from its rarest days. The long horizon uses them as buy and sell targets." },
+    Term { key: "q10", name: "Q10 / Q90", text: "The prices 10% and 90% of days traded below. Q10 is a cheap day, Q90 an expensive one." },
+    Term { key: "support", name: "Support / Resistance", text: "Prices where a lot of the item's volume has traded, read off the volume profile. Falls tend to stop at a busy level under the price (support) and rises tend to stall at one over it (resistance), so they can make better offer prices than the plain Q10 / Q90." },
+    Term { key: "volatility", name: "Volatility", text: "Width of the Q10-Q90 range as a percentage of the median price - how far the price usually swings." },
+
+    // Score components
+    Term { key: "roi_score", name: "ROI score", text: "ROI after tax, counted double." },
+    Term { key: "volume_score", name: "Volume score", text: "Rewards liquid items on a log scale, up to 100." },
+    Term { key: "profit_score", name: "Profit score", text: "Absolute profit per item, up to ±50." },
+    Term { key: "volatility_score", name: "Volatility score", text: "Bonus for price swings - more swing, more room to flip." },
+    Term { key: "reliability_score", name: "Reliability score", text: "Bonus for having more days of history, up to 10." },
+    Term { key: "spread_penalty", name: "Spread penalty", text: "-20 when the spread is under 2% of the buy price." },
+    Term { key: "trend_score", name: "Trend score", text: "Bonus for rising prices, penalty for falling ones, up to ±25." },
+    Term { key: "outlier_penalty", name: "Outlier penalty", text: "-10 when spike prices (e.g. DXP or update days) were removed, -30 when many were." },
+    Term { key: "confidence", name: "Low confidence", text: "Items with under 20 data points or prices on under half of the last 90 days drop one tier (Diamond to Gold, and so on), since a handful of prices can make any spread look great. The score itself is unchanged." },
+    Term { key: "crash_penalty", name: "Crash penalty", text: "-30 for spikes, -50 for crashes and -80 for items crashing right now." },
+];
+
+pub const SCORE_COMPONENTS: [&str; 9] = [
+    "roi_score",
+    "volume_score",
+    "profit_score",
+    "volatility_score",
+    "reliability_score",
+    "spread_penalty",
+    "trend_score",
+    "outlier_penalty",
+    "crash_penalty",
+];
+
+pub fn term(key: &str) -> Option<&'static Term> {
+    TERMS.iter().find(|t| t.key == key)
+}
+
+pub fn explain(key: &str) -> &'static str {
+    term(key).map(|t| t.text).unwrap_or("")
+}
+
+// Header tooltip for the score column: the explanation plus each component
+pub fn score_breakdown() -> String {
+    let mut text = format!("{}\n\n{}", explain("score_pct"), explain("score"));
+    for key in SCORE_COMPONENTS {
+        if let Some(t) = term(key) {
+            text.push_str(&format!("\n• {}: {}", t.name, t.text));
+        }
+    }
+    text.push_str(&format!("\n\n{}", explain("confidence")));
+    text
+}
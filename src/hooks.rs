@@ -0,0 +1,90 @@
+// Post-scan integrations: after every scan the results JSON is piped to a
+// user command and/or POSTed to a URL (dashboards, spreadsheets, bots).
+use serde::{Serialize, Deserialize};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HookConfig {
+    pub command: String,  // Run through the shell with the JSON on stdin
+    pub url: String,  // Receives the JSON as a POST body
+}
+
+impl HookConfig {
+    pub fn is_set(&self) -> bool {
+        !self.command.trim().is_empty() || !self.url.trim().is_empty()
+    }
+}
+
+#[derive(Default)]
+pub struct Hooks {
+    status: Arc<Mutex<Option<String>>>,
+}
+
+impl Hooks {
+    pub fn status(&self) -> Option<String> {
+        self.status.lock().unwrap().clone()
+    }
+
+    pub fn run(&self, config: &HookConfig, payload: String) {
+        let url = config.url.trim();
+        if !url.is_empty() {
+            let mut request = ehttp::Request::post(url, payload.clone().into_bytes());
+            request.headers.insert("Content-Type", "application/json");
+            let status = self.status.clone();
+            ehttp::fetch(request, move |response| {
+                *status.lock().unwrap() = Some(match response {
+                    Ok(r) if r.ok => format!("Posted to webhook ({})", r.status),
+                    Ok(r) => format!("Webhook answered {} {}", r.status, r.status_text),
+                    Err(e) => format!("Webhook failed: {}", e),
+                });
+            });
+        }
+
+        let command = config.command.trim();
+        if !command.is_empty() {
+            self.run_command(command.to_string(), payload);
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn run_command(&self, command: String, payload: String) {
+        use std::io::Write;
+        use std::process::{Command, Stdio};
+
+        let status = self.status.clone();
+        std::thread::spawn(move || {
+            let mut shell = if cfg!(windows) {
+                let mut c = Command::new("cmd");
+                c.arg("/C");
+                c
+            } else {
+                let mut c = Command::new("sh");
+                c.arg("-c");
+                c
+            };
+            let result = shell
+                .arg(&command)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .spawn()
+                .and_then(|mut child| {
+                    if let Some(mut stdin) = child.stdin.take() {
+                        // The command may not read its input; that's not an error
+                        let _ = stdin.write_all(payload.as_bytes());
+                    }
+                    child.wait()
+                });
+            *status.lock().unwrap() = Some(match result {
+                Ok(exit) if exit.success() => "Hook command finished".to_string(),
+                Ok(exit) => format!("Hook command exited with {}", exit),
+                Err(e) => format!("Couldn't run hook command: {}", e),
+            });
+        });
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn run_command(&self, _command: String, _payload: String) {
+        *self.status.lock().unwrap() = Some("Commands can't run in the web build".into());
+    }
+}
@@ -0,0 +1,84 @@
+// Long-term investment screener. Unlike flips this ignores the spread: it
+// looks for items sitting near their low of the last few months that still
+// trade well and whose usage (volume) is growing, on the bet that price
+// returns to normal. Scans only load stats::COVERAGE_DAYS of history, so
+// that's as far back as the lookback can reach.
+use crate::model::ItemStats;
+use crate::stats::COVERAGE_DAYS;
+
+pub struct Investment {
+    pub item_id: i32,
+    pub name: String,
+    pub price: f64,
+    pub low: f64,  // Lowest price in the lookback
+    pub median: f64,  // Typical price in the lookback - the recovery target
+    pub above_low_pct: f64,
+    pub upside_pct: f64,  // Price back at the median, before tax
+    pub volume: f64,
+    pub volume_growth_pct: f64,  // Recent volume against the long-run average
+    pub score: f64,
+}
+
+pub struct Screen {
+    pub lookback_days: i64,  // At most COVERAGE_DAYS
+    pub max_above_low_pct: f64,
+    pub min_volume: f64,
+}
+
+impl Default for Screen {
+    fn default() -> Self {
+        Screen { lookback_days: COVERAGE_DAYS, max_above_low_pct: 10.0, min_volume: 1_000.0 }
+    }
+}
+
+pub fn screen(stats: &[ItemStats], s: &Screen) -> Vec<Investment> {
+    let mut found: Vec<Investment> = stats
+        .iter()
+        .filter_map(|item| {
+            let lookback_days = s.lookback_days.min(COVERAGE_DAYS);
+            let last_day = item.history.last()?.0;
+            let mut window: Vec<f64> = item.history
+                .iter()
+                .filter(|(day, _)| *day > last_day - lookback_days)
+                .map(|(_, p)| *p)
+                .collect();
+            // Need a couple of months to call anything a low
+            if window.len() < 60 || item.avg_volume < s.min_volume || item.current_price <= 0.0 {
+                return None;
+            }
+            window.sort_by(|a, b| a.total_cmp(b));
+
+            let low = window[0];
+            let median = window[window.len() / 2];
+            let above_low_pct = (item.current_price - low) / low.max(1.0) * 100.0;
+            let upside_pct = (median - item.current_price) / item.current_price * 100.0;
+            if above_low_pct > s.max_above_low_pct || upside_pct <= 0.0 {
+                return None;
+            }
+
+            let volume_growth_pct = if item.avg_volume > 0.0 {
+                (item.recent_volume / item.avg_volume - 1.0) * 100.0
+            } else {
+                0.0
+            };
+            // Upside counts most; growing usage helps, shrinking usage hurts
+            let score = upside_pct + volume_growth_pct.clamp(-50.0, 50.0) * 0.5 - above_low_pct;
+
+            Some(Investment {
+                item_id: item.item_id,
+                name: item.name.clone(),
+                price: item.current_price,
+                low,
+                median,
+                above_low_pct,
+                upside_pct,
+                volume: item.avg_volume,
+                volume_growth_pct,
+                score,
+            })
+        })
+        .collect();
+
+    found.sort_by(|a, b| b.score.total_cmp(&a.score));
+    found
+}
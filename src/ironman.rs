@@ -0,0 +1,94 @@
+// Market data for players who can't (or don't want to) flip: ironmen buying
+// supplies from shops, or anyone just watching prices. Built from the same
+// ItemStats as the flip analysis, but with no buy/sell targets or profit.
+use crate::categories;
+use crate::model::ItemStats;
+
+const WEEKDAYS: [&str; 7] = ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"];
+
+// A weekday needs this many weeks of history before it counts as a pattern
+const MIN_WEEKS: usize = 3;
+
+pub struct PriceRow {
+    pub name: String,
+    pub price: f64,
+    pub change_1d_pct: Option<f64>,
+    pub change_7d_pct: Option<f64>,
+    pub trend: f64,
+    pub avg_volume: f64,
+    pub recent_volume: f64,
+    pub range_pct: f64,  // Where the price sits between Q10 (0) and Q90 (100)
+    pub cheap_day: Option<(&'static str, f64)>,  // Cheapest weekday and its discount vs average, %
+    pub skill: Option<&'static str>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PriceSort {
+    Range,
+    Change,
+    Volume,
+    Name,
+}
+
+pub fn overview(stats: &[ItemStats]) -> Vec<PriceRow> {
+    stats
+        .iter()
+        .filter(|s| s.data_points > 0 && s.current_price > 0.0)
+        .map(|s| {
+            let change = |before: Option<f64>| before.filter(|b| *b > 0.0).map(|b| (s.current_price / b - 1.0) * 100.0);
+            let range_pct = if s.q90 > s.q10 {
+                ((s.current_price - s.q10) / (s.q90 - s.q10) * 100.0).clamp(0.0, 100.0)
+            } else {
+                50.0
+            };
+            PriceRow {
+                name: s.name.clone(),
+                price: s.current_price,
+                change_1d_pct: change(s.price_1d_ago),
+                change_7d_pct: change(s.price_7d_ago),
+                trend: s.price_trend,
+                avg_volume: s.avg_volume,
+                recent_volume: s.recent_volume,
+                range_pct,
+                cheap_day: cheapest_weekday(&s.history),
+                skill: categories::skill_group(&s.name),
+            }
+        })
+        .collect()
+}
+
+pub fn sort(rows: &mut [PriceRow], by: PriceSort) {
+    match by {
+        PriceSort::Range => rows.sort_by(|a, b| a.range_pct.total_cmp(&b.range_pct)),
+        PriceSort::Change => rows.sort_by(|a, b| a.change_7d_pct.unwrap_or(0.0).total_cmp(&b.change_7d_pct.unwrap_or(0.0))),
+        PriceSort::Volume => rows.sort_by(|a, b| b.avg_volume.total_cmp(&a.avg_volume)),
+        PriceSort::Name => rows.sort_by(|a, b| a.name.cmp(&b.name)),
+    }
+}
+
+// The weekday whose prices run furthest below the item's average, if any
+// runs at least half a percent below it
+fn cheapest_weekday(history: &[(i64, f64)]) -> Option<(&'static str, f64)> {
+    if history.len() < MIN_WEEKS * 7 {
+        return None;
+    }
+    let mean = history.iter().map(|(_, p)| p).sum::<f64>() / history.len() as f64;
+    if mean <= 0.0 {
+        return None;
+    }
+
+    let mut sums = [(0.0, 0usize); 7];
+    for &(day, price) in history {
+        // Day 0 (1970-01-01) was a Thursday
+        let weekday = (day + 4).rem_euclid(7) as usize;
+        sums[weekday].0 += price / mean;
+        sums[weekday].1 += 1;
+    }
+
+    sums.iter()
+        .enumerate()
+        .filter(|(_, (_, n))| *n >= MIN_WEEKS)
+        .map(|(i, (sum, n))| (WEEKDAYS[i], (1.0 - sum / *n as f64) * 100.0))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .filter(|(_, discount)| *discount >= 0.5)
+}
@@ -0,0 +1,157 @@
+use crate::flips::{sale_tax, Tax};
+use crate::game::Game;
+use crate::model::Gp;
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use web_time::{SystemTime, UNIX_EPOCH};
+
+const JOURNAL_PATH: &str = "journal.json";
+const PROFILE_PREFIX: &str = "journal_";  // Other profiles live in journal_<name>.json
+
+// The default profile is the unnamed one, kept in journal.json
+pub const DEFAULT_PROFILE: &str = "";
+
+// Each game has its own copy of every profile's journal (see Game::file)
+fn path(game: Game, profile: &str) -> String {
+    if profile.is_empty() {
+        game.file(JOURNAL_PATH)
+    } else {
+        game.file(&format!("{}{}.json", PROFILE_PREFIX, profile))
+    }
+}
+
+// Every profile with a journal on disk for `game`, default first
+pub fn profiles(game: Game) -> Vec<String> {
+    let prefix = game.file(PROFILE_PREFIX);
+    let mut names: Vec<String> = std::fs::read_dir(".")
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let file = e.file_name().to_string_lossy().into_owned();
+            Some(file.strip_prefix(&prefix)?.strip_suffix(".json")?.to_string())
+        })
+        .filter(|name| !name.is_empty())
+        .collect();
+    names.sort();
+    names.insert(0, DEFAULT_PROFILE.to_string());
+    names
+}
+
+pub fn profile_label(profile: &str) -> &str {
+    if profile.is_empty() { "Main" } else { profile }
+}
+
+// One completed flip: bought `qty` at `buy`, sold at `sell`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trade {
+    pub item: String,  // As typed or imported from GE history, neither of which has an item ID
+    pub qty: i32,
+    pub buy: Gp,
+    pub sell: Gp,
+    pub timestamp: u64,  // Unix seconds when the trade was logged
+}
+
+impl Trade {
+    // Whole gp throughout, so a long journal totals exactly
+    pub fn profit(&self, tax: &Tax) -> Gp {
+        (self.sell - self.buy - sale_tax(&self.item, self.sell, tax)) * self.qty as i64
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Journal {
+    pub trades: Vec<Trade>,
+    #[serde(skip)]
+    pub profile: String,  // Whose journal this is; with `game`, decides the file it saves to
+    #[serde(skip)]
+    game: Game,
+}
+
+impl Journal {
+    pub fn load(game: Game, profile: &str) -> Self {
+        let mut journal: Journal = match std::fs::read_to_string(path(game, profile)) {
+            Ok(data) => serde_json::from_str(&data).unwrap_or_default(),
+            Err(_) => Journal::default(),
+        };
+        journal.profile = profile.to_string();
+        journal.game = game;
+        journal
+    }
+
+    pub fn save(&self) {
+        if let Ok(json) = serde_json::to_string_pretty(self) {
+            let _ = std::fs::write(path(self.game, &self.profile), json);
+        }
+    }
+
+    // Realized profit per week, keyed by week number (see `week_of`)
+    pub fn weekly(&self, tax: &Tax) -> HashMap<u64, Gp> {
+        let mut weeks = HashMap::new();
+        for t in &self.trades {
+            *weeks.entry(week_of(t.timestamp)).or_insert(Gp::ZERO) += t.profit(tax);
+        }
+        weeks
+    }
+
+    pub fn realized_since(&self, since: u64, tax: &Tax) -> Gp {
+        self.trades
+            .iter()
+            .filter(|t| t.timestamp >= since)
+            .map(|t| t.profit(tax))
+            .sum()
+    }
+}
+
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// Weeks since the epoch, starting on Mondays (1970-01-01 was a Thursday)
+pub fn week_of(timestamp: u64) -> u64 {
+    (timestamp / 86_400 + 3) / 7
+}
+
+// Start of the current game day - the GE resets at 00:00 UTC
+pub fn day_start(now: u64) -> u64 {
+    now - now % 86_400
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(item: &str, qty: i32, buy: i64, sell: i64, timestamp: u64) -> Trade {
+        Trade { item: item.to_string(), qty, buy: Gp(buy), sell: Gp(sell), timestamp }
+    }
+
+    #[test]
+    fn large_journal_totals_exactly() {
+        let tax = Tax::rs3();
+        let monday = 1_699_833_600;  // 2023-11-13
+        let journal = Journal {
+            trades: vec![
+                // 26,000 tax each: 39,434 per item
+                trade("Rune bar", 30_000_000, 1_234_567, 1_300_001, monday + 3_600),
+                // Capped at 5M tax: -3,999,997 per item
+                trade("Blue partyhat", 2_000_000, 300_000_000, 301_000_003, monday + 7_200),
+                // Untaxed at this price: 4 per item
+                trade("Feather", 1_999_999_999, 7, 11, monday + 7 * 86_400),
+            ],
+            profile: String::new(),
+            game: Game::Rs3,
+        };
+        assert_eq!(journal.trades[0].profit(&tax), Gp(1_183_020_000_000));
+        assert_eq!(journal.trades[1].profit(&tax), Gp(-7_999_994_000_000));
+        assert_eq!(journal.trades[2].profit(&tax), Gp(7_999_999_996));
+
+        let weeks = journal.weekly(&tax);
+        assert_eq!(weeks[&week_of(monday)], Gp(-6_816_974_000_000));
+        assert_eq!(weeks[&week_of(monday + 7 * 86_400)], Gp(7_999_999_996));
+        assert_eq!(journal.realized_since(0, &tax), Gp(-6_808_974_000_004));
+        assert_eq!(journal.realized_since(monday + 7 * 86_400, &tax), Gp(7_999_999_996));
+    }
+}
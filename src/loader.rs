@@ -1,17 +1,174 @@
-use rusqlite::{Connection, Result};
-use crate::model::ItemSnapshot;
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension, Result};
+use crate::model::{Annotation, DailyPrice, DataVersion, DbStats, Holding, Gp, ItemCoverage, ItemSnapshot, RawSnapshot, ScanEntry, SnapshotEdit};
+use std::collections::HashMap;
+
+pub const DB_PATH: &str = "rs3_market.db";
+pub const DB_ARCHIVE_PATH: &str = "rs3_market.db.gz";  // Compressed copy kept in the repo with Git LFS
+
+// Amounts are written as integers. Databases from before whole-gp amounts
+// have their REAL amount columns rebuilt as INTEGER by ensure_user_tables;
+// a float that turns up anyway rounds on load.
+impl FromSql for Gp {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value {
+            ValueRef::Integer(gp) => Ok(Gp(gp)),
+            ValueRef::Real(gp) => Ok(Gp::from_f64(gp)),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+impl ToSql for Gp {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        Ok(self.0.into())
+    }
+}
+
+// True once the file has the items and history tables. Opened read-only
+// because a plain open would create an empty file at a missing path.
+pub fn database_ready(db_path: &str) -> bool {
+    Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .and_then(|conn| {
+            conn.query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name IN ('items', 'history')",
+                [],
+                |row| row.get::<_, i64>(0),
+            )
+        })
+        .is_ok_and(|n| n == 2)
+}
+
+// A clone without `git lfs pull` leaves a small text pointer instead of the archive
+pub fn is_lfs_pointer(path: &str) -> bool {
+    std::fs::read(path).is_ok_and(|data| data.starts_with(b"version https://git-lfs"))
+}
+
+// Copies a database into place, decompressing it first if it's a .gz.
+// Writes to a temporary file and checks that before replacing anything, so a
+// failed copy or a file that isn't a database leaves the old one untouched.
+pub fn install_database(source: &str, db_path: &str) -> std::io::Result<u64> {
+    use std::io::{Error, ErrorKind};
+
+    let tmp = format!("{}.part", db_path);
+    let result = copy_database(source, &tmp).and_then(|bytes| {
+        if !database_ready(&tmp) {
+            return Err(Error::new(ErrorKind::InvalidData, "file is not an analyzer database (no items/history tables)"));
+        }
+        std::fs::rename(&tmp, db_path)?;
+        Ok(bytes)
+    });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+fn copy_database(source: &str, dest: &str) -> std::io::Result<u64> {
+    let mut input = std::io::BufReader::new(std::fs::File::open(source)?);
+    let mut output = std::fs::File::create(dest)?;
+    if source.ends_with(".gz") {
+        std::io::copy(&mut flate2::read::GzDecoder::new(input), &mut output)
+    } else {
+        std::io::copy(&mut input, &mut output)
+    }
+}
+
+// Writes today's prices, creating the tables on first use so a fetched game
+// can start from an empty file. Fetching twice in a day replaces the earlier
+// row, and so does a manual price added for today - the collected price is
+// the one it stood in for. Returns the number of items written.
+pub fn record_daily_prices(db_path: &str, prices: &[DailyPrice]) -> Result<usize> {
+    let mut conn = Connection::open(db_path)?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS items (
+            id INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            ge_limit INTEGER NOT NULL DEFAULT 0,
+            high_alch INTEGER NOT NULL DEFAULT 0
+         );
+         CREATE TABLE IF NOT EXISTS history (
+            item_id INTEGER NOT NULL,
+            record_date TEXT NOT NULL,
+            price INTEGER NOT NULL,
+            volume INTEGER
+         );
+         CREATE INDEX IF NOT EXISTS idx_history_item_date ON history(item_id, record_date);"
+    )?;
+    add_column_if_missing(&conn, "history", "manual", "INTEGER NOT NULL DEFAULT 0")?;
+
+    let tx = conn.transaction()?;
+    {
+        let mut item = tx.prepare(
+            "INSERT INTO items (id, name, ge_limit, high_alch) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(id) DO UPDATE SET name = excluded.name, ge_limit = excluded.ge_limit, high_alch = excluded.high_alch"
+        )?;
+        let mut clear = tx.prepare("DELETE FROM history WHERE item_id = ?1 AND record_date = date('now')")?;
+        let mut insert = tx.prepare("INSERT INTO history (item_id, record_date, price, volume) VALUES (?1, date('now'), ?2, ?3)")?;
+        for p in prices {
+            item.execute(params![p.item_id, p.name, p.ge_limit, p.high_alch])?;
+            clear.execute([p.item_id])?;
+            insert.execute(params![p.item_id, p.price.0, p.volume])?;
+        }
+    }
+    tx.commit()?;
+    Ok(prices.len())
+}
 
 pub fn load_snapshots(db_path: &str) -> Result<Vec<ItemSnapshot>> {
+    let mut snaps = Vec::new();
+    stream_snapshots(db_path, usize::MAX, |batch| {
+        snaps.extend(batch);
+        true
+    })?;
+    Ok(snaps)
+}
+
+// Cheap check for new history rows: reads only the newest row by rowid, so
+// it costs the same however large the table is. Deleting rows doesn't
+// change it; callers that prune have to drop their cache themselves.
+pub fn data_version(db_path: &str) -> Result<DataVersion> {
     let conn = Connection::open(db_path)?;
-    
-    let mut stmt = conn.prepare(
+    conn.query_row(
+        "SELECT COALESCE(MAX(rowid), 0),
+                COALESCE((SELECT record_date FROM history ORDER BY rowid DESC LIMIT 1), ''),
+                date('now')
+         FROM history",
+        [],
+        |row| Ok(DataVersion { last_rowid: row.get(0)?, last_date: row.get(1)?, today: row.get(2)? }),
+    )
+}
+
+// How many rows load_snapshots would return, for progress bars
+pub fn count_snapshots(db_path: &str) -> Result<usize> {
+    let conn = Connection::open(db_path)?;
+    conn.query_row(
+        "SELECT COUNT(*) FROM history h
+         JOIN items i ON h.item_id = i.id
+         WHERE h.record_date >= date('now', '-90 days')",
+        [],
+        |row| row.get::<_, i64>(0),
+    )
+    .map(|n| n as usize)
+}
+
+// The rows of load_snapshots handed over `batch_size` at a time, so a caller
+// can report progress and never holds more than a batch of the query result.
+// Rows are sorted by item, then date, so each item's rows arrive together.
+// Stops early when `on_batch` returns false. Returns the rows read.
+pub fn stream_snapshots(db_path: &str, batch_size: usize, mut on_batch: impl FnMut(Vec<ItemSnapshot>) -> bool) -> Result<usize> {
+    let conn = Connection::open(db_path)?;
+    // Databases no manual price has been added to don't have the column yet
+    let manual = if has_column(&conn, "history", "manual")? { "h.manual" } else { "0" };
+
+    let mut stmt = conn.prepare(&format!(
         "SELECT i.id, i.name, i.ge_limit, h.record_date,
-                h.price, h.volume
+                h.price, CASE WHEN {manual} THEN COALESCE(h.volume, 0) ELSE h.volume END, {manual}
          FROM history h
          JOIN items i ON h.item_id = i.id
          WHERE h.record_date >= date('now', '-90 days')
-         ORDER BY h.record_date"
-    )?;
+         ORDER BY h.item_id, h.record_date"
+    ))?;
 
     let rows = stmt.query_map([], |row| {
         Ok(ItemSnapshot {
@@ -19,29 +176,1130 @@ pub fn load_snapshots(db_path: &str) -> Result<Vec<ItemSnapshot>> {
             name: row.get(1)?,
             ge_limit: row.get(2)?,
             record_date: row.get(3)?,
-            price: row.get(4)?,
+            price: Gp(row.get(4)?),
             volume: row.get(5)?,
+            manual: row.get(6)?,
+        })
+    })?;
+
+    let batch_size = batch_size.max(1);
+    let mut read = 0;
+    let mut batch = Vec::new();
+    for snap in rows.filter_map(|r| r.ok()) {
+        batch.push(snap);
+        if batch.len() >= batch_size {
+            read += batch.len();
+            if !on_batch(std::mem::take(&mut batch)) {
+                return Ok(read);
+            }
+        }
+    }
+    if !batch.is_empty() {
+        read += batch.len();
+        on_batch(batch);
+    }
+    Ok(read)
+}
+
+// High-alch values from the items table; older collectors didn't record them
+pub fn load_alch_values(db_path: &str) -> Result<HashMap<i32, Gp>> {
+    let conn = Connection::open(db_path)?;
+    if !has_column(&conn, "items", "high_alch")? {
+        return Ok(HashMap::new());
+    }
+
+    let mut stmt = conn.prepare("SELECT id, high_alch FROM items WHERE high_alch > 0")?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, i32>(0)?, row.get::<_, Gp>(1)?)))?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+// (date, price, volume) for the item's last year. Manual rows have no volume
+// worth showing, so theirs comes back as None like a missing one.
+pub fn load_item_history(db_path: &str, item_id: i32) -> Result<Vec<(String, f64, Option<f64>)>> {
+    let conn = Connection::open(db_path)?;
+    let manual = if has_column(&conn, "history", "manual")? { "manual" } else { "0" };
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT record_date, price, CASE WHEN {manual} THEN NULL ELSE volume END
+         FROM history
+         WHERE item_id = ?1
+         AND record_date >= date('now', '-365 days')
+         ORDER BY record_date"
+    ))?;
+
+    let rows = stmt.query_map([item_id], |row| {
+        Ok((row.get(0)?, row.get::<_, i64>(1)? as f64, row.get::<_, Option<i64>>(2)?.map(|v| v as f64)))
+    })?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+// Every row for the item between two dates (inclusive, either end open),
+// without load_item_history's 365-day cut-off - for charting long histories
+pub fn load_item_history_range(db_path: &str, item_id: i32, from: Option<&str>, to: Option<&str>) -> Result<Vec<(String, f64, Option<f64>)>> {
+    let conn = Connection::open(db_path)?;
+    let manual = if has_column(&conn, "history", "manual")? { "manual" } else { "0" };
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT record_date, price, CASE WHEN {manual} THEN NULL ELSE volume END
+         FROM history
+         WHERE item_id = ?1
+         AND (?2 IS NULL OR record_date >= ?2)
+         AND (?3 IS NULL OR substr(record_date, 1, 10) <= ?3)
+         ORDER BY record_date"
+    ))?;
+
+    let rows = stmt.query_map(params![item_id, from, to], |row| {
+        Ok((row.get(0)?, row.get::<_, i64>(1)? as f64, row.get::<_, Option<i64>>(2)?.map(|v| v as f64)))
+    })?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+// Every history row for the item over the same window as load_item_history,
+// with the rowid needed to correct or delete it
+pub fn load_item_snapshots(db_path: &str, item_id: i32) -> Result<Vec<RawSnapshot>> {
+    let conn = Connection::open(db_path)?;
+    let manual = if has_column(&conn, "history", "manual")? { "manual" } else { "0" };
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT rowid, record_date, price, volume, {manual}
+         FROM history
+         WHERE item_id = ?1
+         AND record_date >= date('now', '-365 days')
+         ORDER BY record_date, rowid"
+    ))?;
+
+    let rows = stmt.query_map([item_id], |row| {
+        Ok(RawSnapshot {
+            rowid: row.get(0)?,
+            date: row.get(1)?,
+            price: row.get(2)?,
+            volume: row.get(3)?,
+            manual: row.get(4)?,
+        })
+    })?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+// A price seen in game that the data source hasn't caught up to yet, flagged
+// as manual. An empty `date` records it against today; anything else has to
+// be a `YYYY-MM-DD` date, or it would never sort or match alongside the rest.
+// It replaces an earlier manual price for the day, but a day that already has
+// a collected price is refused (correct that row instead): the collector's
+// history is keyed on (item_id, record_date), so there's one row per day.
+pub fn add_manual_snapshot(db_path: &str, item_id: i32, date: &str, price: Gp, volume: Option<i64>) -> Result<()> {
+    let date = date.trim();
+    if !date.is_empty() && !crate::stats::is_date(date) {
+        return Err(rusqlite::Error::ToSqlConversionFailure(
+            format!("'{}' is not a YYYY-MM-DD date", date).into(),
+        ));
+    }
+    let mut conn = Connection::open(db_path)?;
+    add_column_if_missing(&conn, "history", "manual", "INTEGER NOT NULL DEFAULT 0")?;
+
+    let tx = conn.transaction()?;
+    let date: String = tx.query_row("SELECT COALESCE(NULLIF(?1, ''), date('now'))", [date], |row| row.get(0))?;
+    let collected: bool = tx.query_row(
+        "SELECT EXISTS (SELECT 1 FROM history WHERE item_id = ?1 AND record_date = ?2 AND manual = 0)",
+        params![item_id, date],
+        |row| row.get(0),
+    )?;
+    if collected {
+        return Err(rusqlite::Error::ToSqlConversionFailure(
+            format!("{} already has a collected price; correct that row instead", date).into(),
+        ));
+    }
+    tx.execute("DELETE FROM history WHERE item_id = ?1 AND record_date = ?2", params![item_id, date])?;
+    tx.execute(
+        "INSERT INTO history (item_id, record_date, price, volume, manual) VALUES (?1, ?2, ?3, ?4, 1)",
+        params![item_id, date, price, volume],
+    )?;
+    tx.commit()
+}
+
+// Replaces a bad row's price and volume, logging the old values. The row
+// must belong to `item_id`, so a stale rowid can't touch another item.
+pub fn correct_snapshot(db_path: &str, item_id: i32, rowid: i64, price: Gp, volume: Option<i64>) -> Result<()> {
+    let mut conn = Connection::open(db_path)?;
+    ensure_user_tables(&conn)?;
+
+    let tx = conn.transaction()?;
+    log_snapshot_edit(&tx, item_id, rowid, Some(price), volume)?;
+    tx.execute("UPDATE history SET price = ?1, volume = ?2 WHERE rowid = ?3", params![price, volume, rowid])?;
+    tx.commit()
+}
+
+// Removes a bad row, logging what it held
+pub fn delete_snapshot(db_path: &str, item_id: i32, rowid: i64) -> Result<()> {
+    let mut conn = Connection::open(db_path)?;
+    ensure_user_tables(&conn)?;
+
+    let tx = conn.transaction()?;
+    log_snapshot_edit(&tx, item_id, rowid, None, None)?;
+    tx.execute("DELETE FROM history WHERE rowid = ?1", [rowid])?;
+    tx.commit()
+}
+
+fn log_snapshot_edit(conn: &Connection, item_id: i32, rowid: i64, price: Option<Gp>, volume: Option<i64>) -> Result<()> {
+    let (date, old_price, old_volume): (String, Gp, Option<i64>) = conn.query_row(
+        "SELECT record_date, price, volume FROM history WHERE rowid = ?1 AND item_id = ?2",
+        params![rowid, item_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?;
+    conn.execute(
+        "INSERT INTO snapshot_edits (item_id, record_date, old_price, old_volume, new_price, new_volume)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![item_id, date, old_price, old_volume, price, volume],
+    )?;
+    Ok(())
+}
+
+// The item's audit log, newest edit first
+pub fn load_snapshot_edits(db_path: &str, item_id: i32) -> Result<Vec<SnapshotEdit>> {
+    let conn = Connection::open(db_path)?;
+    ensure_user_tables(&conn)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT edited_at, record_date, old_price, old_volume, new_price, new_volume
+         FROM snapshot_edits WHERE item_id = ?1 ORDER BY id DESC"
+    )?;
+    let rows = stmt.query_map([item_id], |row| {
+        Ok(SnapshotEdit {
+            edited_at: row.get(0)?,
+            record_date: row.get(1)?,
+            old_price: row.get(2)?,
+            old_volume: row.get(3)?,
+            new_price: row.get(4)?,
+            new_volume: row.get(5)?,
         })
     })?;
 
     Ok(rows.filter_map(|r| r.ok()).collect())
 }
 
-pub fn load_item_history(db_path: &str, item_name: &str) -> Result<Vec<(String, f64)>> {
+// (price, volume) for each of the item's snapshots over the same window as
+// load_item_history; snapshots without a volume, and manual ones whose
+// volume is only a guess, are left out
+pub fn load_item_trades(db_path: &str, item_id: i32) -> Result<Vec<(f64, f64)>> {
+    let conn = Connection::open(db_path)?;
+    let manual = if has_column(&conn, "history", "manual")? { "manual" } else { "0" };
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT price, volume
+         FROM history
+         WHERE item_id = ?1
+         AND volume IS NOT NULL
+         AND {manual} = 0
+         AND record_date >= date('now', '-365 days')"
+    ))?;
+
+    let rows = stmt.query_map([item_id], |row| {
+        Ok((row.get::<_, i64>(0)? as f64, row.get::<_, i64>(1)? as f64))
+    })?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+// Items whose first-ever snapshot falls within the last `days` days
+pub fn load_new_items(db_path: &str, days: u32) -> Result<Vec<(i32, String, String)>> {
+    let conn = Connection::open(db_path)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT i.id, i.name, MIN(h.record_date) AS first_seen
+         FROM history h
+         JOIN items i ON h.item_id = i.id
+         GROUP BY i.id
+         HAVING first_seen >= date('now', ?1)
+         ORDER BY first_seen DESC"
+    )?;
+
+    let rows = stmt.query_map([format!("-{} days", days)], |row| {
+        Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+    })?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+// Deletes all history for the given items, returning the number of rows removed
+pub fn prune_item_history(db_path: &str, item_ids: &[i32]) -> Result<usize> {
+    let mut conn = Connection::open(db_path)?;
+    let tx = conn.transaction()?;
+
+    let mut removed = 0;
+    {
+        let mut stmt = tx.prepare("DELETE FROM history WHERE item_id = ?1")?;
+        for id in item_ids {
+            removed += stmt.execute([id])?;
+        }
+    }
+
+    tx.commit()?;
+    Ok(removed)
+}
+
+pub fn load_db_stats(db_path: &str) -> Result<DbStats> {
     let conn = Connection::open(db_path)?;
-    
+
+    let item_count = conn.query_row("SELECT COUNT(*) FROM items", [], |row| row.get(0))?;
+    let (history_rows, first_date, last_date) = conn.query_row(
+        "SELECT COUNT(*), COALESCE(MIN(record_date), ''), COALESCE(MAX(record_date), '') FROM history",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?;
+
+    let mut stmt = conn.prepare(
+        "SELECT i.name, COUNT(*), MIN(h.record_date), MAX(h.record_date)
+         FROM history h
+         JOIN items i ON h.item_id = i.id
+         GROUP BY h.item_id
+         ORDER BY i.name"
+    )?;
+    let coverage = stmt
+        .query_map([], |row| {
+            Ok(ItemCoverage {
+                name: row.get(0)?,
+                rows: row.get(1)?,
+                first_date: row.get(2)?,
+                last_date: row.get(3)?,
+            })
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(DbStats {
+        file_size: std::fs::metadata(db_path).map(|m| m.len()).unwrap_or(0),
+        item_count,
+        history_rows,
+        first_date,
+        last_date,
+        coverage,
+    })
+}
+
+pub fn vacuum(db_path: &str) -> Result<()> {
+    Connection::open(db_path)?.execute_batch("VACUUM")
+}
+
+pub fn reindex(db_path: &str) -> Result<()> {
+    Connection::open(db_path)?.execute_batch("REINDEX")
+}
+
+pub fn prune_older_than(db_path: &str, days: u32) -> Result<usize> {
+    Connection::open(db_path)?.execute(
+        "DELETE FROM history WHERE record_date < date('now', ?1)",
+        [format!("-{} days", days)],
+    )
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RetentionReport {
+    pub days: usize,  // (item, day) groups collapsed to one row
+    pub weeks: usize,
+    pub removed: usize,  // History rows deleted
+}
+
+// Raw snapshots older than `daily_after_days` collapse to one row per item
+// per day, and anything older than `weekly_after_days` to one per week
+// (starting Monday). The row that's kept carries the period's closing price
+// and the average of the volumes it has (none if no row had one), so
+// everything reading history still sees ordinary rows; the open/high/low/close
+// go to history_ohlc.
+pub fn apply_retention(db_path: &str, daily_after_days: u32, weekly_after_days: u32) -> Result<RetentionReport> {
+    let mut conn = Connection::open(db_path)?;
+    ensure_user_tables(&conn)?;
+    let tx = conn.transaction()?;
+
+    let daily_cutoff = format!("-{} days", daily_after_days);
+    let weekly_cutoff = format!("-{} days", weekly_after_days.max(daily_after_days));
+    let (days, removed_daily) = compact_history(&tx, "day", "date(record_date)", &daily_cutoff, Some(&weekly_cutoff))?;
+    let (weeks, removed_weekly) = compact_history(&tx, "week", "date(record_date, 'weekday 0', '-6 days')", &weekly_cutoff, None)?;
+
+    tx.commit()?;
+    Ok(RetentionReport { days, weeks, removed: removed_daily + removed_weekly })
+}
+
+// Collapses history rows dated before `before` (and on or after `since`) into
+// one row per item per bucket, returning (buckets collapsed, rows removed)
+fn compact_history(
+    tx: &rusqlite::Transaction,
+    period: &str,
+    bucket_sql: &str,
+    before: &str,
+    since: Option<&str>,
+) -> Result<(usize, usize)> {
+    // Earlier runs' OHLC, so a bucket that's compacted again keeps its true open/high/low
+    let mut previous: HashMap<(i32, String), (i64, i64, i64)> = HashMap::new();
+    {
+        let mut stmt = tx.prepare("SELECT item_id, start_date, open, high, low FROM history_ohlc ORDER BY period = 'week'")?;
+        let rows = stmt.query_map([], |row| Ok(((row.get(0)?, row.get(1)?), (row.get(2)?, row.get(3)?, row.get(4)?))))?;
+        previous.extend(rows.filter_map(|r| r.ok()));
+    }
+
+    let mut stmt = tx.prepare(&format!(
+        "SELECT rowid, item_id, record_date, price, volume, {}
+         FROM history
+         WHERE record_date < date('now', ?1) AND record_date >= COALESCE(date('now', ?2), '')
+         ORDER BY item_id, record_date, rowid",
+        bucket_sql
+    ))?;
+    type Snapshot = (i64, i32, String, i64, Option<i64>, String);
+    let rows: Vec<Snapshot> = stmt
+        .query_map(params![before, since], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?))
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut save_ohlc = tx.prepare(
+        "INSERT OR REPLACE INTO history_ohlc (item_id, period, start_date, open, high, low, close, volume)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+    )?;
+    let mut drop_days = tx.prepare(
+        "DELETE FROM history_ohlc WHERE item_id = ?1 AND period = 'day' AND start_date >= ?2 AND start_date < date(?2, '+7 days')",
+    )?;
+    let mut keep = tx.prepare("UPDATE history SET record_date = ?2, price = ?3, volume = ?4 WHERE rowid = ?1")?;
+    let mut delete = tx.prepare("DELETE FROM history WHERE rowid = ?1")?;
+
+    let (mut buckets, mut removed) = (0, 0);
+    for group in rows.chunk_by(|a, b| a.1 == b.1 && a.5 == b.5) {
+        if group.len() < 2 {
+            continue;
+        }
+        let (item_id, bucket) = (group[0].1, &group[0].5);
+        let bounds = |r: &Snapshot| previous.get(&(r.1, r.2.clone())).copied().unwrap_or((r.3, r.3, r.3));
+        let open = bounds(&group[0]).0;
+        let high = group.iter().map(|r| bounds(r).1).max().unwrap_or(open);
+        let low = group.iter().map(|r| bounds(r).2).min().unwrap_or(open);
+        let last = &group[group.len() - 1];
+        // Rows without a volume weren't traded at 0, so they stay out of the average
+        let volumes: Vec<i64> = group.iter().filter_map(|r| r.4).collect();
+        let volume = (!volumes.is_empty()).then(|| volumes.iter().sum::<i64>() / volumes.len() as i64);
+
+        save_ohlc.execute(params![item_id, period, bucket, open, high, low, last.3, volume])?;
+        if period == "week" {
+            drop_days.execute(params![item_id, bucket])?;
+        }
+        // Deleted first: with the collector's (item_id, record_date) key the
+        // bucket date may still be held by one of the rows being dropped
+        for r in &group[..group.len() - 1] {
+            removed += delete.execute([r.0])?;
+        }
+        keep.execute(params![last.0, bucket, last.3, volume])?;
+        buckets += 1;
+    }
+    Ok((buckets, removed))
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MergeReport {
+    pub new_items: usize,
+    pub inserted: usize,
+    pub replaced: usize,
+}
+
+// Copies items and history, alch values and manual flags included, from
+// another analyzer database into this one. Rows sharing (item_id, record_date)
+// keep the local copy unless `prefer_incoming`.
+pub fn merge_database(db_path: &str, other_path: &str, prefer_incoming: bool) -> Result<MergeReport> {
+    let mut conn = Connection::open(db_path)?;
+    add_column_if_missing(&conn, "items", "high_alch", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(&conn, "history", "manual", "INTEGER NOT NULL DEFAULT 0")?;
+    conn.execute("ATTACH DATABASE ?1 AS other", [other_path])?;
+    conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_history_item_date ON history(item_id, record_date)")?;
+
+    // Databases from older collectors have neither column
+    let high_alch = if has_column_in(&conn, "other", "items", "high_alch")? { "high_alch" } else { "0" };
+    let manual = if has_column_in(&conn, "other", "history", "manual")? { "manual" } else { "0" };
+
+    let tx = conn.transaction()?;
+    let mut report = MergeReport {
+        new_items: tx.execute(&format!(
+            "INSERT OR IGNORE INTO main.items (id, name, ge_limit, high_alch)
+             SELECT id, name, ge_limit, {high_alch} FROM other.items"
+        ), [])?,
+        ..Default::default()
+    };
+    // Alch values the local collector never recorded
+    tx.execute(&format!(
+        "UPDATE main.items AS i SET high_alch = o.high_alch
+         FROM (SELECT id, {high_alch} AS high_alch FROM other.items) o
+         WHERE o.id = i.id AND i.high_alch = 0 AND o.high_alch > 0"
+    ), [])?;
+
+    // Incoming duplicates collapse to their last row
+    let incoming = format!(
+        "SELECT item_id, record_date, price, volume, {manual} AS manual FROM other.history
+         WHERE rowid IN (SELECT MAX(rowid) FROM other.history GROUP BY item_id, record_date)"
+    );
+
+    if prefer_incoming {
+        // Rows already holding the incoming values aren't counted
+        report.replaced = tx.execute(&format!(
+            "UPDATE main.history AS h SET price = o.price, volume = o.volume, manual = o.manual
+             FROM ({incoming}) o
+             WHERE o.item_id = h.item_id AND o.record_date = h.record_date
+             AND NOT (h.price IS o.price AND h.volume IS o.volume AND h.manual IS o.manual)"
+        ), [])?;
+    }
+
+    report.inserted = tx.execute(&format!(
+        "INSERT INTO main.history (item_id, record_date, price, volume, manual)
+         SELECT o.item_id, o.record_date, o.price, o.volume, o.manual
+         FROM ({incoming}) o
+         WHERE NOT EXISTS (SELECT 1 FROM main.history h
+                           WHERE h.item_id = o.item_id AND h.record_date = o.record_date)"
+    ), [])?;
+
+    tx.commit()?;
+    conn.execute("DETACH DATABASE other", [])?;
+    Ok(report)
+}
+
+// Columns of the Parquet history export. Dates are Parquet DATEs (days since
+// 1970-01-01) and prices whole gp, the same as `Gp`, so pandas/Polars load
+// them as dates and integers without any parsing.
+const PARQUET_SCHEMA: &str = "
+    message rs3_history {
+        REQUIRED INT32 item_id;
+        REQUIRED BYTE_ARRAY name (UTF8);
+        REQUIRED INT32 date (DATE);
+        REQUIRED INT64 price;
+        OPTIONAL INT64 volume;
+    }
+";
+const PARQUET_ROW_GROUP: usize = 250_000;
+
+#[derive(Default)]
+struct HistoryBatch {
+    item_ids: Vec<i32>,
+    names: Vec<parquet::data_type::ByteArray>,
+    dates: Vec<i32>,
+    prices: Vec<i64>,
+    volumes: Vec<i64>,  // Only the present ones; volume_defs says which rows have one
+    volume_defs: Vec<i16>,
+}
+
+impl HistoryBatch {
+    fn write<W: std::io::Write + Send>(
+        &self,
+        writer: &mut parquet::file::writer::SerializedFileWriter<W>,
+    ) -> parquet::errors::Result<()> {
+        use parquet::data_type::{ByteArrayType, Int32Type, Int64Type};
+
+        let mut group = writer.next_row_group()?;
+        let mut index = 0;
+        while let Some(mut column) = group.next_column()? {
+            match index {
+                0 => column.typed::<Int32Type>().write_batch(&self.item_ids, None, None)?,
+                1 => column.typed::<ByteArrayType>().write_batch(&self.names, None, None)?,
+                2 => column.typed::<Int32Type>().write_batch(&self.dates, None, None)?,
+                3 => column.typed::<Int64Type>().write_batch(&self.prices, None, None)?,
+                _ => column.typed::<Int64Type>().write_batch(&self.volumes, Some(&self.volume_defs), None)?,
+            };
+            column.close()?;
+            index += 1;
+        }
+        group.close()?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExportReport {
+    pub written: usize,
+    pub skipped: usize,  // Rows without a readable YYYY-MM-DD date, which a Parquet DATE can't hold
+}
+
+// Writes every snapshot (or one item's) to a Parquet file
+pub fn export_parquet(db_path: &str, out_path: &str, item_id: Option<i32>) -> std::result::Result<ExportReport, String> {
+    use parquet::basic::Compression;
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::SerializedFileWriter;
+    use std::sync::Arc;
+
+    let schema = Arc::new(parquet::schema::parser::parse_message_type(PARQUET_SCHEMA).map_err(|e| e.to_string())?);
+    let props = Arc::new(WriterProperties::builder().set_compression(Compression::SNAPPY).build());
+    let file = std::fs::File::create(out_path).map_err(|e| e.to_string())?;
+    let mut writer = SerializedFileWriter::new(file, schema, props).map_err(|e| e.to_string())?;
+
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
     let mut stmt = conn.prepare(
-        "SELECT h.record_date, h.price
+        "SELECT h.item_id, i.name, h.record_date, h.price, h.volume
          FROM history h
          JOIN items i ON h.item_id = i.id
-         WHERE i.name = ?1
-         AND h.record_date >= date('now', '-365 days')
-         ORDER BY h.record_date"
+         WHERE ?1 IS NULL OR h.item_id = ?1
+         ORDER BY h.item_id, h.record_date"
+    ).map_err(|e| e.to_string())?;
+    let mut rows = stmt.query([item_id]).map_err(|e| e.to_string())?;
+
+    let mut batch = HistoryBatch::default();
+    let mut report = ExportReport::default();
+    while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        let date: String = row.get(2).map_err(|e| e.to_string())?;
+        let Some(day) = crate::stats::day_number(&date) else {
+            report.skipped += 1;
+            continue;
+        };
+        let name: String = row.get(1).map_err(|e| e.to_string())?;
+        batch.item_ids.push(row.get(0).map_err(|e| e.to_string())?);
+        batch.names.push(name.into_bytes().into());
+        batch.dates.push(day as i32);
+        batch.prices.push(row.get(3).map_err(|e| e.to_string())?);
+        match row.get::<_, Option<i64>>(4).map_err(|e| e.to_string())? {
+            Some(volume) => {
+                batch.volumes.push(volume);
+                batch.volume_defs.push(1);
+            }
+            None => batch.volume_defs.push(0),
+        }
+
+        if batch.item_ids.len() >= PARQUET_ROW_GROUP {
+            batch.write(&mut writer).map_err(|e| e.to_string())?;
+            report.written += batch.item_ids.len();
+            batch = HistoryBatch::default();
+        }
+    }
+    if !batch.item_ids.is_empty() {
+        batch.write(&mut writer).map_err(|e| e.to_string())?;
+        report.written += batch.item_ids.len();
+    }
+
+    writer.close().map_err(|e| e.to_string())?;
+    Ok(report)
+}
+
+// Returns "ok" for a healthy database, otherwise SQLite's list of problems
+pub fn integrity_check(db_path: &str) -> Result<String> {
+    let conn = Connection::open(db_path)?;
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    let lines: Vec<String> = stmt
+        .query_map([], |row| row.get(0))?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+// Amount columns older versions declared REAL, with the select that copies a
+// table's rows over to its INTEGER replacement
+const REAL_AMOUNTS: [(&str, &str, &str); 3] = [
+    ("holdings", "cost_basis", "item_id, qty, CAST(ROUND(cost_basis) AS INTEGER), CAST(ROUND(target_sell) AS INTEGER)"),
+    ("bank_value", "value", "recorded_at, CAST(ROUND(value) AS INTEGER)"),
+    ("scan_log", "buy", "scan_id, scanned_at, item_id, tier, score, CAST(ROUND(buy) AS INTEGER), CAST(ROUND(sell) AS INTEGER)"),
+];
+
+// Tables owned by the analyzer itself (the collector only writes items/history)
+fn ensure_user_tables(conn: &Connection) -> Result<()> {
+    let mut legacy = Vec::new();
+    for (table, column, select) in REAL_AMOUNTS {
+        if column_type(conn, table, column)?.as_deref() == Some("REAL") {
+            legacy.push((table, select));
+        }
+    }
+    if legacy.is_empty() {
+        return create_user_tables(conn);
+    }
+
+    // SQLite can't change a column's type, so the old tables are moved aside,
+    // recreated and refilled with the amounts rounded to whole gp
+    let tx = conn.unchecked_transaction()?;
+    for (table, _) in &legacy {
+        match *table {
+            // The oldest holdings have no target_sell for the copy to read
+            "holdings" => add_column_if_missing(&tx, "holdings", "target_sell", "REAL")?,
+            // The index would move with the old table and keep its name from the new one
+            "scan_log" => tx.execute_batch("DROP INDEX IF EXISTS idx_scan_log_scan")?,
+            _ => {}
+        }
+        tx.execute_batch(&format!("ALTER TABLE {table} RENAME TO {table}_real"))?;
+    }
+    create_user_tables(&tx)?;
+    for (table, select) in &legacy {
+        tx.execute_batch(&format!("INSERT INTO {table} SELECT {select} FROM {table}_real; DROP TABLE {table}_real"))?;
+    }
+    tx.commit()
+}
+
+fn create_user_tables(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS holdings (
+            item_id INTEGER PRIMARY KEY,
+            qty INTEGER NOT NULL,
+            cost_basis INTEGER NOT NULL DEFAULT 0,
+            target_sell INTEGER
+         );
+         CREATE TABLE IF NOT EXISTS bank_value (
+            recorded_at TEXT NOT NULL,
+            value INTEGER NOT NULL
+         );
+         CREATE TABLE IF NOT EXISTS scan_log (
+            scan_id INTEGER NOT NULL,
+            scanned_at TEXT NOT NULL,
+            item_id INTEGER NOT NULL,
+            tier TEXT NOT NULL,
+            score INTEGER NOT NULL,
+            buy INTEGER NOT NULL,
+            sell INTEGER NOT NULL
+         );
+         CREATE INDEX IF NOT EXISTS idx_scan_log_scan ON scan_log(scan_id);
+         CREATE TABLE IF NOT EXISTS annotations (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            item_id INTEGER NOT NULL,
+            note_date TEXT NOT NULL,
+            note TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now'))
+         );
+         CREATE TABLE IF NOT EXISTS snapshot_edits (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            item_id INTEGER NOT NULL,
+            record_date TEXT NOT NULL,
+            old_price INTEGER NOT NULL,
+            old_volume INTEGER,
+            new_price INTEGER,
+            new_volume INTEGER,
+            edited_at TEXT NOT NULL DEFAULT (datetime('now'))
+         );
+         CREATE TABLE IF NOT EXISTS history_ohlc (
+            item_id INTEGER NOT NULL,
+            period TEXT NOT NULL,
+            start_date TEXT NOT NULL,
+            open INTEGER NOT NULL,
+            high INTEGER NOT NULL,
+            low INTEGER NOT NULL,
+            close INTEGER NOT NULL,
+            volume INTEGER,
+            PRIMARY KEY (item_id, period, start_date)
+         );"
+    )?;
+    add_column_if_missing(conn, "holdings", "target_sell", "INTEGER")
+}
+
+// Lightweight migration for tables created by older versions
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, decl: &str) -> Result<()> {
+    if !has_column(conn, table, column)? {
+        conn.execute_batch(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, decl))?;
+    }
+    Ok(())
+}
+
+fn has_column(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+    has_column_in(conn, "main", table, column)
+}
+
+// Declared type of a column, None if the table or column doesn't exist
+fn column_type(conn: &Connection, table: &str, column: &str) -> Result<Option<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let found = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(1)?, row.get::<_, String>(2)?)))?
+        .filter_map(|r| r.ok())
+        .find(|(name, _)| name == column)
+        .map(|(_, decl)| decl);
+    Ok(found)
+}
+
+// has_column for an attached database
+fn has_column_in(conn: &Connection, schema: &str, table: &str, column: &str) -> Result<bool> {
+    let mut stmt = conn.prepare(&format!("PRAGMA {}.table_info({})", schema, table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(|r| r.ok())
+        .any(|name| name == column);
+    Ok(exists)
+}
+
+// Resolve an item by exact ID or case-insensitive name
+pub fn find_item(db_path: &str, query: &str) -> Result<Option<(i32, String)>> {
+    let conn = Connection::open(db_path)?;
+    let query = query.trim();
+
+    if let Ok(id) = query.parse::<i32>() {
+        return conn
+            .query_row("SELECT id, name FROM items WHERE id = ?1", [id], |row| Ok((row.get(0)?, row.get(1)?)))
+            .optional();
+    }
+
+    conn.query_row(
+        "SELECT id, name FROM items WHERE name = ?1 COLLATE NOCASE LIMIT 1",
+        [query],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .optional()
+}
+
+pub fn load_holdings(db_path: &str) -> Result<Vec<Holding>> {
+    let conn = Connection::open(db_path)?;
+    ensure_user_tables(&conn)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT h.item_id, i.name, h.qty, h.cost_basis, h.target_sell
+         FROM holdings h
+         JOIN items i ON h.item_id = i.id
+         ORDER BY i.name"
+    )?;
+
+    let rows = stmt.query_map([], |row| {
+        Ok(Holding {
+            item_id: row.get(0)?,
+            name: row.get(1)?,
+            qty: row.get(2)?,
+            cost_basis: row.get(3)?,
+            target_sell: row.get(4)?,
+        })
+    })?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+pub fn save_holding(db_path: &str, holding: &Holding) -> Result<()> {
+    let conn = Connection::open(db_path)?;
+    ensure_user_tables(&conn)?;
+
+    conn.execute(
+        "INSERT INTO holdings (item_id, qty, cost_basis, target_sell) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(item_id) DO UPDATE SET
+            qty = excluded.qty, cost_basis = excluded.cost_basis, target_sell = excluded.target_sell",
+        params![holding.item_id, holding.qty, holding.cost_basis, holding.target_sell],
     )?;
+    Ok(())
+}
+
+pub fn delete_holding(db_path: &str, item_id: i32) -> Result<()> {
+    let conn = Connection::open(db_path)?;
+    ensure_user_tables(&conn)?;
+    conn.execute("DELETE FROM holdings WHERE item_id = ?1", [item_id])?;
+    Ok(())
+}
+
+pub fn record_bank_value(db_path: &str, value: Gp) -> Result<()> {
+    let conn = Connection::open(db_path)?;
+    ensure_user_tables(&conn)?;
+    conn.execute(
+        "INSERT INTO bank_value (recorded_at, value) VALUES (datetime('now'), ?1)",
+        [value],
+    )?;
+    Ok(())
+}
+
+pub fn load_bank_history(db_path: &str) -> Result<Vec<(String, Gp)>> {
+    let conn = Connection::open(db_path)?;
+    ensure_user_tables(&conn)?;
+
+    let mut stmt = conn.prepare("SELECT recorded_at, value FROM bank_value ORDER BY recorded_at")?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+pub fn load_annotations(db_path: &str, item_id: i32) -> Result<Vec<Annotation>> {
+    let conn = Connection::open(db_path)?;
+    ensure_user_tables(&conn)?;
 
-    let rows = stmt.query_map([item_name], |row| {
-        Ok((row.get(0)?, row.get::<_, i32>(1)? as f64))
+    let mut stmt = conn.prepare(
+        "SELECT id, item_id, note_date, note FROM annotations WHERE item_id = ?1 ORDER BY note_date"
+    )?;
+    let rows = stmt.query_map([item_id], |row| {
+        Ok(Annotation {
+            id: row.get(0)?,
+            item_id: row.get(1)?,
+            date: row.get(2)?,
+            text: row.get(3)?,
+        })
     })?;
 
     Ok(rows.filter_map(|r| r.ok()).collect())
 }
+
+// An empty `date` records the note against today
+pub fn add_annotation(db_path: &str, item_id: i32, date: &str, text: &str) -> Result<()> {
+    let conn = Connection::open(db_path)?;
+    ensure_user_tables(&conn)?;
+    conn.execute(
+        "INSERT INTO annotations (item_id, note_date, note)
+         VALUES (?1, COALESCE(NULLIF(?2, ''), date('now')), ?3)",
+        params![item_id, date.trim(), text.trim()],
+    )?;
+    Ok(())
+}
+
+pub fn delete_annotation(db_path: &str, id: i64) -> Result<()> {
+    let conn = Connection::open(db_path)?;
+    ensure_user_tables(&conn)?;
+    conn.execute("DELETE FROM annotations WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+// Number of past scans kept in the scan log
+const SCAN_LOG_KEEP: i64 = 30;
+
+// Recommendations from the most recent logged scan, keyed by item
+pub fn load_last_scan(db_path: &str) -> Result<HashMap<i32, ScanEntry>> {
+    let conn = Connection::open(db_path)?;
+    ensure_user_tables(&conn)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT item_id, tier, score, buy, sell FROM scan_log
+         WHERE scan_id = (SELECT MAX(scan_id) FROM scan_log)"
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok(ScanEntry {
+            item_id: row.get(0)?,
+            tier: row.get(1)?,
+            score: row.get(2)?,
+            buy: row.get(3)?,
+            sell: row.get(4)?,
+        })
+    })?;
+
+    Ok(rows.filter_map(|r| r.ok()).map(|e| (e.item_id, e)).collect())
+}
+
+pub fn record_scan(db_path: &str, entries: &[ScanEntry]) -> Result<()> {
+    let mut conn = Connection::open(db_path)?;
+    ensure_user_tables(&conn)?;
+
+    let tx = conn.transaction()?;
+    let scan_id: i64 = tx.query_row("SELECT COALESCE(MAX(scan_id), 0) + 1 FROM scan_log", [], |row| row.get(0))?;
+    {
+        let mut stmt = tx.prepare(
+            "INSERT INTO scan_log (scan_id, scanned_at, item_id, tier, score, buy, sell)
+             VALUES (?1, datetime('now'), ?2, ?3, ?4, ?5, ?6)"
+        )?;
+        for e in entries {
+            stmt.execute(params![scan_id, e.item_id, e.tier, e.score, e.buy, e.sell])?;
+        }
+    }
+    tx.execute("DELETE FROM scan_log WHERE scan_id <= ?1", [scan_id - SCAN_LOG_KEEP])?;
+    tx.commit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An empty database with the tables as rs3_collector.py creates them,
+    // where history is keyed on (item_id, record_date)
+    fn collector_db(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("rs3_analyzer_{}_{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT, ge_limit INTEGER, high_alch INTEGER);
+                 CREATE TABLE history (item_id INTEGER, record_date TEXT, price INTEGER, volume INTEGER,
+                                       PRIMARY KEY (item_id, record_date));
+                 INSERT INTO items VALUES (1050, 'Santa hat', 2, 0);",
+            )
+            .unwrap();
+        path
+    }
+
+    #[test]
+    fn failed_installs_keep_the_old_database() {
+        let path = collector_db("install");
+        let db = path.to_str().unwrap();
+        let dir = std::env::temp_dir();
+        let pointer = dir.join(format!("rs3_analyzer_install_pointer_{}.db", std::process::id()));
+        let broken = dir.join(format!("rs3_analyzer_install_broken_{}.db.gz", std::process::id()));
+        std::fs::write(&pointer, "version https://git-lfs.github.com/spec/v1\n").unwrap();
+        std::fs::write(&broken, "not gzip").unwrap();
+
+        let part = std::path::PathBuf::from(format!("{}.part", db));
+        let not_a_database = install_database(pointer.to_str().unwrap(), db);
+        let part_left = part.exists();
+        let failed_copy = install_database(broken.to_str().unwrap(), db);
+        let part_left = part_left || part.exists();
+        let ready = database_ready(db);
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(&pointer);
+        let _ = std::fs::remove_file(&broken);
+
+        assert_eq!(not_a_database.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+        assert!(failed_copy.is_err());
+        assert!(ready);
+        assert!(!part_left);
+    }
+
+    #[test]
+    fn retention_compacts_a_collector_database() {
+        let path = collector_db("retention");
+        let db = path.to_str().unwrap();
+
+        let today = (crate::journal::now_secs() / 86_400) as i64;
+        let old = today - 500;
+        let monday = old - (old + 3).rem_euclid(7);  // Day 0 was a Thursday
+        let day = today - 200;
+        {
+            let conn = Connection::open(db).unwrap();
+            let mut insert = conn.prepare("INSERT INTO history VALUES (1050, ?1, ?2, ?3)").unwrap();
+            for i in 0..7 {
+                let volume = (i % 2 == 0).then_some(10 + i);  // Every other day without one
+                insert.execute(params![crate::stats::date_from_day(monday + i), 100 + i, volume]).unwrap();
+            }
+            insert.execute(params![crate::stats::date_from_day(day), 50, None::<i64>]).unwrap();
+            insert.execute(params![format!("{} 12:00", crate::stats::date_from_day(day)), 60, None::<i64>]).unwrap();
+        }
+
+        let report = apply_retention(db, 120, 400).unwrap();
+        let again = apply_retention(db, 120, 400).unwrap();
+        let conn = Connection::open(db).unwrap();
+        let rows: Vec<(String, i64, Option<i64>)> = conn
+            .prepare("SELECT record_date, price, volume FROM history ORDER BY record_date")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        let week: (i64, i64, i64, i64) = conn
+            .query_row("SELECT open, high, low, close FROM history_ohlc WHERE period = 'week'", [], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })
+            .unwrap();
+        drop(conn);
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!((report.days, report.weeks, report.removed), (1, 1, 7));
+        assert_eq!((again.days, again.weeks, again.removed), (0, 0, 0));
+        assert_eq!(rows, [(crate::stats::date_from_day(monday), 106, Some(13)), (crate::stats::date_from_day(day), 60, None)]);
+        assert_eq!(week, (100, 106, 100, 106));
+    }
+
+    #[test]
+    fn manual_snapshots_need_a_date_and_stay_out_of_trades() {
+        let path = std::env::temp_dir().join(format!("rs3_analyzer_manual_{}.db", std::process::id()));
+        let db = path.to_str().unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let price = DailyPrice { item_id: 1050, name: "Santa hat".into(), ge_limit: 2, high_alch: 0, price: Gp(40_000), volume: 12 };
+        record_daily_prices(db, &[price]).unwrap();
+        let rejected: Vec<bool> = ["2024-02-30", "2024-2-03", "03/02/2024", "2024-02-03x", "yesterday"]
+            .iter()
+            .map(|date| add_manual_snapshot(db, 1050, date, Gp(41_000), Some(5)).is_err())
+            .collect();
+        let yesterday = crate::stats::date_from_day((crate::journal::now_secs() / 86_400) as i64 - 1);
+        add_manual_snapshot(db, 1050, &yesterday, Gp(42_000), Some(5)).unwrap();
+        let trades = load_item_trades(db, 1050).unwrap();
+        let history = load_item_history(db, 1050).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(rejected, [true; 5]);
+        assert_eq!(trades, [(40_000.0, 12.0)]);
+        assert_eq!(history.len(), 2);
+    }
+
+    #[test]
+    fn manual_snapshots_share_the_collectors_one_row_per_day() {
+        let path = collector_db("manual_pk");
+        let db = path.to_str().unwrap();
+
+        let yesterday = crate::stats::date_from_day((crate::journal::now_secs() / 86_400) as i64 - 1);
+        let price = DailyPrice { item_id: 1050, name: "Santa hat".into(), ge_limit: 2, high_alch: 0, price: Gp(40_000), volume: 12 };
+        add_manual_snapshot(db, 1050, "", Gp(41_000), None).unwrap();
+        add_manual_snapshot(db, 1050, "", Gp(42_000), None).unwrap();
+        let replaced = load_item_snapshots(db, 1050).unwrap();
+        record_daily_prices(db, &[price]).unwrap();
+        let over_collected = add_manual_snapshot(db, 1050, "", Gp(43_000), None);
+        add_manual_snapshot(db, 1050, &yesterday, Gp(39_000), None).unwrap();
+        let snaps = load_item_snapshots(db, 1050).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let rows = |snaps: &[RawSnapshot]| snaps.iter().map(|s| (s.price, s.manual)).collect::<Vec<_>>();
+        assert_eq!(rows(&replaced), [(Gp(42_000), true)]);
+        assert!(over_collected.is_err());
+        assert_eq!(rows(&snaps), [(Gp(39_000), true), (Gp(40_000), false)]);
+        assert_eq!(snaps[0].date, yesterday);
+    }
+
+    #[test]
+    fn real_amount_columns_become_integer_gp() {
+        let path = collector_db("real_amounts");
+        let db = path.to_str().unwrap();
+        Connection::open(db)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE holdings (item_id INTEGER PRIMARY KEY, qty INTEGER NOT NULL, cost_basis REAL NOT NULL DEFAULT 0);
+                 CREATE TABLE bank_value (recorded_at TEXT NOT NULL, value REAL NOT NULL);
+                 CREATE TABLE scan_log (scan_id INTEGER NOT NULL, scanned_at TEXT NOT NULL, item_id INTEGER NOT NULL,
+                                        tier TEXT NOT NULL, score INTEGER NOT NULL, buy REAL NOT NULL, sell REAL NOT NULL);
+                 CREATE INDEX idx_scan_log_scan ON scan_log(scan_id);
+                 INSERT INTO holdings VALUES (1050, 3, 40000.6);
+                 INSERT INTO bank_value VALUES ('2024-02-03 12:00:00', 120000.4);
+                 INSERT INTO scan_log VALUES (1, '2024-02-03 12:00:00', 1050, 'GOOD', 70, 39999.5, 41000.2);",
+            )
+            .unwrap();
+
+        let holdings = load_holdings(db).unwrap();
+        let bank = load_bank_history(db).unwrap();
+        let scan = load_last_scan(db).unwrap();
+        record_bank_value(db, Gp(3_000_000_000)).unwrap();
+        let bank_after = load_bank_history(db).unwrap();
+        let conn = Connection::open(db).unwrap();
+        let types: Vec<Option<String>> = [("holdings", "cost_basis"), ("holdings", "target_sell"), ("bank_value", "value"), ("scan_log", "sell")]
+            .iter()
+            .map(|(table, column)| column_type(&conn, table, column).unwrap())
+            .collect();
+        let index: i64 = conn
+            .query_row("SELECT COUNT(*) FROM sqlite_master WHERE name = 'idx_scan_log_scan' AND tbl_name = 'scan_log'", [], |row| row.get(0))
+            .unwrap();
+        drop(conn);
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!((holdings[0].cost_basis, holdings[0].target_sell), (Gp(40_001), None));
+        assert_eq!(bank[0].1, Gp(120_000));
+        assert_eq!((scan[&1050].buy, scan[&1050].sell), (Gp(40_000), Gp(41_000)));
+        assert_eq!(bank_after[1].1, Gp(3_000_000_000));
+        assert!(types.iter().all(|t| t.as_deref() == Some("INTEGER")));
+        assert_eq!(index, 1);
+    }
+
+    #[test]
+    fn parquet_export_counts_rows_it_skips() {
+        let path = collector_db("parquet");
+        let db = path.to_str().unwrap();
+        let out = std::env::temp_dir().join(format!("rs3_analyzer_parquet_{}.parquet", std::process::id()));
+        Connection::open(db)
+            .unwrap()
+            .execute_batch("INSERT INTO history VALUES (1050, '2024-02-03', 40000, 12), (1050, '03/02/2024', 41000, 5);")
+            .unwrap();
+
+        let report = export_parquet(db, out.to_str().unwrap(), None);
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(&out);
+        let report = report.unwrap();
+
+        assert_eq!((report.written, report.skipped), (1, 1));
+    }
+
+    #[test]
+    fn merge_copies_manual_rows_and_alch_values_and_counts_real_changes() {
+        let dir = std::env::temp_dir();
+        let local = dir.join(format!("rs3_analyzer_merge_local_{}.db", std::process::id()));
+        let other = dir.join(format!("rs3_analyzer_merge_other_{}.db", std::process::id()));
+        let (db, other_db) = (local.to_str().unwrap(), other.to_str().unwrap());
+        let _ = std::fs::remove_file(&local);
+        let _ = std::fs::remove_file(&other);
+
+        let today = (crate::journal::now_secs() / 86_400) as i64;
+        let (changed, added) = (crate::stats::date_from_day(today - 10), crate::stats::date_from_day(today - 9));
+        let price = |high_alch| DailyPrice { item_id: 1050, name: "Santa hat".into(), ge_limit: 2, high_alch, price: Gp(40_000), volume: 12 };
+        record_daily_prices(db, &[price(0)]).unwrap();
+        add_manual_snapshot(db, 1050, &changed, Gp(41_000), None).unwrap();
+        record_daily_prices(other_db, &[price(5_000)]).unwrap();
+        add_manual_snapshot(other_db, 1050, &changed, Gp(45_000), None).unwrap();
+        add_manual_snapshot(other_db, 1050, &added, Gp(46_000), None).unwrap();
+
+        let report = merge_database(db, other_db, true).unwrap();
+        let alch = load_alch_values(db).unwrap();
+        let snaps = load_item_snapshots(db, 1050).unwrap();
+        let _ = std::fs::remove_file(&local);
+        let _ = std::fs::remove_file(&other);
+
+        // Today's row is identical on both sides, so only the manual day changed
+        assert_eq!((report.new_items, report.inserted, report.replaced), (0, 1, 1));
+        assert_eq!(alch[&1050], Gp(5_000));
+        let row = |date: &str| snaps.iter().find(|s| s.date == date).map(|s| (s.price, s.manual));
+        assert_eq!(row(&changed), Some((Gp(45_000), true)));
+        assert_eq!(row(&added), Some((Gp(46_000), true)));
+        assert_eq!(snaps.len(), 3);
+    }
+}
@@ -1,17 +1,83 @@
+#[cfg(not(target_arch = "wasm32"))]
+mod loader;
+#[cfg(target_arch = "wasm32")]
+#[path = "web_loader.rs"]
 mod loader;
 mod stats;
 mod flips;
+mod game;
+mod floor;
+mod format;
+mod ge_import;
+mod glossary;
+mod hooks;
+mod investments;
+mod ironman;
+mod alerts;
+mod alt1;
+mod baskets;
+mod bundle;
+mod categories;
+mod compare;
+mod config;
+mod cycles;
+mod downsample;
+mod email;
+mod exit;
+mod fees;
+mod explain;
+mod journal;
+mod maintenance;
 mod model;
+mod montecarlo;
+mod orderbook;
+mod osrs;
+mod pairs;
+mod palette;
+mod planner;
+mod promo;
+mod regime;
+mod report;
+mod rules;
+mod schedule;
+mod snapshot_stream;
+mod strategy;
+mod telegram;
+mod timemachine;
+#[cfg(any(windows, target_os = "macos"))]
+mod tray;
+#[cfg(not(any(windows, target_os = "macos")))]
+#[path = "no_tray.rs"]
+mod tray;
+mod treemap;
 mod ui;
+mod variants;
+mod watchlist;
 
+#[cfg(not(target_arch = "wasm32"))]
 use eframe::egui;
 use ui::RS3App;
 
+#[cfg(not(target_arch = "wasm32"))]
 fn main() -> eframe::Result<()> {
+    // `rs3_analyzer --export-web <dir>` dumps the snapshot window as JSON for the web build
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() >= 3 && args[1] == "--export-web" {
+        export_web_data(&args[2]);
+        return Ok(());
+    }
+
+    let window = config::AppConfig::load().window;
+    let mut viewport = egui::ViewportBuilder::default()
+        .with_inner_size(window.size.unwrap_or([1600.0, 1000.0]))
+        .with_min_inner_size([1200.0, 700.0])
+        .with_maximized(window.maximized);
+    if let Some(position) = window.position {
+        viewport = viewport.with_position(position);
+    }
+
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_inner_size([1600.0, 1000.0])
-            .with_min_inner_size([1200.0, 700.0]),
+        viewport,
         ..Default::default()
     };
 
@@ -20,7 +86,7 @@ fn main() -> eframe::Result<()> {
         options,
         Box::new(|cc| {
             let mut fonts = egui::FontDefinitions::default();
-            
+
             if let Ok(segoe_data) = std::fs::read("C:\\Windows\\Fonts\\segoeui.ttf") {
                 fonts.font_data.insert(
                     "SegoeUI".to_owned(),
@@ -30,10 +96,63 @@ fn main() -> eframe::Result<()> {
                     .unwrap()
                     .insert(0, "SegoeUI".to_owned());
             }
-            
+
             cc.egui_ctx.set_fonts(fonts);
             ui::set_custom_style(&cc.egui_ctx);
             Ok(Box::new(RS3App::new()))
         }),
     )
 }
+
+#[cfg(not(target_arch = "wasm32"))]
+fn export_web_data(dir: &str) {
+    let db_path = config::AppConfig::load().game.db_path();
+    let snaps = match loader::load_snapshots(db_path) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", db_path, e);
+            return;
+        }
+    };
+
+    let path = std::path::Path::new(dir).join("snapshots.json");
+    let export = model::SnapshotExport { schema_version: model::SCAN_SCHEMA_VERSION, snapshots: snaps };
+    match serde_json::to_string(&export) {
+        Ok(json) => match std::fs::write(&path, json) {
+            Ok(_) => println!("Wrote {} snapshots to {}", export.snapshots.len(), path.display()),
+            Err(e) => eprintln!("Failed to write {}: {}", path.display(), e),
+        },
+        Err(e) => eprintln!("Failed to serialize snapshots: {}", e),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    use eframe::wasm_bindgen::JsCast;
+
+    let web_options = eframe::WebOptions::default();
+
+    wasm_bindgen_futures::spawn_local(async {
+        let canvas = web_sys::window()
+            .and_then(|w| w.document())
+            .and_then(|d| d.get_element_by_id("the_canvas_id"))
+            .and_then(|e| e.dyn_into::<web_sys::HtmlCanvasElement>().ok())
+            .expect("index.html is missing the_canvas_id");
+
+        let result = eframe::WebRunner::new()
+            .start(
+                canvas,
+                web_options,
+                Box::new(|cc| {
+                    ui::set_custom_style(&cc.egui_ctx);
+                    loader::start_fetch(cc.egui_ctx.clone());
+                    Ok(Box::new(RS3App::new()))
+                }),
+            )
+            .await;
+
+        if let Err(e) = result {
+            web_sys::console::error_1(&e);
+        }
+    });
+}
@@ -0,0 +1,175 @@
+// Database maintenance tasks, run off the UI thread
+use crate::loader;
+use crate::osrs;
+use crate::model::DbStats;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use web_time::Instant;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Task {
+    Stats,
+    Vacuum,
+    Reindex,
+    PruneOlderThan(u32),
+    Retention { daily_after_days: u32, weekly_after_days: u32 },
+    IntegrityCheck,
+    Merge { other_path: String, prefer_incoming: bool },
+    InstallDatabase { source: String },
+    ExportParquet { path: String, item: Option<(i32, String)> },
+    FetchOsrsPrices,
+}
+
+pub enum Progress {
+    Log(String),
+    Stats(Box<DbStats>),
+    Done { task: Task, ok: bool },
+}
+
+#[derive(Default)]
+pub struct Maintenance {
+    rx: Option<Receiver<Progress>>,
+    pub log: Vec<String>,
+    pub stats: Option<DbStats>,
+}
+
+impl Maintenance {
+    pub fn is_running(&self) -> bool {
+        self.rx.is_some()
+    }
+
+    // Runs `task` against the database at `db_path`
+    pub fn start(&mut self, task: Task, db_path: &str) {
+        if self.is_running() {
+            return;
+        }
+
+        let (tx, rx) = channel();
+        self.rx = Some(rx);
+        let db_path = db_path.to_string();
+        spawn(move || run(task, &db_path, tx));
+    }
+
+    // Drain progress messages; call once per frame. Returns the task that just
+    // finished and whether it succeeded, since it may have changed the database.
+    pub fn poll(&mut self) -> Option<(Task, bool)> {
+        let rx = self.rx.as_ref()?;
+
+        let mut finished = None;
+        while let Ok(msg) = rx.try_recv() {
+            match msg {
+                Progress::Log(line) => self.log.push(line),
+                Progress::Stats(stats) => self.stats = Some(*stats),
+                Progress::Done { task, ok } => finished = Some((task, ok)),
+            }
+        }
+
+        if finished.is_some() {
+            self.rx = None;
+        }
+        finished
+    }
+}
+
+fn run(task: Task, db_path: &str, tx: Sender<Progress>) {
+    let started = Instant::now();
+    let log = |line: String| {
+        let _ = tx.send(Progress::Log(line));
+    };
+
+    let finished = task.clone();
+    let result: Result<String, String> = match task {
+        Task::Stats => {
+            log("Collecting database statistics...".into());
+            loader::load_db_stats(db_path).map(|stats| {
+                let _ = tx.send(Progress::Stats(Box::new(stats)));
+                "Statistics updated".to_string()
+            }).map_err(|e| e.to_string())
+        }
+        Task::Vacuum => {
+            log("Running VACUUM (this can take a while on large databases)...".into());
+            loader::vacuum(db_path).map(|_| "VACUUM complete".to_string()).map_err(|e| e.to_string())
+        }
+        Task::Reindex => {
+            log("Rebuilding indexes...".into());
+            loader::reindex(db_path).map(|_| "REINDEX complete".to_string()).map_err(|e| e.to_string())
+        }
+        Task::PruneOlderThan(days) => {
+            log(format!("Deleting snapshots older than {} days...", days));
+            loader::prune_older_than(db_path, days)
+                .map(|n| format!("Removed {} snapshots", n))
+                .map_err(|e| e.to_string())
+        }
+        Task::Retention { daily_after_days, weekly_after_days } => {
+            log(format!(
+                "Compacting snapshots to daily after {} days and weekly after {} days...",
+                daily_after_days, weekly_after_days
+            ));
+            loader::apply_retention(db_path, daily_after_days, weekly_after_days)
+                .and_then(|r| {
+                    // Deleted rows only give space back to the file after a VACUUM
+                    if r.removed > 0 {
+                        log("Reclaiming space...".into());
+                        loader::vacuum(db_path)?;
+                    }
+                    Ok(format!("Compacted {} item-days and {} item-weeks, removed {} snapshots", r.days, r.weeks, r.removed))
+                })
+                .map_err(|e| e.to_string())
+        }
+        Task::Merge { other_path, prefer_incoming } => {
+            log(format!("Merging history from {}...", other_path));
+            loader::merge_database(db_path, &other_path, prefer_incoming).map(|r| {
+                format!(
+                    "Merge complete: {} new items, {} snapshots added, {} replaced",
+                    r.new_items, r.inserted, r.replaced
+                )
+            }).map_err(|e| e.to_string())
+        }
+        Task::IntegrityCheck => {
+            log("Running integrity check...".into());
+            loader::integrity_check(db_path)
+                .map(|r| format!("Integrity check: {}", r))
+                .map_err(|e| e.to_string())
+        }
+        Task::ExportParquet { path, item } => {
+            match &item {
+                Some((_, name)) => log(format!("Exporting {} history to {}...", name, path)),
+                None => log(format!("Exporting all history to {}...", path)),
+            }
+            loader::export_parquet(db_path, &path, item.map(|(id, _)| id))
+                .map(|r| match r.skipped {
+                    0 => format!("Wrote {} snapshots to {}", r.written, path),
+                    skipped => format!("Wrote {} snapshots to {}, skipped {} with an unreadable date", r.written, path, skipped),
+                })
+        }
+        Task::FetchOsrsPrices => {
+            log("Fetching Old School prices from the OSRS Wiki...".into());
+            osrs::fetch_daily()
+                .and_then(|prices| loader::record_daily_prices(db_path, &prices).map_err(|e| e.to_string()))
+                .map(|items| format!("Recorded today's price for {} items", items))
+        }
+        Task::InstallDatabase { source } => {
+            log(format!("Installing database from {}...", source));
+            loader::install_database(&source, db_path)
+                .map(|bytes| format!("Database ready ({:.1} MB)", bytes as f64 / 1_048_576.0))
+                .map_err(|e| e.to_string())
+        }
+    };
+
+    let ok = result.is_ok();
+    match result {
+        Ok(msg) => log(format!("{} ({:.1}s)", msg, started.elapsed().as_secs_f64())),
+        Err(e) => log(format!("Failed: {}", e)),
+    }
+    let _ = tx.send(Progress::Done { task: finished, ok });
+}
+
+// Browsers can't spawn threads, so the web build runs tasks inline
+#[cfg(not(target_arch = "wasm32"))]
+pub fn spawn(f: impl FnOnce() + Send + 'static) {
+    std::thread::spawn(f);
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn spawn(f: impl FnOnce() + Send + 'static) {
+    f();
+}
@@ -1,23 +1,194 @@
-use serde::{Serialize, Deserialize};
+use serde::{Deserializer, Serialize, Deserialize};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, Sub};
 
-#[derive(Debug, Serialize, Deserialize)]
+// A whole-gp amount. i64 because party hats and other rares trade above
+// i32::MAX (~2.1B); SQLite stores INTEGER as 64-bit so nothing is lost on load.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize)]
+#[serde(transparent)]
+pub struct Gp(pub i64);
+
+// Written as a whole number, but journals, holdings and bundles saved before
+// amounts were whole gp hold floats, which round to the nearest gp
+impl<'de> Deserialize<'de> for Gp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Gp, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Amount {
+            Whole(i64),
+            Float(f64),
+        }
+
+        Ok(match Amount::deserialize(deserializer)? {
+            Amount::Whole(gp) => Gp(gp),
+            Amount::Float(gp) => Gp::from_f64(gp),
+        })
+    }
+}
+
+impl Gp {
+    pub const ZERO: Gp = Gp(0);
+
+    // Nearest whole gp
+    pub fn from_f64(value: f64) -> Gp {
+        Gp(value.round() as i64)
+    }
+
+    pub fn as_f64(self) -> f64 {
+        self.0 as f64
+    }
+}
+
+impl Add for Gp {
+    type Output = Gp;
+    fn add(self, rhs: Gp) -> Gp {
+        Gp(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for Gp {
+    fn add_assign(&mut self, rhs: Gp) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sum for Gp {
+    fn sum<I: Iterator<Item = Gp>>(iter: I) -> Gp {
+        iter.fold(Gp::ZERO, Add::add)
+    }
+}
+
+impl Sub for Gp {
+    type Output = Gp;
+    fn sub(self, rhs: Gp) -> Gp {
+        Gp(self.0 - rhs.0)
+    }
+}
+
+impl Mul<i64> for Gp {
+    type Output = Gp;
+    fn mul(self, qty: i64) -> Gp {
+        Gp(self.0 * qty)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ItemSnapshot {
     pub item_id: i32,
     pub name: String,
     pub ge_limit: i32,
     pub record_date: String,
-    pub price: i32,
+    pub price: Gp,
     pub volume: i32,
+    #[serde(default)]
+    pub manual: bool,  // Entered by hand from an in-game observation; its volume means nothing
+}
+
+// An item the user currently owns, valued against each scan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Holding {
+    pub item_id: i32,
+    pub name: String,
+    pub qty: i64,
+    pub cost_basis: Gp,  // Average price paid per item
+    pub target_sell: Option<Gp>,
+}
+
+// A dated user note on an item, drawn as a marker on its price chart
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Annotation {
+    pub id: i64,
+    pub item_id: i32,
+    pub date: String,
+    pub text: String,
+}
+
+// One history row as the collector wrote it, for the raw snapshot viewer
+#[derive(Debug, Clone)]
+pub struct RawSnapshot {
+    pub rowid: i64,
+    pub date: String,
+    pub price: Gp,
+    pub volume: Option<i64>,
+    pub manual: bool,
+}
+
+// A hand correction to a history row, kept in the audit log. No new price
+// means the row was deleted.
+#[derive(Debug, Clone)]
+pub struct SnapshotEdit {
+    pub edited_at: String,
+    pub record_date: String,
+    pub old_price: Gp,
+    pub old_volume: Option<i64>,
+    pub new_price: Option<Gp>,
+    pub new_volume: Option<i64>,
+}
+
+// One item's recommendation as recorded in the scan log
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]  // only the SQLite loader writes these
+#[derive(Debug, Clone)]
+pub struct ScanEntry {
+    pub item_id: i32,
+    pub tier: String,
+    pub score: i32,
+    pub buy: Gp,
+    pub sell: Gp,
+}
+
+// Identifies what's in a database's price history. The newest row changes
+// whenever the collector writes, and the date rolls the scan's 90-day window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataVersion {
+    pub last_rowid: i64,
+    pub last_date: String,
+    pub today: String,
+}
+
+// One item's price for the day, as fetched from a prices API
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]  // only the SQLite loader writes these
+#[derive(Debug, Clone)]
+pub struct DailyPrice {
+    pub item_id: i32,
+    pub name: String,
+    pub ge_limit: i64,
+    pub high_alch: i64,
+    pub price: Gp,
+    pub volume: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ItemCoverage {
+    pub name: String,
+    pub rows: i64,
+    pub first_date: String,
+    pub last_date: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DbStats {
+    pub file_size: u64,
+    pub item_count: i64,
+    pub history_rows: i64,
+    pub first_date: String,
+    pub last_date: String,
+    pub coverage: Vec<ItemCoverage>,
 }
 
 pub struct ItemStats {
     pub item_id: i32,
     pub name: String,
 
-    pub current_price: f64,
+    pub current_price: f64,  // Latest snapshot price
     pub prev_price: f64,
+    pub price_1d_ago: Option<f64>,
+    pub price_7d_ago: Option<f64>,
+
+    pub first_date: String,
+    pub last_date: String,
 
     pub avg_volume: f64,
+    pub recent_volume: f64,  // Average over the same 14-record window as recent_prices
     pub std_dev: f64,
 
     pub q10: f64,
@@ -34,19 +205,154 @@ pub struct ItemStats {
     pub outliers_removed: usize,
     pub recent_prices: Vec<f64>,  // Last 14 records (sorted) for time-weighted analysis
     pub recent_prices_chrono: Vec<f64>,  // Last 14 records in chronological order
+    pub history: Vec<(i64, f64)>,  // (day number, price) for every record, oldest first
+    pub timeframes: [Option<Timeframe>; 3],  // One per stats::TIMEFRAMES window; None with under two snapshots in it
 }
 
+// What buying at a given price would have gone through historically
+#[derive(Debug, Clone, Copy)]
+pub struct Drawdown {
+    pub max_pct: f64,  // Deepest fall below the buy price after a fill
+    pub recovery_days: Option<i64>,  // Days that worst fall took to get back to the buy price; None = still under
+    pub longest_underwater_days: i64,  // Longest stretch of any fill spent below the buy price
+}
+
+// Price behaviour over the last `days` days of an item's history
+#[derive(Debug, Clone, Copy)]
+pub struct Timeframe {
+    pub days: i64,
+    pub q10: f64,
+    pub q90: f64,
+    pub change_pct: f64,  // First snapshot in the window to the latest
+    pub volatility: f64,  // Q10-Q90 width as % of the window's median
+}
+
+// Scan results as handed to anything outside the app - the post-scan hook's
+// command and URL, webhook alert rules and the --export-web snapshot file.
+// They share one version number. Readers should ignore fields they
+// don't know: new ones can be added within a version, and anything missing
+// reads as its default. Renaming, removing or retyping a field bumps
+// SCAN_SCHEMA_VERSION.
+//
+//   {
+//     "schema_version": 1,
+//     "title": "RS3 Grand Exchange Flips",
+//     "data_date": "2026-01-01",       // Latest price snapshot the scan used
+//     "budget": 100000000,             // Amounts are whole gp
+//     "rows": [
+//       {
+//         "item_id": 4151,
+//         "name": "Abyssal whip",
+//         "tier": "GREEN",             // DIAMOND, GOLD, GREEN, NORMAL or CRASH
+//         "score": 74,
+//         "buy": 1180000,
+//         "sell": 1320000,
+//         "qty": 10,                   // Suggested quantity for the budget
+//         "profit": 1136000,           // Whole quantity, after tax
+//         "roi": 9.6,                  // Percent
+//         "sparkline": [1210000, ...],     // Recent prices, oldest first
+//         "summary": "Buy at 1.18M now because ..."
+//       }
+//     ]
+//   }
+//
+// Webhook alert rules post ScanAlerts instead, one entry per rule that
+// matched, and --export-web writes SnapshotExport.
+pub const SCAN_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanResults {
+    pub schema_version: u32,
+    pub title: String,
+    pub data_date: String,
+    pub budget: Gp,
+    pub rows: Vec<ScanRow>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanRow {
+    pub item_id: i32,  // Names aren't unique; this tells same-named items apart
+    pub name: String,
+    pub tier: String,
+    pub score: i32,
+    pub buy: Gp,
+    pub sell: Gp,
+    pub qty: i32,
+    pub profit: Gp,
+    pub roi: f64,
+    pub sparkline: Vec<Gp>,  // Recent prices in chronological order
+    pub summary: String,  // Plain-language reasoning from explain::summary
+}
+
+//   {
+//     "schema_version": 1,
+//     "alerts": [
+//       {
+//         "rule": "Cheap whips",
+//         "channel": "Webhook",
+//         "item_id": 4151,
+//         "item": "Abyssal whip",
+//         "price": 1180000,            // Price the rule saw
+//         "reason": "buy <= 1.2M"
+//       }
+//     ]
+//   }
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanAlerts {
+    pub schema_version: u32,
+    pub alerts: Vec<ScanAlert>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanAlert {
+    pub rule: String,
+    pub channel: String,  // Always "Webhook" here
+    pub item_id: i32,
+    pub item: String,
+    pub price: Gp,
+    pub reason: String,
+}
+
+// The web build's snapshots.json: {"schema_version": 1, "snapshots": [...]}
+// with ItemSnapshot's fields, the last 90 days from the database
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnapshotExport {
+    pub schema_version: u32,
+    pub snapshots: Vec<ItemSnapshot>,
+}
+
+impl SnapshotExport {
+    // Exports from before versioning are a bare array of snapshots
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]  // only the web loader reads these
+    pub fn parse(bytes: &[u8]) -> serde_json::Result<Vec<ItemSnapshot>> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum File {
+            Bare(Vec<ItemSnapshot>),
+            Versioned(SnapshotExport),
+        }
+
+        Ok(match serde_json::from_slice(bytes)? {
+            File::Bare(snapshots) => snapshots,
+            File::Versioned(export) => export.snapshots,
+        })
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct FlipResult {
     pub score: i32,
     pub tier: String,
 
-    pub buy: i32,
-    pub sell: i32,
+    pub buy: Gp,
+    pub sell: Gp,
 
-    pub qty: i32,
-    pub profit: i32,
+    pub profit: Gp,
     pub roi: f64,
     pub avg_volume: f64,
 
@@ -58,13 +364,183 @@ impl FlipResult {
         FlipResult {
             score: 0,
             tier: "NONE".to_string(),
-            buy: 0,
-            sell: 0,
-            qty: 0,
-            profit: 0,
+            buy: Gp::ZERO,
+            sell: Gp::ZERO,
+            profit: Gp::ZERO,
             roi: 0.0,
             avg_volume: 0.0,
             notes: String::new(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flips::{self, Tax};
+    use crate::{loader, stats};
+
+    const PARTYHAT: i64 = 3_000_000_123;  // Well past i32::MAX
+
+    #[test]
+    fn gp_arithmetic_past_i32() {
+        assert_eq!(Gp::from_f64(3_000_000_123.4), Gp(PARTYHAT));
+        assert_eq!(Gp(PARTYHAT).as_f64(), 3_000_000_123.0);
+        assert_eq!(Gp(PARTYHAT) + Gp(PARTYHAT), Gp(6_000_000_246));
+        assert_eq!(Gp(PARTYHAT) - Gp(1), Gp(3_000_000_122));
+        assert_eq!(Gp(PARTYHAT) * 1_000, Gp(3_000_000_123_000));
+        let mut total = Gp::ZERO;
+        total += Gp(PARTYHAT);
+        assert_eq!(total, Gp(PARTYHAT));
+    }
+
+    #[test]
+    fn gp_serializes_as_a_bare_integer() {
+        assert_eq!(serde_json::to_string(&Gp(PARTYHAT)).unwrap(), "3000000123");
+        assert_eq!(serde_json::from_str::<Gp>("3000000123").unwrap(), Gp(PARTYHAT));
+    }
+
+    // Journals and bundles saved before whole-gp amounts hold floats
+    #[test]
+    fn gp_reads_old_float_amounts() {
+        assert_eq!(serde_json::from_str::<Gp>("1180000.4").unwrap(), Gp(1_180_000));
+        assert_eq!(serde_json::from_str::<Gp>("3000000123.0").unwrap(), Gp(PARTYHAT));
+        let holding: Holding = serde_json::from_str(r#"{"item_id": 4151, "name": "Abyssal whip", "qty": 3, "cost_basis": 1180000.6, "target_sell": null}"#).unwrap();
+        assert_eq!(holding.cost_basis, Gp(1_180_001));
+        assert!(serde_json::from_str::<Gp>(r#""12""#).is_err());
+    }
+
+    #[test]
+    fn gp_survives_a_sqlite_round_trip() {
+        let path = std::env::temp_dir().join(format!("rs3_analyzer_gp_{}.db", std::process::id()));
+        let db = path.to_str().unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let price = DailyPrice { item_id: 1050, name: "Santa hat".into(), ge_limit: 2, high_alch: 0, price: Gp(PARTYHAT), volume: 1 };
+        loader::record_daily_prices(db, &[price]).unwrap();
+        let holding = Holding { item_id: 1050, name: String::new(), qty: 1, cost_basis: Gp(PARTYHAT), target_sell: Some(Gp(PARTYHAT + 1)) };
+        loader::save_holding(db, &holding).unwrap();
+        let entry = ScanEntry { item_id: 1050, tier: "GOLD".into(), score: 60, buy: Gp(PARTYHAT), sell: Gp(PARTYHAT + 7) };
+        loader::record_scan(db, &[entry]).unwrap();
+
+        let snaps = loader::load_snapshots(db).unwrap();
+        let history = loader::load_item_history(db, 1050).unwrap();
+        let holdings = loader::load_holdings(db).unwrap();
+        let scan = loader::load_last_scan(db).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(snaps.len(), 1);
+        assert_eq!(snaps[0].price, Gp(PARTYHAT));
+        assert_eq!(history[0].1, PARTYHAT as f64);
+        assert_eq!((holdings[0].cost_basis, holdings[0].target_sell), (Gp(PARTYHAT), Some(Gp(PARTYHAT + 1))));
+        assert_eq!((scan[&1050].buy, scan[&1050].sell), (Gp(PARTYHAT), Gp(PARTYHAT + 7)));
+    }
+
+    #[test]
+    fn analyze_profit_past_i32() {
+        let snaps: Vec<ItemSnapshot> = (0..30)
+            .map(|day| ItemSnapshot {
+                item_id: 1038,
+                name: "Red partyhat".into(),
+                ge_limit: 2,
+                record_date: format!("2026-01-{:02}", day + 1),
+                price: Gp(if day % 2 == 0 { 3_000_000_000 } else { 3_300_000_000 }),
+                volume: 5,
+                manual: false,
+            })
+            .collect();
+        let stats = stats::build_stats(&snaps, 1);
+        let flip = flips::analyze(&stats[0], &Tax::rs3(), &Default::default(), Default::default(), Default::default());
+
+        assert!(flip.buy.0 > i32::MAX as i64);
+        // Above 250M the tax is the flat 5M cap
+        assert_eq!(flip.profit, flip.sell - Gp(5_000_000) - flip.buy);
+        assert!(flip.profit > Gp::ZERO);
+    }
+
+    // Field names version 1 promises; renaming one needs a version bump
+    #[test]
+    fn scan_schema_v1_field_names() {
+        let keys = |value: serde_json::Value| {
+            let mut keys: Vec<String> = value.as_object().unwrap().keys().cloned().collect();
+            keys.sort();
+            keys
+        };
+        let row = ScanRow { buy: Gp(PARTYHAT), sell: Gp(1), profit: Gp(-5), sparkline: vec![Gp(2)], ..Default::default() };
+        let results = ScanResults { budget: Gp(100_000_000), rows: vec![row], ..Default::default() };
+        let json = serde_json::to_value(&results).unwrap();
+        assert_eq!(keys(json.clone()), ["budget", "data_date", "rows", "schema_version", "title"]);
+        assert_eq!(
+            keys(json["rows"][0].clone()),
+            ["buy", "item_id", "name", "profit", "qty", "roi", "score", "sell", "sparkline", "summary", "tier"]
+        );
+
+        let alerts = ScanAlerts { alerts: vec![ScanAlert { price: Gp(1_180_000), ..Default::default() }], ..Default::default() };
+        let alerts_json = serde_json::to_value(&alerts).unwrap();
+        assert_eq!(keys(alerts_json.clone()), ["alerts", "schema_version"]);
+        assert_eq!(keys(alerts_json["alerts"][0].clone()), ["channel", "item", "item_id", "price", "reason", "rule"]);
+
+        // Gp amounts are written as whole numbers, never floats
+        let row = &json["rows"][0];
+        for amount in [&json["budget"], &row["buy"], &row["sell"], &row["profit"], &row["sparkline"][0], &alerts_json["alerts"][0]["price"]] {
+            assert!(amount.is_i64(), "{}", amount);
+        }
+        assert_eq!(row["buy"], serde_json::json!(PARTYHAT));
+        let export = serde_json::to_value(SnapshotExport {
+            schema_version: SCAN_SCHEMA_VERSION,
+            snapshots: vec![ItemSnapshot { item_id: 1, name: String::new(), ge_limit: 1, record_date: String::new(), price: Gp(PARTYHAT), volume: 1, manual: false }],
+        })
+        .unwrap();
+        assert!(export["snapshots"][0]["price"].is_i64());
+    }
+
+    #[test]
+    fn scan_results_read_with_missing_fields() {
+        let json = r#"{"schema_version": 1, "rows": [{"name": "Abyssal whip", "buy": 1180000}]}"#;
+        let results: ScanResults = serde_json::from_str(json).unwrap();
+        assert_eq!(results.schema_version, 1);
+        assert_eq!(results.title, "");
+        assert_eq!(results.rows[0].name, "Abyssal whip");
+        assert_eq!(results.rows[0].buy, Gp(1_180_000));
+        assert_eq!(results.rows[0].qty, 0);
+        assert!(results.rows[0].sparkline.is_empty());
+
+        let alerts: ScanAlerts = serde_json::from_str(r#"{"alerts": [{"item": "Abyssal whip"}]}"#).unwrap();
+        assert_eq!(alerts.schema_version, 0);
+        assert_eq!(alerts.alerts[0].item, "Abyssal whip");
+    }
+
+    #[test]
+    fn scan_results_read_with_unknown_fields() {
+        let json = r#"{
+            "schema_version": 1, "title": "RS3", "data_date": "2026-01-01", "budget": 1.0,
+            "generated_by": "a newer build",
+            "rows": [{"name": "Abyssal whip", "score": 74, "margin_band": [1, 2], "extra": {"nested": true}}]
+        }"#;
+        let results: ScanResults = serde_json::from_str(json).unwrap();
+        assert_eq!(results.title, "RS3");
+        assert_eq!(results.rows[0].score, 74);
+
+        let json = r#"{"schema_version": 1, "alerts": [{"rule": "Cheap", "item_id": 4151, "cooldown": 3}], "sent_at": 0}"#;
+        let alerts: ScanAlerts = serde_json::from_str(json).unwrap();
+        assert_eq!(alerts.alerts[0].rule, "Cheap");
+        assert_eq!(alerts.alerts[0].item_id, 4151);
+    }
+
+    #[test]
+    fn snapshot_export_reads_both_layouts() {
+        let snap = r#"{"item_id": 4151, "name": "Abyssal whip", "ge_limit": 10, "record_date": "2026-01-01", "price": 1180000, "volume": 500}"#;
+        let bare = format!("[{}]", snap);
+        let versioned = format!(r#"{{"schema_version": 1, "snapshots": [{}], "exported_at": "later"}}"#, snap);
+        for json in [bare, versioned] {
+            let snaps = SnapshotExport::parse(json.as_bytes()).unwrap();
+            assert_eq!(snaps.len(), 1);
+            assert_eq!(snaps[0].price, Gp(1_180_000));
+            assert!(!snaps[0].manual);
+        }
+
+        let written = serde_json::to_string(&SnapshotExport { schema_version: SCAN_SCHEMA_VERSION, snapshots: vec![] }).unwrap();
+        assert_eq!(written, r#"{"schema_version":1,"snapshots":[]}"#);
+        assert!(SnapshotExport::parse(b"[]").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,105 @@
+// Monte Carlo outcomes for a single flip: replay the item's own historical
+// daily moves in random order to see how the trade could end.
+use crate::flips::{after_tax, Tax};
+
+const PATHS: usize = 2000;
+
+pub struct Simulation {
+    pub profits: Vec<f64>,  // One per path, sorted ascending
+    pub p5: f64,
+    pub p50: f64,
+    pub p95: f64,
+    pub sold_pct: f64,  // Share of paths that reached the sell price
+}
+
+// Log returns between records on consecutive days; gaps are skipped so a
+// missing week isn't counted as one day's move.
+pub fn daily_returns(history: &[(i64, f64)]) -> Vec<f64> {
+    history
+        .windows(2)
+        .filter(|w| w[1].0 - w[0].0 == 1 && w[0].1 > 0.0 && w[1].1 > 0.0)
+        .map(|w| (w[1].1 / w[0].1).ln())
+        .collect()
+}
+
+// SplitMix64 - plenty for resampling, and seeding per item keeps the
+// histogram steady between frames
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn index(&mut self, len: usize) -> usize {
+        (self.next() % len as u64) as usize
+    }
+}
+
+// Buys `qty` at `buy`, then walks `horizon_days` of resampled returns. A path
+// sells at `sell` the first day it gets there, otherwise at its final price.
+#[allow(clippy::too_many_arguments)]
+pub fn simulate(
+    name: &str,
+    returns: &[f64],
+    buy: f64,
+    sell: f64,
+    qty: i32,
+    tax: &Tax,
+    horizon_days: u32,
+    seed: u64,
+) -> Option<Simulation> {
+    if returns.len() < 5 || buy <= 0.0 || qty <= 0 {
+        return None;
+    }
+
+    let mut rng = Rng(seed);
+    let mut sold = 0;
+    let mut profits: Vec<f64> = (0..PATHS)
+        .map(|_| {
+            let mut price = buy;
+            let mut exit = None;
+            for _ in 0..horizon_days {
+                price *= returns[rng.index(returns.len())].exp();
+                if price >= sell {
+                    exit = Some(sell);
+                    break;
+                }
+            }
+            if exit.is_some() {
+                sold += 1;
+            }
+            let exit = exit.unwrap_or(price.round());
+            (after_tax(name, exit, tax) - buy) * qty as f64
+        })
+        .collect();
+    profits.sort_by(|a, b| a.total_cmp(b));
+
+    let pick = |q: f64| profits[((profits.len() - 1) as f64 * q).round() as usize];
+    Some(Simulation {
+        p5: pick(0.05),
+        p50: pick(0.50),
+        p95: pick(0.95),
+        sold_pct: sold as f64 / PATHS as f64 * 100.0,
+        profits,
+    })
+}
+
+// Equal-width bins over the simulated profits, as (centre, width, count)
+pub fn histogram(profits: &[f64], bins: usize) -> Vec<(f64, f64, usize)> {
+    let (Some(&min), Some(&max)) = (profits.first(), profits.last()) else { return vec![] };
+    let width = ((max - min) / bins as f64).max(1.0);
+    let mut counts = vec![0; bins];
+    for p in profits {
+        counts[(((p - min) / width) as usize).min(bins - 1)] += 1;
+    }
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, c)| (min + (i as f64 + 0.5) * width, width, c))
+        .collect()
+}
@@ -0,0 +1,27 @@
+// Stand-in for platforms without tray support (Linux needs GTK, the web has no tray)
+use eframe::egui;
+
+#[allow(dead_code)]  // Never produced without a tray
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrayCommand {
+    ScanNow,
+    Open,
+    TogglePause,
+    Quit,
+}
+
+pub struct Tray;
+
+impl Tray {
+    pub fn new(_ctx: &egui::Context) -> Option<Tray> {
+        None
+    }
+
+    pub fn poll(&self) -> Vec<TrayCommand> {
+        Vec::new()
+    }
+
+    pub fn set_highlight(&mut self, _on: bool) {}
+
+    pub fn set_paused(&self, _paused: bool) {}
+}
@@ -0,0 +1,104 @@
+// A stand-in for the GE's offer book, which the game never shows. Every past
+// snapshot's volume is piled onto its price, so the busy levels are the prices
+// the item actually trades at - not just how often it sat there. Levels below
+// today's price stand in for waiting buy offers, levels above for sell offers.
+// Its volume peaks are the support and resistance prices.
+use crate::stats::quantile;
+
+// Prices beyond these percentiles are folded into the end levels, so one odd
+// day doesn't stretch the book and squash everything else into a few rows
+const TRIM: f64 = 0.01;
+
+// A peak needs at least this multiple of the average level's volume to count
+// as a node; flatter books have no prices worth singling out
+const NODE_STRENGTH: f64 = 1.5;
+
+pub struct Level {
+    pub low: f64,
+    pub high: f64,
+    pub volume: f64,
+}
+
+impl Level {
+    pub fn mid(&self) -> f64 {
+        (self.low + self.high) / 2.0
+    }
+}
+
+pub struct OfferBook {
+    pub levels: Vec<Level>,  // Cheapest first
+    pub total: f64,
+}
+
+// `trades` is (price, volume) per snapshot
+pub fn build(trades: &[(f64, f64)], bins: usize) -> Option<OfferBook> {
+    let mut prices: Vec<f64> = trades.iter().filter(|t| t.1 > 0.0).map(|t| t.0).collect();
+    if prices.len() < 2 || bins == 0 {
+        return None;
+    }
+    prices.sort_by(|a, b| a.total_cmp(b));
+    let (min, max) = (quantile(&prices, TRIM), quantile(&prices, 1.0 - TRIM));
+    if max <= min {
+        return None;
+    }
+
+    let width = (max - min) / bins as f64;
+    let mut levels: Vec<Level> = (0..bins)
+        .map(|i| Level { low: min + i as f64 * width, high: min + (i + 1) as f64 * width, volume: 0.0 })
+        .collect();
+    for &(price, volume) in trades.iter().filter(|t| t.1 > 0.0) {
+        let i = (((price - min) / width).max(0.0) as usize).min(bins - 1);
+        levels[i].volume += volume;
+    }
+    let total = levels.iter().map(|l| l.volume).sum();
+    Some(OfferBook { levels, total })
+}
+
+impl OfferBook {
+    // Share of traded volume (%) at or below `price`, taking a level as
+    // evenly spread across its range
+    pub fn share_below(&self, price: f64) -> f64 {
+        if self.total <= 0.0 {
+            return 0.0;
+        }
+        let below: f64 = self.levels
+            .iter()
+            .map(|l| l.volume * ((price - l.low) / (l.high - l.low)).clamp(0.0, 1.0))
+            .sum();
+        below / self.total * 100.0
+    }
+
+    // The level the most volume changed hands at
+    pub fn busiest(&self) -> Option<&Level> {
+        self.levels.iter().max_by(|a, b| a.volume.total_cmp(&b.volume))
+    }
+
+    // High-volume nodes, strongest first: levels busier than both neighbours
+    // and well above average. Prices the item keeps trading at tend to stop a
+    // fall (support) or stall a rise (resistance).
+    pub fn nodes(&self) -> Vec<&Level> {
+        let average = self.total / self.levels.len() as f64;
+        let mut nodes: Vec<&Level> = self.levels
+            .iter()
+            .enumerate()
+            .filter(|(i, l)| {
+                let before = i.checked_sub(1).map_or(0.0, |j| self.levels[j].volume);
+                let after = self.levels.get(i + 1).map_or(0.0, |n| n.volume);
+                l.volume >= average * NODE_STRENGTH && l.volume >= before && l.volume >= after
+            })
+            .map(|(_, l)| l)
+            .collect();
+        nodes.sort_by(|a, b| b.volume.total_cmp(&a.volume));
+        nodes
+    }
+
+    // Strongest node entirely below `price` - a buy target backed by real trading
+    pub fn support(&self, price: f64) -> Option<&Level> {
+        self.nodes().into_iter().find(|l| l.high <= price)
+    }
+
+    // Strongest node entirely above `price` - a sell target the same way
+    pub fn resistance(&self, price: f64) -> Option<&Level> {
+        self.nodes().into_iter().find(|l| l.low >= price)
+    }
+}
@@ -0,0 +1,84 @@
+// Old School prices from the OSRS Wiki's real-time prices API. There's no
+// collector for Old School like there is for RS3, so each fetch records one
+// daily snapshot - the 24-hour average price and traded volume - and the
+// history builds up a day at a time.
+//
+// The API asks for a User-Agent that says what's calling it and how to get in
+// touch; requests without one can be blocked.
+use crate::model::{DailyPrice, Gp};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[cfg(not(target_arch = "wasm32"))]
+const API: &str = "https://prices.runescape.wiki/api/v1/osrs";
+#[cfg(not(target_arch = "wasm32"))]
+const USER_AGENT: &str = "rs3_analyzer - Grand Exchange flip analyzer (github.com/AlexMollard/rs3_analyzer)";
+
+#[derive(Deserialize)]
+struct MappingItem {
+    id: i32,
+    name: String,
+    limit: Option<i64>,
+    highalch: Option<i64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DayAverage {
+    avg_high_price: Option<i64>,
+    high_price_volume: i64,
+    avg_low_price: Option<i64>,
+    low_price_volume: i64,
+}
+
+#[derive(Deserialize)]
+struct DayAverages {
+    data: HashMap<String, DayAverage>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn get<T: serde::de::DeserializeOwned>(endpoint: &str) -> Result<T, String> {
+    let mut request = ehttp::Request::get(format!("{}/{}", API, endpoint));
+    request.headers.insert("User-Agent", USER_AGENT);
+    let response = ehttp::fetch_blocking(&request)?;
+    if !response.ok {
+        return Err(format!("{} returned {} {}", endpoint, response.status, response.status_text));
+    }
+    serde_json::from_slice(&response.bytes).map_err(|e| format!("{}: {}", endpoint, e))
+}
+
+// The browser can't make blocking requests, and the web build has no database to fill anyway
+#[cfg(target_arch = "wasm32")]
+fn get<T: serde::de::DeserializeOwned>(_endpoint: &str) -> Result<T, String> {
+    Err("not available in the web build".to_string())
+}
+
+// Today's price for every item traded in the last 24 hours. The price is the
+// midpoint of the average instant-buy and instant-sell prices, or whichever
+// one there is.
+pub fn fetch_daily() -> Result<Vec<DailyPrice>, String> {
+    let mapping: Vec<MappingItem> = get("mapping")?;
+    let averages: DayAverages = get("24h")?;
+
+    let mut prices: Vec<DailyPrice> = mapping
+        .into_iter()
+        .filter_map(|item| {
+            let day = averages.data.get(&item.id.to_string())?;
+            let price = match (day.avg_high_price, day.avg_low_price) {
+                (Some(high), Some(low)) => (high + low) as f64 / 2.0,
+                (Some(one), None) | (None, Some(one)) => one as f64,
+                (None, None) => return None,
+            };
+            Some(DailyPrice {
+                item_id: item.id,
+                name: item.name,
+                ge_limit: item.limit.unwrap_or(0),
+                high_alch: item.highalch.unwrap_or(0),
+                price: Gp::from_f64(price),
+                volume: day.high_price_volume + day.low_price_volume,
+            })
+        })
+        .collect();
+    prices.sort_by_key(|p| p.item_id);
+    Ok(prices)
+}
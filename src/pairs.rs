@@ -0,0 +1,124 @@
+// Pair trades: items whose prices normally move together (substitutes, set
+// pieces, a potion and its ingredients) that have drifted apart. The cheap
+// side is bought and the dear side sold now - or its purchase put off - on the
+// bet the price ratio returns to its usual level.
+use crate::model::ItemStats;
+use std::collections::HashMap;
+
+const MAX_ITEMS: usize = 300;  // Most-traded items considered; pairs grow with the square
+const MIN_VOLUME: f64 = 1_000.0;
+const MIN_COMMON_DAYS: usize = 30;
+const MIN_CORRELATION: f64 = 0.7;  // Of daily log returns
+const MAX_PAIRS: usize = 50;
+
+pub struct Pair {
+    pub a: String,
+    pub b: String,
+    pub correlation: f64,
+    pub ratio_history: Vec<(i64, f64)>,  // (day, price of a / price of b) on shared days
+    pub mean_ratio: f64,
+    pub std_ratio: f64,
+    pub z: f64,  // How many standard deviations today's ratio sits from the mean
+}
+
+impl Pair {
+    pub fn current_ratio(&self) -> f64 {
+        self.ratio_history.last().map_or(self.mean_ratio, |(_, r)| *r)
+    }
+
+    // (long, short): `a` is cheap relative to `b` when the ratio is below its mean
+    pub fn legs(&self) -> (&str, &str) {
+        if self.z < 0.0 { (&self.a, &self.b) } else { (&self.b, &self.a) }
+    }
+
+    // How much the long leg gains on the short one if the ratio returns to its mean
+    pub fn reversion_pct(&self) -> f64 {
+        let ratio = self.current_ratio();
+        let relative = if self.z < 0.0 { self.mean_ratio / ratio } else { ratio / self.mean_ratio };
+        (relative - 1.0) * 100.0
+    }
+}
+
+fn correlation(xs: &[f64], ys: &[f64]) -> f64 {
+    let n = xs.len() as f64;
+    let (mx, my) = (xs.iter().sum::<f64>() / n, ys.iter().sum::<f64>() / n);
+    let (mut cov, mut vx, mut vy) = (0.0, 0.0, 0.0);
+    for (x, y) in xs.iter().zip(ys) {
+        cov += (x - mx) * (y - my);
+        vx += (x - mx).powi(2);
+        vy += (y - my).powi(2);
+    }
+    if vx <= 0.0 || vy <= 0.0 { 0.0 } else { cov / (vx * vy).sqrt() }
+}
+
+fn mean_std(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+    (mean, var.sqrt())
+}
+
+// Highly correlated pairs whose price ratio sits at least `min_z` standard
+// deviations from normal, most diverged first
+pub fn find_pairs(stats: &[ItemStats], min_z: f64) -> Vec<Pair> {
+    let mut liquid: Vec<&ItemStats> = stats
+        .iter()
+        .filter(|s| s.avg_volume >= MIN_VOLUME && s.history.len() >= MIN_COMMON_DAYS)
+        .collect();
+    liquid.sort_by(|a, b| b.avg_volume.total_cmp(&a.avg_volume));
+    liquid.truncate(MAX_ITEMS);
+
+    let by_day: Vec<HashMap<i64, f64>> = liquid
+        .iter()
+        .map(|s| s.history.iter().copied().filter(|(_, p)| *p > 0.0).collect())
+        .collect();
+
+    let mut pairs = Vec::new();
+    for i in 0..liquid.len() {
+        for j in i + 1..liquid.len() {
+            let a = &liquid[i];
+            let shared: Vec<(i64, f64, f64)> = a.history
+                .iter()
+                .filter_map(|(day, _)| Some((*day, *by_day[i].get(day)?, *by_day[j].get(day)?)))
+                .collect();
+            if shared.len() < MIN_COMMON_DAYS {
+                continue;
+            }
+
+            let (ra, rb): (Vec<f64>, Vec<f64>) = shared
+                .windows(2)
+                .map(|w| ((w[1].1 / w[0].1).ln(), (w[1].2 / w[0].2).ln()))
+                .unzip();
+            let corr = correlation(&ra, &rb);
+            if corr < MIN_CORRELATION {
+                continue;
+            }
+
+            let ratio_history: Vec<(i64, f64)> = shared.iter().map(|(d, pa, pb)| (*d, pa / pb)).collect();
+            let ratios: Vec<f64> = ratio_history.iter().map(|(_, r)| *r).collect();
+            let (mean_ratio, std_ratio) = mean_std(&ratios);
+            if std_ratio <= 0.0 {
+                continue;
+            }
+            let z = (ratios[ratios.len() - 1] - mean_ratio) / std_ratio;
+            if z.abs() < min_z {
+                continue;
+            }
+
+            let b = &liquid[j];
+            pairs.push(Pair {
+                a: a.name.clone(),
+                b: b.name.clone(),
+                correlation: corr,
+                ratio_history,
+                mean_ratio,
+                std_ratio,
+                z,
+            });
+        }
+    }
+
+    pairs.sort_by(|x, y| y.z.abs().total_cmp(&x.z.abs()));
+    pairs.truncate(MAX_PAIRS);
+    pairs
+}
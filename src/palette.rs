@@ -0,0 +1,93 @@
+// Colours for the good/bad and tier indicators. Standard is the game-like
+// red/green; the others stay readable with colour-blindness, and add a +/-
+// sign so gains and losses don't rely on colour at all.
+use eframe::egui::Color32;
+use serde::{Serialize, Deserialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Palette {
+    #[default]
+    Standard,
+    BlueOrange,  // Okabe-Ito hues, safe for red-green colour-blindness
+    HighContrast,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 3] = [Palette::Standard, Palette::BlueOrange, Palette::HighContrast];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Palette::Standard => "Red / green",
+            Palette::BlueOrange => "Blue / orange",
+            Palette::HighContrast => "High contrast",
+        }
+    }
+
+    pub fn gain(self, strong: bool) -> Color32 {
+        match (self, strong) {
+            (Palette::Standard, true) => Color32::from_rgb(100, 255, 100),
+            (Palette::Standard, false) => Color32::from_rgb(150, 255, 150),
+            (Palette::BlueOrange, true) => Color32::from_rgb(86, 180, 233),
+            (Palette::BlueOrange, false) => Color32::from_rgb(150, 210, 240),
+            (Palette::HighContrast, _) => Color32::WHITE,
+        }
+    }
+
+    // Small but positive figures
+    pub fn gain_faint(self) -> Color32 {
+        match self {
+            Palette::Standard => Color32::from_rgb(200, 255, 200),
+            Palette::BlueOrange => Color32::from_rgb(195, 228, 246),
+            Palette::HighContrast => Color32::from_rgb(220, 220, 220),
+        }
+    }
+
+    pub fn loss(self, strong: bool) -> Color32 {
+        match (self, strong) {
+            (Palette::Standard, true) => Color32::from_rgb(255, 100, 100),
+            (Palette::Standard, false) => Color32::from_rgb(255, 150, 150),
+            (Palette::BlueOrange, true) => Color32::from_rgb(230, 159, 0),
+            (Palette::BlueOrange, false) => Color32::from_rgb(240, 195, 100),
+            (Palette::HighContrast, _) => Color32::from_rgb(255, 230, 0),
+        }
+    }
+
+    pub fn neutral(self) -> Color32 {
+        Color32::from_rgb(200, 200, 200)
+    }
+
+    pub fn tier(self, tier: &str) -> Color32 {
+        match (self, tier) {
+            (Palette::Standard, "DIAMOND") => Color32::from_rgb(0, 255, 255),
+            (Palette::Standard, "GOLD") => Color32::from_rgb(255, 200, 50),
+            (Palette::Standard, "GREEN") => Color32::from_rgb(50, 255, 50),
+            (Palette::Standard, "CRASH") => Color32::RED,
+            (Palette::BlueOrange, "DIAMOND") => Color32::from_rgb(86, 180, 233),
+            (Palette::BlueOrange, "GOLD") => Color32::from_rgb(240, 228, 66),
+            (Palette::BlueOrange, "GREEN") => Color32::from_rgb(0, 158, 115),
+            (Palette::BlueOrange, "CRASH") => Color32::from_rgb(213, 94, 0),
+            (Palette::HighContrast, "DIAMOND") => Color32::WHITE,
+            (Palette::HighContrast, "GOLD") => Color32::from_rgb(255, 230, 0),
+            (Palette::HighContrast, "GREEN") => Color32::from_rgb(0, 255, 255),
+            (Palette::HighContrast, "CRASH") => Color32::from_rgb(255, 0, 255),
+            _ => Color32::LIGHT_GRAY,
+        }
+    }
+
+    // Full-strength heatmap colours for rising and falling
+    pub fn heat(self, rising: bool) -> (f32, f32, f32) {
+        match (self, rising) {
+            (Palette::Standard, true) => (40.0, 160.0, 60.0),
+            (Palette::Standard, false) => (190.0, 50.0, 40.0),
+            (Palette::BlueOrange, true) => (0.0, 114.0, 178.0),
+            (Palette::BlueOrange, false) => (213.0, 94.0, 0.0),
+            (Palette::HighContrast, true) => (230.0, 230.0, 230.0),
+            (Palette::HighContrast, false) => (200.0, 0.0, 200.0),
+        }
+    }
+
+    // Sign shown in front of positive figures so they read without colour
+    pub fn plus(self) -> &'static str {
+        if self == Palette::Standard { "" } else { "+" }
+    }
+}
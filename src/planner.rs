@@ -0,0 +1,124 @@
+// Budget allocation across the filtered flips, in display order.
+
+use crate::model::Gp;
+use crate::report::escape_markdown;
+
+#[derive(Debug, Clone)]
+pub struct PlanLine {
+    pub name: String,
+    pub buy: Gp,
+    pub sell: Gp,
+    pub qty: i32,
+    pub cost: Gp,
+    pub profit: Gp,
+    pub downside: f64,  // Worst-case (P5) loss; per item on candidates, total on plan lines
+}
+
+// Greedily fills the budget from the best-ranked candidates down.
+// Each candidate's `qty` is the most we'd ever buy of it (GE limit / budget cap)
+// and `profit`/`downside` are per item; the returned lines carry allocated qty
+// and totals. With a `risk_budget`, quantities are also trimmed so the summed
+// worst-case loss stays within it, which mostly bites on volatile items.
+pub fn allocate(
+    candidates: impl IntoIterator<Item = PlanLine>,
+    budget: f64,
+    max_lines: usize,
+    risk_budget: Option<f64>,
+) -> Vec<PlanLine> {
+    let mut remaining = budget;
+    let mut risk_left = risk_budget.unwrap_or(f64::INFINITY);
+    let mut plan = Vec::new();
+
+    for c in candidates {
+        if plan.len() >= max_lines || remaining < 1.0 {
+            break;
+        }
+        if c.buy <= Gp::ZERO || c.qty <= 0 {
+            continue;
+        }
+
+        let affordable = (remaining / c.buy.as_f64()) as i32;
+        let mut qty = c.qty.min(affordable);
+        if c.downside > 0.0 {
+            qty = qty.min((risk_left / c.downside).min(i32::MAX as f64) as i32);
+        }
+        if qty <= 0 {
+            continue;
+        }
+
+        let cost = c.buy * qty as i64;
+        let downside = c.downside * qty as f64;
+        remaining -= cost.as_f64();
+        risk_left -= downside;
+        plan.push(PlanLine {
+            qty,
+            cost,
+            profit: c.profit * qty as i64,
+            downside,
+            ..c
+        });
+    }
+
+    plan
+}
+
+pub fn shopping_list_markdown(plan: &[PlanLine], format_gp: impl Fn(f64) -> String) -> String {
+    let mut out = String::from("| Item | Buy | Sell | Qty |\n|---|---:|---:|---:|\n");
+    for line in plan {
+        out.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            escape_markdown(&line.name),
+            format_gp(line.buy.as_f64()),
+            format_gp(line.sell.as_f64()),
+            line.qty
+        ));
+    }
+
+    let total_cost = plan.iter().fold(Gp::ZERO, |sum, l| sum + l.cost);
+    let total_profit = plan.iter().fold(Gp::ZERO, |sum, l| sum + l.profit);
+    let total_downside: f64 = plan.iter().map(|l| l.downside).sum();
+    out.push_str(&format!(
+        "\nTotal cost: {} | Expected profit: {} | Worst case (P5): -{}\n",
+        format_gp(total_cost.as_f64()),
+        format_gp(total_profit.as_f64()),
+        format_gp(total_downside)
+    ));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flips::{net_profit, Tax};
+
+    fn candidate(name: &str, buy: i64, sell: i64, qty: i32) -> PlanLine {
+        PlanLine {
+            name: name.to_string(),
+            buy: Gp(buy),
+            sell: Gp(sell),
+            qty,
+            cost: Gp::ZERO,
+            profit: net_profit(name, Gp(buy), Gp(sell), &Tax::rs3()),
+            downside: 0.0,
+        }
+    }
+
+    #[test]
+    fn shopping_list_totals_are_exact() {
+        let candidates = vec![
+            candidate("Feather", 3, 5, 30_000_000),
+            candidate("Nature rune", 1_234, 1_337, 10_000),
+            candidate("Christmas cracker", 400_000_000, 420_000_000, 5),  // Taxed at the 5M cap
+        ];
+        let plan = allocate(candidates, 1_000_000_000.0, 10, None);
+
+        assert_eq!(plan.iter().map(|l| l.qty).collect::<Vec<_>>(), vec![30_000_000, 10_000, 2]);
+        assert_eq!(plan[0].profit, Gp(60_000_000));
+        assert_eq!(plan[1].profit, Gp(770_000));
+        assert_eq!(plan[2].cost, Gp(800_000_000));
+        assert_eq!(plan[2].profit, Gp(30_000_000));
+
+        let list = shopping_list_markdown(&plan, |v| format!("{:.0}", v));
+        assert!(list.contains("Total cost: 902340000 | Expected profit: 90770000"), "{}", list);
+    }
+}
@@ -0,0 +1,49 @@
+// Post-promotion dump detection. Treasure Hunter and similar promos flood the
+// GE with certain reward items; their price drops while volume spikes, then
+// usually recovers over the following weeks.
+use crate::model::ItemStats;
+
+// Name keywords for item classes that promos hand out in bulk, and the tag shown
+const PROMO_TAGS: &[(&str, &str)] = &[
+    ("protean", "Protean skilling item"),
+    ("sirenic scale", "Promo reward resource"),
+    ("taijitu", "Promo reward resource"),
+    ("crystal triskelion", "Promo reward resource"),
+    ("hydrix", "Gem from promo reward caches"),
+    ("onyx", "Gem from promo reward caches"),
+    ("dragonstone", "Gem from promo reward caches"),
+    ("elder rune", "Promo reward resource"),
+    ("dragon bones", "Promo reward resource"),  // Frost dragon bones too
+    ("overload", "Promo reward potion"),
+];
+
+// Price at least this far down over the week...
+const MIN_DROP_PCT: f64 = 8.0;
+// ...while recent volume runs at least this multiple of normal
+const MIN_VOLUME_SURGE: f64 = 1.5;
+
+pub fn promo_tag(name: &str) -> Option<&'static str> {
+    let lower = name.to_lowercase();
+    PROMO_TAGS.iter().find(|(keyword, _)| lower.contains(keyword)).map(|(_, tag)| *tag)
+}
+
+#[derive(Debug, Clone)]
+pub struct Dump {
+    pub tag: &'static str,
+    pub drop_pct: f64,  // Price change over the last week (positive = fell)
+    pub volume_surge: f64,  // Recent volume as a multiple of the average
+}
+
+// A tagged item whose price fell over the week on heavy volume - a
+// "buy the dump" recovery candidate
+pub fn detect_dump(stats: &ItemStats) -> Option<Dump> {
+    let tag = promo_tag(&stats.name)?;
+    let week_ago = stats.price_7d_ago.filter(|p| *p > 0.0)?;
+    if stats.avg_volume <= 0.0 {
+        return None;
+    }
+
+    let drop_pct = (week_ago - stats.current_price) / week_ago * 100.0;
+    let volume_surge = stats.recent_volume.max(stats.current_volume) / stats.avg_volume;
+    (drop_pct >= MIN_DROP_PCT && volume_surge >= MIN_VOLUME_SURGE).then_some(Dump { tag, drop_pct, volume_surge })
+}
@@ -0,0 +1,108 @@
+// Volatility regimes: what kind of movement an item's price has been making,
+// from rolling statistics over its history. The same spread means different
+// things in each - a steady cycle is what flipping feeds on, while chaotic
+// swings are mostly risk.
+
+const WINDOW: usize = 7;  // Records per rolling window
+const STABLE_VOL: f64 = 0.01;  // Typical daily move below 1%
+const TRENDING_EFFICIENCY: f64 = 0.4;  // Net move vs distance travelled
+const TRENDING_MIN_MOVE: f64 = 0.05;  // ...and at least 5% net over the history
+const CYCLICAL_CROSSINGS: f64 = 0.15;  // Share of records that cross back over the rolling mean
+const CYCLICAL_VOL_OF_VOL: f64 = 0.6;  // Cycles swing by a similar amount each time
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Regime {
+    Stable,
+    Cyclical,
+    Trending,
+    Chaotic,
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn std_dev(values: &[f64]) -> f64 {
+    let m = mean(values);
+    (values.iter().map(|v| (v - m).powi(2)).sum::<f64>() / values.len() as f64).sqrt()
+}
+
+impl Regime {
+    pub const ALL: [Regime; 4] = [Regime::Stable, Regime::Cyclical, Regime::Trending, Regime::Chaotic];
+
+    // None until there are two windows' worth of records
+    pub fn of(history: &[(i64, f64)]) -> Option<Regime> {
+        let prices: Vec<f64> = history.iter().map(|(_, p)| *p).filter(|p| *p > 0.0).collect();
+        if prices.len() < WINDOW * 2 {
+            return None;
+        }
+
+        // A trend covers ground; noise and cycles mostly go back and forth
+        let net_move = (prices[prices.len() - 1] - prices[0]).abs();
+        let travelled: f64 = prices.windows(2).map(|w| (w[1] - w[0]).abs()).sum();
+        if net_move >= prices[0] * TRENDING_MIN_MOVE && net_move / travelled >= TRENDING_EFFICIENCY {
+            return Some(Regime::Trending);
+        }
+
+        // Rolling volatility of daily returns, and how much that itself varies
+        let returns: Vec<f64> = prices.windows(2).map(|w| (w[1] / w[0]).ln()).collect();
+        let rolling_vol: Vec<f64> = returns.windows(WINDOW).map(std_dev).collect();
+        let typical_vol = mean(&rolling_vol);
+        if typical_vol < STABLE_VOL {
+            return Some(Regime::Stable);
+        }
+        let vol_of_vol = std_dev(&rolling_vol) / typical_vol;
+
+        // Cycles keep crossing back over their rolling mean at a steady amplitude
+        let sides: Vec<bool> = prices
+            .windows(WINDOW)
+            .map(|w| w[WINDOW - 1] >= mean(w))
+            .collect();
+        let crossings = sides.windows(2).filter(|w| w[0] != w[1]).count();
+        let crossing_rate = crossings as f64 / sides.len() as f64;
+
+        if crossing_rate >= CYCLICAL_CROSSINGS && vol_of_vol <= CYCLICAL_VOL_OF_VOL {
+            Some(Regime::Cyclical)
+        } else {
+            Some(Regime::Chaotic)
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Regime::Stable => "Stable",
+            Regime::Cyclical => "Cyclical",
+            Regime::Trending => "Trending",
+            Regime::Chaotic => "Chaotic",
+        }
+    }
+
+    pub fn icon(self) -> &'static str {
+        match self {
+            Regime::Stable => "➖",
+            Regime::Cyclical => "🔁",
+            Regime::Trending => "↗",
+            Regime::Chaotic => "🌀",
+        }
+    }
+
+    pub fn describe(self) -> &'static str {
+        match self {
+            Regime::Stable => "Quiet price with small daily moves",
+            Regime::Cyclical => "Swings back and forth around a steady level - good for flipping",
+            Regime::Trending => "Moving steadily one way; the range may not come back",
+            Regime::Chaotic => "Large, irregular swings with no repeating pattern",
+        }
+    }
+
+    // How much of the scorer's volatility bonus an item gets. Chaotic swings
+    // count against it instead of for it.
+    pub fn volatility_factor(self) -> f64 {
+        match self {
+            Regime::Stable => 1.0,
+            Regime::Cyclical => 1.5,
+            Regime::Trending => 0.5,
+            Regime::Chaotic => -0.5,
+        }
+    }
+}
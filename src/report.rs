@@ -0,0 +1,291 @@
+// Standalone scan reports for sharing results outside the app
+use crate::format::format_gp;
+use crate::glossary;
+use crate::model::{Gp, ScanAlert, ScanAlerts, ScanResults, ScanRow, SCAN_SCHEMA_VERSION};
+use crate::rules::Firing;
+
+pub struct Report<'a> {
+    pub title: String,
+    pub data_date: String,
+    pub budget: f64,
+    pub rows: &'a [ScanRow],
+}
+
+// Glossary entries for the columns a report shows
+const REPORT_TERMS: [&str; 7] = ["tier", "score", "buy", "sell", "qty", "profit", "roi"];
+
+const TIERS: [&str; 5] = ["DIAMOND", "GOLD", "GREEN", "NORMAL", "CRASH"];
+
+fn tier_hex(tier: &str) -> &'static str {
+    match tier {
+        "DIAMOND" => "#00ffff",
+        "GOLD" => "#ffc832",
+        "GREEN" => "#32ff32",
+        "CRASH" => "#ff4040",
+        _ => "#c0c0c0",
+    }
+}
+
+// Keeps a value inside its Markdown table cell: a stray | would start a new
+// column and a newline end the row
+pub fn escape_markdown(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn sparkline_svg(prices: &[Gp]) -> String {
+    let prices: Vec<f64> = prices.iter().map(|p| p.as_f64()).collect();
+    let (w, h) = (120.0, 28.0);
+    if prices.len() < 2 {
+        return String::new();
+    }
+
+    let min = prices.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = prices.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let range = if max > min { max - min } else { 1.0 };
+    let step = w / (prices.len() - 1) as f64;
+
+    let points: Vec<String> = prices
+        .iter()
+        .enumerate()
+        .map(|(i, p)| format!("{:.1},{:.1}", i as f64 * step, h - (p - min) / range * h))
+        .collect();
+    let color = if prices[prices.len() - 1] >= prices[0] { "#64ff64" } else { "#ff6464" };
+
+    format!(
+        "<svg width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\"><polyline fill=\"none\" stroke=\"{color}\" stroke-width=\"1.5\" points=\"{}\"/></svg>",
+        points.join(" ")
+    )
+}
+
+fn tier_chart_svg(rows: &[ScanRow]) -> String {
+    let counts: Vec<(&str, usize)> = TIERS
+        .iter()
+        .map(|t| (*t, rows.iter().filter(|r| r.tier == *t).count()))
+        .collect();
+    let max = counts.iter().map(|(_, c)| *c).max().unwrap_or(0).max(1) as f64;
+
+    let mut svg = String::from("<svg width=\"420\" height=\"130\">");
+    for (i, (tier, count)) in counts.iter().enumerate() {
+        let y = i as f64 * 25.0;
+        let width = *count as f64 / max * 300.0;
+        svg.push_str(&format!(
+            "<text x=\"0\" y=\"{:.0}\" fill=\"#c8b48c\" font-size=\"13\">{}</text>\
+             <rect x=\"80\" y=\"{:.0}\" width=\"{:.1}\" height=\"18\" fill=\"{}\"/>\
+             <text x=\"{:.1}\" y=\"{:.0}\" fill=\"#c8b48c\" font-size=\"13\">{}</text>",
+            y + 14.0, tier, y, width, tier_hex(tier), 86.0 + width, y + 14.0, count
+        ));
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+// Bank value against what was paid for it, for the daily digest
+pub struct Portfolio {
+    pub value: Gp,
+    pub cost: Gp,
+}
+
+// The daily email: top flips plus alerts and portfolio P&L
+pub struct Digest<'a> {
+    pub report: Report<'a>,
+    pub alerts: Vec<String>,
+    pub portfolio: Option<Portfolio>,
+}
+
+fn html_open(report: &Report) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{title}</title>\n<style>\
+         body {{ background: #14100a; color: #dcc8a0; font-family: 'Segoe UI', sans-serif; margin: 24px; }}\
+         h1 {{ color: #ffd264; }} table {{ border-collapse: collapse; }}\
+         th {{ color: #c8b48c; text-align: left; border-bottom: 1px solid #504128; padding: 6px 10px; }}\
+         td {{ padding: 4px 10px; border-bottom: 1px solid #2d2416; }} .num {{ text-align: right; }}\
+         .why {{ color: #b4a078; font-size: 0.9em; padding-bottom: 8px; }}\
+         </style></head><body>\n<h1>{title}</h1>\n<p>Market data up to {date} &middot; Budget {budget} &middot; {count} flips</p>\n",
+        title = escape_html(&report.title),
+        date = escape_html(&report.data_date),
+        budget = format_gp(report.budget),
+        count = report.rows.len(),
+    )
+}
+
+fn flips_table(rows: &[ScanRow]) -> String {
+    let mut html = String::from("<table>\n<tr><th>Item</th><th>Tier</th><th>Score</th><th>Buy</th><th>Sell</th>\
+                   <th>Qty</th><th>Profit</th><th>ROI</th><th>14d</th></tr>\n");
+    for r in rows {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td style=\"color:{}\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td>\
+             <td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{:.1}%</td><td>{}</td></tr>\n",
+            escape_html(&r.name),
+            tier_hex(&r.tier),
+            r.tier,
+            r.score,
+            format_gp(r.buy.as_f64()),
+            format_gp(r.sell.as_f64()),
+            r.qty,
+            format_gp(r.profit.as_f64()),
+            r.roi,
+            sparkline_svg(&r.sparkline)
+        ));
+        if !r.summary.is_empty() {
+            html.push_str(&format!("<tr><td colspan=\"9\" class=\"why\">{}</td></tr>\n", escape_html(&r.summary)));
+        }
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+pub fn render_html(report: &Report) -> String {
+    let mut html = html_open(report);
+
+    html.push_str("<h2>Tier distribution</h2>\n");
+    html.push_str(&tier_chart_svg(report.rows));
+
+    html.push_str("\n<h2>Flips</h2>\n");
+    html.push_str(&flips_table(report.rows));
+    html.push_str("<h2>Glossary</h2>\n<dl>\n");
+    for term in REPORT_TERMS.iter().filter_map(|k| glossary::term(k)) {
+        html.push_str(&format!("<dt>{}</dt><dd>{}</dd>\n", escape_html(term.name), escape_html(term.text)));
+    }
+    html.push_str("</dl>\n</body></html>\n");
+    html
+}
+
+pub fn render_digest_html(digest: &Digest, limit: usize) -> String {
+    let mut html = html_open(&digest.report);
+
+    if let Some(p) = &digest.portfolio {
+        let pnl = p.value - p.cost;
+        let pct = if p.cost > Gp::ZERO { pnl.as_f64() / p.cost.as_f64() * 100.0 } else { 0.0 };
+        html.push_str(&format!(
+            "<h2>Portfolio</h2>\n<p>Worth {} against {} paid: <span style=\"color:{}\">{}{} ({:+.1}%)</span></p>\n",
+            format_gp(p.value.as_f64()),
+            format_gp(p.cost.as_f64()),
+            if pnl >= Gp::ZERO { "#64ff64" } else { "#ff6464" },
+            if pnl >= Gp::ZERO { "+" } else { "-" },
+            format_gp(pnl.0.unsigned_abs() as f64),
+            pct
+        ));
+    }
+
+    if !digest.alerts.is_empty() {
+        html.push_str("<h2>Alerts</h2>\n<ul>\n");
+        for alert in &digest.alerts {
+            html.push_str(&format!("<li>{}</li>\n", escape_html(alert)));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    let top = &digest.report.rows[..digest.report.rows.len().min(limit)];
+    html.push_str(&format!("<h2>Top {} flips</h2>\n", top.len()));
+    html.push_str(&flips_table(top));
+    html.push_str("</body></html>\n");
+    html
+}
+
+pub fn render_markdown(report: &Report) -> String {
+    let mut md = format!(
+        "# {}\n\nMarket data up to {} · Budget {} · {} flips\n\n",
+        report.title,
+        report.data_date,
+        format_gp(report.budget),
+        report.rows.len()
+    );
+
+    md.push_str("| Item | Tier | Score | Buy | Sell | Qty | Profit | ROI |\n|---|---|---:|---:|---:|---:|---:|---:|\n");
+    for r in report.rows {
+        md.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} | {} | {:.1}% |\n",
+            escape_markdown(&r.name),
+            r.tier,
+            r.score,
+            format_gp(r.buy.as_f64()),
+            format_gp(r.sell.as_f64()),
+            r.qty,
+            format_gp(r.profit.as_f64()),
+            r.roi
+        ));
+    }
+
+    if report.rows.iter().any(|r| !r.summary.is_empty()) {
+        md.push_str("\n## Why\n\n");
+        for r in report.rows.iter().filter(|r| !r.summary.is_empty()) {
+            md.push_str(&format!("- **{}**: {}\n", escape_markdown(&r.name), escape_markdown(&r.summary)));
+        }
+    }
+
+    md.push_str("\n## Glossary\n\n");
+    for term in REPORT_TERMS.iter().filter_map(|k| glossary::term(k)) {
+        md.push_str(&format!("- **{}**: {}\n", term.name, term.text));
+    }
+    md
+}
+
+// Machine-readable results for post-scan hooks, in the versioned schema
+pub fn render_json(report: &Report) -> String {
+    let results = ScanResults {
+        schema_version: SCAN_SCHEMA_VERSION,
+        title: report.title.clone(),
+        data_date: report.data_date.clone(),
+        budget: Gp::from_f64(report.budget),
+        rows: report.rows.to_vec(),
+    };
+    serde_json::to_string(&results).unwrap_or_default()
+}
+
+// Alert-rule firings for webhook rules, in the versioned schema
+pub fn render_alerts_json(firings: &[&Firing]) -> String {
+    let alerts = ScanAlerts {
+        schema_version: SCAN_SCHEMA_VERSION,
+        alerts: firings
+            .iter()
+            .map(|f| ScanAlert {
+                rule: f.rule.clone(),
+                channel: "Webhook".to_string(),
+                item_id: f.item_id,
+                item: f.item.clone(),
+                price: Gp::from_f64(f.price),
+                reason: f.reason.clone(),
+            })
+            .collect(),
+    };
+    serde_json::to_string(&alerts).unwrap_or_default()
+}
+
+// Alert-rule firings on their own, for rules that notify by email
+pub fn render_alerts_html(lines: &[String]) -> String {
+    let items: String = lines.iter().map(|l| format!("<li>{}</li>", escape_html(l))).collect();
+    format!("<html><body style=\"font-family:sans-serif\"><h2>Alert rules</h2><ul>{}</ul></body></html>", items)
+}
+
+// Short plain-text summary for chat bots; chat apps don't render tables
+pub fn render_text(report: &Report, limit: usize) -> String {
+    let mut text = format!("{} (data up to {})\n", report.title, report.data_date);
+    if report.rows.is_empty() {
+        text.push_str("No flips in the latest scan.\n");
+        return text;
+    }
+    for (i, r) in report.rows.iter().take(limit).enumerate() {
+        text.push_str(&format!(
+            "{}. {} [{}] buy {} sell {} x{} -> {} ({:.1}%)\n",
+            i + 1,
+            r.name,
+            r.tier,
+            format_gp(r.buy.as_f64()),
+            format_gp(r.sell.as_f64()),
+            r.qty,
+            format_gp(r.profit.as_f64()),
+            r.roi
+        ));
+        if !r.summary.is_empty() {
+            text.push_str(&format!("   {}\n", r.summary));
+        }
+    }
+    text
+}
@@ -0,0 +1,503 @@
+// Alert rules: user-defined conditions checked against every item after a
+// scan. A rule matches when any of its groups does, and a group matches when
+// all of its conditions hold, so "(ROI above 10 and volume above 1000) or
+// tier enters DIAMOND" is two groups. Each rule has its own notification
+// channel, and a cooldown so a condition that stays true doesn't alert on
+// every scan.
+use crate::flips::{after_tax, Tax};
+use crate::game::Game;
+use crate::stats;
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+
+const RULES_PATH: &str = "alert_rules.json";
+
+pub const TIERS: [&str; 5] = ["DIAMOND", "GOLD", "GREEN", "NORMAL", "CRASH"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Field {
+    Price,
+    Buy,
+    Sell,
+    Roi,
+    Profit,
+    Score,
+    Volume,
+    Trend,
+}
+
+impl Field {
+    pub const ALL: [Field; 8] = [
+        Field::Price,
+        Field::Buy,
+        Field::Sell,
+        Field::Roi,
+        Field::Profit,
+        Field::Score,
+        Field::Volume,
+        Field::Trend,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Field::Price => "Price",
+            Field::Buy => "Buy",
+            Field::Sell => "Sell",
+            Field::Roi => "ROI %",
+            Field::Profit => "Profit",
+            Field::Score => "Score",
+            Field::Volume => "Avg Vol/Day",
+            Field::Trend => "Trend %",
+        }
+    }
+
+    fn value(self, s: &Subject) -> f64 {
+        match self {
+            Field::Price => s.price,
+            Field::Buy => s.buy,
+            Field::Sell => s.sell,
+            Field::Roi => s.roi,
+            Field::Profit => s.profit,
+            Field::Score => s.score,
+            Field::Volume => s.volume,
+            Field::Trend => s.trend,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Compare {
+    Above,
+    Below,
+}
+
+impl Compare {
+    pub fn label(self) -> &'static str {
+        match self {
+            Compare::Above => "above",
+            Compare::Below => "below",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Condition {
+    Metric { field: Field, compare: Compare, value: f64 },
+    TierEnters(String),  // Wasn't this tier in the previous scan, is now
+    TierLeaves(String),
+}
+
+impl Default for Condition {
+    fn default() -> Self {
+        Condition::Metric { field: Field::Roi, compare: Compare::Above, value: 10.0 }
+    }
+}
+
+impl Condition {
+    pub fn matches(&self, s: &Subject) -> bool {
+        match self {
+            Condition::Metric { field, compare: Compare::Above, value } => field.value(s) > *value,
+            Condition::Metric { field, compare: Compare::Below, value } => field.value(s) < *value,
+            Condition::TierEnters(tier) => s.tier == tier && s.prev_tier.is_some_and(|p| p != tier),
+            Condition::TierLeaves(tier) => s.tier != tier && s.prev_tier == Some(tier.as_str()),
+        }
+    }
+
+    // What made the condition true, for the alert text
+    pub fn describe(&self, s: &Subject) -> String {
+        match self {
+            Condition::Metric { field, compare, value } => {
+                format!("{} {:.1} {} {}", field.label(), field.value(s), compare.label(), value)
+            }
+            Condition::TierEnters(_) | Condition::TierLeaves(_) => {
+                format!("tier {} → {}", s.prev_tier.unwrap_or("?"), s.tier)
+            }
+        }
+    }
+}
+
+// Where a rule's alerts go besides the in-app alert list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Channel {
+    #[default]
+    App,
+    Telegram,
+    Email,
+    Webhook,  // The post-scan webhook URL/command
+}
+
+impl Channel {
+    pub const ALL: [Channel; 4] = [Channel::App, Channel::Telegram, Channel::Email, Channel::Webhook];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Channel::App => "In app only",
+            Channel::Telegram => "Telegram",
+            Channel::Email => "Email",
+            Channel::Webhook => "Webhook",
+        }
+    }
+
+    // Held back by quiet hours and Pause alerts
+    pub fn notifies(self) -> bool {
+        self != Channel::App
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rule {
+    pub name: String,
+    pub enabled: bool,
+    pub items: String,  // Comma-separated item names; empty = every item
+    pub groups: Vec<Vec<Condition>>,
+    pub channel: Channel,
+    pub cooldown_hours: f64,
+    pub last_fired: HashMap<i32, u64>,  // Item ID -> Unix seconds
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Rule {
+            name: "New rule".into(),
+            enabled: true,
+            items: String::new(),
+            groups: vec![vec![Condition::default()]],
+            channel: Channel::App,
+            cooldown_hours: 24.0,
+            last_fired: HashMap::new(),
+        }
+    }
+}
+
+impl Rule {
+    fn applies_to(&self, name: &str) -> bool {
+        let mut names = self.items.split(',').map(str::trim).filter(|n| !n.is_empty()).peekable();
+        names.peek().is_none() || names.any(|n| n.eq_ignore_ascii_case(name))
+    }
+
+    // The first group that matches, described; empty groups never match
+    fn matching_group(&self, s: &Subject) -> Option<String> {
+        self.groups
+            .iter()
+            .find(|g| !g.is_empty() && g.iter().all(|c| c.matches(s)))
+            .map(|g| g.iter().map(|c| c.describe(s)).collect::<Vec<_>>().join(", "))
+    }
+}
+
+// One item's numbers from the scan, as the rules see them
+pub struct Subject<'a> {
+    pub item_id: i32,
+    pub name: &'a str,
+    pub price: f64,
+    pub buy: f64,
+    pub sell: f64,
+    pub roi: f64,
+    pub profit: f64,
+    pub score: f64,
+    pub volume: f64,
+    pub trend: f64,
+    pub tier: &'a str,
+    pub prev_tier: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+pub struct Firing {
+    pub rule: String,
+    pub channel: Channel,
+    pub item_id: i32,
+    pub item: String,
+    pub price: f64,
+    pub reason: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Rules {
+    pub rules: Vec<Rule>,
+    #[serde(skip)]
+    path: String,  // Per-game file it was loaded from
+}
+
+impl Rules {
+    pub fn load(game: Game) -> Self {
+        let path = game.file(RULES_PATH);
+        let mut loaded: Rules = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default();
+        loaded.path = path;
+        loaded
+    }
+
+    pub fn save(&self) {
+        if let Ok(json) = serde_json::to_string_pretty(self) {
+            let _ = std::fs::write(&self.path, json);
+        }
+    }
+
+    // Every enabled rule against every item, skipping items still in a rule's
+    // cooldown. Firing restarts the cooldown, so save afterwards. While
+    // `quiet`, rules that notify outside the app aren't checked at all: a
+    // firing nobody is sent shouldn't start the cooldown, so they fire on the
+    // first scan after quiet hours instead.
+    pub fn evaluate(&mut self, subjects: &[Subject], now: u64, quiet: bool) -> Vec<Firing> {
+        let mut firings = Vec::new();
+        for rule in self.rules.iter_mut().filter(|r| r.enabled && !(quiet && r.channel.notifies())) {
+            let cooldown = (rule.cooldown_hours.max(0.0) * 3600.0) as u64;
+            for s in subjects {
+                if !rule.applies_to(s.name) || rule.last_fired.get(&s.item_id).is_some_and(|&t| now < t + cooldown) {
+                    continue;
+                }
+                let Some(reason) = rule.matching_group(s) else { continue };
+                rule.last_fired.insert(s.item_id, now);
+                firings.push(Firing {
+                    rule: rule.name.clone(),
+                    channel: rule.channel,
+                    item_id: s.item_id,
+                    item: s.name.to_string(),
+                    price: s.price,
+                    reason,
+                });
+            }
+        }
+        firings
+    }
+}
+
+// Firing history, so rules can be judged by what prices did afterwards
+const HISTORY_PATH: &str = "alert_history.json";
+const HISTORY_LIMIT: usize = 5000;
+
+// How long after a firing the price is looked at again
+pub const HORIZONS: [(u64, &str); 3] = [(24, "1d"), (72, "3d"), (168, "7d")];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fired {
+    pub rule: String,
+    pub item_id: i32,
+    pub item: String,
+    pub fired_at: u64,  // Unix seconds
+    pub price: f64,
+    pub later: [Option<f64>; HORIZONS.len()],  // First recorded price on or after the day each horizon ends
+}
+
+// How a rule's firings have played out, per horizon
+pub struct Effectiveness {
+    pub rule: String,
+    pub fired: usize,
+    pub last_fired: u64,
+    pub resolved: [usize; HORIZONS.len()],
+    pub avg_change: [Option<f64>; HORIZONS.len()],  // % from the trigger price
+    pub profitable: [Option<f64>; HORIZONS.len()],  // % of firings where buying then and selling later beat the tax
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct History {
+    pub fired: Vec<Fired>,
+    #[serde(skip)]
+    path: String,
+}
+
+impl History {
+    pub fn load(game: Game) -> Self {
+        let path = game.file(HISTORY_PATH);
+        let mut loaded: History = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default();
+        loaded.path = path;
+        loaded
+    }
+
+    pub fn save(&self) {
+        if let Ok(json) = serde_json::to_string(self) {
+            let _ = std::fs::write(&self.path, json);
+        }
+    }
+
+    pub fn record(&mut self, firings: &[Firing], now: u64) {
+        self.fired.extend(firings.iter().map(|f| Fired {
+            rule: f.rule.clone(),
+            item_id: f.item_id,
+            item: f.item.clone(),
+            fired_at: now,
+            price: f.price,
+            later: [None; HORIZONS.len()],
+        }));
+        if self.fired.len() > HISTORY_LIMIT {
+            self.fired.drain(..self.fired.len() - HISTORY_LIMIT);
+        }
+    }
+
+    // Fills in follow-up prices for horizons that have passed since each
+    // firing, from the item's own history so a scan long after firing still
+    // gives each horizon the price of its day. `history(item_id, from)` is the
+    // item's (date, price) rows from that date on, oldest first. Horizons
+    // with no data yet stay empty. Returns true if anything was filled in.
+    pub fn follow_up(&mut self, mut history: impl FnMut(i32, &str) -> Vec<(String, f64)>, now: u64) -> bool {
+        let mut changed = false;
+        for f in &mut self.fired {
+            let due: Vec<(usize, String)> = HORIZONS
+                .iter()
+                .enumerate()
+                .filter(|(h, (hours, _))| f.later[*h].is_none() && now >= f.fired_at + hours * 3600)
+                .map(|(h, (hours, _))| (h, stats::date_from_day(((f.fired_at + hours * 3600) / 86_400) as i64)))
+                .collect();
+            let Some((_, first)) = due.first() else { continue };
+
+            let rows = history(f.item_id, first);
+            for (h, date) in &due {
+                if let Some((_, price)) = rows.iter().find(|(d, _)| d.as_str() >= date.as_str()) {
+                    f.later[*h] = Some(*price);
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+
+    pub fn forget_rule(&mut self, rule: &str) {
+        self.fired.retain(|f| f.rule != rule);
+    }
+
+    // One row per rule that has fired, most active first
+    pub fn effectiveness(&self, tax: &Tax) -> Vec<Effectiveness> {
+        let mut by_rule: HashMap<&str, Vec<&Fired>> = HashMap::new();
+        for f in &self.fired {
+            by_rule.entry(f.rule.as_str()).or_default().push(f);
+        }
+
+        let mut rows: Vec<Effectiveness> = by_rule
+            .into_iter()
+            .map(|(rule, fired)| {
+                let mut resolved = [0; HORIZONS.len()];
+                let mut avg_change = [None; HORIZONS.len()];
+                let mut profitable = [None; HORIZONS.len()];
+                for h in 0..HORIZONS.len() {
+                    let outcomes: Vec<(f64, bool)> = fired
+                        .iter()
+                        .filter(|f| f.price > 0.0)
+                        .filter_map(|f| {
+                            let later = f.later[h]?;
+                            let net = after_tax(&f.item, later, tax) - f.price;
+                            Some(((later - f.price) / f.price * 100.0, net > 0.0))
+                        })
+                        .collect();
+                    resolved[h] = outcomes.len();
+                    if !outcomes.is_empty() {
+                        let n = outcomes.len() as f64;
+                        avg_change[h] = Some(outcomes.iter().map(|o| o.0).sum::<f64>() / n);
+                        profitable[h] = Some(outcomes.iter().filter(|o| o.1).count() as f64 / n * 100.0);
+                    }
+                }
+                Effectiveness {
+                    rule: rule.to_string(),
+                    fired: fired.len(),
+                    last_fired: fired.iter().map(|f| f.fired_at).max().unwrap_or(0),
+                    resolved,
+                    avg_change,
+                    profitable,
+                }
+            })
+            .collect();
+        rows.sort_by(|a, b| b.fired.cmp(&a.fired).then_with(|| a.rule.cmp(&b.rule)));
+        rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 86_400;
+    const FIRED_DAY: u64 = 20_000;
+
+    fn fired_history() -> History {
+        let mut history = History::default();
+        let firing = Firing {
+            rule: "Cheap whips".into(),
+            channel: Channel::App,
+            item_id: 4151,
+            item: "Abyssal whip".into(),
+            price: 100.0,
+            reason: String::new(),
+        };
+        history.record(&[firing], FIRED_DAY * DAY + 3_600);
+        history
+    }
+
+    fn cheap_rule(channel: Channel) -> Rule {
+        Rule {
+            name: format!("{:?}", channel),
+            groups: vec![vec![Condition::Metric { field: Field::Price, compare: Compare::Below, value: 150.0 }]],
+            channel,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn quiet_hours_hold_notifying_rules_without_starting_their_cooldown() {
+        let mut rules = Rules { rules: vec![cheap_rule(Channel::App), cheap_rule(Channel::Telegram)], ..Default::default() };
+        let whip = Subject {
+            item_id: 4151,
+            name: "Abyssal whip",
+            price: 100.0,
+            buy: 100.0,
+            sell: 120.0,
+            roi: 10.0,
+            profit: 20.0,
+            score: 50.0,
+            volume: 1_000.0,
+            trend: 0.0,
+            tier: "GOLD",
+            prev_tier: Some("GOLD"),
+        };
+        let now = FIRED_DAY * DAY;
+
+        let quiet = rules.evaluate(std::slice::from_ref(&whip), now, true);
+        let telegram_cooldown = rules.rules[1].last_fired.get(&4151).copied();
+        let after = rules.evaluate(&[whip], now + 3_600, false);
+
+        assert_eq!(quiet.iter().map(|f| f.channel).collect::<Vec<_>>(), [Channel::App]);
+        assert_eq!(telegram_cooldown, None);
+        // The app rule is still cooling down; the held Telegram one fires now
+        assert_eq!(after.iter().map(|f| f.channel).collect::<Vec<_>>(), [Channel::Telegram]);
+    }
+
+    // (date, price) rows `days` after the firing day
+    fn rows(days: &[(u64, f64)]) -> Vec<(String, f64)> {
+        days.iter().map(|&(d, p)| (stats::date_from_day((FIRED_DAY + d) as i64), p)).collect()
+    }
+
+    #[test]
+    fn late_scan_fills_each_horizon_from_its_own_day() {
+        let mut history = fired_history();
+        let data = rows(&[(1, 110.0), (2, 115.0), (3, 120.0), (5, 125.0), (7, 130.0), (9, 140.0)]);
+        let mut asked_from = vec![];
+        let changed = history.follow_up(
+            |item_id, from| {
+                assert_eq!(item_id, 4151);
+                asked_from.push(from.to_string());
+                data.iter().filter(|(d, _)| d.as_str() >= from).cloned().collect()
+            },
+            FIRED_DAY * DAY + 10 * DAY,
+        );
+        assert!(changed);
+        assert_eq!(history.fired[0].later, [Some(110.0), Some(120.0), Some(130.0)]);
+        assert_eq!(asked_from, [stats::date_from_day(FIRED_DAY as i64 + 1)]);
+    }
+
+    #[test]
+    fn horizons_without_data_stay_empty() {
+        let mut history = fired_history();
+        // A gap after day 2, then nothing until well past the 3-day mark
+        let data = rows(&[(1, 110.0), (2, 115.0), (6, 128.0)]);
+        history.follow_up(|_, from| data.iter().filter(|(d, _)| d.as_str() >= from).cloned().collect(), FIRED_DAY * DAY + 10 * DAY);
+        assert_eq!(history.fired[0].later, [Some(110.0), Some(128.0), None]);
+
+        // Horizons still in the future aren't looked up at all
+        let mut history = fired_history();
+        history.follow_up(|_, _| rows(&[(1, 110.0)]), FIRED_DAY * DAY + 2 * DAY);
+        assert_eq!(history.fired[0].later, [Some(110.0), None, None]);
+    }
+}
@@ -0,0 +1,124 @@
+// Automatic rescans and quiet hours. Hours are UTC to line up with the GE
+// daily reset; both windows may wrap past midnight (e.g. 22 -> 7).
+use serde::{Serialize, Deserialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanSchedule {
+    pub enabled: bool,
+    pub every_minutes: u32,
+    pub from_hour: u32,  // Only scan from this hour...
+    pub to_hour: u32,  // ...up to (not including) this one
+}
+
+impl Default for ScanSchedule {
+    fn default() -> Self {
+        ScanSchedule {
+            enabled: false,
+            every_minutes: 60,
+            from_hour: 7,
+            to_hour: 23,
+        }
+    }
+}
+
+impl ScanSchedule {
+    pub fn is_due(&self, last_scan: u64, now: u64) -> bool {
+        self.enabled
+            && now.saturating_sub(last_scan) >= self.every_minutes.max(1) as u64 * 60
+            && in_window(hour_of(now), self.from_hour, self.to_hour)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuietHours {
+    pub enabled: bool,
+    pub from_hour: u32,
+    pub to_hour: u32,
+}
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        QuietHours {
+            enabled: false,
+            from_hour: 23,
+            to_hour: 7,
+        }
+    }
+}
+
+impl QuietHours {
+    // Notifications are held back while this is true
+    pub fn is_quiet(&self, now: u64) -> bool {
+        self.enabled && in_window(hour_of(now), self.from_hour, self.to_hour)
+    }
+}
+
+fn hour_of(unix_secs: u64) -> u32 {
+    ((unix_secs % 86_400) / 3_600) as u32
+}
+
+fn in_window(hour: u32, from: u32, to: u32) -> bool {
+    if from == to {
+        true  // A zero-length window means all day
+    } else if from < to {
+        hour >= from && hour < to
+    } else {
+        hour >= from || hour < to
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 20_000 * 86_400;  // Midnight UTC
+
+    fn at(hour: u64, minute: u64) -> u64 {
+        DAY + hour * 3_600 + minute * 60
+    }
+
+    #[test]
+    fn windows_include_the_start_hour_and_exclude_the_end() {
+        assert!(!in_window(6, 7, 23));
+        assert!(in_window(7, 7, 23));
+        assert!(in_window(22, 7, 23));
+        assert!(!in_window(23, 7, 23));
+    }
+
+    #[test]
+    fn windows_wrap_past_midnight() {
+        let hours: Vec<u32> = (0..24).filter(|h| in_window(*h, 22, 6)).collect();
+        assert_eq!(hours, [0, 1, 2, 3, 4, 5, 22, 23]);
+    }
+
+    #[test]
+    fn equal_start_and_end_means_all_day() {
+        assert!((0..24).all(|h| in_window(h, 9, 9)));
+    }
+
+    #[test]
+    fn quiet_hours_follow_the_utc_clock() {
+        let quiet = QuietHours { enabled: true, from_hour: 22, to_hour: 6 };
+        assert!(!quiet.is_quiet(at(21, 59)));
+        assert!(quiet.is_quiet(at(22, 0)));
+        assert!(quiet.is_quiet(at(0, 0)));
+        assert!(quiet.is_quiet(at(5, 59)));
+        assert!(!quiet.is_quiet(at(6, 0)));
+        assert!(!QuietHours { enabled: false, ..quiet }.is_quiet(at(23, 0)));
+    }
+
+    #[test]
+    fn scans_are_due_after_the_interval_inside_the_window() {
+        let schedule = ScanSchedule { enabled: true, every_minutes: 60, from_hour: 22, to_hour: 6 };
+        assert!(!schedule.is_due(at(22, 1), at(22, 59)));
+        assert!(schedule.is_due(at(22, 0), at(23, 0)));
+        assert!(schedule.is_due(at(4, 0), at(5, 59)));
+        assert!(!schedule.is_due(at(4, 0), at(6, 0)));
+        assert!(!ScanSchedule { enabled: false, ..schedule.clone() }.is_due(0, at(23, 0)));
+        // Zero minutes is treated as one
+        assert!(!ScanSchedule { every_minutes: 0, ..schedule.clone() }.is_due(at(23, 0), at(23, 0) + 59));
+        assert!(ScanSchedule { every_minutes: 0, ..schedule }.is_due(at(23, 0), at(23, 1)));
+    }
+}
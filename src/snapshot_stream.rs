@@ -0,0 +1,95 @@
+// Reads a scan's snapshots off the UI thread a batch at a time, so a large
+// database shows a moving progress bar instead of a frozen window. Only a few
+// batches can wait in the channel; past that the reader blocks until the UI
+// drains them, so a slow frame never lets the query run far ahead.
+//
+// Rows come sorted by item, and each batch is folded into the stats as it
+// arrives and then dropped. Besides the queued batches, only the snapshots
+// of the item being read are held, never the whole 90-day window.
+use crate::loader;
+use crate::maintenance::spawn;
+use crate::model::{ItemSnapshot, ItemStats};
+use crate::stats::StatsFold;
+use std::sync::mpsc::{sync_channel, Receiver};
+
+const QUEUED_BATCHES: usize = 4;
+pub const DEFAULT_BATCH_SIZE: usize = 50_000;
+
+enum Message {
+    Total(usize),
+    Batch(Vec<ItemSnapshot>),
+    Done,
+    Failed(String),
+}
+
+#[derive(Default)]
+pub struct SnapshotStream {
+    rx: Option<Receiver<Message>>,
+    fold: Option<StatsFold>,
+    pub loaded: usize,
+    pub total: Option<usize>,  // Unknown until the count query returns
+}
+
+impl SnapshotStream {
+    pub fn is_running(&self) -> bool {
+        self.rx.is_some()
+    }
+
+    // Starting again while a load is running abandons it; the old reader
+    // stops the next time it tries to hand over a batch
+    pub fn start(&mut self, db_path: &str, batch_size: usize, manual_weight: usize) {
+        let (tx, rx) = sync_channel(QUEUED_BATCHES);
+        self.rx = Some(rx);
+        self.fold = Some(StatsFold::new(manual_weight));
+        self.loaded = 0;
+        self.total = None;
+
+        let db_path = db_path.to_string();
+        spawn(move || {
+            if let Ok(total) = loader::count_snapshots(&db_path) {
+                if tx.send(Message::Total(total)).is_err() {
+                    return;
+                }
+            }
+            let result = loader::stream_snapshots(&db_path, batch_size, |batch| tx.send(Message::Batch(batch)).is_ok());
+            let _ = tx.send(match result {
+                Ok(_) => Message::Done,
+                Err(e) => Message::Failed(e.to_string()),
+            });
+        });
+    }
+
+    // Drains waiting batches; call once per frame. Returns the stats once the
+    // last batch is in.
+    pub fn poll(&mut self) -> Option<Result<Vec<ItemStats>, String>> {
+        let rx = self.rx.as_ref()?;
+
+        let mut finished = None;
+        while let Ok(msg) = rx.try_recv() {
+            match msg {
+                Message::Total(total) => self.total = Some(total),
+                Message::Batch(batch) => {
+                    self.loaded += batch.len();
+                    if let Some(fold) = &mut self.fold {
+                        fold.push(batch);
+                    }
+                }
+                Message::Done => finished = self.fold.take().map(|fold| Ok(fold.finish())),
+                Message::Failed(e) => finished = Some(Err(e)),
+            }
+        }
+
+        if finished.is_some() {
+            self.rx = None;
+            self.fold = None;
+        }
+        finished
+    }
+
+    pub fn fraction(&self) -> f32 {
+        match self.total {
+            Some(total) if total > 0 => (self.loaded as f32 / total as f32).min(1.0),
+            _ => 0.0,
+        }
+    }
+}
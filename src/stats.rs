@@ -1,102 +1,503 @@
 use statrs::statistics::Statistics;
-use crate::model::{ItemSnapshot, ItemStats};
+use crate::model::{Drawdown, ItemSnapshot, ItemStats, Timeframe};
 use std::collections::HashMap;
 
-pub fn build_stats(data: &[ItemSnapshot]) -> Vec<ItemStats> {
+// Manual price points count `manual_weight` times in the recent window, and
+// never towards volume
+pub fn build_stats(data: &[ItemSnapshot], manual_weight: usize) -> Vec<ItemStats> {
     let mut map: HashMap<i32, Vec<&ItemSnapshot>> = HashMap::new();
 
     for snap in data {
         map.entry(snap.item_id).or_default().push(snap);
     }
 
-    let mut results = Vec::new();
+    map.into_iter().map(|(id, records)| item_stats(id, &records, manual_weight)).collect()
+}
 
-    for (id, records) in map {
-        let mut prices: Vec<f64> = records.iter().map(|x| x.price as f64).collect();
-        let volumes: Vec<f64> = records.iter().map(|x| x.volume as f64).collect();
+// Builds stats from snapshots that arrive in batches sorted by item, then
+// date. Only the item being read is held; each finished item is folded into
+// its stats and its snapshots dropped.
+pub struct StatsFold {
+    manual_weight: usize,
+    current: Vec<ItemSnapshot>,
+    stats: Vec<ItemStats>,
+}
 
-        // Get recent prices (last 14 days) for time-weighted analysis
-        // Using 14 days instead of 30 to catch rapid crashes/spikes
-        let recent_cutoff = if records.len() >= 14 {
-            records.len() - 14
-        } else {
-            0
-        };
-        let recent_prices_chrono: Vec<f64> = records[recent_cutoff..]
-            .iter()
-            .map(|x| x.price as f64)
-            .collect();
-        let mut recent_prices = recent_prices_chrono.clone();
-        recent_prices.sort_by(|a, b| a.partial_cmp(b).unwrap());
+impl StatsFold {
+    pub fn new(manual_weight: usize) -> Self {
+        StatsFold { manual_weight, current: Vec::new(), stats: Vec::new() }
+    }
 
-        prices.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    pub fn push(&mut self, batch: Vec<ItemSnapshot>) {
+        for snap in batch {
+            if self.current.last().is_some_and(|c| c.item_id != snap.item_id) {
+                self.flush();
+            }
+            self.current.push(snap);
+        }
+    }
 
-        let current = records.last().unwrap();
-        let prev = if records.len() > 1 {
-            records[records.len() - 2].price as f64
-        } else {
-            current.price as f64
-        };
+    pub fn finish(mut self) -> Vec<ItemStats> {
+        self.flush();
+        self.stats
+    }
 
-        let std_dev = prices.clone().std_dev();
+    fn flush(&mut self) {
+        let Some(first) = self.current.first() else { return };
+        let records: Vec<&ItemSnapshot> = self.current.iter().collect();
+        self.stats.push(item_stats(first.item_id, &records, self.manual_weight));
+        self.current.clear();
+    }
+}
 
-        // Remove outliers using IQR method (removes DXP/update spikes)
-        let (filtered_prices, outliers_removed) = remove_outliers(&prices);
-        
-        let price_trend = if filtered_prices.len() >= 3 {
-            calculate_trend(&filtered_prices)
-        } else if prices.len() >= 3 {
-            calculate_trend(&prices)
-        } else {
-            0.0
-        };
+// Stats for one item from its snapshots, oldest first
+fn item_stats(id: i32, records: &[&ItemSnapshot], manual_weight: usize) -> ItemStats {
+    let mut prices: Vec<f64> = records.iter().map(|x| x.price.as_f64()).collect();
+    let volumes: Vec<f64> = records.iter().filter(|x| !x.manual).map(|x| x.volume as f64).collect();
 
-        let stats = ItemStats {
-            item_id: id,
-            name: current.name.clone(),
+    // Get recent prices (last 14 days) for time-weighted analysis
+    // Using 14 days instead of 30 to catch rapid crashes/spikes
+    let recent_cutoff = if records.len() >= 14 {
+        records.len() - 14
+    } else {
+        0
+    };
+    let recent_prices_chrono: Vec<f64> = records[recent_cutoff..]
+        .iter()
+        .map(|x| x.price.as_f64())
+        .collect();
+    let recent_volumes: Vec<f64> = records[recent_cutoff..].iter().filter(|x| !x.manual).map(|x| x.volume as f64).collect();
+    let recent_volume = if recent_volumes.is_empty() { 0.0 } else { recent_volumes.iter().sum::<f64>() / recent_volumes.len() as f64 };
+    let mut recent_prices: Vec<f64> = records[recent_cutoff..]
+        .iter()
+        .flat_map(|x| std::iter::repeat_n(x.price.as_f64(), if x.manual { manual_weight.max(1) } else { 1 }))
+        .collect();
+    recent_prices.sort_by(|a, b| a.total_cmp(b));
 
-            current_price: *prices.last().unwrap(),
-            prev_price: prev,
+    prices.sort_by(|a, b| a.total_cmp(b));
 
-            avg_volume: volumes.mean(),
-            std_dev,
+    let current = records.last().unwrap();
+    let prev = if records.len() > 1 {
+        records[records.len() - 2].price.as_f64()
+    } else {
+        current.price.as_f64()
+    };
 
-            q10: quantile(&prices, 0.10),
-            q50: quantile(&prices, 0.50),
-            q90: quantile(&prices, 0.90),
+    let std_dev = prices.clone().std_dev();
 
-            data_points: prices.len(),
-            ge_limit: current.ge_limit,
-            current_volume: current.volume as f64,
-            prices: prices.clone(),
-            price_trend,
-            filtered_prices,
-            outliers_removed,
-            recent_prices,
-            recent_prices_chrono,
-        };
+    let last_day = day_number(&current.record_date);
+    let price_1d_ago = price_days_ago(records, last_day, 1);
+    let price_7d_ago = price_days_ago(records, last_day, 7);
 
-        results.push(stats);
+    // Remove outliers using IQR method (removes DXP/update spikes)
+    let (filtered_prices, outliers_removed) = remove_outliers(&prices);
+    
+    let price_trend = if filtered_prices.len() >= 3 {
+        calculate_trend(&filtered_prices)
+    } else if prices.len() >= 3 {
+        calculate_trend(&prices)
+    } else {
+        0.0
+    };
+
+    let history: Vec<(i64, f64)> = records
+        .iter()
+        .filter_map(|r| Some((day_number(&r.record_date)?, r.price.as_f64())))
+        .collect();
+
+    ItemStats {
+        item_id: id,
+        name: current.name.clone(),
+
+        current_price: current.price.as_f64(),
+        prev_price: prev,
+        price_1d_ago,
+        price_7d_ago,
+
+        first_date: records[0].record_date.clone(),
+        last_date: current.record_date.clone(),
+
+        avg_volume: if volumes.is_empty() { 0.0 } else { volumes.mean() },
+        recent_volume,
+        std_dev,
+
+        q10: quantile(&prices, 0.10),
+        q50: quantile(&prices, 0.50),
+        q90: quantile(&prices, 0.90),
+
+        data_points: prices.len(),
+        ge_limit: current.ge_limit,
+        current_volume: records.iter().rev().find(|x| !x.manual).map_or(0.0, |x| x.volume as f64),
+        prices: prices.clone(),
+        price_trend,
+        filtered_prices,
+        outliers_removed,
+        recent_prices,
+        recent_prices_chrono,
+        timeframes: TIMEFRAMES.map(|days| timeframe(&history, days)),
+        history,
     }
+}
 
-    results
+// Latest price recorded at least `days` before `last_day`
+fn price_days_ago(records: &[&ItemSnapshot], last_day: Option<i64>, days: i64) -> Option<f64> {
+    let cutoff = last_day? - days;
+    records
+        .iter()
+        .rev()
+        .find(|r| day_number(&r.record_date).is_some_and(|d| d <= cutoff))
+        .map(|r| r.price.as_f64())
 }
 
+// Drawdown for a buyer filled at `buy`. A fill is any day the price touched
+// the buy price; from there the position is under water until the price gets
+// back to `buy`, and the lowest price on the way is its drawdown. None when
+// the price never touched the buy price.
+pub fn drawdown(history: &[(i64, f64)], buy: f64) -> Option<Drawdown> {
+    if buy <= 0.0 {
+        return None;
+    }
 
-fn quantile(sorted: &Vec<f64>, q: f64) -> f64 {
+    let last_day = history.last()?.0;
+    let mut worst: Option<Drawdown> = None;
+    let mut longest = 0;
+    let mut fill: Option<(i64, f64)> = None;  // (day, lowest price since)
+
+    for &(day, price) in history {
+        match fill {
+            None if price <= buy => fill = Some((day, price)),
+            Some((start, low)) if price >= buy => {
+                let pct = (buy - low) / buy * 100.0;
+                longest = longest.max(day - start);
+                if worst.is_none_or(|w| pct > w.max_pct) {
+                    worst = Some(Drawdown { max_pct: pct, recovery_days: Some(day - start), longest_underwater_days: 0 });
+                }
+                fill = None;
+            }
+            Some((start, low)) => fill = Some((start, low.min(price))),
+            None => {}
+        }
+    }
+    // Still holding at the end of the history
+    if let Some((start, low)) = fill {
+        let pct = (buy - low) / buy * 100.0;
+        longest = longest.max(last_day - start);
+        if worst.is_none_or(|w| pct >= w.max_pct) {
+            worst = Some(Drawdown { max_pct: pct, recovery_days: None, longest_underwater_days: 0 });
+        }
+    }
+
+    worst.map(|w| Drawdown { longest_underwater_days: longest, ..w })
+}
+
+// Windows for the multi-timeframe columns, shortest first
+pub const TIMEFRAMES: [i64; 3] = [7, 30, 90];
+
+// Moves smaller than this (%) don't count as a direction when comparing windows
+const DISAGREE_PCT: f64 = 3.0;
+
+fn timeframe(history: &[(i64, f64)], days: i64) -> Option<Timeframe> {
+    let last_day = history.last()?.0;
+    let window: Vec<f64> = history
+        .iter()
+        .filter(|(day, _)| *day > last_day - days)
+        .map(|(_, price)| *price)
+        .collect();
+    if window.len() < 2 {
+        return None;
+    }
+
+    let (first, latest) = (window[0], window[window.len() - 1]);
+    let mut sorted = window;
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let (q10, q50, q90) = (quantile(&sorted, 0.10), quantile(&sorted, 0.50), quantile(&sorted, 0.90));
+    Some(Timeframe {
+        days,
+        q10,
+        q90,
+        change_pct: if first > 0.0 { (latest - first) / first * 100.0 } else { 0.0 },
+        volatility: if q50 > 0.0 { (q90 - q10) / q50 * 100.0 } else { 0.0 },
+    })
+}
+
+// Short and long windows pointing opposite ways, e.g. a dip in an uptrend.
+// The long window is the longest one with data.
+pub fn timeframe_conflict(timeframes: &[Option<Timeframe>]) -> Option<&'static str> {
+    let short = timeframes.first()?.as_ref()?;
+    let long = timeframes.iter().rev().flatten().find(|t| t.days > short.days)?;
+    if short.change_pct <= -DISAGREE_PCT && long.change_pct >= DISAGREE_PCT {
+        Some("Short-term dip in a long-term uptrend")
+    } else if short.change_pct >= DISAGREE_PCT && long.change_pct <= -DISAGREE_PCT {
+        Some("Short-term bounce in a long-term downtrend")
+    } else {
+        None
+    }
+}
+
+// Empirical flip cycle: average days from the price touching the buy zone
+// (<= buy) to the next time it touches the sell zone (>= sell). None until
+// history contains at least one complete round trip.
+pub fn typical_cycle_days(history: &[(i64, f64)], buy: f64, sell: f64) -> Option<f64> {
+    let mut bought_on: Option<i64> = None;
+    let mut cycles = Vec::new();
+
+    for &(day, price) in history {
+        match bought_on {
+            None if price <= buy => bought_on = Some(day),
+            Some(start) if price >= sell => {
+                cycles.push((day - start).max(1) as f64);
+                bought_on = None;
+            }
+            _ => {}
+        }
+    }
+
+    if cycles.is_empty() {
+        None
+    } else {
+        Some(cycles.iter().sum::<f64>() / cycles.len() as f64)
+    }
+}
+
+// Days a position gets to climb towards the sell target when measuring capture
+pub const CAPTURE_WINDOW_DAYS: i64 = 14;
+
+// Fraction (0-1) of the buy-to-sell spread the price history actually offered.
+// Every day the price touched the buy target is an entry; the best price over
+// the next CAPTURE_WINDOW_DAYS, capped at the sell target, is what that entry
+// could have sold for. Entries too recent for a full window count only once
+// they've reached the target. Only prices the item really traded at count, so
+// it can't assume a move faster than the guide price made. None when the price
+// never touched the buy target.
+pub fn capture_rate(history: &[(i64, f64)], buy: f64, sell: f64) -> Option<f64> {
+    if sell <= buy {
+        return None;
+    }
+
+    let last_day = history.last().map_or(0, |(d, _)| *d);
+    let mut captured = Vec::new();
+    for (i, &(day, price)) in history.iter().enumerate() {
+        if price > buy {
+            continue;
+        }
+        let best = history[i + 1..]
+            .iter()
+            .take_while(|(d, _)| *d - day <= CAPTURE_WINDOW_DAYS)
+            .map(|(_, p)| *p)
+            .fold(f64::NEG_INFINITY, f64::max);
+        // Too recent to judge unless it already made it
+        if best < sell && last_day - day < CAPTURE_WINDOW_DAYS {
+            continue;
+        }
+        captured.push(((best.min(sell) - buy) / (sell - buy)).clamp(0.0, 1.0));
+    }
+
+    if captured.is_empty() {
+        None
+    } else {
+        Some(captured.iter().sum::<f64>() / captured.len() as f64)
+    }
+}
+
+// Length of the coverage window, matching the loader's snapshot window
+pub const COVERAGE_DAYS: i64 = 90;
+
+// How many of the `COVERAGE_DAYS` days up to `today` have a snapshot
+pub fn coverage_days(history: &[(i64, f64)], today: i64) -> usize {
+    let mut days: Vec<i64> = history
+        .iter()
+        .map(|(day, _)| *day)
+        .filter(|day| *day > today - COVERAGE_DAYS && *day <= today)
+        .collect();
+    days.dedup();
+    days.len()
+}
+
+// Days since 1970-01-01 for a `YYYY-MM-DD...` date string
+pub fn day_number(date: &str) -> Option<i64> {
+    let y: i64 = date.get(0..4)?.parse().ok()?;
+    let m: i64 = date.get(5..7)?.parse().ok()?;
+    let d: i64 = date.get(8..10)?.parse().ok()?;
+
+    // Howard Hinnant's days_from_civil
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let doy = (153 * ((m + 9) % 12) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    Some(era * 146_097 + doe - 719_468)
+}
+
+// A real calendar date written as `YYYY-MM-DD`, nothing more or less
+pub fn is_date(date: &str) -> bool {
+    day_number(date).is_some_and(|day| date_from_day(day) == date)
+}
+
+// `YYYY-MM-DD` for a day_number
+pub fn date_from_day(day: i64) -> String {
+    // Howard Hinnant's civil_from_days
+    let z = day + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+// Items trading less than this per day recently are considered abandoned
+const DEAD_VOLUME: f64 = 1.0;
+
+// No snapshot within `max_age_days` of `newest_day`, the latest day anything
+// was recorded, or volume has collapsed to ~0
+pub fn is_dead(s: &ItemStats, newest_day: i64, max_age_days: i64) -> bool {
+    let stale = day_number(&s.last_date).is_none_or(|d| newest_day - d > max_age_days);
+    stale || s.recent_volume < DEAD_VOLUME
+}
+
+// Minimum daily volume for an item to count as a mover - filters illiquid noise
+const MOVER_MIN_VOLUME: f64 = 100.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MoverWindow {
+    Day,
+    Week,
+}
+
+#[derive(Debug, Clone)]
+pub struct Mover {
+    pub name: String,
+    pub price: f64,
+    pub change: f64,
+    pub change_pct: f64,
+}
+
+// Biggest gainers and losers over the window, ranked by % or absolute gp
+pub fn top_movers(stats: &[ItemStats], window: MoverWindow, by_pct: bool, n: usize) -> (Vec<Mover>, Vec<Mover>) {
+    let mut movers: Vec<Mover> = stats
+        .iter()
+        .filter(|s| s.avg_volume >= MOVER_MIN_VOLUME)
+        .filter_map(|s| {
+            let before = match window {
+                MoverWindow::Day => s.price_1d_ago,
+                MoverWindow::Week => s.price_7d_ago,
+            }?;
+            if before <= 0.0 {
+                return None;
+            }
+            let change = s.current_price - before;
+            Some(Mover {
+                name: s.name.clone(),
+                price: s.current_price,
+                change,
+                change_pct: change / before * 100.0,
+            })
+        })
+        .collect();
+
+    let key = |m: &Mover| if by_pct { m.change_pct } else { m.change };
+    movers.sort_by(|a, b| key(b).total_cmp(&key(a)));
+
+    let gainers = movers.iter().take_while(|m| key(m) > 0.0).take(n).cloned().collect();
+    let losers = movers.iter().rev().take_while(|m| key(m) < 0.0).take(n).cloned().collect();
+    (gainers, losers)
+}
+
+// Price and volume momentum across a group of items (e.g. one skill's supplies)
+#[derive(Debug, Clone)]
+pub struct GroupTrend {
+    pub name: &'static str,
+    pub items: Vec<Mover>,  // Members with a week-old price, biggest 7d gain first
+    pub change_1d_pct: f64,  // Median member change
+    pub change_7d_pct: f64,
+    pub volume_change_pct: f64,  // Recent traded volume against the long-run average, summed over members
+    pub traded_value: f64,  // gp per day across the group
+}
+
+impl GroupTrend {
+    // Rising prices on rising volume = demand picking up
+    pub fn heat(&self) -> f64 {
+        self.change_7d_pct + self.volume_change_pct.clamp(-100.0, 100.0) / 4.0
+    }
+}
+
+pub fn group_trends(stats: &[ItemStats], group_of: impl Fn(&str) -> Option<&'static str>) -> Vec<GroupTrend> {
+    let mut groups: HashMap<&'static str, Vec<&ItemStats>> = HashMap::new();
+    for s in stats.iter().filter(|s| s.avg_volume >= MOVER_MIN_VOLUME) {
+        if let Some(g) = group_of(&s.name) {
+            groups.entry(g).or_default().push(s);
+        }
+    }
+
+    let pct = |before: Option<f64>, now: f64| before.filter(|b| *b > 0.0).map(|b| (now - b) / b * 100.0);
+    let median = |mut v: Vec<f64>| {
+        v.sort_by(|a, b| a.total_cmp(b));
+        quantile(&v, 0.5)
+    };
+
+    let mut trends: Vec<GroupTrend> = groups
+        .into_iter()
+        .map(|(name, members)| {
+            let day: Vec<f64> = members.iter().filter_map(|s| pct(s.price_1d_ago, s.current_price)).collect();
+            let week: Vec<f64> = members.iter().filter_map(|s| pct(s.price_7d_ago, s.current_price)).collect();
+            let recent: f64 = members.iter().map(|s| s.recent_volume).sum();
+            let average: f64 = members.iter().map(|s| s.avg_volume).sum();
+
+            let mut items: Vec<Mover> = members
+                .iter()
+                .filter_map(|s| {
+                    let change_pct = pct(s.price_7d_ago, s.current_price)?;
+                    Some(Mover {
+                        name: s.name.clone(),
+                        price: s.current_price,
+                        change: s.current_price - s.price_7d_ago?,
+                        change_pct,
+                    })
+                })
+                .collect();
+            items.sort_by(|a, b| b.change_pct.total_cmp(&a.change_pct));
+
+            GroupTrend {
+                name,
+                items,
+                change_1d_pct: median(day),
+                change_7d_pct: median(week),
+                volume_change_pct: if average > 0.0 { (recent / average - 1.0) * 100.0 } else { 0.0 },
+                traded_value: members.iter().map(|s| s.avg_volume * s.current_price).sum(),
+            }
+        })
+        .collect();
+
+    trends.sort_by(|a, b| b.heat().total_cmp(&a.heat()));
+    trends
+}
+
+pub fn quantile(sorted: &[f64], q: f64) -> f64 {
     if sorted.is_empty() { return 0.0; }
     let idx = ((sorted.len() - 1) as f64 * q).round() as usize;
     sorted[idx]
 }
 
+// Share of `sorted` strictly below `value`, 0-100, with the top of the list
+// at 100. Ties share the lower rank.
+pub fn percentile_rank(sorted: &[f64], value: f64) -> f64 {
+    if sorted.len() < 2 {
+        return 100.0;
+    }
+    sorted.partition_point(|v| *v < value) as f64 / (sorted.len() - 1) as f64 * 100.0
+}
+
 // Remove outliers using IQR method (filters DXP/update spikes)
 fn remove_outliers(prices: &[f64]) -> (Vec<f64>, usize) {
     if prices.len() < 10 {
         return (prices.to_vec(), 0);
     }
     
-    let q1 = quantile(&prices.to_vec(), 0.25);
-    let q3 = quantile(&prices.to_vec(), 0.75);
+    let q1 = quantile(prices, 0.25);
+    let q3 = quantile(prices, 0.75);
     let iqr = q3 - q1;
     
     // Use 1.5 * IQR for outlier detection (standard method)
@@ -141,3 +542,60 @@ fn calculate_trend(prices: &[f64]) -> f64 {
         0.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Gp;
+
+    #[test]
+    fn folding_batches_matches_building_at_once() {
+        let snaps: Vec<ItemSnapshot> = (1..=3)
+            .flat_map(|item_id| {
+                (0..20).map(move |day| ItemSnapshot {
+                    item_id,
+                    name: format!("Item {}", item_id),
+                    ge_limit: 100,
+                    record_date: format!("2026-01-{:02}", day + 1),
+                    price: Gp(1_000 * item_id as i64 + day * 37 % 11),
+                    volume: day as i32,
+                    manual: day == 19,
+                })
+            })
+            .collect();
+
+        let mut fold = StatsFold::new(3);
+        for batch in snaps.chunks(7) {
+            fold.push(batch.to_vec());
+        }
+        let folded = fold.finish();
+        let mut built = build_stats(&snaps, 3);
+        built.sort_by_key(|s| s.item_id);
+
+        let summary = |s: &ItemStats| (s.item_id, s.data_points, s.last_date.clone(), s.q50, s.avg_volume, s.recent_prices.clone());
+        assert_eq!(folded.iter().map(summary).collect::<Vec<_>>(), built.iter().map(summary).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn dead_items_are_judged_against_the_newest_data() {
+        let snaps: Vec<ItemSnapshot> = [(1, "2024-03-01"), (1, "2024-03-31"), (2, "2024-03-01"), (2, "2024-03-02")]
+            .iter()
+            .map(|&(item_id, date)| ItemSnapshot {
+                item_id,
+                name: format!("Item {}", item_id),
+                ge_limit: 100,
+                record_date: date.to_string(),
+                price: Gp(1_000),
+                volume: 50,
+                manual: false,
+            })
+            .collect();
+        let mut stats = build_stats(&snaps, 1);
+        stats.sort_by_key(|s| s.item_id);
+        let newest = stats.iter().filter_map(|s| day_number(&s.last_date)).max().unwrap();
+
+        // Years old by the clock, but only item 2 has gone quiet
+        assert!(!is_dead(&stats[0], newest, 14));
+        assert!(is_dead(&stats[1], newest, 14));
+    }
+}
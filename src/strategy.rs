@@ -0,0 +1,152 @@
+// User strategies: rhai scripts in strategies/ that can rescore or drop
+// items after the built-in analysis. A script may define either or both of
+//
+//   fn score(item, flip) { ... }   // returns the new score (integer)
+//   fn keep(item, flip) { ... }    // returns false to hide the item
+//
+// `item` carries the ItemStats fields and `flip` the built-in result, both
+// as object maps (item.q10, item.avg_volume, flip.roi, ...).
+use crate::model::{FlipResult, ItemStats};
+use rhai::{Array, Dynamic, Engine, Map, Scope, AST};
+use std::path::Path;
+
+pub const STRATEGY_DIR: &str = "strategies";
+
+// Runaway scripts are stopped rather than freezing the scan
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+pub struct Strategy {
+    pub name: String,  // File stem, e.g. "high_volume" for strategies/high_volume.rhai
+    ast: AST,
+    has_score: bool,
+    has_keep: bool,
+}
+
+pub struct Strategies {
+    engine: Engine,
+    pub list: Vec<Strategy>,
+    pub errors: Vec<String>,  // Scripts that failed to compile
+}
+
+impl Default for Strategies {
+    fn default() -> Self {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        Strategies { engine, list: Vec::new(), errors: Vec::new() }
+    }
+}
+
+impl Strategies {
+    // Reads every *.rhai file in `dir`, sorted by name. A missing folder just
+    // means no strategies.
+    pub fn load(dir: &str) -> Self {
+        let mut strategies = Strategies::default();
+        let Ok(entries) = std::fs::read_dir(dir) else { return strategies };
+
+        let mut paths: Vec<_> = entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|ext| ext == "rhai"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            match strategies.compile(&path) {
+                Ok(s) => strategies.list.push(s),
+                Err(e) => strategies.errors.push(format!("{}: {}", path.display(), e)),
+            }
+        }
+        strategies
+    }
+
+    fn compile(&self, path: &Path) -> Result<Strategy, String> {
+        let source = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let ast = self.engine.compile(&source).map_err(|e| e.to_string())?;
+        let has_fn = |name: &str| ast.iter_functions().any(|f| f.name == name && f.params.len() == 2);
+        let (has_score, has_keep) = (has_fn("score"), has_fn("keep"));
+        if !has_score && !has_keep {
+            return Err("defines neither score(item, flip) nor keep(item, flip)".into());
+        }
+
+        Ok(Strategy {
+            name: path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default(),
+            ast,
+            has_score,
+            has_keep,
+        })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Strategy> {
+        self.list.iter().find(|s| s.name == name)
+    }
+
+    // Applies the script to one item. Ok(false) means the script filtered it out.
+    pub fn apply(&self, strategy: &Strategy, stats: &ItemStats, flip: &mut FlipResult) -> Result<bool, String> {
+        let item = item_map(stats);
+        let result = flip_map(flip);
+        let mut scope = Scope::new();
+
+        if strategy.has_keep {
+            let keep: bool = self.engine
+                .call_fn(&mut scope, &strategy.ast, "keep", (item.clone(), result.clone()))
+                .map_err(|e| format!("{} keep(): {}", strategy.name, e))?;
+            if !keep {
+                return Ok(false);
+            }
+        }
+
+        if strategy.has_score {
+            let score: Dynamic = self.engine
+                .call_fn(&mut scope, &strategy.ast, "score", (item, result))
+                .map_err(|e| format!("{} score(): {}", strategy.name, e))?;
+            // Accept floats too; scripts doing maths rarely remember to_int()
+            let score = score.as_int()
+                .map(|v| v as f64)
+                .or_else(|_| score.as_float())
+                .map_err(|t| format!("{} score() returned {} instead of a number", strategy.name, t))?;
+            flip.score = score.round().clamp(i32::MIN as f64, i32::MAX as f64) as i32;
+        }
+
+        Ok(true)
+    }
+}
+
+fn prices(values: &[f64]) -> Dynamic {
+    values.iter().map(|&p| Dynamic::from_float(p)).collect::<Array>().into()
+}
+
+fn item_map(s: &ItemStats) -> Dynamic {
+    let mut m = Map::new();
+    m.insert("item_id".into(), (s.item_id as i64).into());
+    m.insert("name".into(), s.name.clone().into());
+    m.insert("current_price".into(), s.current_price.into());
+    m.insert("prev_price".into(), s.prev_price.into());
+    m.insert("price_1d_ago".into(), s.price_1d_ago.map_or(Dynamic::UNIT, Dynamic::from_float));
+    m.insert("price_7d_ago".into(), s.price_7d_ago.map_or(Dynamic::UNIT, Dynamic::from_float));
+    m.insert("first_date".into(), s.first_date.clone().into());
+    m.insert("last_date".into(), s.last_date.clone().into());
+    m.insert("avg_volume".into(), s.avg_volume.into());
+    m.insert("recent_volume".into(), s.recent_volume.into());
+    m.insert("current_volume".into(), s.current_volume.into());
+    m.insert("std_dev".into(), s.std_dev.into());
+    m.insert("q10".into(), s.q10.into());
+    m.insert("q50".into(), s.q50.into());
+    m.insert("q90".into(), s.q90.into());
+    m.insert("data_points".into(), (s.data_points as i64).into());
+    m.insert("ge_limit".into(), (s.ge_limit as i64).into());
+    m.insert("price_trend".into(), s.price_trend.into());
+    m.insert("outliers_removed".into(), (s.outliers_removed as i64).into());
+    m.insert("prices".into(), prices(&s.prices));
+    m.insert("recent_prices".into(), prices(&s.recent_prices_chrono));
+    m.into()
+}
+
+fn flip_map(f: &FlipResult) -> Dynamic {
+    let mut m = Map::new();
+    m.insert("score".into(), (f.score as i64).into());
+    m.insert("tier".into(), f.tier.clone().into());
+    m.insert("buy".into(), f.buy.0.into());
+    m.insert("sell".into(), f.sell.0.into());
+    m.insert("profit".into(), f.profit.0.into());
+    m.insert("roi".into(), f.roi.into());
+    m.into()
+}
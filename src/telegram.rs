@@ -0,0 +1,150 @@
+// Telegram bot channel: pushes alerts to a chat and answers `/flips` with the
+// latest scan. Uses the plain Bot API over HTTPS, polled from the UI loop.
+use serde::{Serialize, Deserialize};
+use std::sync::{Arc, Mutex};
+
+const API: &str = "https://api.telegram.org/bot";
+const POLL_SECS: f64 = 5.0;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TelegramConfig {
+    pub enabled: bool,
+    pub bot_token: String,  // From @BotFather
+    pub chat_id: String,  // Where alerts go; `/flips` is only answered here when set
+}
+
+impl TelegramConfig {
+    pub fn is_ready(&self) -> bool {
+        self.enabled && !self.bot_token.trim().is_empty()
+    }
+}
+
+// A `/flips` request waiting for an answer
+#[derive(Debug, Clone)]
+pub struct FlipsQuery {
+    pub chat_id: i64,
+    pub limit: usize,
+}
+
+#[derive(Default)]
+struct Shared {
+    queries: Vec<FlipsQuery>,
+    offset: i64,  // Next update id to fetch
+    polling: bool,
+    status: Option<String>,
+}
+
+#[derive(Default)]
+pub struct Telegram {
+    shared: Arc<Mutex<Shared>>,
+    last_poll: f64,
+}
+
+impl Telegram {
+    pub fn send(&self, config: &TelegramConfig, text: &str) {
+        let chat_id = config.chat_id.trim();
+        if chat_id.is_empty() {
+            self.set_status("No chat id set".into());
+            return;
+        }
+        self.send_to(config, chat_id, text);
+    }
+
+    pub fn reply(&self, config: &TelegramConfig, chat_id: i64, text: &str) {
+        self.send_to(config, &chat_id.to_string(), text);
+    }
+
+    fn send_to(&self, config: &TelegramConfig, chat_id: &str, text: &str) {
+        let body = serde_json::json!({ "chat_id": chat_id, "text": text });
+        let mut request = ehttp::Request::post(
+            format!("{}{}/sendMessage", API, config.bot_token.trim()),
+            body.to_string().into_bytes(),
+        );
+        request.headers.insert("Content-Type", "application/json");
+
+        let shared = self.shared.clone();
+        ehttp::fetch(request, move |response| {
+            let status = match response {
+                Ok(r) if r.ok => "Sent".to_string(),
+                Ok(r) => format!("Telegram refused the message: {} {}", r.status, r.text().unwrap_or_default()),
+                Err(e) => format!("Couldn't reach Telegram: {}", e),
+            };
+            shared.lock().unwrap().status = Some(status);
+        });
+    }
+
+    // Fetches new bot commands every few seconds; `now` is egui's input time
+    pub fn poll(&mut self, config: &TelegramConfig, now: f64) {
+        if !config.is_ready() || now - self.last_poll < POLL_SECS {
+            return;
+        }
+        self.last_poll = now;
+
+        let offset = {
+            let mut shared = self.shared.lock().unwrap();
+            if shared.polling {
+                return;
+            }
+            shared.polling = true;
+            shared.offset
+        };
+
+        let allowed_chat = config.chat_id.trim().parse::<i64>().ok();
+        let request = ehttp::Request::get(format!("{}{}/getUpdates?offset={}&timeout=0", API, config.bot_token.trim(), offset));
+        let shared = self.shared.clone();
+        ehttp::fetch(request, move |response| {
+            let mut shared = shared.lock().unwrap();
+            shared.polling = false;
+
+            let updates = match response.map_err(|e| e.to_string()).and_then(|r| {
+                serde_json::from_slice::<serde_json::Value>(&r.bytes).map_err(|e| e.to_string())
+            }) {
+                Ok(v) => v,
+                Err(e) => {
+                    shared.status = Some(format!("Couldn't poll Telegram: {}", e));
+                    return;
+                }
+            };
+
+            for update in updates["result"].as_array().into_iter().flatten() {
+                if let Some(id) = update["update_id"].as_i64() {
+                    shared.offset = shared.offset.max(id + 1);
+                }
+                let message = &update["message"];
+                let (Some(chat_id), Some(text)) = (message["chat"]["id"].as_i64(), message["text"].as_str()) else {
+                    continue;
+                };
+                if allowed_chat.is_some_and(|c| c != chat_id) {
+                    continue;
+                }
+                if let Some(query) = parse_flips_command(text, chat_id) {
+                    shared.queries.push(query);
+                }
+            }
+        });
+    }
+
+    pub fn take_queries(&self) -> Vec<FlipsQuery> {
+        std::mem::take(&mut self.shared.lock().unwrap().queries)
+    }
+
+    pub fn status(&self) -> Option<String> {
+        self.shared.lock().unwrap().status.clone()
+    }
+
+    fn set_status(&self, status: String) {
+        self.shared.lock().unwrap().status = Some(status);
+    }
+}
+
+// "/flips", "/flips 5" or "/flips@MyBot 20"; the count is capped to keep replies short
+fn parse_flips_command(text: &str, chat_id: i64) -> Option<FlipsQuery> {
+    let mut parts = text.split_whitespace();
+    let command = parts.next()?.split('@').next()?;
+    if command != "/flips" {
+        return None;
+    }
+    let limit = parts.next().and_then(|n| n.parse::<usize>().ok()).unwrap_or(10).clamp(1, 25);
+    Some(FlipsQuery { chat_id, limit })
+}
@@ -0,0 +1,134 @@
+// "Time machine": the flips table as it would have looked on a past date,
+// built by running the normal analysis on only the snapshots up to that day.
+use crate::flips::{analyze, confidence_demotion, tier_rank, Horizon, PriceRounding, ScoreWeights, Tax};
+use crate::model::{Gp, ItemSnapshot};
+use crate::stats::{build_stats, coverage_days, day_number, COVERAGE_DAYS};
+use std::collections::{BTreeSet, HashMap};
+
+// Rows kept per date; the diff covers items in either side's list
+const TOP_ROWS: usize = 100;
+
+#[derive(Debug, Clone)]
+pub struct PastRow {
+    pub item_id: i32,
+    pub name: String,
+    pub price: f64,
+    pub tier: String,
+    pub score: i32,
+    pub buy: Gp,
+    pub sell: Gp,
+    pub roi: f64,
+}
+
+pub struct DiffRow {
+    pub name: String,
+    pub price_a: Option<f64>,
+    pub price_b: Option<f64>,
+    pub tier_a: Option<String>,
+    pub tier_b: Option<String>,
+    pub score_a: Option<i32>,
+    pub score_b: Option<i32>,
+}
+
+impl DiffRow {
+    pub fn price_change_pct(&self) -> Option<f64> {
+        match (self.price_a, self.price_b) {
+            (Some(a), Some(b)) if a > 0.0 => Some((b / a - 1.0) * 100.0),
+            _ => None,
+        }
+    }
+
+    // Positive when the item moved up the tiers from A to B
+    pub fn tier_move(&self) -> i32 {
+        match (&self.tier_a, &self.tier_b) {
+            (Some(a), Some(b)) => tier_rank(b) - tier_rank(a),
+            _ => 0,
+        }
+    }
+}
+
+pub struct TimeMachine {
+    pub date_a: String,
+    pub date_b: String,
+    pub rows_a: Vec<PastRow>,
+    pub rows_b: Vec<PastRow>,
+    pub diff: Vec<DiffRow>,  // Tier moves first, then the biggest price moves
+}
+
+// Distinct snapshot dates, oldest first
+pub fn dates(snaps: &[ItemSnapshot]) -> Vec<String> {
+    snaps.iter().map(|s| s.record_date.clone()).collect::<BTreeSet<_>>().into_iter().collect()
+}
+
+// Top rows by score using only data recorded on or before `date`
+pub fn as_of(snaps: &[ItemSnapshot], date: &str, weights: &ScoreWeights, horizon: Horizon, tax: &Tax, rounding: PriceRounding) -> Vec<PastRow> {
+    let visible: Vec<ItemSnapshot> = snaps.iter().filter(|s| s.record_date.as_str() <= date).cloned().collect();
+    let day = day_number(date).unwrap_or(0);
+    // Manual price points count once here; their weighting is a live-table setting
+    let mut rows: Vec<PastRow> = build_stats(&visible, 1)
+        .iter()
+        .filter(|s| s.last_date.as_str() == date)  // Items not traded that day weren't on the table
+        .filter_map(|s| {
+            let mut f = analyze(s, tax, weights, horizon, rounding);
+            // Same sparse-data demotion the live table applies, as of that day
+            let coverage_pct = coverage_days(&s.history, day) as f64 / COVERAGE_DAYS as f64 * 100.0;
+            if let Some((tier, _)) = confidence_demotion(&f.tier, s.data_points, coverage_pct) {
+                f.tier = tier;
+            }
+            (f.profit > Gp::ZERO).then(|| PastRow {
+                item_id: s.item_id,
+                name: s.name.clone(),
+                price: s.current_price,
+                tier: f.tier,
+                score: f.score,
+                buy: f.buy,
+                sell: f.sell,
+                roi: f.roi,
+            })
+        })
+        .collect();
+    rows.sort_by(|a, b| b.score.cmp(&a.score).then(a.name.cmp(&b.name)));
+    rows.truncate(TOP_ROWS);
+    rows
+}
+
+pub fn run(snaps: &[ItemSnapshot], date_a: &str, date_b: &str, weights: &ScoreWeights, horizon: Horizon, tax: &Tax, rounding: PriceRounding) -> TimeMachine {
+    let rows_a = as_of(snaps, date_a, weights, horizon, tax, rounding);
+    let rows_b = as_of(snaps, date_b, weights, horizon, tax, rounding);
+
+    // Prices for items that fell out of one side's top list still come from its date
+    let price_on = |date: &str| -> HashMap<i32, f64> {
+        snaps.iter().filter(|s| s.record_date == date).map(|s| (s.item_id, s.price.as_f64())).collect()
+    };
+    let (prices_a, prices_b) = (price_on(date_a), price_on(date_b));
+    let by_id = |rows: &[PastRow]| -> HashMap<i32, PastRow> { rows.iter().map(|r| (r.item_id, r.clone())).collect() };
+    let (map_a, map_b) = (by_id(&rows_a), by_id(&rows_b));
+
+    let mut names: HashMap<i32, String> = HashMap::new();
+    for r in rows_a.iter().chain(&rows_b) {
+        names.entry(r.item_id).or_insert_with(|| r.name.clone());
+    }
+
+    let mut diff: Vec<DiffRow> = names
+        .into_iter()
+        .map(|(id, name)| {
+            let (a, b) = (map_a.get(&id), map_b.get(&id));
+            DiffRow {
+                name,
+                price_a: prices_a.get(&id).copied(),
+                price_b: prices_b.get(&id).copied(),
+                tier_a: a.map(|r| r.tier.clone()),
+                tier_b: b.map(|r| r.tier.clone()),
+                score_a: a.map(|r| r.score),
+                score_b: b.map(|r| r.score),
+            }
+        })
+        .collect();
+    diff.sort_by(|x, y| {
+        y.tier_move().abs().cmp(&x.tier_move().abs())
+            .then(y.price_change_pct().unwrap_or(0.0).abs().total_cmp(&x.price_change_pct().unwrap_or(0.0).abs()))
+            .then(x.name.cmp(&y.name))
+    });
+
+    TimeMachine { date_a: date_a.to_string(), date_b: date_b.to_string(), rows_a, rows_b, diff }
+}
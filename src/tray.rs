@@ -0,0 +1,108 @@
+use eframe::egui;
+use std::sync::mpsc::{self, Receiver};
+use tray_icon::menu::{CheckMenuItem, Menu, MenuEvent, MenuId, MenuItem, PredefinedMenuItem};
+use tray_icon::{Icon, TrayIcon, TrayIconBuilder};
+
+const ICON_SIZE: u32 = 32;
+const IDLE_COLOR: [u8; 3] = [255, 210, 100];  // App gold
+const HIGHLIGHT_COLOR: [u8; 3] = [0, 255, 255];  // Diamond cyan
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrayCommand {
+    ScanNow,
+    Open,
+    TogglePause,
+    Quit,
+}
+
+pub struct Tray {
+    icon: TrayIcon,
+    pause: CheckMenuItem,
+    commands: Receiver<TrayCommand>,
+    highlighted: bool,
+}
+
+impl Tray {
+    // Must be called from the UI thread once the event loop is running
+    pub fn new(ctx: &egui::Context) -> Option<Tray> {
+        let scan = MenuItem::new("Scan now", true, None);
+        let open = MenuItem::new("Open", true, None);
+        let pause = CheckMenuItem::new("Pause alerts", true, false, None);
+        let quit = MenuItem::new("Quit", true, None);
+
+        let menu = Menu::new();
+        menu.append_items(&[&scan, &open, &pause, &PredefinedMenuItem::separator(), &quit]).ok()?;
+
+        let ids: Vec<(MenuId, TrayCommand)> = vec![
+            (scan.id().clone(), TrayCommand::ScanNow),
+            (open.id().clone(), TrayCommand::Open),
+            (pause.id().clone(), TrayCommand::TogglePause),
+            (quit.id().clone(), TrayCommand::Quit),
+        ];
+
+        // Menu events arrive on the tray's thread; wake the UI so they are handled even while hidden
+        let (tx, rx) = mpsc::channel();
+        let ctx = ctx.clone();
+        MenuEvent::set_event_handler(Some(move |event: MenuEvent| {
+            if let Some((_, cmd)) = ids.iter().find(|(id, _)| *id == event.id) {
+                if *cmd == TrayCommand::Open {
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+                }
+                let _ = tx.send(*cmd);
+                ctx.request_repaint();
+            }
+        }));
+
+        let icon = TrayIconBuilder::new()
+            .with_menu(Box::new(menu))
+            .with_tooltip("RS3 Market Analyzer")
+            .with_icon(make_icon(IDLE_COLOR)?)
+            .build()
+            .ok()?;
+
+        Some(Tray { icon, pause, commands: rx, highlighted: false })
+    }
+
+    pub fn poll(&self) -> Vec<TrayCommand> {
+        self.commands.try_iter().collect()
+    }
+
+    // Swaps the icon colour when a Diamond flip or alert needs attention
+    pub fn set_highlight(&mut self, on: bool) {
+        if on == self.highlighted {
+            return;
+        }
+        self.highlighted = on;
+        let color = if on { HIGHLIGHT_COLOR } else { IDLE_COLOR };
+        let _ = self.icon.set_icon(make_icon(color));
+        let _ = self.icon.set_tooltip(Some(if on {
+            "RS3 Market Analyzer - new opportunities"
+        } else {
+            "RS3 Market Analyzer"
+        }));
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.pause.set_checked(paused);
+    }
+}
+
+// A filled coin with a darker rim, drawn rather than shipped as an image
+fn make_icon(color: [u8; 3]) -> Option<Icon> {
+    let center = (ICON_SIZE as f32 - 1.0) / 2.0;
+    let radius = ICON_SIZE as f32 / 2.0 - 1.0;
+    let mut rgba = Vec::with_capacity((ICON_SIZE * ICON_SIZE * 4) as usize);
+    for y in 0..ICON_SIZE {
+        for x in 0..ICON_SIZE {
+            let d = ((x as f32 - center).powi(2) + (y as f32 - center).powi(2)).sqrt();
+            if d > radius {
+                rgba.extend_from_slice(&[0, 0, 0, 0]);
+            } else if d > radius - 3.0 {
+                rgba.extend_from_slice(&[color[0] / 2, color[1] / 2, color[2] / 2, 255]);
+            } else {
+                rgba.extend_from_slice(&[color[0], color[1], color[2], 255]);
+            }
+        }
+    }
+    Icon::from_rgba(rgba, ICON_SIZE, ICON_SIZE).ok()
+}
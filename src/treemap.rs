@@ -0,0 +1,72 @@
+// Squarified treemap layout (Bruls, Huizing & van Wijk)
+use egui::{pos2, vec2, Rect};
+
+// Lays `values` (sorted largest first) out inside `rect`, returning one
+// rectangle per value in the same order with area proportional to the value.
+pub fn squarify(values: &[f64], rect: Rect) -> Vec<Rect> {
+    let total: f64 = values.iter().sum();
+    if values.is_empty() || total <= 0.0 {
+        return vec![Rect::NOTHING; values.len()];
+    }
+
+    let scale = (rect.width() * rect.height()) as f64 / total;
+    let areas: Vec<f64> = values.iter().map(|v| v.max(0.0) * scale).collect();
+
+    let mut out = vec![Rect::NOTHING; values.len()];
+    let mut remaining = rect;
+    let mut i = 0;
+
+    while i < areas.len() {
+        let short_side = remaining.width().min(remaining.height()) as f64;
+
+        // Grow the row while it keeps the aspect ratios from getting worse
+        let mut j = i + 1;
+        let mut best = worst_ratio(&areas[i..j], short_side);
+        while j < areas.len() {
+            let next = worst_ratio(&areas[i..j + 1], short_side);
+            if next > best {
+                break;
+            }
+            best = next;
+            j += 1;
+        }
+
+        let row_area: f64 = areas[i..j].iter().sum();
+        if remaining.width() >= remaining.height() {
+            // Column along the left edge
+            let w = (row_area / remaining.height() as f64) as f32;
+            let mut y = remaining.top();
+            for k in i..j {
+                let h = (areas[k] / w as f64) as f32;
+                out[k] = Rect::from_min_size(pos2(remaining.left(), y), vec2(w, h));
+                y += h;
+            }
+            remaining.min.x += w;
+        } else {
+            // Row along the top edge
+            let h = (row_area / remaining.width() as f64) as f32;
+            let mut x = remaining.left();
+            for k in i..j {
+                let w = (areas[k] / h as f64) as f32;
+                out[k] = Rect::from_min_size(pos2(x, remaining.top()), vec2(w, h));
+                x += w;
+            }
+            remaining.min.y += h;
+        }
+
+        i = j;
+    }
+
+    out
+}
+
+fn worst_ratio(row: &[f64], side: f64) -> f64 {
+    let sum: f64 = row.iter().sum();
+    let max = row.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let min = row.iter().cloned().fold(f64::INFINITY, f64::min);
+    if sum <= 0.0 || min <= 0.0 {
+        return f64::INFINITY;
+    }
+    let side2 = side * side;
+    (side2 * max / (sum * sum)).max((sum * sum) / (side2 * min))
+}
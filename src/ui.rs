@@ -1,11 +1,52 @@
-use crate::{loader::{load_snapshots, load_item_history}, stats::build_stats, flips::analyze};
+use crate::{loader::{self, load_snapshots, load_item_history}, flips::{analyze, tier_rank}};
+use crate::stats::{self, GroupTrend, Mover, MoverWindow};
+use crate::planner::{self, PlanLine};
+use crate::flips::{self, Difficulty, Horizon, Liquidity, PriceRounding, Reach, Tax};
+use crate::game::Game;
+use crate::exit::{self, ExitAdvice, ExitCall};
+use crate::explain::{self, Facts};
+use crate::floor::{self, ComponentValues};
+use crate::cycles::{self, Cycle};
+use crate::config::{AppConfig, Currency, Retention, RiskTolerance, RowDensity};
+use crate::journal::{self, Journal, Trade};
+use crate::model::{Annotation, DataVersion, Drawdown, Gp, Holding, ItemSnapshot, ItemStats, RawSnapshot, ScanEntry, ScanRow, SnapshotEdit, Timeframe};
+use crate::montecarlo::{self, Simulation};
+use crate::orderbook::{self, OfferBook};
+use crate::pairs::{self, Pair};
+use crate::palette::Palette;
+use crate::alerts::{self, Alert, AlertKind};
+use crate::alt1::{self, Targets};
+use crate::maintenance::{Maintenance, Task};
+use crate::snapshot_stream::SnapshotStream;
+use crate::baskets::{self, Basket, Baskets};
+use crate::bundle::{self, StateBundle};
+use crate::format::{format_gp, offer_price, Money};
+use crate::report::{self, Digest, Portfolio, Report};
+use crate::fees::Fees;
+use crate::rules::{self, Channel, Compare, Condition, Field as RuleField, Firing, History, Rule, Rules, Subject};
+use crate::ge_import::{self, Import};
+use crate::glossary;
+use crate::watchlist::{self, Watchlist};
+use crate::email::Mailer;
+use crate::hooks::Hooks;
+use crate::promo::{self, Dump};
+use crate::regime::Regime;
+use crate::investments::{self, Investment, Screen};
+use crate::ironman::{self, PriceRow, PriceSort};
+use crate::compare::{self, Comparison, Variant};
+use crate::strategy::{Strategies, STRATEGY_DIR};
+use crate::telegram::Telegram;
+use crate::timemachine::{self, PastRow, TimeMachine};
+use crate::tray::{Tray, TrayCommand};
+use crate::{categories, downsample, treemap, variants};
 use eframe::egui;
 use egui::{
     Color32, Context, FontFamily, FontId, Margin, RichText, Visuals, Stroke, Vec2
 };
 use egui_extras::{Column, TableBuilder};
-use egui_plot::{Line, Plot, PlotPoints};
-use std::collections::HashMap;
+use egui_plot::{Bar, BarChart, HLine, Line, LineStyle, Plot, PlotPoint, PlotPoints, Points, Polygon, VLine};
+use std::collections::{HashMap, HashSet};
+use web_time::Instant;
 
 pub fn set_custom_style(ctx: &Context) {
     // RS3 Grand Exchange dark gold UI theme
@@ -63,20 +104,53 @@ pub fn set_custom_style(ctx: &Context) {
 
 #[derive(Clone)]
 struct Row {
+    item_id: i32,
     name: String,
     score: i32,
+    score_pct: f64,  // Percentile of the score within this scan, 0-100
     tier: String,
-    buy: f64,
-    sell: i32,
+    buy: Gp,
+    sell: Gp,
     qty: i32,
-    profit: f64,
+    unit_profit: Gp,  // After tax (and slippage, when modelled)
+    profit: Gp,  // unit_profit * qty
     roi: f64,
     notes: String,
     trend: f64,  // Price trend indicator
-    total_cost: f64,  // Total cost of buying qty items
+    total_cost: Gp,  // Total cost of buying qty items
     avg_volume: f64,
+    prev_tier: Option<String>,  // Tier in the previous logged scan
+    cycle_days: Option<f64>,  // Typical days from buy-zone touch to sell-zone touch
+    gp_per_hour: Option<f64>,  // Total profit spread over one typical cycle
+    break_even: f64,  // Lowest sell price that recovers the buy price after tax
+    downside: f64,  // Worst-case (P5) loss per item
+    slippage: f64,  // Expected slippage per side (fraction) already taken off profit and ROI
+    capture: Option<f64>,  // Share of the spread history says was capturable; scales profit in realistic mode
+    timeframes: [Option<(Timeframe, f64)>; 3],  // (window, ROI after tax buying its Q10 and selling its Q90) per stats::TIMEFRAMES
+    timeframe_conflict: Option<&'static str>,
+    drawdown: Option<Drawdown>,  // For a buyer at the buy price, over the loaded history
+    low_confidence: Option<String>,  // Why the tier was lowered for sparse data
+    summary: String,  // Plain-language reasoning for the detail panel and reports
+    dump: Option<Dump>,  // Post-promo supply dump in progress
+    floor: Option<f64>,  // Alch / disassembly value floor per item
+    regime: Option<Regime>,
+    reach: Reach,  // Daily updates the targets are away, given the guide price's move cap
+    coverage_days: usize,  // Days of the last stats::COVERAGE_DAYS with a snapshot
+    data_points: usize,  // Snapshots the stats were built from
+    stale_days: i64,  // Days between this item's last snapshot and the newest one in the scan
+    variant: Option<String>,  // e.g. "broken" or "(4)" when the item is a variant of another
+    merged_variants: Vec<String>,  // Other variants folded into this row when variants are merged
+    difficulty: Difficulty,
+    difficulty_reasons: Vec<String>,
 }
 
+impl Row {
+    fn coverage_pct(&self) -> f64 {
+        self.coverage_days as f64 / stats::COVERAGE_DAYS as f64 * 100.0
+    }
+}
+
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum SortBy {
     Score,
@@ -87,6 +161,384 @@ enum SortBy {
     Sell,
 }
 
+// Everything undo/redo restores
+#[derive(Debug, Clone, PartialEq)]
+struct ViewState {
+    search: String,
+    budget: f64,
+    show_bad: bool,
+    sort_by: SortBy,
+    sort_order: SortOrder,
+    min_profit: f64,
+    min_roi: f64,
+    min_coverage: f64,
+    min_data_points: usize,
+    max_stale_days: i64,
+    max_drawdown: f64,
+    hidden_regimes: HashSet<Regime>,
+    selected_tier: Option<String>,
+    show_favorites_only: bool,
+}
+
+const UNDO_LIMIT: usize = 50;
+
+// Beginner mode only shows liquid, affordable items
+const BEGINNER_MIN_VOLUME: f64 = 10_000.0;
+const BEGINNER_MAX_PRICE: Gp = Gp(1_000_000);
+
+// Per-item values the scatter explorer can put on either axis
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Metric {
+    Roi,
+    Volume,
+    Profit,
+    Score,
+    Buy,
+    Trend,
+    TotalCost,
+}
+
+impl Metric {
+    const ALL: [Metric; 7] = [
+        Metric::Roi,
+        Metric::Volume,
+        Metric::Profit,
+        Metric::Score,
+        Metric::Buy,
+        Metric::Trend,
+        Metric::TotalCost,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Metric::Roi => "ROI %",
+            Metric::Volume => "Avg Vol/Day",
+            Metric::Profit => "Profit",
+            Metric::Score => "Score",
+            Metric::Buy => "Buy Price",
+            Metric::Trend => "Trend %",
+            Metric::TotalCost => "Total Cost",
+        }
+    }
+
+    fn value(self, r: &Row) -> f64 {
+        match self {
+            Metric::Roi => r.roi,
+            Metric::Volume => r.avg_volume,
+            Metric::Profit => r.profit.as_f64(),
+            Metric::Score => r.score as f64,
+            Metric::Buy => r.buy.as_f64(),
+            Metric::Trend => r.trend,
+            Metric::TotalCost => r.total_cost.as_f64(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Tab {
+    Flips,
+    Market,
+    Heatmap,
+    Scatter,
+    Investments,
+    Skilling,
+    Arbitrage,
+    Bank,
+    Database,
+}
+
+impl Tab {
+    const ALL: [Tab; 9] = [Tab::Flips, Tab::Market, Tab::Heatmap, Tab::Scatter, Tab::Investments, Tab::Skilling, Tab::Arbitrage, Tab::Bank, Tab::Database];
+
+    // Name stored in the config's window state
+    fn key(self) -> &'static str {
+        match self {
+            Tab::Flips => "flips",
+            Tab::Market => "market",
+            Tab::Heatmap => "heatmap",
+            Tab::Scatter => "scatter",
+            Tab::Investments => "investments",
+            Tab::Skilling => "skilling",
+            Tab::Arbitrage => "arbitrage",
+            Tab::Bank => "bank",
+            Tab::Database => "database",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Tab> {
+        Tab::ALL.into_iter().find(|t| t.key() == key)
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Tab::Flips => "📊 Flips",
+            Tab::Market => "🌐 Market",
+            Tab::Heatmap => "🗺 Heatmap",
+            Tab::Scatter => "✨ Scatter",
+            Tab::Investments => "🌱 Investments",
+            Tab::Skilling => "⛏ Skilling",
+            Tab::Arbitrage => "🔀 Arbitrage",
+            Tab::Bank => "🏦 Bank",
+            Tab::Database => "🗄 Database",
+        }
+    }
+}
+
+// Sections of the item detail panel
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DetailTab {
+    Chart,
+    Stats,
+    History,
+    Journal,
+}
+
+impl DetailTab {
+    const ALL: [DetailTab; 4] = [DetailTab::Chart, DetailTab::Stats, DetailTab::History, DetailTab::Journal];
+
+    fn label(self) -> &'static str {
+        match self {
+            DetailTab::Chart => "📈 Chart",
+            DetailTab::Stats => "📋 Stats",
+            DetailTab::History => "🗒 History",
+            DetailTab::Journal => "📒 Journal",
+        }
+    }
+}
+
+// One step of apply_filters. Each runs only when its setting is in use, and
+// any of them can be switched off without losing that setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum FilterKind {
+    Affordable,
+    Sanity,
+    Blacklist,
+    Favorites,
+    Search,
+    Tier,
+    MinProfit,
+    MinRoi,
+    Coverage,
+    DataPoints,
+    Staleness,
+    Drawdown,
+    Regime,
+    BadScore,
+    Beginner,
+}
+
+impl FilterKind {
+    const ALL: [FilterKind; 15] = [
+        FilterKind::Affordable,
+        FilterKind::Sanity,
+        FilterKind::Blacklist,
+        FilterKind::Favorites,
+        FilterKind::Search,
+        FilterKind::Tier,
+        FilterKind::MinProfit,
+        FilterKind::MinRoi,
+        FilterKind::Coverage,
+        FilterKind::DataPoints,
+        FilterKind::Staleness,
+        FilterKind::Drawdown,
+        FilterKind::Regime,
+        FilterKind::BadScore,
+        FilterKind::Beginner,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            FilterKind::Affordable => "Budget buys at least one",
+            FilterKind::Sanity => "Volume, ROI and price sanity",
+            FilterKind::Blacklist => "Blacklist",
+            FilterKind::Favorites => "Favorites only",
+            FilterKind::Search => "Search",
+            FilterKind::Tier => "Tier",
+            FilterKind::MinProfit => "Min profit",
+            FilterKind::MinRoi => "Min ROI",
+            FilterKind::Coverage => "Min data coverage",
+            FilterKind::DataPoints => "Min data points",
+            FilterKind::Staleness => "Max staleness",
+            FilterKind::Drawdown => "Max drawdown",
+            FilterKind::Regime => "Volatility regimes",
+            FilterKind::BadScore => "Hide bad scores",
+            FilterKind::Beginner => "Beginner mode",
+        }
+    }
+}
+
+// What the filters read from the app, borrowed for one pass. `search` is
+// lowercased once here rather than for every row.
+struct FilterSettings<'a> {
+    disabled: &'a HashSet<FilterKind>,
+    blacklist: &'a HashSet<i32>,
+    favorites: &'a HashSet<i32>,
+    favorites_only: bool,
+    search: String,
+    tier: Option<&'a str>,
+    min_profit: f64,
+    min_roi: f64,
+    min_coverage: f64,
+    min_data_points: usize,
+    max_stale_days: i64,
+    max_drawdown: f64,
+    hidden_regimes: &'a HashSet<Regime>,
+    show_bad: bool,
+    beginner_mode: bool,
+}
+
+impl FilterSettings<'_> {
+    // Whether a filter's setting currently asks for anything
+    fn in_use(&self, kind: FilterKind) -> bool {
+        match kind {
+            FilterKind::Affordable | FilterKind::Sanity => true,
+            FilterKind::Blacklist => !self.blacklist.is_empty(),
+            FilterKind::Favorites => self.favorites_only,
+            FilterKind::Search => !self.search.is_empty(),
+            FilterKind::Tier => self.tier.is_some(),
+            FilterKind::MinProfit => self.min_profit > 0.0,
+            FilterKind::MinRoi => self.min_roi > 0.0,
+            FilterKind::Coverage => self.min_coverage > 0.0,
+            FilterKind::DataPoints => self.min_data_points > 0,
+            FilterKind::Staleness => self.max_stale_days > 0,
+            FilterKind::Drawdown => self.max_drawdown > 0.0,
+            FilterKind::Regime => !self.hidden_regimes.is_empty(),
+            FilterKind::BadScore => !self.show_bad,
+            FilterKind::Beginner => self.beginner_mode,
+        }
+    }
+
+    fn keeps(&self, kind: FilterKind, r: &Row) -> bool {
+        match kind {
+            FilterKind::Affordable => r.qty > 0,
+            FilterKind::Sanity => r.avg_volume >= 500.0 && r.roi <= 150.0 && r.buy >= Gp(100),
+            FilterKind::Blacklist => !self.blacklist.contains(&r.item_id),
+            FilterKind::Favorites => self.favorites.contains(&r.item_id),
+            FilterKind::Search => r.name.to_lowercase().contains(&self.search),
+            FilterKind::Tier => self.tier == Some(r.tier.as_str()),
+            FilterKind::MinProfit => r.profit.as_f64() >= self.min_profit,
+            FilterKind::MinRoi => r.roi >= self.min_roi,
+            FilterKind::Coverage => r.coverage_pct() >= self.min_coverage,
+            FilterKind::DataPoints => r.data_points >= self.min_data_points,
+            FilterKind::Staleness => r.stale_days <= self.max_stale_days,
+            FilterKind::Drawdown => r.drawdown.is_none_or(|d| d.max_pct <= self.max_drawdown),
+            FilterKind::Regime => r.regime.is_none_or(|g| !self.hidden_regimes.contains(&g)),
+            FilterKind::BadScore => r.score > 0,
+            FilterKind::Beginner => {
+                r.avg_volume >= BEGINNER_MIN_VOLUME
+                    && r.buy <= BEGINNER_MAX_PRICE
+                    && r.difficulty <= Difficulty::Moderate
+                    && r.tier != "CRASH"
+                    && !r.notes.contains("🚨")
+                    && !r.notes.contains("📉")
+            }
+        }
+    }
+}
+
+// Every row goes through each filter that's in use and switched on; what
+// each one took out is kept for the filter summary
+fn filter_rows(rows: &[Row], settings: &FilterSettings) -> (Vec<Row>, Vec<(FilterKind, usize)>) {
+    let mut kept: Vec<&Row> = rows.iter().collect();
+    let mut removed = Vec::new();
+    for kind in FilterKind::ALL {
+        if !settings.in_use(kind) || settings.disabled.contains(&kind) {
+            continue;
+        }
+        let before = kept.len();
+        kept.retain(|r| settings.keeps(kind, r));
+        removed.push((kind, before - kept.len()));
+    }
+    (kept.into_iter().cloned().collect(), removed)
+}
+
+// Y-axis width shared by the stacked price and volume charts so their days line up
+const CHART_AXIS_WIDTH: f32 = 56.0;
+
+// How long a cell that changed in a refresh stays highlighted
+const FLASH_SECS: f32 = 2.5;
+
+// Cells that moved since the table shown before a refresh; true = up
+#[derive(Clone, Copy, Default)]
+struct CellChange {
+    tier: Option<bool>,
+    buy: Option<bool>,
+    sell: Option<bool>,
+}
+
+// The detail chart's data: the item's whole history thinned to CHART_POINTS,
+// plus full-resolution rows around the window the user zoomed into
+struct ChartData {
+    key: (i32, usize),  // Item and history length, like `cycle`
+    overview: ChartSeries,
+    thinned: bool,
+    window: Option<(i64, i64, ChartSeries)>,  // First and last day loaded, and their rows
+}
+
+// A stretch of history ready to plot, x being the day number
+#[derive(Clone)]
+struct ChartSeries {
+    prices: Vec<[f64; 2]>,
+    volumes: Vec<(f64, f64, f64)>,  // Bars: centre day, volume, width in days
+}
+
+impl ChartSeries {
+    fn new(history: &[(String, f64, Option<f64>)]) -> Self {
+        let mut prices = Vec::with_capacity(history.len());
+        let mut volumes = Vec::new();
+        for (date, price, volume) in history {
+            let Some(day) = stats::day_number(date) else { continue };
+            prices.push([day as f64, *price]);
+            if let Some(volume) = volume {
+                volumes.push([day as f64, *volume]);
+            }
+        }
+        Self {
+            prices: downsample::lttb(&prices, downsample::CHART_POINTS),
+            volumes: downsample::bucket_means(&volumes, downsample::CHART_POINTS),
+        }
+    }
+}
+
+// Actions from a table row's right-click menu that change app state
+#[derive(Clone, Copy)]
+enum RowAction {
+    Favorite,
+    Watch,
+    Blacklist,
+    AddToPortfolio,
+    OpenChart,
+}
+
+// Something done to every ticked row at once
+#[derive(Clone, Copy)]
+enum BulkAction {
+    Favorite,
+    Watch,
+    Blacklist,
+    Export,
+}
+
+// Something the command palette can do
+#[derive(Clone, Copy)]
+enum PaletteCommand {
+    Tab(Tab),
+    Scan,
+    Report,
+    ExportState,
+    Alt1,
+    ToggleFavorite(i32),
+    Item(i32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TimeView {
+    A,
+    B,
+    Diff,
+    Replay,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum SortOrder {
     Ascending,
@@ -95,8 +547,20 @@ enum SortOrder {
 
 pub struct RS3App {
     loaded: bool,
+    scan_error: Option<String>,
+    last_scan_at: u64,
+
+    // First-run setup
+    show_wizard: bool,
+    wizard_step: usize,
+    wizard_db_source: String,
+    tab: Tab,
+    stats: Vec<ItemStats>,
+    previous_scan: HashMap<i32, ScanEntry>,
     items: Vec<Row>,
     filtered_items: Vec<Row>,
+    search_matches: Vec<usize>,  // Rows of filtered_items whose name matches the search; pinned rows can be shown without
+    scroll_to_row: Option<usize>,  // Brought into view on the next frame
     search: String,
     budget: f64,
     show_bad: bool,
@@ -108,28 +572,232 @@ pub struct RS3App {
     // Filters
     min_profit: f64,
     min_roi: f64,
+    min_coverage: f64,  // % of the coverage window with snapshots
+    min_data_points: usize,
+    max_stale_days: i64,  // 0 = no limit
+    max_drawdown: f64,  // % below the buy price an item has historically fallen; 0 = no limit
+    disabled_filters: HashSet<FilterKind>,  // Switched off in the filter summary, settings kept
+    filter_removed: Vec<(FilterKind, usize)>,  // Rows each active filter took out, in pipeline order
+    hidden_regimes: HashSet<Regime>,
     selected_tier: Option<String>,
     show_favorites_only: bool,
     
     // UI state
     selected_row: Option<usize>,
     
-    favorites: HashMap<String, bool>,
+    favorites: HashSet<i32>,  // Item IDs, so duplicate names and renames don't collide
+    pinned: HashSet<i32>,  // Item ids kept at the top of the table
+    blacklist: HashSet<i32>,  // Item ids never shown in the table
+    bulk_selection: HashSet<i32>,  // Rows ticked for bulk actions
+    bulk_status: Option<String>,
     
-    selected_item_history: Vec<(String, f64)>,
+    selected_item_history: Vec<(String, f64, Option<f64>)>,  // Date, price, volume
+    selected_annotations: Vec<Annotation>,
+    annotation_date: String,
+    annotation_text: String,
     graph_height: f32,
+    detail_tab: DetailTab,
+    detail_history_page: usize,  // Pages of 50 snapshots back from the newest
+    raw_snapshots: Option<(i32, Vec<RawSnapshot>, Vec<SnapshotEdit>)>,  // Item, its history rows and audit log
+    snapshot_edit: Option<(i64, String, String)>,  // Row being corrected, with its price and volume text
+    snapshot_delete: Option<i64>,  // Row waiting on delete confirmation
+    snapshot_status: Option<String>,
+    manual_date: String,  // New manual price point: date, price and optional volume
+    manual_price: String,
+    manual_volume: String,
     target_graph_height: f32,
+
+    // Monte Carlo outcome for the selected flip, keyed by (item, horizon, qty)
+    sim_horizon: u32,
+    simulation: Option<((i32, u32, i32), Option<Simulation>)>,
+    offer_book: Option<((i32, usize), Option<OfferBook>)>,
+    cycle: Option<((i32, usize), Option<Cycle>)>,  // Keyed by item and history length
+    chart: Option<ChartData>,
+    chart_item: Option<i32>,  // Item the chart was last drawn for; a new one resets the zoom
+
+    shopping_list_size: usize,
+
+    config: AppConfig,
+    fees: Fees,
+    tax: Tax,  // The GE tax in use: the game's own, or a schedule from fees.json
+    fee_status: Option<String>,
+    strategies: Strategies,
+    strategy_error: Option<String>,
+
+    // Strategy A/B comparison
+    show_compare: bool,
+    compare_a: usize,  // Indexes into compare_variants()
+    compare_b: usize,
+    compare_holdout: i64,
+    compare_top_n: usize,
+    comparison: Option<Comparison>,
+
+    // Time machine: the table as of two past dates
+    show_time_machine: bool,
+    tm_dates: Vec<String>,  // Snapshot dates, oldest first
+    tm_a: usize,  // Indexes into tm_dates
+    tm_b: usize,
+    tm_view: TimeView,
+    time_machine: Option<TimeMachine>,
+    tm_snaps: Vec<ItemSnapshot>,  // Loaded when the window opens, dropped when it closes
+
+    // Replay: steps day by day through tm_dates
+    replay_index: usize,
+    replay_playing: bool,
+    replay_speed: f32,  // Days per second
+    replay_last_step: f64,
+    replay_rows: Option<(usize, Vec<PastRow>)>,  // Date index the rows were scored for
+    replay_prev_ranks: HashMap<i32, usize>,  // Ranks one step earlier, for the movement arrows
+
+    // Trade journal and session tracking
+    journal: Journal,
+    show_journal: bool,
+    rules: Rules,
+    alert_history: History,
+    show_rules: bool,
+    profiles: Vec<String>,  // Everyone with a journal on this machine
+    new_profile: String,
+    show_leaderboard: bool,
+    leaderboard: Vec<(String, HashMap<u64, Gp>)>,  // Realized profit by week, per profile
+    show_palette: bool,
+    palette_query: String,
+    palette_status: Option<String>,  // Result of the last palette command
+    session_start: u64,
+    new_trade: Trade,
+    ge_paste: String,  // Pasted GE history waiting to be imported
+    ge_import: Option<Import>,
+
+    // Bank holdings, valued at the latest scan's prices
+    holdings: Vec<Holding>,
+    current_prices: HashMap<i32, f64>,
+    bond_price: Option<f64>,
+    bank_history: Vec<(String, Gp)>,
+    holding_query: String,
+    holding_qty: i64,
+    holding_cost: f64,
+    holding_target: f64,
+    holding_error: Option<String>,
+
+    alerts: Vec<Alert>,
+    notified: alerts::Notified,  // Standing alerts already sent, so rescans don't repeat them
+    show_alerts: bool,
+    alerts_paused: bool,
+    telegram: Telegram,
+    mailer: Mailer,
+    hooks: Hooks,
+
+    // Tray icon, created on the first frame where the platform supports one
+    tray: Option<Tray>,
+    tray_started: bool,
+    quitting: bool,
+
+    // Market dashboard
+    watchlist: Watchlist,
+    watch_query: String,
+    watch_status: Option<String>,
+    baskets: Baskets,
+    basket_name: String,
+    basket_query: String,
+    basket_qty: i64,
+    basket_target: usize,  // Which basket the add row puts items into
+    basket_status: Option<String>,
+    mover_window: MoverWindow,
+    movers_by_pct: bool,
+    movers: (Vec<Mover>, Vec<Mover>),
+    new_items: Vec<(i32, String, String)>,
+    dead_items: Vec<(i32, String, String)>,
+    confirm_prune: bool,
+    confirm_prune_older: bool,
+    confirm_retention: bool,
+    retention_failed: bool,  // Stops a failing compaction retrying every frame; Run now or a restart tries again
+    prune_status: Option<String>,
+
+    maintenance: Maintenance,
+    snapshot_stream: SnapshotStream,
+    finishing_scan: bool,  // The running load is a full scan, not just a re-analysis
+    refreshing: bool,  // ...replacing a table that was already on screen
+    cell_changes: HashMap<i32, CellChange>,
+    changes_at: Option<Instant>,
+    stats_version: Option<(&'static str, DataVersion)>,  // Database and data `stats` were built from
+    pending_version: Option<(&'static str, DataVersion)>,  // ...and the same for the load in progress
+    alch_values: HashMap<i32, Gp>,
+    prune_days: u32,
+    parquet_path: String,
+    parquet_item: String,  // Item name or ID to export; empty = everything
+    parquet_error: Option<String>,
+    merge_path: String,
+    merge_prefer_incoming: bool,
+    bundle_path: String,
+    bundle_status: Option<String>,
+
+    report_status: Option<String>,
+    alt1_status: Option<String>,
+
+    // Long-term investment screener
+    investment_screen: Screen,
+    investments: Vec<Investment>,
+
+    skill_trends: Vec<GroupTrend>,  // Skilling supplies grouped by skill, hottest first
+    pairs: Vec<Pair>,  // Diverged pair trades, most diverged first
+    pair_min_z: f64,
+    selected_pair: Option<usize>,
+    detail_popped: bool,  // Item detail shown in its own viewport instead of the bottom panel
+
+    price_rows: Vec<PriceRow>,  // Ironman mode's market data, in place of the flips table
+    price_sort: PriceSort,
+
+    // Scatter explorer axes
+    scatter_x: Metric,
+    scatter_y: Metric,
+    scatter_log_x: bool,
+    scatter_log_y: bool,
+
+    // Undo/redo of filter, sort and budget changes
+    undo_stack: Vec<ViewState>,
+    redo_stack: Vec<ViewState>,
+    view_committed: Option<ViewState>,
+    view_pending: Option<ViewState>,
+    view_changed_at: f64,
 }
 
 impl RS3App {
     pub fn new() -> Self {
-        let favorites = Self::load_favorites();
+        let config = AppConfig::load();
+        let game = config.game;
+        let db_path = game.db_path();
+        let favorites = Self::load_favorites(game);
+        let journal = Journal::load(game, &config.profile);
+        let fees = Fees::load(game);
+        let tax = fees.active(game);
         Self {
             loaded: false,
+            scan_error: None,
+            last_scan_at: 0,
+
+            show_wizard: !AppConfig::exists() || !loader::database_ready(db_path),
+            wizard_step: 0,
+            wizard_db_source: String::new(),
+            tab: Tab::from_key(&config.window.tab).unwrap_or(Tab::Flips),
+            investment_screen: Screen::default(),
+            investments: vec![],
+            skill_trends: vec![],
+            pairs: vec![],
+            pair_min_z: 2.0,
+            selected_pair: None,
+            detail_popped: false,
+            price_rows: vec![],
+            price_sort: PriceSort::Range,
+            stats: vec![],
+            previous_scan: HashMap::new(),
             items: vec![],
             filtered_items: vec![],
+            search_matches: vec![],
+            scroll_to_row: None,
             search: "".into(),
-            budget: 50_000_000.0,
+            budget: match config.allocation.enabled && config.bank_size > 0.0 {
+                true => config.allocation.flips(config.bank_size).max(100_000.0),
+                false => config.budget,
+            },
             show_bad: false,
             
             sort_by: SortBy::Score,
@@ -137,207 +805,4980 @@ impl RS3App {
             
             min_profit: 0.0,
             min_roi: 0.0,
+            min_coverage: 0.0,
+            min_data_points: 0,
+            max_stale_days: 0,
+            max_drawdown: 0.0,
+            disabled_filters: HashSet::new(),
+            filter_removed: vec![],
+            hidden_regimes: HashSet::new(),
             selected_tier: None,
             show_favorites_only: false,
             
             selected_row: None,
             
             favorites,
+            pinned: Self::load_pinned(game),
+            bulk_selection: HashSet::new(),
+            bulk_status: None,
+            blacklist: Self::load_blacklist(game),
             
             selected_item_history: vec![],
+            sim_horizon: 7,
+            simulation: None,
+            offer_book: None,
+            cycle: None,
+            chart: None,
+            chart_item: None,
+            selected_annotations: vec![],
+            annotation_date: String::new(),
+            annotation_text: String::new(),
             graph_height: 0.0,
+            detail_tab: DetailTab::Chart,
+            detail_history_page: 0,
+            raw_snapshots: None,
+            snapshot_edit: None,
+            snapshot_delete: None,
+            snapshot_status: None,
+            manual_date: String::new(),
+            manual_price: String::new(),
+            manual_volume: String::new(),
             target_graph_height: 0.0,
+
+            shopping_list_size: 10,
+
+            config,
+            fees,
+            tax,
+            fee_status: None,
+            strategies: Strategies::load(STRATEGY_DIR),
+            strategy_error: None,
+            show_compare: false,
+            show_time_machine: false,
+            tm_dates: vec![],
+            tm_a: 0,
+            tm_b: 0,
+            tm_view: TimeView::Diff,
+            time_machine: None,
+            tm_snaps: vec![],
+            replay_index: 0,
+            replay_playing: false,
+            replay_speed: 2.0,
+            replay_last_step: 0.0,
+            replay_rows: None,
+            replay_prev_ranks: HashMap::new(),
+            compare_a: 0,
+            compare_b: 1,
+            compare_holdout: 14,
+            compare_top_n: 10,
+            comparison: None,
+
+            journal,
+            show_journal: false,
+            rules: Rules::load(game),
+            alert_history: History::load(game),
+            show_rules: false,
+            profiles: journal::profiles(game),
+            new_profile: String::new(),
+            show_leaderboard: false,
+            leaderboard: vec![],
+            show_palette: false,
+            palette_query: String::new(),
+            palette_status: None,
+            session_start: journal::now_secs(),
+            new_trade: Trade { item: String::new(), qty: 1, buy: Gp::ZERO, sell: Gp::ZERO, timestamp: 0 },
+            ge_paste: String::new(),
+            ge_import: None,
+
+            holdings: loader::load_holdings(db_path).unwrap_or_default(),
+            current_prices: HashMap::new(),
+            bond_price: None,
+            bank_history: loader::load_bank_history(db_path).unwrap_or_default(),
+            holding_query: String::new(),
+            holding_qty: 1,
+            holding_cost: 0.0,
+            holding_target: 0.0,
+            holding_error: None,
+
+            alerts: vec![],
+            notified: alerts::Notified::default(),
+            show_alerts: false,
+            alerts_paused: false,
+            telegram: Telegram::default(),
+            mailer: Mailer::default(),
+            hooks: Hooks::default(),
+            tray: None,
+            tray_started: false,
+            quitting: false,
+
+            watchlist: Watchlist::load(game),
+            watch_query: String::new(),
+            watch_status: None,
+            baskets: Baskets::load(game),
+            basket_name: String::new(),
+            basket_query: String::new(),
+            basket_qty: 1,
+            basket_target: 0,
+            basket_status: None,
+            mover_window: MoverWindow::Day,
+            movers_by_pct: true,
+            movers: (vec![], vec![]),
+            new_items: vec![],
+            dead_items: vec![],
+            confirm_prune: false,
+            confirm_prune_older: false,
+            confirm_retention: false,
+            retention_failed: false,
+            prune_status: None,
+
+            maintenance: Maintenance::default(),
+            snapshot_stream: SnapshotStream::default(),
+            finishing_scan: false,
+            refreshing: false,
+            cell_changes: HashMap::new(),
+            changes_at: None,
+            stats_version: None,
+            pending_version: None,
+            alch_values: HashMap::new(),
+            prune_days: 365,
+            parquet_path: "history.parquet".into(),
+            parquet_item: String::new(),
+            parquet_error: None,
+            merge_path: String::new(),
+            merge_prefer_incoming: false,
+            bundle_path: "rs3_analyzer_state.json".into(),
+            bundle_status: None,
+
+            report_status: None,
+            alt1_status: None,
+
+            scatter_x: Metric::Volume,
+            scatter_y: Metric::Roi,
+            scatter_log_x: true,
+            scatter_log_y: false,
+
+            undo_stack: vec![],
+            redo_stack: vec![],
+            view_committed: None,
+            view_pending: None,
+            view_changed_at: 0.0,
         }
     }
     
-    fn load_favorites() -> HashMap<String, bool> {
+    fn view_state(&self) -> ViewState {
+        ViewState {
+            search: self.search.clone(),
+            budget: self.budget,
+            show_bad: self.show_bad,
+            sort_by: self.sort_by,
+            sort_order: self.sort_order,
+            min_profit: self.min_profit,
+            min_roi: self.min_roi,
+            min_coverage: self.min_coverage,
+            min_data_points: self.min_data_points,
+            max_stale_days: self.max_stale_days,
+            max_drawdown: self.max_drawdown,
+            hidden_regimes: self.hidden_regimes.clone(),
+            selected_tier: self.selected_tier.clone(),
+            show_favorites_only: self.show_favorites_only,
+        }
+    }
+
+    fn restore_view_state(&mut self, v: ViewState) {
+        let budget_changed = v.budget != self.budget;
+        self.search = v.search.clone();
+        self.budget = v.budget;
+        self.show_bad = v.show_bad;
+        self.sort_by = v.sort_by;
+        self.sort_order = v.sort_order;
+        self.min_profit = v.min_profit;
+        self.min_roi = v.min_roi;
+        self.min_coverage = v.min_coverage;
+        self.min_data_points = v.min_data_points;
+        self.max_stale_days = v.max_stale_days;
+        self.max_drawdown = v.max_drawdown;
+        self.hidden_regimes = v.hidden_regimes.clone();
+        self.selected_tier = v.selected_tier.clone();
+        self.show_favorites_only = v.show_favorites_only;
+        self.view_committed = Some(v.clone());
+        self.view_pending = Some(v);
+
+        if budget_changed && self.loaded {
+            self.load_data();
+        } else if self.loaded {
+            self.apply_filters();
+        }
+    }
+
+    // Records a history entry once a change has settled, so a slider drag
+    // or a typed search term becomes one undo step rather than dozens
+    fn track_view_history(&mut self, ctx: &Context) {
+        let current = self.view_state();
+        let now = ctx.input(|i| i.time);
+
+        if self.view_pending.as_ref() != Some(&current) {
+            self.view_pending = Some(current.clone());
+            self.view_changed_at = now;
+        }
+
+        let settled = now - self.view_changed_at > 0.5 && !ctx.input(|i| i.pointer.any_down());
+        match &self.view_committed {
+            None => self.view_committed = Some(current),
+            Some(committed) if settled && *committed != current => {
+                let previous = self.view_committed.replace(current).unwrap();
+                self.undo_stack.push(previous);
+                if self.undo_stack.len() > UNDO_LIMIT {
+                    self.undo_stack.remove(0);
+                }
+                self.redo_stack.clear();
+            }
+            _ => {}
+        }
+    }
+
+    fn undo(&mut self) {
+        let current = self.view_state();
+        // An unsettled change is undone back to the last committed state first
+        let target = match &self.view_committed {
+            Some(committed) if *committed != current => Some(committed.clone()),
+            _ => self.undo_stack.pop(),
+        };
+        if let Some(target) = target {
+            self.redo_stack.push(current);
+            self.restore_view_state(target);
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(target) = self.redo_stack.pop() {
+            self.undo_stack.push(self.view_state());
+            self.restore_view_state(target);
+        }
+    }
+
+    fn handle_undo_shortcuts(&mut self, ctx: &Context) {
+        // Leave Ctrl+Z to text fields while one is being edited
+        if ctx.memory(|m| m.focused().is_some()) {
+            return;
+        }
+
+        use egui::{Key, KeyboardShortcut, Modifiers};
+        let undo = KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
+        let redo = KeyboardShortcut::new(Modifiers::COMMAND, Key::Y);
+        let redo_alt = KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::Z);
+
+        // Check the shift variant first - consume_shortcut ignores extra modifiers
+        if ctx.input_mut(|i| i.consume_shortcut(&redo_alt) || i.consume_shortcut(&redo)) {
+            self.redo();
+        } else if ctx.input_mut(|i| i.consume_shortcut(&undo)) {
+            self.undo();
+        }
+    }
+
+    fn handle_palette_shortcut(&mut self, ctx: &Context) {
+        use egui::{Key, KeyboardShortcut, Modifiers};
+        let toggle = KeyboardShortcut::new(Modifiers::COMMAND, Key::K);
+        if ctx.input_mut(|i| i.consume_shortcut(&toggle)) {
+            self.show_palette = !self.show_palette;
+            self.palette_query.clear();
+            self.palette_status = None;
+        } else if self.show_palette && ctx.input(|i| i.key_pressed(Key::Escape)) {
+            self.show_palette = false;
+        }
+    }
+
+    fn db_path(&self) -> &'static str {
+        self.config.game.db_path()
+    }
+
+    fn start_task(&mut self, task: Task) {
+        self.maintenance.start(task, self.config.game.db_path());
+    }
+
+    fn load_favorites(game: Game) -> HashSet<i32> {
         use std::fs;
-        if let Ok(data) = fs::read_to_string("favorites.json") {
-            serde_json::from_str(&data).unwrap_or_default()
-        } else {
-            HashMap::new()
+        let Ok(data) = fs::read_to_string(game.file("favorites.json")) else { return HashSet::new() };
+        if let Ok(ids) = serde_json::from_str::<Vec<i32>>(&data) {
+            return ids.into_iter().collect();
+        }
+
+        // Older versions stored {"name": true}; look the names up once and rewrite the file.
+        // Without a database yet the old file is left alone for the next launch.
+        // Only RS3 ever had the old format.
+        if game != Game::Rs3 || !loader::database_ready(loader::DB_PATH) {
+            return HashSet::new();
         }
+        let by_name: HashMap<String, bool> = serde_json::from_str(&data).unwrap_or_default();
+        let favorites = favorites_from_names(by_name);
+        save_favorite_ids(game, &favorites);
+        favorites
     }
     
     fn save_favorites(&self) {
-        use std::fs;
-        if let Ok(json) = serde_json::to_string(&self.favorites) {
-            let _ = fs::write("favorites.json", json);
+        save_favorite_ids(self.config.game, &self.favorites);
+    }
+
+    fn load_pinned(game: Game) -> HashSet<i32> {
+        std::fs::read_to_string(game.file("pinned.json"))
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_pinned(&self) {
+        if let Ok(json) = serde_json::to_string(&self.pinned) {
+            let _ = std::fs::write(self.config.game.file("pinned.json"), json);
+        }
+    }
+
+    fn load_blacklist(game: Game) -> HashSet<i32> {
+        std::fs::read_to_string(game.file("blacklist.json"))
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_blacklist(&self) {
+        if let Ok(json) = serde_json::to_string(&self.blacklist) {
+            let _ = std::fs::write(self.config.game.file("blacklist.json"), json);
         }
     }
 
+    // Re-reads the snapshots in the background; the table is rebuilt once
+    // the last batch arrives
     fn load_data(&mut self) {
-        let tax = 0.02;
+        let db_path = self.db_path();
+        let version = loader::data_version(db_path).ok().map(|v| (db_path, v));
+
+        // Nothing new in the database: re-score the stats already built
+        if version.is_some() && version == self.stats_version && !self.snapshot_stream.is_running() {
+            let stats = std::mem::take(&mut self.stats);
+            self.analyze_stats(stats);
+            if std::mem::take(&mut self.finishing_scan) {
+                self.finish_scan();
+            }
+            return;
+        }
 
-        let result = load_snapshots("rs3_market.db");
+        self.stats_version = None;
+        self.pending_version = version;
+        self.snapshot_stream.start(db_path, self.config.scan_batch_size, self.config.manual_weight);
+    }
 
-        let snaps = match result {
-            Ok(v) => v,
-            Err(_) => return,
-        };
+    fn poll_snapshots(&mut self) {
+        let Some(result) = self.snapshot_stream.poll() else { return };
+        match result {
+            Ok(stats) => self.analyze_new_stats(stats),
+            Err(e) => self.scan_error = Some(format!("Couldn't read {}: {}", self.db_path(), e)),
+        }
+        if std::mem::take(&mut self.finishing_scan) {
+            self.finish_scan();
+        }
+    }
+
+    fn analyze_new_stats(&mut self, stats: Vec<ItemStats>) {
+        self.alch_values = loader::load_alch_values(self.db_path()).unwrap_or_default();
+        self.new_items = loader::load_new_items(self.db_path(), self.config.new_item_days).unwrap_or_default();
+        self.stats_version = self.pending_version.take();
+        self.analyze_stats(stats);
+    }
+
+    fn analyze_stats(&mut self, stats: Vec<ItemStats>) {
+        let tax = self.tax.clone();
+        self.scan_error = None;
 
-        let stats = build_stats(&snaps);
+        self.current_prices = stats.iter().map(|s| (s.item_id, s.current_price)).collect();
+        self.bond_price = stats.iter().find(|s| s.name == self.config.game.bond_name()).map(|s| s.current_price);
+
+        // Value floors: re-read each scan so hand edits to components.json apply
+        let components = ComponentValues::load();
+        let rune_cost = stats.iter().find(|s| s.name == floor::NATURE_RUNE).map_or(0.0, |s| s.current_price);
 
         let mut rows = vec![];
+        let today = (journal::now_secs() / 86_400) as i64;
+        // Staleness and dead items are measured against the newest data rather
+        // than the clock, so an old database doesn't make every item look stale
+        let newest_day = stats.iter().filter_map(|s| stats::day_number(&s.last_date)).max();
+        self.dead_items.clear();
+
+        let strategies = std::mem::take(&mut self.strategies);
+        let strategy = self.config.strategy.as_deref().and_then(|name| strategies.get(name));
+        let mut strategy_error = None;
 
-        for s in stats {
+        for s in &stats {
             if s.data_points < 1 { continue; }
 
-            let f = analyze(&s, tax);
+            if stats::is_dead(s, newest_day.unwrap_or(today), self.config.dead_item_days) {
+                self.dead_items.push((s.item_id, s.name.clone(), s.last_date.clone()));
+                if !self.config.include_dead_items {
+                    continue;
+                }
+            }
+
+            let mut f = analyze(s, &tax, &self.config.weights, self.config.horizon, self.config.rounding);
+            if let Some(strategy) = strategy {
+                match strategies.apply(strategy, s, &mut f) {
+                    Ok(true) => {}
+                    Ok(false) => continue,
+                    Err(e) => {
+                        strategy_error.get_or_insert(e);
+                    }
+                }
+            }
+
+            let coverage_days = stats::coverage_days(&s.history, today);
+            let coverage_pct = coverage_days as f64 / stats::COVERAGE_DAYS as f64 * 100.0;
+            let low_confidence = flips::confidence_demotion(&f.tier, s.data_points, coverage_pct).map(|(tier, reason)| {
+                f.tier = tier;
+                reason
+            });
 
-            let qty = if f.buy > 0 {
-                ((self.budget / f.buy as f64) as i32).min(s.ge_limit)
+            let qty = if f.buy > Gp::ZERO {
+                ((self.budget / f.buy.as_f64()) as i32).min(s.ge_limit)
             } else {
                 0
             };
-            let total_profit = f.profit as f64 * qty as f64;
-            let total_cost = f.buy as f64 * qty as f64;
+            let slippage = if self.config.model_slippage { flips::slippage(s.avg_volume, qty) } else { 0.0 };
+            let (mut unit_profit, mut roi) = if slippage > 0.0 {
+                flips::slipped_profit(&s.name, f.buy, f.sell, slippage, &tax)
+            } else {
+                (f.profit, f.roi)
+            };
+            let capture = stats::capture_rate(&s.history, f.buy.as_f64(), f.sell.as_f64());
+            // Only gains shrink; a losing flip doesn't get any less bad
+            if let Some(rate) = capture.filter(|_| self.config.realistic_profit && unit_profit > Gp::ZERO) {
+                unit_profit = Gp::from_f64(unit_profit.as_f64() * rate);
+                roi *= rate;
+            }
+            let total_profit = unit_profit * qty as i64;
+            let total_cost = f.buy * qty as i64;
+            // A cycle can't beat the updates the guide price needs to get to both targets
+            let reach = flips::reach(s, f.buy.as_f64(), f.sell.as_f64());
+            let cycle_days = stats::typical_cycle_days(&s.history, f.buy.as_f64(), f.sell.as_f64())
+                .map(|d| d.max(reach.days() as f64));
+            let (difficulty, difficulty_reasons) = flips::difficulty(s, &f);
+            let drawdown = stats::drawdown(&s.history, f.buy.as_f64());
+            let regime = Regime::of(&s.history);
+            let dump = promo::detect_dump(s);
+            let summary = explain::summary(&Facts {
+                price: Some(s.current_price),
+                buy: f.buy.as_f64(),
+                sell: f.sell.as_f64(),
+                tier: &f.tier,
+                roi,
+                cycle_days,
+                volume_ratio: (s.avg_volume > 0.0).then(|| s.recent_volume / s.avg_volume),
+                month: s.timeframes.iter().flatten().find(|t| t.days == 30),
+                regime,
+                dump: dump.is_some(),
+                low_confidence: low_confidence.as_deref(),
+                drawdown,
+            });
 
             rows.push(Row {
+                item_id: s.item_id,
                 name: s.name.clone(),
                 score: f.score,
+                score_pct: 0.0,  // Ranked once the whole scan is in
                 tier: f.tier.clone(),
-                buy: f.buy as f64,
+                buy: f.buy,
                 sell: f.sell,
                 qty,
+                unit_profit,
                 profit: total_profit,
-                roi: f.roi,
+                roi,
                 notes: f.notes.clone(),
                 trend: s.price_trend,
                 total_cost,
                 avg_volume: f.avg_volume,
+                prev_tier: self.previous_scan.get(&s.item_id).map(|e| e.tier.clone()),
+                cycle_days,
+                gp_per_hour: cycle_days.map(|d| total_profit.as_f64() / (d * 24.0)),
+                break_even: flips::break_even_sell(&s.name, f.buy.as_f64(), &tax),
+                downside: flips::worst_case_loss(s, f.buy.as_f64(), &tax),
+                slippage,
+                capture,
+                timeframes: s.timeframes.map(|t| t.map(|t| (t, timeframe_roi(&s.name, &t, &tax)))),
+                timeframe_conflict: stats::timeframe_conflict(&s.timeframes),
+                drawdown,
+                low_confidence,
+                summary,
+                dump,
+                reach,
+                coverage_days,
+                data_points: s.data_points,
+                stale_days: match (newest_day, stats::day_number(&s.last_date)) {
+                    (Some(newest), Some(last)) => newest - last,
+                    _ => 0,
+                },
+                variant: variants::variant_of(&s.name).map(|(_, label)| label),
+                merged_variants: vec![],
+                floor: floor::value_floor(self.alch_values.get(&s.item_id).map(|v| v.as_f64()), rune_cost, components.get(&s.name)),
+                regime,
+                difficulty,
+                difficulty_reasons,
             });
         }
 
+        let mut scores: Vec<f64> = rows.iter().map(|r| r.score as f64).collect();
+        scores.sort_by(|a, b| a.total_cmp(b));
+        for r in &mut rows {
+            r.score_pct = stats::percentile_rank(&scores, r.score as f64);
+        }
+
+        self.strategy_error = strategy_error;
+        self.strategies = strategies;
         self.items = rows;
+        self.stats = stats;
+        self.investments = investments::screen(&self.stats, &self.investment_screen);
+        self.skill_trends = stats::group_trends(&self.stats, categories::skill_group);
+        self.refresh_pairs();
+        self.price_rows = ironman::overview(&self.stats);
+        ironman::sort(&mut self.price_rows, self.price_sort);
+        self.refresh_movers();
         self.apply_filters();
         self.loaded = true;
     }
 
-    fn apply_filters(&mut self) {
-        let mut filtered = self.items.clone();
-
-        filtered.retain(|r| r.qty > 0);
-        
-        filtered.retain(|r| {
-            let has_volume = r.avg_volume >= 500.0;
-            let reasonable_roi = r.roi <= 150.0;
-            let reasonable_price = r.buy >= 100.0;
-            has_volume && reasonable_roi && reasonable_price
+    fn strategy_picker(&mut self, ui: &mut egui::Ui) {
+        ui.label(RichText::new("🧪 Strategy").strong())
+            .on_hover_text(format!("Rhai scripts in {}/ can rescore items with score(item, flip) or hide them with keep(item, flip)", STRATEGY_DIR));
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            let current = self.config.strategy.clone().unwrap_or_else(|| "Built-in".to_string());
+            egui::ComboBox::from_id_salt("strategy")
+                .selected_text(current)
+                .show_ui(ui, |ui| {
+                    changed |= ui.selectable_value(&mut self.config.strategy, None, "Built-in").clicked();
+                    for s in &self.strategies.list {
+                        changed |= ui.selectable_value(&mut self.config.strategy, Some(s.name.clone()), &s.name).clicked();
+                    }
+                });
+            if ui.button("🔄").on_hover_text(format!("Reload {}/", STRATEGY_DIR)).clicked() {
+                self.strategies = Strategies::load(STRATEGY_DIR);
+                changed = true;
+            }
         });
-        
-        if self.show_favorites_only {
-            filtered.retain(|r| self.favorites.get(&r.name).copied().unwrap_or(false));
-        }
 
-        if !self.search.is_empty() {
-            let search_lower = self.search.to_lowercase();
-            filtered.retain(|r| r.name.to_lowercase().contains(&search_lower));
+        if ui.button("⚖ Compare strategies").clicked() {
+            self.show_compare = !self.show_compare;
         }
 
-        if let Some(ref tier) = self.selected_tier {
-            filtered.retain(|r| &r.tier == tier);
+        let red = Color32::from_rgb(255, 120, 100);
+        for e in self.strategies.errors.iter().chain(&self.strategy_error) {
+            ui.label(RichText::new(e).small().color(red));
         }
 
-        if self.min_profit > 0.0 {
-            filtered.retain(|r| r.profit >= self.min_profit);
+        if changed {
+            self.config.save();
+            if self.loaded {
+                self.load_data();
+            }
         }
+    }
 
-        if self.min_roi > 0.0 {
-            filtered.retain(|r| r.roi >= self.min_roi);
+    // Everything the comparison can pit against each other
+    fn compare_variants(&self) -> Vec<Variant> {
+        let current = |label: String, script: Option<String>| Variant {
+            label,
+            weights: self.config.weights,
+            horizon: self.config.horizon,
+            script,
+            tax: self.tax.clone(),
+            rounding: self.config.rounding,
+            manual_weight: self.config.manual_weight,
+        };
+        let mut variants = vec![current("Current settings".into(), None)];
+        for risk in [RiskTolerance::Cautious, RiskTolerance::Balanced, RiskTolerance::Aggressive] {
+            variants.push(Variant { label: format!("{:?} preset", risk), weights: risk.weights(), ..current(String::new(), None) });
         }
-
-        if !self.show_bad {
-            filtered.retain(|r| r.score > 0);
+        for horizon in Horizon::ALL.into_iter().filter(|h| *h != self.config.horizon) {
+            variants.push(Variant { label: format!("{} horizon", horizon.label()), horizon, ..current(String::new(), None) });
         }
-
-        match self.sort_by {
-            SortBy::Score => filtered.sort_by(|a, b| {
-                if self.sort_order == SortOrder::Descending {
-                    b.score.cmp(&a.score)
-                } else {
-                    a.score.cmp(&b.score)
-                }
-            }),
-            SortBy::Profit => filtered.sort_by(|a, b| {
-                if self.sort_order == SortOrder::Descending {
-                    b.profit.partial_cmp(&a.profit).unwrap()
-                } else {
-                    a.profit.partial_cmp(&b.profit).unwrap()
-                }
-            }),
-            SortBy::ROI => filtered.sort_by(|a, b| {
-                if self.sort_order == SortOrder::Descending {
-                    b.roi.partial_cmp(&a.roi).unwrap()
-                } else {
-                    a.roi.partial_cmp(&b.roi).unwrap()
-                }
-            }),
-            SortBy::Name => filtered.sort_by(|a, b| {
-                if self.sort_order == SortOrder::Descending {
-                    b.name.cmp(&a.name)
-                } else {
-                    a.name.cmp(&b.name)
-                }
-            }),
-            SortBy::Buy => filtered.sort_by(|a, b| {
-                if self.sort_order == SortOrder::Descending {
-                    b.buy.partial_cmp(&a.buy).unwrap()
-                } else {
-                    a.buy.partial_cmp(&b.buy).unwrap()
-                }
-            }),
-            SortBy::Sell => filtered.sort_by(|a, b| {
-                if self.sort_order == SortOrder::Descending {
-                    b.sell.cmp(&a.sell)
-                } else {
-                    a.sell.cmp(&b.sell)
-                }
-            }),
+        for s in &self.strategies.list {
+            variants.push(current(format!("Script: {}", s.name), Some(s.name.clone())));
         }
-
-        self.filtered_items = filtered;
+        variants
     }
 
-    fn tier_color(&self, t: &str) -> Color32 {
-        match t {
-            "DIAMOND" => Color32::from_rgb(0, 255, 255),
-            "GOLD"    => Color32::from_rgb(255, 200, 50),
-            "GREEN"   => Color32::from_rgb(50, 255, 50),
-            "CRASH"   => Color32::RED,
-            _         => Color32::LIGHT_GRAY,
-        }
-    }
-
-    fn tier_badge<'a>(&self, ui: &mut egui::Ui, tier: &str) {
-        let (icon, text, color) = match tier {
-            "DIAMOND" => ("◆", "Diamond", Color32::from_rgb(100, 200, 255)),
-            "GOLD" => ("★", "Gold", Color32::from_rgb(255, 215, 0)),
-            "GREEN" => ("✓", "Good", Color32::from_rgb(100, 255, 150)),
-            "CRASH" => ("▼", "Crash", Color32::from_rgb(255, 100, 100)),
-            _ => ("●", "Normal", Color32::from_rgb(180, 180, 180)),
-        };
-        
-        ui.horizontal(|ui| {
-            ui.label(RichText::new(icon).size(18.0).color(color));
-            ui.label(RichText::new(text).color(color).small());
-        });
-    }
-}
+    fn compare_window(&mut self, ctx: &Context) {
+        let variants = self.compare_variants();
+        self.compare_a = self.compare_a.min(variants.len() - 1);
+        self.compare_b = self.compare_b.min(variants.len() - 1);
 
-impl eframe::App for RS3App {
-    fn update(&mut self, ctx: &Context, _frame: &mut eframe::Frame) {
-        egui::TopBottomPanel::top("top").show(ctx, |ui| {
-            ui.add_space(4.0);
-            
-            // Title bar with RS3 styling
-            ui.horizontal(|ui| {
-                ui.heading(RichText::new("⚔ RuneScape 3 Grand Exchange Analyzer")
-                    .color(Color32::from_rgb(255, 210, 100))
-                    .strong()
-                    .size(24.0)
-                );
-                
+        let mut open = self.show_compare;
+        egui::Window::new("⚖ Compare Strategies")
+            .open(&mut open)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    for (label, choice) in [("A", &mut self.compare_a), ("B", &mut self.compare_b)] {
+                        ui.label(RichText::new(label).strong());
+                        egui::ComboBox::from_id_salt(format!("compare_{}", label))
+                            .selected_text(&variants[*choice].label)
+                            .show_ui(ui, |ui| {
+                                for (i, v) in variants.iter().enumerate() {
+                                    ui.selectable_value(choice, i, &v.label);
+                                }
+                            });
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Backtest the top");
+                    ui.add(egui::DragValue::new(&mut self.compare_top_n).range(1..=100));
+                    ui.label("picks over the last");
+                    ui.add(egui::DragValue::new(&mut self.compare_holdout).range(1..=180).suffix(" days"));
+                });
+
+                if ui.add_enabled(self.loaded, egui::Button::new("▶ Run comparison")).clicked() {
+                    match load_snapshots(self.db_path()) {
+                        Ok(snaps) => {
+                            self.comparison = Some(compare::compare(
+                                &variants[self.compare_a],
+                                &variants[self.compare_b],
+                                &self.strategies,
+                                &snaps,
+                                &self.stats,
+                                self.compare_holdout,
+                                self.compare_top_n,
+                                self.budget,
+                            ));
+                        }
+                        Err(e) => self.scan_error = Some(format!("Couldn't read {}: {}", self.db_path(), e)),
+                    }
+                }
+
+                let Some(cmp) = &self.comparison else { return };
+                ui.separator();
+                ui.label(RichText::new(format!("Backtest: scored on data before the last {} days, then replayed them", cmp.holdout_days)).strong());
+                egui::Grid::new("compare_backtest").striped(true).show(ui, |ui| {
+                    ui.label("");
+                    ui.label("Picks");
+                    ui.label("Filled");
+                    ui.label("Sold");
+                    ui.label("Profit");
+                    ui.label("ROI");
+                    ui.end_row();
+                    for (label, bt) in [("A", &cmp.backtest_a), ("B", &cmp.backtest_b)] {
+                        ui.label(RichText::new(label).strong());
+                        ui.label(bt.picks.to_string());
+                        ui.label(bt.filled.to_string());
+                        ui.label(bt.completed.to_string());
+                        ui.label(format_gp(bt.profit.as_f64()));
+                        ui.label(format!("{:.1}%", bt.roi()));
+                        ui.end_row();
+                    }
+                });
+
+                ui.separator();
+                ui.label(RichText::new("Rank changes (biggest first)").strong())
+                    .on_hover_text("Items in either side's top 100; – means that side filters the item out");
+                let rank = |r: Option<usize>| r.map_or("–".to_string(), |r| format!("#{}", r));
+                egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    egui::Grid::new("compare_ranks").striped(true).num_columns(4).show(ui, |ui| {
+                        ui.label(RichText::new("Item").strong());
+                        ui.label(RichText::new("A").strong());
+                        ui.label(RichText::new("B").strong());
+                        ui.label(RichText::new("Δ").strong());
+                        ui.end_row();
+                        for c in &cmp.changes {
+                            ui.label(&c.name);
+                            ui.label(rank(c.rank_a));
+                            ui.label(rank(c.rank_b));
+                            match (c.rank_a, c.rank_b) {
+                                (Some(a), Some(b)) if a > b => ui.label(RichText::new(format!("▲{}", a - b)).color(Color32::from_rgb(100, 255, 100))),
+                                (Some(a), Some(b)) if b > a => ui.label(RichText::new(format!("▼{}", b - a)).color(Color32::from_rgb(255, 100, 100))),
+                                (Some(_), Some(_)) => ui.label("="),
+                                _ => ui.label(RichText::new("only one side").weak()),
+                            };
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+        self.show_compare = open;
+    }
+
+    fn open_time_machine(&mut self) {
+        if self.show_time_machine {
+            self.show_time_machine = false;
+            self.tm_snaps.clear();
+            return;
+        }
+        match load_snapshots(self.db_path()) {
+            Ok(snaps) => {
+                self.tm_dates = timemachine::dates(&snaps);
+                self.tm_snaps = snaps;
+                let last = self.tm_dates.len().saturating_sub(1);
+                // Default to a month ago against the latest data
+                self.tm_a = last.saturating_sub(30);
+                self.tm_b = last;
+                self.replay_index = 0;
+                self.replay_rows = None;
+                self.show_time_machine = true;
+            }
+            Err(e) => self.scan_error = Some(format!("Couldn't read {}: {}", self.db_path(), e)),
+        }
+    }
+
+    fn time_machine_window(&mut self, ctx: &Context) {
+        let mut open = self.show_time_machine;
+        egui::Window::new("⏳ Time Machine")
+            .open(&mut open)
+            .default_width(620.0)
+            .show(ctx, |ui| {
+                if self.tm_dates.is_empty() {
+                    ui.label("No snapshots to look back through");
+                    return;
+                }
+
+                ui.horizontal(|ui| {
+                    for (label, choice) in [("A", &mut self.tm_a), ("B", &mut self.tm_b)] {
+                        ui.label(RichText::new(label).strong());
+                        egui::ComboBox::from_id_salt(format!("time_machine_{}", label))
+                            .selected_text(&self.tm_dates[*choice])
+                            .height(300.0)
+                            .show_ui(ui, |ui| {
+                                for (i, date) in self.tm_dates.iter().enumerate().rev() {
+                                    ui.selectable_value(choice, i, date);
+                                }
+                            });
+                    }
+                    if ui.button("▶ Run").clicked() {
+                        self.time_machine = Some(timemachine::run(
+                            &self.tm_snaps,
+                            &self.tm_dates[self.tm_a],
+                            &self.tm_dates[self.tm_b],
+                            &self.config.weights,
+                            self.config.horizon,
+                            &self.tax,
+                            self.config.rounding,
+                        ));
+                        if self.tm_view == TimeView::Replay {
+                            self.tm_view = TimeView::Diff;
+                        }
+                    }
+                });
+                ui.label(RichText::new("Each date is scored with your current settings using only the data recorded up to it")
+                    .small()
+                    .color(Color32::from_rgb(180, 160, 120)));
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if let Some(tm) = &self.time_machine {
+                        ui.selectable_value(&mut self.tm_view, TimeView::Diff, "Changes");
+                        ui.selectable_value(&mut self.tm_view, TimeView::A, format!("Table on {}", tm.date_a));
+                        ui.selectable_value(&mut self.tm_view, TimeView::B, format!("Table on {}", tm.date_b));
+                    }
+                    ui.selectable_value(&mut self.tm_view, TimeView::Replay, "🎞 Replay");
+                });
+
+                if self.tm_view == TimeView::Replay {
+                    self.replay_controls(ui);
+                    return;
+                }
+                let Some(tm) = &self.time_machine else { return };
+
+                let palette = self.config.palette;
+                egui::ScrollArea::vertical().max_height(420.0).show(ui, |ui| match self.tm_view {
+                    TimeView::A => past_table(ui, "time_machine_a", &tm.rows_a, palette, None),
+                    TimeView::B => past_table(ui, "time_machine_b", &tm.rows_b, palette, None),
+                    TimeView::Replay => {}
+                    TimeView::Diff => {
+                        egui::Grid::new("time_machine_diff").striped(true).num_columns(5).show(ui, |ui| {
+                            for h in ["Item", "Price A → B", "Change", "Tier A → B", "Score A → B"] {
+                                ui.label(RichText::new(h).strong());
+                            }
+                            ui.end_row();
+                            let opt_gp = |v: Option<f64>| v.map_or("–".to_string(), format_gp);
+                            for d in &tm.diff {
+                                ui.label(&d.name);
+                                ui.label(format!("{} → {}", opt_gp(d.price_a), opt_gp(d.price_b)));
+                                match d.price_change_pct() {
+                                    Some(pct) => {
+                                        let color = if pct >= 0.0 { palette.gain(false) } else { palette.loss(false) };
+                                        ui.label(RichText::new(format!("{:+.1}%", pct)).color(color));
+                                    }
+                                    None => { ui.label("–"); }
+                                }
+                                let tier = |t: &Option<String>| t.clone().unwrap_or_else(|| "–".to_string());
+                                let tier_text = RichText::new(format!("{} → {}", tier(&d.tier_a), tier(&d.tier_b)));
+                                ui.label(match d.tier_move() {
+                                    m if m > 0 => tier_text.color(palette.gain(true)),
+                                    m if m < 0 => tier_text.color(palette.loss(true)),
+                                    _ => tier_text,
+                                });
+                                let score = |s: Option<i32>| s.map_or("–".to_string(), |s| s.to_string());
+                                ui.label(format!("{} → {}", score(d.score_a), score(d.score_b)));
+                                ui.end_row();
+                            }
+                        });
+                    }
+                });
+            });
+        self.show_time_machine = open;
+        if !open {
+            self.tm_snaps.clear();
+            self.replay_playing = false;
+        }
+    }
+
+    // Plays the table forward a day at a time, rescoring on what was known each day
+    fn replay_controls(&mut self, ui: &mut egui::Ui) {
+        let last = self.tm_dates.len() - 1;
+        let now = ui.input(|i| i.time);
+
+        ui.horizontal(|ui| {
+            let play = if self.replay_playing { "⏸ Pause" } else { "▶ Play" };
+            if ui.button(play).clicked() {
+                if !self.replay_playing && self.replay_index >= last {
+                    self.replay_index = 0;
+                }
+                self.replay_playing = !self.replay_playing;
+                self.replay_last_step = now;
+            }
+            if ui.add(egui::Slider::new(&mut self.replay_index, 0..=last).show_value(false)).changed() {
+                self.replay_playing = false;
+            }
+            ui.label(RichText::new(&self.tm_dates[self.replay_index]).strong());
+            ui.separator();
+            ui.add(egui::DragValue::new(&mut self.replay_speed).range(0.25..=10.0).speed(0.1).suffix(" days/s"));
+        });
+
+        if self.replay_playing && now - self.replay_last_step >= 1.0 / self.replay_speed as f64 {
+            self.replay_last_step = now;
+            if self.replay_index < last {
+                self.replay_index += 1;
+            } else {
+                self.replay_playing = false;
+            }
+        }
+
+        if self.replay_rows.as_ref().is_none_or(|(i, _)| *i != self.replay_index) {
+            if let Some((_, rows)) = &self.replay_rows {
+                self.replay_prev_ranks = rows.iter().enumerate().map(|(rank, r)| (r.item_id, rank)).collect();
+            }
+            let rows = timemachine::as_of(&self.tm_snaps, &self.tm_dates[self.replay_index], &self.config.weights, self.config.horizon, &self.tax, self.config.rounding);
+            self.replay_rows = Some((self.replay_index, rows));
+        }
+
+        let Some((_, rows)) = &self.replay_rows else { return };
+        let palette = self.config.palette;
+        egui::ScrollArea::vertical().max_height(420.0).show(ui, |ui| {
+            past_table(ui, "replay_table", &rows[..rows.len().min(25)], palette, Some(&self.replay_prev_ranks));
+        });
+    }
+
+    fn refresh_movers(&mut self) {
+        self.movers = stats::top_movers(&self.stats, self.mover_window, self.movers_by_pct, 15);
+    }
+
+    // Everything tied to a game's database, saved lists and journals is
+    // swapped out; settings are shared
+    fn switch_game(&mut self, game: Game) {
+        self.config.game = game;
+        self.config.save();
+
+        self.favorites = Self::load_favorites(game);
+        self.pinned = Self::load_pinned(game);
+        self.blacklist = Self::load_blacklist(game);
+        self.bulk_selection.clear();
+        self.watchlist = Watchlist::load(game);
+        self.baskets = Baskets::load(game);
+        self.rules = Rules::load(game);
+        self.alert_history = History::load(game);
+        self.fees = Fees::load(game);
+        self.tax = self.fees.active(game);
+        self.journal = Journal::load(game, &self.config.profile);
+        self.profiles = journal::profiles(game);
+        self.refresh_leaderboard();
+        self.fee_status = None;
+        self.holdings = loader::load_holdings(self.db_path()).unwrap_or_default();
+        self.bank_history = loader::load_bank_history(self.db_path()).unwrap_or_default();
+
+        self.loaded = false;
+        self.stats.clear();
+        self.stats_version = None;
+        self.items.clear();
+        self.filtered_items.clear();
+        self.price_rows.clear();
+        self.current_prices.clear();
+        self.previous_scan.clear();
+        self.selected_row = None;
+        self.maintenance.stats = None;
+        self.maintenance.log.clear();
+
+        if loader::database_ready(self.db_path()) {
+            self.scan();
+        } else {
+            self.scan_error = Some(format!("No {} yet - fetch today's prices from the Database tab", self.db_path()));
+        }
+    }
+
+    // Runs the alert rules over the scan, adding what fired to the alert list,
+    // and records the new prices against earlier firings
+    fn evaluate_rules(&mut self, quiet: bool) -> Vec<Firing> {
+        let subjects: Vec<Subject> = self.items
+            .iter()
+            .map(|r| Subject {
+                item_id: r.item_id,
+                name: &r.name,
+                price: self.current_prices.get(&r.item_id).copied().unwrap_or(r.buy.as_f64()),
+                buy: r.buy.as_f64(),
+                sell: r.sell.as_f64(),
+                roi: r.roi,
+                profit: r.profit.as_f64(),
+                score: r.score as f64,
+                volume: r.avg_volume,
+                trend: r.trend,
+                tier: &r.tier,
+                prev_tier: r.prev_tier.as_deref(),
+            })
+            .collect();
+        let firings = self.rules.evaluate(&subjects, self.last_scan_at, quiet);
+        let db_path = self.db_path();
+        let followed_up = self.alert_history.follow_up(
+            |item_id, from| {
+                loader::load_item_history_range(db_path, item_id, Some(from), None)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(date, price, _)| (date, price))
+                    .collect()
+            },
+            self.last_scan_at,
+        );
+        if !firings.is_empty() {
+            self.rules.save();
+            self.alert_history.record(&firings, self.last_scan_at);
+        }
+        if followed_up || !firings.is_empty() {
+            self.alert_history.save();
+        }
+        self.alerts.extend(firings.iter().map(|f| Alert {
+            kind: AlertKind::Rule,
+            item_id: Some(f.item_id),
+            item: f.item.clone(),
+            message: format!("matched \"{}\": {}", f.rule, f.reason),
+        }));
+        firings
+    }
+
+    // Email and webhook rule alerts; Telegram ones go out with the other alerts
+    fn notify_rules(&self, firings: &[Firing]) {
+        let emailed: Vec<String> = firings.iter().filter(|f| f.channel == Channel::Email).map(rule_alert_text).collect();
+        if !emailed.is_empty() && !self.config.email.smtp_host.trim().is_empty() && !self.config.email.to.trim().is_empty() {
+            let subject = format!("{} alert rules: {} matched", self.config.game.short_label(), emailed.len());
+            self.mailer.send(&self.config.email, subject, report::render_alerts_html(&emailed));
+        }
+
+        let posted: Vec<&Firing> = firings.iter().filter(|f| f.channel == Channel::Webhook).collect();
+        if !posted.is_empty() && self.config.post_scan.is_set() {
+            self.hooks.run(&self.config.post_scan, report::render_alerts_json(&posted));
+        }
+    }
+
+    fn rules_window(&mut self, ctx: &Context) {
+        let mut open = self.show_rules;
+        let mut changed = false;
+        egui::Window::new("📣 Alert Rules")
+            .open(&mut open)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                ui.label(RichText::new("Checked after every scan. A rule fires when any of its groups matches; a group matches when all of its conditions hold.")
+                    .small()
+                    .color(Color32::from_rgb(180, 160, 120)));
+                ui.add_space(4.0);
+
+                let mut remove = None;
+                egui::ScrollArea::vertical().max_height(480.0).show(ui, |ui| {
+                    for (ri, rule) in self.rules.rules.iter_mut().enumerate() {
+                        egui::CollapsingHeader::new(RichText::new(&rule.name).strong())
+                            .id_salt(("alert_rule", ri))
+                            .default_open(rule.groups.iter().all(|g| g.is_empty()))
+                            .show(ui, |ui| {
+                                changed |= rule_editor(ui, ri, rule);
+                                if ui.button("🗑 Delete rule").clicked() {
+                                    remove = Some(ri);
+                                }
+                            });
+                    }
+                });
+                if let Some(ri) = remove {
+                    self.rules.rules.remove(ri);
+                    changed = true;
+                }
+
+                ui.separator();
+                if ui.button("➕ New rule").clicked() {
+                    self.rules.rules.push(Rule::default());
+                    changed = true;
+                }
+
+                ui.separator();
+                egui::CollapsingHeader::new(RichText::new("📊 Effectiveness").strong())
+                    .id_salt("rule_effectiveness")
+                    .show(ui, |ui| self.rule_effectiveness(ui));
+            });
+        if changed {
+            self.rules.save();
+        }
+        self.show_rules = open;
+    }
+
+    // What prices did after each rule fired. "Profitable" is buying at the
+    // trigger price and selling at the later one, after tax.
+    fn rule_effectiveness(&mut self, ui: &mut egui::Ui) {
+        let rows = self.alert_history.effectiveness(&self.tax);
+        if rows.is_empty() {
+            ui.label(RichText::new("No rule has fired yet. Prices are checked again 1, 3 and 7 days after each alert.")
+                .color(Color32::from_rgb(180, 160, 120)));
+            return;
+        }
+
+        let now = journal::now_secs();
+        let mut forget = None;
+        egui::Grid::new("rule_effectiveness_grid").striped(true).num_columns(3 + 2 * rules::HORIZONS.len() + 1).show(ui, |ui| {
+            ui.label(RichText::new("Rule").strong());
+            ui.label(RichText::new("Fired").strong());
+            ui.label(RichText::new("Last").strong());
+            for (_, label) in rules::HORIZONS {
+                ui.label(RichText::new(format!("{} chg", label)).strong());
+                ui.label(RichText::new(format!("{} win", label)).strong());
+            }
+            ui.label("");
+            ui.end_row();
+
+            for row in &rows {
+                ui.label(&row.rule);
+                ui.label(row.fired.to_string());
+                ui.label(format!("{}d ago", now.saturating_sub(row.last_fired) / 86_400));
+                for h in 0..rules::HORIZONS.len() {
+                    let resolved = format!("{} of {} firings checked so far", row.resolved[h], row.fired);
+                    match row.avg_change[h] {
+                        Some(change) => {
+                            let color = if change >= 0.0 { Color32::from_rgb(100, 220, 100) } else { Color32::from_rgb(255, 100, 100) };
+                            ui.label(RichText::new(format!("{:+.1}%", change)).color(color)).on_hover_text(&resolved);
+                        }
+                        None => {
+                            ui.label("-").on_hover_text(&resolved);
+                        }
+                    }
+                    match row.profitable[h] {
+                        Some(pct) => ui.label(format!("{:.0}%", pct)).on_hover_text("Share of firings where a flip would have made money"),
+                        None => ui.label("-"),
+                    };
+                }
+                if ui.small_button("🗑").on_hover_text("Forget this rule's history").clicked() {
+                    forget = Some(row.rule.clone());
+                }
+                ui.end_row();
+            }
+        });
+
+        if let Some(rule) = forget {
+            self.alert_history.forget_rule(&rule);
+            self.alert_history.save();
+        }
+    }
+
+    // Full market scan - also snapshots the bank value at the new prices
+    fn scan(&mut self) {
+        self.last_scan_at = journal::now_secs();
+        self.previous_scan = loader::load_last_scan(self.db_path()).unwrap_or_default();
+        self.finishing_scan = true;
+        self.refreshing = self.loaded;
+        self.load_data();
+    }
+
+    // Diffs the new table against the previous scan so the cells that moved
+    // can flash, instead of the whole table silently swapping
+    fn mark_changes(&mut self) {
+        let direction = |old: Gp, new: Gp| (old != new).then_some(new > old);
+        self.cell_changes = self.items
+            .iter()
+            .filter_map(|r| {
+                let prev = self.previous_scan.get(&r.item_id)?;
+                let change = CellChange {
+                    tier: (prev.tier != r.tier).then(|| tier_rank(&r.tier) > tier_rank(&prev.tier)),
+                    buy: direction(prev.buy, r.buy),
+                    sell: direction(prev.sell, r.sell),
+                };
+                (change.tier.is_some() || change.buy.is_some() || change.sell.is_some()).then_some((r.item_id, change))
+            })
+            .collect();
+        self.changes_at = (!self.cell_changes.is_empty()).then(Instant::now);
+    }
+
+    // Logging, alerts and notifications, once a scan's snapshots are analysed
+    fn finish_scan(&mut self) {
+        if std::mem::take(&mut self.refreshing) && self.loaded {
+            self.mark_changes();
+        }
+        if self.loaded {
+            let entries: Vec<ScanEntry> = self.items
+                .iter()
+                .map(|r| ScanEntry { item_id: r.item_id, tier: r.tier.clone(), score: r.score, buy: r.buy, sell: r.sell })
+                .collect();
+            let _ = loader::record_scan(self.db_path(), &entries);
+        }
+        if self.loaded && !self.holdings.is_empty() {
+            let _ = loader::record_bank_value(self.db_path(), self.bank_value());
+            self.bank_history = loader::load_bank_history(self.db_path()).unwrap_or_default();
+        }
+        // Quiet hours and Pause alerts hold back everything that leaves the app
+        let quiet = self.alerts_paused || self.config.quiet_hours.is_quiet(self.last_scan_at);
+        if self.loaded {
+            self.alerts = alerts::holding_alerts(&self.holdings, &self.current_prices, self.config.holding_drop_alert_pct);
+            self.alerts.extend(alerts::basket_alerts(&self.baskets, &self.stats));
+            // Only marked sent when they can actually go out
+            let mut telegram: Vec<String> = if quiet {
+                vec![]
+            } else {
+                self.notified.due(&self.alerts, self.last_scan_at).into_iter().map(|a| a.text()).collect()
+            };
+            let reopen = !telegram.is_empty();
+            let firings = self.evaluate_rules(quiet);
+            self.show_alerts = !quiet && (reopen || !firings.is_empty());
+
+            if !quiet {
+                telegram.extend(firings.iter().filter(|f| f.channel == Channel::Telegram).map(rule_alert_text));
+                if !telegram.is_empty() && self.config.telegram.is_ready() {
+                    self.telegram.send(&self.config.telegram, &telegram.join("\n"));
+                }
+                self.notify_rules(&firings);
+            }
+        }
+
+        if self.loaded && !quiet && self.config.post_scan.is_set() {
+            let rows = self.report_rows();
+            self.hooks.run(&self.config.post_scan, report::render_json(&self.report(&rows)));
+        }
+
+        if self.loaded && !self.config.alt1_path.is_empty() {
+            self.write_alt1_targets();
+        }
+
+        // Not marked sent while quiet, so it goes out with the first scan after
+        if self.loaded && !quiet && self.config.email.is_due(self.last_scan_at) {
+            self.send_digest();
+        }
+
+        // Light up the tray for alerts or items that just became Diamond
+        let new_diamond = self.items.iter().any(|r| {
+            r.tier == "DIAMOND" && self.previous_scan.get(&r.item_id).is_none_or(|p| p.tier != "DIAMOND")
+        });
+        if let Some(tray) = &mut self.tray {
+            tray.set_highlight(!self.alerts.is_empty() || new_diamond);
+        }
+    }
+
+    // Answers `/flips` from the current (filtered) results
+    fn handle_telegram(&mut self, ctx: &Context) {
+        self.telegram.poll(&self.config.telegram, ctx.input(|i| i.time));
+        let queries = self.telegram.take_queries();
+        if queries.is_empty() {
+            return;
+        }
+        let rows = self.report_rows();
+        let report = self.report(&rows);
+        for q in queries {
+            let text = if self.loaded {
+                report::render_text(&report, q.limit)
+            } else {
+                "No scan yet - open the app and press Scan Market.".to_string()
+            };
+            self.telegram.reply(&self.config.telegram, q.chat_id, &text);
+        }
+    }
+
+    fn handle_tray(&mut self, ctx: &Context) {
+        if !self.tray_started {
+            self.tray_started = true;
+            self.tray = Tray::new(ctx);
+        }
+        let Some(tray) = &self.tray else { return };
+
+        for cmd in tray.poll() {
+            match cmd {
+                TrayCommand::ScanNow => self.scan(),
+                TrayCommand::Open => {
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+                    if let Some(tray) = &mut self.tray {
+                        tray.set_highlight(false);
+                    }
+                }
+                TrayCommand::TogglePause => {
+                    self.alerts_paused = !self.alerts_paused;
+                    if let Some(tray) = &self.tray {
+                        tray.set_paused(self.alerts_paused);
+                    }
+                }
+                TrayCommand::Quit => {
+                    self.quitting = true;
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                }
+            }
+        }
+
+        if self.config.close_to_tray && !self.quitting && ctx.input(|i| i.viewport().close_requested()) {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            ctx.send_viewport_cmd(egui::ViewportCommand::Visible(false));
+        }
+    }
+
+    // Keeps the window state current and writes it out when the window closes
+    fn track_window(&mut self, ctx: &Context) {
+        let (inner, outer, maximized, minimized, closing) = ctx.input(|i| {
+            let v = i.viewport();
+            (v.inner_rect, v.outer_rect, v.maximized, v.minimized, v.close_requested())
+        });
+
+        let window = &mut self.config.window;
+        if let Some(maximized) = maximized {
+            window.maximized = maximized;
+        }
+        // Keep the restored size and spot, not the maximized or minimized one
+        if !window.maximized && minimized != Some(true) {
+            if let Some(rect) = inner {
+                window.size = Some([rect.width(), rect.height()]);
+            }
+            if let Some(rect) = outer {
+                window.position = Some([rect.min.x, rect.min.y]);
+            }
+        }
+        window.tab = self.tab.key().to_string();
+
+        if closing {
+            self.config.save();
+        }
+    }
+
+    fn bank_value(&self) -> Gp {
+        self.holdings
+            .iter()
+            .map(|h| self.current_prices.get(&h.item_id).map_or(h.cost_basis, |&p| Gp::from_f64(p)) * h.qty)
+            .sum()
+    }
+
+    fn filter_settings(&self) -> FilterSettings<'_> {
+        FilterSettings {
+            disabled: &self.disabled_filters,
+            blacklist: &self.blacklist,
+            favorites: &self.favorites,
+            favorites_only: self.show_favorites_only,
+            search: self.search.to_lowercase(),
+            tier: self.selected_tier.as_deref(),
+            min_profit: self.min_profit,
+            min_roi: self.min_roi,
+            min_coverage: self.min_coverage,
+            min_data_points: self.min_data_points,
+            max_stale_days: self.max_stale_days,
+            max_drawdown: self.max_drawdown,
+            hidden_regimes: &self.hidden_regimes,
+            show_bad: self.show_bad,
+            beginner_mode: self.config.beginner_mode,
+        }
+    }
+
+    // Filters in use, what each removed, and a switch to turn each off
+    fn filter_summary(&mut self, ui: &mut egui::Ui) {
+        ui.label(RichText::new("🧹 Active Filters").strong())
+            .on_hover_text("Rows each filter removed, in the order they run. Untick one to see what it hides without losing its setting.");
+        let mut toggled = None;
+        egui::Grid::new("filter_summary").num_columns(2).show(ui, |ui| {
+            let settings = self.filter_settings();
+            for kind in FilterKind::ALL.into_iter().filter(|k| settings.in_use(*k)) {
+                let mut on = !self.disabled_filters.contains(&kind);
+                if ui.checkbox(&mut on, kind.label()).changed() {
+                    toggled = Some(kind);
+                }
+                match self.filter_removed.iter().find(|(k, _)| *k == kind) {
+                    Some((_, 0)) => ui.label(RichText::new("-0").color(Color32::GRAY)),
+                    Some((_, n)) => ui.label(RichText::new(format!("-{}", n)).color(Color32::from_rgb(255, 150, 150))),
+                    None => ui.label(RichText::new("off").italics().color(Color32::GRAY)),
+                };
+                ui.end_row();
+            }
+        });
+        ui.label(RichText::new(format!("{} of {} items shown", self.filtered_items.len(), self.items.len()))
+            .small()
+            .color(Color32::from_rgb(180, 160, 120)));
+
+        if let Some(kind) = toggled {
+            if !self.disabled_filters.remove(&kind) {
+                self.disabled_filters.insert(kind);
+            }
+            self.apply_filters();
+        }
+    }
+
+    fn apply_filters(&mut self) {
+        let (mut filtered, removed) = filter_rows(&self.items, &self.filter_settings());
+        self.filter_removed = removed;
+
+        // One row per variant family: its best-scoring variant stands in for the rest
+        if self.config.merge_variants {
+            filtered.sort_by(|a, b| b.score.cmp(&a.score).then(a.item_id.cmp(&b.item_id)));
+            let mut leaders: HashMap<String, usize> = HashMap::new();
+            let mut merged: Vec<Row> = Vec::with_capacity(filtered.len());
+            for r in filtered {
+                match leaders.get(variants::family(&r.name)) {
+                    Some(&i) => merged[i].merged_variants.push(r.name),
+                    None => {
+                        leaders.insert(variants::family(&r.name).to_string(), merged.len());
+                        merged.push(r);
+                    }
+                }
+            }
+            filtered = merged;
+        }
+
+        // Pinned items are shown even when the filters would hide them
+        for r in &self.items {
+            if self.pinned.contains(&r.item_id) && !filtered.iter().any(|f| f.item_id == r.item_id) {
+                filtered.push(r.clone());
+            }
+        }
+
+        filtered.sort_by(|a, b| compare_rows(a, b, self.sort_by, self.sort_order));
+
+        // Stable, so pinned rows keep the chosen order among themselves
+        filtered.sort_by_key(|r| !self.pinned.contains(&r.item_id));
+
+        let search = self.search.to_lowercase();
+        self.search_matches = if search.is_empty() {
+            vec![]
+        } else {
+            (0..filtered.len()).filter(|&i| filtered[i].name.to_lowercase().contains(&search)).collect()
+        };
+        self.filtered_items = filtered;
+    }
+
+    fn shopping_list(&self) -> String {
+        let candidates = self.filtered_items.iter().map(|r| PlanLine {
+            name: r.name.clone(),
+            buy: r.buy,
+            sell: r.sell,
+            qty: r.qty,
+            cost: r.total_cost,
+            profit: r.unit_profit,
+            downside: r.downside,
+        });
+        let risk_budget = (self.config.max_drawdown_pct > 0.0).then(|| self.budget * self.config.max_drawdown_pct / 100.0);
+        let plan = planner::allocate(candidates, self.budget, self.shopping_list_size, risk_budget);
+        planner::shopping_list_markdown(&plan, format_gp)
+    }
+
+    fn report_rows(&self) -> Vec<ScanRow> {
+        self.report_rows_where(|_| true)
+    }
+
+    fn report_rows_where(&self, keep: impl Fn(&Row) -> bool) -> Vec<ScanRow> {
+        let sparklines: HashMap<i32, &Vec<f64>> = self.stats
+            .iter()
+            .map(|s| (s.item_id, &s.recent_prices_chrono))
+            .collect();
+
+        self.filtered_items
+            .iter()
+            .filter(|r| keep(r))
+            .map(|r| ScanRow {
+                item_id: r.item_id,
+                name: r.name.clone(),
+                tier: r.tier.clone(),
+                score: r.score,
+                buy: r.buy,
+                sell: r.sell,
+                qty: r.qty,
+                profit: r.profit,
+                roi: r.roi,
+                sparkline: sparklines
+                    .get(&r.item_id)
+                    .map(|v| v.iter().map(|&p| Gp::from_f64(p)).collect())
+                    .unwrap_or_default(),
+                summary: r.summary.clone(),
+            })
+            .collect()
+    }
+
+    fn report<'a>(&self, rows: &'a [ScanRow]) -> Report<'a> {
+        Report {
+            title: format!("{} Grand Exchange Flips", self.config.game.short_label()),
+            data_date: self.stats.iter().map(|s| s.last_date.as_str()).max().unwrap_or("").to_string(),
+            budget: self.budget,
+            rows,
+        }
+    }
+
+    // Every analysed item, not just the filtered ones - the overlay can be
+    // hovering anything in the GE
+    fn write_alt1_targets(&mut self) {
+        let targets = self.items.iter().map(|r| {
+            (r.item_id, alt1::Target {
+                name: r.name.clone(),
+                buy: r.buy,
+                sell: r.sell,
+                qty: r.qty,
+                profit_each: r.unit_profit,
+                roi: r.roi,
+                tier: r.tier.clone(),
+                score: r.score,
+            })
+        });
+        let data_date = self.stats.iter().map(|s| s.last_date.as_str()).max().unwrap_or("").to_string();
+        let targets = Targets::new(journal::now_secs(), data_date, targets);
+        self.alt1_status = Some(match targets.write(&self.config.alt1_path) {
+            Ok(_) => format!("Wrote {} items to {}", targets.items.len(), self.config.alt1_path),
+            Err(e) => format!("Couldn't write {}: {}", self.config.alt1_path, e),
+        });
+    }
+
+    fn send_digest(&mut self) {
+        let rows = self.report_rows();
+        let portfolio = (!self.holdings.is_empty()).then(|| Portfolio {
+            value: self.bank_value(),
+            cost: self.holdings.iter().map(|h| h.cost_basis * h.qty).sum(),
+        });
+        let digest = Digest {
+            report: self.report(&rows),
+            alerts: self.alerts.iter().map(|a| a.text()).collect(),
+            portfolio,
+        };
+        let subject = format!("{} flips for {}", self.config.game.short_label(), digest.report.data_date);
+        self.mailer.send(&self.config.email, subject, report::render_digest_html(&digest, 10));
+
+        self.config.email.last_sent_day = (journal::now_secs() / 86_400) as i64;
+        self.config.save();
+    }
+
+    fn generate_report(&self) -> std::io::Result<()> {
+        let rows = self.report_rows();
+        let report = self.report(&rows);
+
+        std::fs::write("report.html", report::render_html(&report))?;
+        std::fs::write("report.md", report::render_markdown(&report))
+    }
+
+    // Guided first-run setup: data source, budget, risk tolerance, first scan
+    fn wizard_window(&mut self, ctx: &Context) {
+        const STEPS: [&str; 4] = ["Data", "Budget", "Risk", "Finish"];
+        let db_ready = loader::database_ready(self.db_path());
+
+        egui::Window::new("⚔ Welcome to the Grand Exchange Analyzer")
+            .collapsible(false)
+            .resizable(false)
+            .default_width(520.0)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    for (i, name) in STEPS.iter().enumerate() {
+                        let color = if i == self.wizard_step {
+                            Color32::from_rgb(255, 210, 100)
+                        } else {
+                            Color32::from_rgb(130, 110, 80)
+                        };
+                        ui.label(RichText::new(format!("{}. {}", i + 1, name)).color(color).strong());
+                        if i + 1 < STEPS.len() {
+                            ui.label(RichText::new("›").color(Color32::from_rgb(130, 110, 80)));
+                        }
+                    }
+                });
+                ui.separator();
+
+                match self.wizard_step {
+                    0 => {
+                        ui.label(RichText::new("Market data").strong().size(16.0));
+                        ui.horizontal(|ui| {
+                            ui.label("Game:");
+                            let mut game = self.config.game;
+                            for g in Game::ALL {
+                                ui.radio_value(&mut game, g, g.label());
+                            }
+                            if game != self.config.game && !self.maintenance.is_running() {
+                                self.switch_game(game);
+                            }
+                        });
+                        if db_ready {
+                            ui.label(RichText::new(format!("✔ Found {}", self.db_path())).color(Color32::from_rgb(150, 255, 150)));
+                        } else if self.config.game == Game::Osrs {
+                            ui.label("Old School prices come from the OSRS Wiki. Each fetch records one day; history builds up from there.");
+                            if ui.add_enabled(!self.maintenance.is_running(), egui::Button::new("🌐 Fetch today's prices")).clicked() {
+                                self.start_task(Task::FetchOsrsPrices);
+                            }
+                        } else if loader::is_lfs_pointer(loader::DB_ARCHIVE_PATH) {
+                            ui.label(RichText::new(format!(
+                                "{} is a Git LFS pointer - run `git lfs pull` to download the archive, then reopen this step.",
+                                loader::DB_ARCHIVE_PATH
+                            )).color(Color32::from_rgb(255, 180, 100)));
+                        } else if std::path::Path::new(loader::DB_ARCHIVE_PATH).exists() {
+                            ui.label(format!("The repository ships a compressed database ({}).", loader::DB_ARCHIVE_PATH));
+                            if ui.add_enabled(!self.maintenance.is_running(), egui::Button::new("📦 Decompress it")).clicked() {
+                                self.start_task(Task::InstallDatabase { source: loader::DB_ARCHIVE_PATH.into() });
+                            }
+                        } else {
+                            ui.label(RichText::new(format!("No {} found.", self.db_path())).color(Color32::from_rgb(255, 180, 100)));
+                        }
+
+                        if !db_ready {
+                            ui.add_space(6.0);
+                            ui.label("Or use an existing database (.db or .db.gz):");
+                            ui.horizontal(|ui| {
+                                ui.add(egui::TextEdit::singleline(&mut self.wizard_db_source)
+                                    .hint_text("path/to/rs3_market.db")
+                                    .desired_width(300.0));
+                                let can_copy = !self.wizard_db_source.trim().is_empty() && !self.maintenance.is_running();
+                                if ui.add_enabled(can_copy, egui::Button::new("Use this file")).clicked() {
+                                    self.start_task(Task::InstallDatabase { source: self.wizard_db_source.trim().to_string() });
+                                }
+                            });
+                        }
+
+                        if self.maintenance.is_running() {
+                            ui.horizontal(|ui| {
+                                ui.spinner();
+                                ui.label("Working...");
+                            });
+                        }
+                        if let Some(line) = self.maintenance.log.last() {
+                            ui.label(RichText::new(line).small().color(Color32::from_rgb(180, 160, 120)));
+                        }
+                    }
+                    1 => {
+                        ui.label(RichText::new("Your bank").strong().size(16.0));
+                        ui.horizontal(|ui| {
+                            ui.label("Total bank value:");
+                            let mut bank_m = self.config.bank_size / 1_000_000.0;
+                            if ui.add(egui::DragValue::new(&mut bank_m).suffix(" M").speed(1.0).range(0.0..=1_000_000.0)).changed() {
+                                self.config.bank_size = bank_m * 1_000_000.0;
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Flipping budget:");
+                            let mut budget_m = self.config.budget / 1_000_000.0;
+                            if ui.add(egui::DragValue::new(&mut budget_m).suffix(" M").speed(1.0).range(0.1..=1_000_000.0)).changed() {
+                                self.config.budget = budget_m * 1_000_000.0;
+                            }
+                        });
+                        ui.label(RichText::new("The budget is the gp you're willing to tie up in offers at once.")
+                            .small()
+                            .color(Color32::from_rgb(180, 160, 120)));
+                    }
+                    2 => {
+                        ui.label(RichText::new("Risk tolerance").strong().size(16.0));
+                        for (risk, label, blurb) in [
+                            (RiskTolerance::Cautious, "🛡 Cautious", "Favour liquid, steady items; punish crashes hard"),
+                            (RiskTolerance::Balanced, "⚖ Balanced", "The default scoring"),
+                            (RiskTolerance::Aggressive, "🔥 Aggressive", "Chase ROI and volatile items; lighter risk penalties"),
+                        ] {
+                            ui.horizontal(|ui| {
+                                ui.radio_value(&mut self.config.risk_tolerance, risk, RichText::new(label).strong());
+                                ui.label(RichText::new(blurb).color(Color32::from_rgb(180, 160, 120)));
+                            });
+                        }
+                        if self.config.bank_size > 0.0 {
+                            let suggested = self.config.bank_size * self.config.risk_tolerance.budget_share();
+                            ui.add_space(4.0);
+                            if ui.button(format!("Use suggested budget: {}", format_gp(suggested))).clicked() {
+                                self.config.budget = suggested.max(100_000.0);
+                            }
+                        }
+                    }
+                    _ => {
+                        ui.label(RichText::new("All set").strong().size(16.0));
+                        ui.label(format!("Budget: {}", format_gp(self.config.budget)));
+                        ui.label(format!("Risk tolerance: {:?}", self.config.risk_tolerance));
+                        if !db_ready {
+                            ui.label(RichText::new("No database yet - go back to step 1 to set one up.")
+                                .color(Color32::from_rgb(255, 180, 100)));
+                        }
+                    }
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if self.wizard_step > 0 && ui.button("◀ Back").clicked() {
+                        self.wizard_step -= 1;
+                    }
+                    if self.wizard_step + 1 < STEPS.len() {
+                        if ui.button("Next ▶").clicked() {
+                            self.wizard_step += 1;
+                        }
+                    } else if ui.add_enabled(db_ready, egui::Button::new(RichText::new("🔍 Finish & Scan").strong())).clicked() {
+                        self.config.weights = self.config.risk_tolerance.weights();
+                        self.config.save();
+                        self.budget = self.config.budget;
+                        self.show_wizard = false;
+                        self.scan();
+                    }
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.small_button("Skip setup").clicked() {
+                            self.config.save();
+                            self.show_wizard = false;
+                        }
+                    });
+                });
+            });
+    }
+
+    // Auto-scan and quiet-hours settings; hours are UTC like the GE reset
+    fn schedule_settings(&mut self, ui: &mut egui::Ui) {
+        let mut changed = false;
+        ui.label(RichText::new("⏰ Auto Scan").strong());
+        changed |= ui.checkbox(&mut self.config.schedule.enabled, "Rescan automatically").changed();
+        ui.add_enabled_ui(self.config.schedule.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Every");
+                changed |= ui.add(egui::DragValue::new(&mut self.config.schedule.every_minutes)
+                    .range(5..=1440)
+                    .suffix(" min"))
+                    .changed();
+            });
+            ui.horizontal(|ui| {
+                ui.label("Between");
+                changed |= ui.add(hour_drag(&mut self.config.schedule.from_hour)).changed();
+                ui.label("and");
+                changed |= ui.add(hour_drag(&mut self.config.schedule.to_hour)).changed();
+                ui.label("UTC");
+            });
+        });
+
+        ui.add_space(6.0);
+        ui.label(RichText::new("🌙 Quiet Hours").strong());
+        changed |= ui.checkbox(&mut self.config.quiet_hours.enabled, "Hold back alerts and notifications")
+            .on_hover_text("No pop-ups, Telegram, email or webhook alerts, post-scan hook or daily digest until quiet hours end. \
+                            In-app alerts are still collected under 🔔; rules that notify elsewhere fire on the first scan after.")
+            .changed();
+        ui.add_enabled_ui(self.config.quiet_hours.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("From");
+                changed |= ui.add(hour_drag(&mut self.config.quiet_hours.from_hour)).changed();
+                ui.label("to");
+                changed |= ui.add(hour_drag(&mut self.config.quiet_hours.to_hour)).changed();
+                ui.label("UTC");
+            });
+        });
+
+        ui.add_space(6.0);
+        ui.label(RichText::new("📨 Telegram").strong());
+        changed |= ui.checkbox(&mut self.config.telegram.enabled, "Send alerts and answer /flips")
+            .on_hover_text("Create a bot with @BotFather, paste its token, then message it /flips")
+            .changed();
+        ui.add_enabled_ui(self.config.telegram.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Bot token");
+                changed |= ui.add(egui::TextEdit::singleline(&mut self.config.telegram.bot_token)
+                    .password(true)
+                    .desired_width(140.0))
+                    .changed();
+            });
+            ui.horizontal(|ui| {
+                ui.label("Chat id");
+                changed |= ui.add(egui::TextEdit::singleline(&mut self.config.telegram.chat_id)
+                    .desired_width(140.0))
+                    .on_hover_text("Alerts go here, and only this chat can ask for /flips")
+                    .changed();
+            });
+            if ui.button("Send test").clicked() {
+                self.telegram.send(&self.config.telegram, "RS3 Market Analyzer is connected");
+            }
+            if let Some(status) = self.telegram.status() {
+                ui.label(RichText::new(status).small().color(Color32::from_rgb(180, 180, 180)));
+            }
+        });
+
+        ui.add_space(6.0);
+        egui::CollapsingHeader::new(RichText::new("✉ Daily Email").strong()).id_salt("email_digest").show(ui, |ui| {
+            let email = &mut self.config.email;
+            changed |= ui.checkbox(&mut email.enabled, "Email a daily digest")
+                .on_hover_text("Top 10 flips, alerts and portfolio P&L, sent after the first scan past the send hour")
+                .changed();
+            egui::Grid::new("email_settings").num_columns(2).show(ui, |ui| {
+                ui.label("SMTP host");
+                changed |= ui.add(egui::TextEdit::singleline(&mut email.smtp_host).desired_width(140.0)).changed();
+                ui.end_row();
+                ui.label("Port");
+                changed |= ui.add(egui::DragValue::new(&mut email.smtp_port)).changed();
+                ui.end_row();
+                ui.label("Username");
+                changed |= ui.add(egui::TextEdit::singleline(&mut email.username).desired_width(140.0)).changed();
+                ui.end_row();
+                ui.label("Password");
+                changed |= ui.add(egui::TextEdit::singleline(&mut email.password).password(true).desired_width(140.0))
+                    .on_hover_text("Saved in config.json in plain text - use an app password")
+                    .changed();
+                ui.end_row();
+                ui.label("From");
+                changed |= ui.add(egui::TextEdit::singleline(&mut email.from).desired_width(140.0)).changed();
+                ui.end_row();
+                ui.label("To");
+                changed |= ui.add(egui::TextEdit::singleline(&mut email.to).desired_width(140.0)).changed();
+                ui.end_row();
+                ui.label("Send after");
+                ui.horizontal(|ui| {
+                    changed |= ui.add(hour_drag(&mut email.send_hour)).changed();
+                    ui.label("UTC");
+                });
+                ui.end_row();
+            });
+        });
+        ui.horizontal(|ui| {
+            if ui.add_enabled(self.loaded, egui::Button::new("Send digest now")).clicked() {
+                self.send_digest();
+            }
+            if let Some(status) = self.mailer.status() {
+                ui.label(RichText::new(status).small().color(Color32::from_rgb(180, 180, 180)));
+            }
+        });
+
+        ui.add_space(6.0);
+        egui::CollapsingHeader::new(RichText::new("🔗 After Each Scan").strong()).id_salt("post_scan_hooks").show(ui, |ui| {
+            ui.label(RichText::new("The results are sent as JSON (title, data_date, budget, rows)").small());
+            ui.label("Run command (JSON on stdin)");
+            changed |= ui.add(egui::TextEdit::singleline(&mut self.config.post_scan.command)
+                .hint_text("python update_sheet.py")
+                .desired_width(200.0))
+                .changed();
+            ui.label("POST to URL");
+            changed |= ui.add(egui::TextEdit::singleline(&mut self.config.post_scan.url)
+                .hint_text("http://homeassistant.local/api/webhook/rs3")
+                .desired_width(200.0))
+                .changed();
+            if let Some(status) = self.hooks.status() {
+                ui.label(RichText::new(status).small().color(Color32::from_rgb(180, 180, 180)));
+            }
+            ui.label("Alt1 overlay file")
+                .on_hover_text(format!("Buy/sell targets for every item, as {} v{} JSON (see src/alt1.rs). Serve its folder over HTTP for the overlay to read.", alt1::SCHEMA, alt1::VERSION));
+            ui.horizontal(|ui| {
+                changed |= ui.add(egui::TextEdit::singleline(&mut self.config.alt1_path)
+                    .hint_text("alt1_targets.json")
+                    .desired_width(150.0))
+                    .changed();
+                if ui.add_enabled(self.loaded && !self.config.alt1_path.is_empty(), egui::Button::new("Write now")).clicked() {
+                    self.write_alt1_targets();
+                }
+            });
+            if let Some(status) = &self.alt1_status {
+                ui.label(RichText::new(status).small().color(Color32::from_rgb(180, 180, 180)));
+            }
+        });
+
+        if self.tray.is_some() {
+            ui.add_space(6.0);
+            changed |= ui.checkbox(&mut self.config.close_to_tray, "Close to tray")
+                .on_hover_text("Closing the window keeps the app and its auto scan running in the tray; quit from the tray menu")
+                .changed();
+            if ui.checkbox(&mut self.alerts_paused, "Pause alerts").changed() {
+                if let Some(tray) = &self.tray {
+                    tray.set_paused(self.alerts_paused);
+                }
+            }
+        }
+
+        if changed {
+            self.config.save();
+        }
+    }
+
+    fn money(&self) -> Money {
+        Money {
+            currency: self.config.currency,
+            bond_price: self.bond_price,
+            usd_per_bond: self.config.usd_per_bond,
+        }
+    }
+
+    fn profit_tracker(&mut self, ui: &mut egui::Ui) {
+        let now = journal::now_secs();
+        let today = self.journal.realized_since(journal::day_start(now), &self.tax).as_f64();
+        let session = self.journal.realized_since(self.session_start, &self.tax).as_f64();
+        let hours = (now.saturating_sub(self.session_start) as f64 / 3600.0).max(1.0 / 60.0);
+
+        let goal = self.config.daily_profit_goal.max(1.0);
+        let progress = (today / goal).clamp(0.0, 1.0) as f32;
+
+        ui.label(RichText::new("🎯").color(Color32::from_rgb(200, 180, 100)));
+        ui.add(egui::ProgressBar::new(progress)
+            .desired_width(160.0)
+            .text(format!("{} / {}", self.money().format(today), self.money().format(goal))))
+            .on_hover_text("Realized profit today (since 00:00 UTC reset) against your daily goal");
+        ui.label(RichText::new(format!("{}/hr", self.money().format(session / hours)))
+            .color(Color32::from_rgb(180, 200, 255)))
+            .on_hover_text("Session profit pace");
+
+        if ui.button("📒 Journal").clicked() {
+            self.show_journal = !self.show_journal;
+        }
+        if ui.button("📣 Rules").on_hover_text("Alert rules checked after every scan").clicked() {
+            self.show_rules = !self.show_rules;
+        }
+
+        if !self.alerts.is_empty()
+            && ui.button(RichText::new(format!("🔔 {}", self.alerts.len()))
+                .color(Color32::from_rgb(255, 120, 100))
+                .strong())
+                .clicked()
+        {
+            self.show_alerts = !self.show_alerts;
+        }
+    }
+
+    fn alerts_window(&mut self, ctx: &Context) {
+        let mut open = self.show_alerts;
+        egui::Window::new("🔔 Alerts")
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                for alert in &self.alerts {
+                    let (icon, color) = match alert.kind {
+                        AlertKind::Rule => ("📣", Color32::from_rgb(255, 210, 100)),
+                        AlertKind::HoldingDrop => ("📉", Color32::from_rgb(255, 120, 100)),
+                        AlertKind::TargetHit => ("🎯", Color32::from_rgb(150, 255, 150)),
+                        AlertKind::BasketCheap => ("🧺", Color32::from_rgb(100, 220, 255)),
+                    };
+                    ui.horizontal_wrapped(|ui| {
+                        ui.label(RichText::new(format!("{} {}", icon, alert.item)).color(color).strong());
+                        ui.label(&alert.message);
+                    });
+                }
+            });
+        self.show_alerts = open;
+    }
+
+    fn journal_window(&mut self, ctx: &Context) {
+        let mut open = self.show_journal;
+        egui::Window::new("📒 Trade Journal")
+            .open(&mut open)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Profile:");
+                    let mut selected = self.journal.profile.clone();
+                    egui::ComboBox::from_id_salt("journal_profile")
+                        .selected_text(journal::profile_label(&selected))
+                        .show_ui(ui, |ui| {
+                            for p in &self.profiles {
+                                ui.selectable_value(&mut selected, p.clone(), journal::profile_label(p));
+                            }
+                        });
+                    if selected != self.journal.profile {
+                        self.switch_profile(selected);
+                    }
+                    ui.add(egui::TextEdit::singleline(&mut self.new_profile)
+                        .hint_text("New profile")
+                        .desired_width(100.0));
+                    let name: String = self.new_profile.trim().chars().filter(|c| c.is_alphanumeric() || *c == '_' || *c == '-').collect();
+                    if ui.add_enabled(!name.is_empty(), egui::Button::new("➕")).on_hover_text("Start a journal for someone else on this machine").clicked() {
+                        self.new_profile.clear();
+                        self.switch_profile(name);
+                        self.journal.save();
+                        self.profiles = journal::profiles(self.config.game);
+                    }
+                    ui.separator();
+                    if ui.button("🏆 Leaderboard").clicked() {
+                        self.show_leaderboard = !self.show_leaderboard;
+                        self.refresh_leaderboard();
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Daily goal:");
+                    let mut goal_m = self.config.daily_profit_goal / 1_000_000.0;
+                    if ui.add(egui::DragValue::new(&mut goal_m).suffix(" M").speed(0.5).range(0.1..=100_000.0)).changed() {
+                        self.config.daily_profit_goal = goal_m * 1_000_000.0;
+                        self.config.save();
+                    }
+                });
+
+                ui.separator();
+                ui.label(RichText::new("Log a completed flip").strong());
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut self.new_trade.item)
+                        .hint_text("Item")
+                        .desired_width(160.0));
+                    ui.label("Qty");
+                    ui.add(egui::DragValue::new(&mut self.new_trade.qty).range(1..=i32::MAX));
+                    ui.label("Buy");
+                    ui.add(egui::DragValue::new(&mut self.new_trade.buy.0).range(0..=i64::MAX));
+                    ui.label("Sell");
+                    ui.add(egui::DragValue::new(&mut self.new_trade.sell.0).range(0..=i64::MAX));
+
+                    if ui.button("➕ Add").clicked() && !self.new_trade.item.is_empty() {
+                        let mut trade = self.new_trade.clone();
+                        trade.timestamp = journal::now_secs();
+                        self.journal.trades.push(trade);
+                        self.journal.save();
+                    }
+                });
+
+                if let Some(r) = self.selected_row.and_then(|i| self.filtered_items.get(i)) {
+                    if ui.small_button(format!("Fill from selected: {}", r.name)).clicked() {
+                        self.new_trade.item = r.name.clone();
+                        self.new_trade.qty = r.qty.max(1);
+                        self.new_trade.buy = r.buy;
+                        self.new_trade.sell = r.sell;
+                    }
+                }
+
+                egui::CollapsingHeader::new(RichText::new("📋 Import GE history").strong())
+                    .id_salt("ge_import")
+                    .show(ui, |ui| self.ge_import_section(ui));
+
+                ui.separator();
+
+                let mut remove = None;
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    for (i, t) in self.journal.trades.iter().enumerate().rev() {
+                        ui.horizontal(|ui| {
+                            if ui.small_button("🗑").clicked() {
+                                remove = Some(i);
+                            }
+                            let profit = t.profit(&self.tax);
+                            let color = if profit >= Gp::ZERO {
+                                Color32::from_rgb(150, 255, 150)
+                            } else {
+                                Color32::from_rgb(255, 100, 100)
+                            };
+                            ui.label(format!("{} × {} @ {} → {}", t.qty, t.item, format_gp(t.buy.as_f64()), format_gp(t.sell.as_f64())));
+                            ui.label(RichText::new(profit.to_string()).color(color).strong());
+                        });
+                    }
+                });
+
+                if let Some(i) = remove {
+                    self.journal.trades.remove(i);
+                    self.journal.save();
+                }
+            });
+        self.show_journal = open;
+    }
+
+    fn switch_profile(&mut self, profile: String) {
+        self.journal = Journal::load(self.config.game, &profile);
+        self.config.profile = profile;
+        self.config.save();
+    }
+
+    fn refresh_leaderboard(&mut self) {
+        self.leaderboard = self.profiles
+            .iter()
+            .map(|p| (p.clone(), Journal::load(self.config.game, p).weekly(&self.tax)))
+            .collect();
+    }
+
+    // Realized profit per profile for recent weeks, best this week first
+    fn leaderboard_window(&mut self, ctx: &Context) {
+        const WEEKS: u64 = 6;
+        let this_week = journal::week_of(journal::now_secs());
+        let mut open = self.show_leaderboard;
+        egui::Window::new("🏆 Profit Leaderboard")
+            .open(&mut open)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new("Realized flip profit per profile, by week (Monday to Sunday, UTC)")
+                        .color(Color32::from_rgb(180, 160, 120)));
+                    if ui.small_button("🔄").on_hover_text("Re-read every profile's journal").clicked() {
+                        self.profiles = journal::profiles(self.config.game);
+                        self.refresh_leaderboard();
+                    }
+                });
+
+                let mut ranked: Vec<&(String, HashMap<u64, Gp>)> = self.leaderboard.iter().collect();
+                ranked.sort_by_key(|(_, weeks)| std::cmp::Reverse(weeks.get(&this_week).copied().unwrap_or(Gp::ZERO)));
+
+                egui::Grid::new("leaderboard").striped(true).num_columns(WEEKS as usize + 3).show(ui, |ui| {
+                    ui.label(RichText::new("#").strong());
+                    ui.label(RichText::new("Profile").strong());
+                    for back in 0..WEEKS {
+                        let label = match back {
+                            0 => "This week".to_string(),
+                            1 => "Last week".to_string(),
+                            n => format!("{} wks ago", n),
+                        };
+                        ui.label(RichText::new(label).strong().color(Color32::from_rgb(200, 180, 140)));
+                    }
+                    ui.label(RichText::new("All time").strong().color(Color32::from_rgb(200, 180, 140)));
+                    ui.end_row();
+
+                    for (rank, (profile, weeks)) in ranked.iter().enumerate() {
+                        ui.label(match rank {
+                            0 => "🥇".to_string(),
+                            1 => "🥈".to_string(),
+                            2 => "🥉".to_string(),
+                            n => (n + 1).to_string(),
+                        });
+                        let name = RichText::new(journal::profile_label(profile));
+                        ui.label(if *profile == self.journal.profile { name.strong() } else { name });
+                        for back in 0..WEEKS {
+                            match this_week.checked_sub(back).and_then(|w| weeks.get(&w)) {
+                                Some(profit) => {
+                                    let color = if *profit >= Gp::ZERO { Color32::from_rgb(150, 255, 150) } else { Color32::from_rgb(255, 120, 100) };
+                                    ui.label(RichText::new(profit.to_string()).color(color));
+                                }
+                                None => { ui.label("-"); }
+                            }
+                        }
+                        ui.label(weeks.values().copied().sum::<Gp>().to_string());
+                        ui.end_row();
+                    }
+                });
+            });
+        self.show_leaderboard = open;
+    }
+
+    // Pasted GE history -> journal trades, next to what the latest scan predicted
+    fn ge_import_section(&mut self, ui: &mut egui::Ui) {
+        ui.label(RichText::new("One offer per line, e.g. \"Bought 1,000 x Blood rune for 351K\" or \"Sold 500 x Blood rune @ 412 each\"")
+            .small()
+            .color(Color32::from_rgb(180, 160, 120)));
+        ui.add(egui::TextEdit::multiline(&mut self.ge_paste)
+            .hint_text("Paste GE history here")
+            .desired_rows(5)
+            .desired_width(f32::INFINITY));
+        ui.horizontal(|ui| {
+            if ui.button("🔍 Preview").clicked() {
+                self.ge_import = Some(ge_import::import(&self.ge_paste, journal::now_secs()));
+            }
+            if ui.button("Clear").clicked() {
+                self.ge_paste.clear();
+                self.ge_import = None;
+            }
+        });
+
+        let Some(import) = &self.ge_import else { return };
+        if !import.skipped.is_empty() {
+            ui.label(RichText::new(format!("Couldn't read {} line(s): {}", import.skipped.len(), import.skipped.join(" | ")))
+                .small()
+                .color(Color32::from_rgb(255, 150, 100)));
+        }
+        if !import.open.is_empty() {
+            let open: Vec<String> = import.open.iter().map(|(name, qty)| format!("{} {:+}", name, qty)).collect();
+            ui.label(RichText::new(format!("Unmatched (still open): {}", open.join(", "))).small().weak());
+        }
+        if import.trades.is_empty() {
+            ui.label(RichText::new("No completed flips - each item needs both a buy and a sell").italics());
+            return;
+        }
+
+        egui::Grid::new("ge_import_preview").striped(true).num_columns(6).show(ui, |ui| {
+            for (h, tip) in [
+                ("Item", ""),
+                ("Qty", "Matched between buys and sells"),
+                ("Buy → Sell", "Average prices paid and received"),
+                ("Realized", "Margin per item after tax"),
+                ("Predicted", "Margin per item the latest scan expects"),
+                ("Diff", "Realized minus predicted"),
+            ] {
+                ui.label(RichText::new(h).strong().color(Color32::from_rgb(200, 180, 140))).on_hover_text(tip);
+            }
+            ui.end_row();
+
+            for t in &import.trades {
+                let realized = t.profit(&self.tax).as_f64() / t.qty.max(1) as f64;
+                let predicted = self.items
+                    .iter()
+                    .find(|r| r.name.eq_ignore_ascii_case(&t.item))
+                    .map(|r| r.unit_profit.as_f64());
+                ui.label(&t.item);
+                ui.label(t.qty.to_string());
+                ui.label(format!("{} → {}", format_gp(t.buy.as_f64()), format_gp(t.sell.as_f64())));
+                let color = |v: f64| if v >= 0.0 { Color32::from_rgb(150, 255, 150) } else { Color32::from_rgb(255, 120, 100) };
+                let signed = |v: f64| format!("{}{}", if v < 0.0 { "-" } else { "" }, format_gp(v.abs()));
+                ui.label(RichText::new(signed(realized)).color(color(realized)));
+                match predicted {
+                    Some(p) => {
+                        ui.label(signed(p));
+                        ui.label(RichText::new(signed(realized - p)).color(color(realized - p)));
+                    }
+                    None => {
+                        ui.label("-").on_hover_text("Not in the latest scan");
+                        ui.label("-");
+                    }
+                }
+                ui.end_row();
+            }
+        });
+
+        if ui.button(format!("➕ Add {} trade(s) to journal", import.trades.len())).clicked() {
+            self.journal.trades.extend(import.trades.iter().cloned());
+            self.journal.save();
+            self.ge_paste.clear();
+            self.ge_import = None;
+        }
+    }
+
+    fn market_tab(&mut self, ui: &mut egui::Ui) {
+        self.watchlist_card(ui);
+        ui.add_space(10.0);
+        self.baskets_card(ui);
+        ui.add_space(10.0);
+
+        if !self.loaded {
+            ui.label(RichText::new("Click 'Scan Market' to load market data")
+                .color(Color32::from_rgb(180, 160, 120))
+                .italics());
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            ui.heading(RichText::new("🚀 Top Movers").color(Color32::from_rgb(255, 210, 100)));
+            ui.separator();
+            let mut changed = ui.selectable_value(&mut self.mover_window, MoverWindow::Day, "24h").clicked();
+            changed |= ui.selectable_value(&mut self.mover_window, MoverWindow::Week, "7d").clicked();
+            ui.separator();
+            changed |= ui.selectable_value(&mut self.movers_by_pct, true, "By %").clicked();
+            changed |= ui.selectable_value(&mut self.movers_by_pct, false, "By gp").clicked();
+            if changed {
+                self.refresh_movers();
+            }
+        });
+
+        ui.add_space(6.0);
+
+        ui.columns(2, |cols| {
+            mover_card(&mut cols[0], "📈 Gainers", &self.movers.0, Color32::from_rgb(150, 255, 150));
+            mover_card(&mut cols[1], "📉 Losers", &self.movers.1, Color32::from_rgb(255, 120, 100));
+        });
+
+        ui.add_space(10.0);
+
+        egui::Frame::new()
+            .fill(Color32::from_rgb(35, 28, 18))
+            .stroke(Stroke::new(1.0, Color32::from_rgb(100, 80, 50)))
+            .inner_margin(Margin::same(10))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new("🆕 New Items").color(Color32::from_rgb(100, 200, 255)).strong().size(16.0));
+                    ui.label("first seen in the last");
+                    if ui.add(egui::DragValue::new(&mut self.config.new_item_days).range(1..=90).suffix(" days"))
+                        .changed()
+                    {
+                        self.config.save();
+                        self.new_items = loader::load_new_items(self.db_path(), self.config.new_item_days).unwrap_or_default();
+                    }
+                });
+                ui.label(RichText::new("Fresh releases are volatile - prices often swing hard in the first weeks")
+                    .color(Color32::from_rgb(180, 160, 120))
+                    .italics());
+                ui.separator();
+
+                if self.new_items.is_empty() {
+                    ui.label(RichText::new("No new items").italics());
+                    return;
+                }
+
+                egui::Grid::new("new_items").striped(true).num_columns(3).show(ui, |ui| {
+                    for (item_id, name, first_seen) in &self.new_items {
+                        ui.label(name);
+                        ui.label(format!("since {}", first_seen));
+                        ui.label(self.current_prices.get(item_id).map(|p| format_gp(*p)).unwrap_or_default());
+                        ui.end_row();
+                    }
+                });
+            });
+
+        ui.add_space(10.0);
+        self.dumps_card(ui);
+
+        ui.add_space(10.0);
+        self.dead_items_card(ui);
+    }
+
+    fn dumps_card(&mut self, ui: &mut egui::Ui) {
+        let dumps: Vec<(&Row, &Dump)> = self.items
+            .iter()
+            .filter_map(|r| r.dump.as_ref().map(|d| (r, d)))
+            .collect();
+
+        let mut open = None;
+        egui::Frame::new()
+            .fill(Color32::from_rgb(35, 28, 18))
+            .stroke(Stroke::new(1.0, Color32::from_rgb(100, 80, 50)))
+            .inner_margin(Margin::same(10))
+            .show(ui, |ui| {
+                ui.label(RichText::new(format!("🎁 Post-Promo Dumps ({})", dumps.len()))
+                    .color(Color32::from_rgb(255, 150, 220))
+                    .strong()
+                    .size(16.0));
+                ui.label(RichText::new("Items promos hand out in bulk, now down this week on heavy volume - they tend to recover once the flood dries up")
+                    .color(Color32::from_rgb(180, 160, 120))
+                    .italics());
+                ui.separator();
+
+                if dumps.is_empty() {
+                    ui.label(RichText::new("No dumps right now").italics());
+                    return;
+                }
+
+                egui::Grid::new("promo_dumps").striped(true).num_columns(5).show(ui, |ui| {
+                    for (r, d) in &dumps {
+                        if ui.link(&r.name).clicked() {
+                            open = Some(r.name.clone());
+                        }
+                        ui.label(RichText::new(d.tag).weak());
+                        ui.label(format_gp(r.buy.as_f64()));
+                        ui.label(RichText::new(format!("-{:.1}%", d.drop_pct)).color(Color32::from_rgb(255, 120, 100)));
+                        ui.label(format!("{:.1}× volume", d.volume_surge));
+                        ui.end_row();
+                    }
+                });
+            });
+
+        if let Some(name) = open {
+            self.search = name;
+            self.tab = Tab::Flips;
+            self.apply_filters();
+        }
+    }
+
+    fn dead_items_card(&mut self, ui: &mut egui::Ui) {
+        egui::Frame::new()
+            .fill(Color32::from_rgb(35, 28, 18))
+            .stroke(Stroke::new(1.0, Color32::from_rgb(100, 80, 50)))
+            .inner_margin(Margin::same(10))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(format!("💀 Dead Items ({})", self.dead_items.len()))
+                        .color(Color32::from_rgb(180, 180, 180))
+                        .strong()
+                        .size(16.0));
+                    ui.label("no snapshot for");
+                    if ui.add(egui::DragValue::new(&mut self.config.dead_item_days).range(1..=365).suffix(" days"))
+                        .changed()
+                    {
+                        self.config.save();
+                        self.load_data();
+                    }
+                    ui.label("or ~0 volume");
+                });
+
+                if self.dead_items.is_empty() {
+                    return;
+                }
+
+                egui::CollapsingHeader::new("Show list").id_salt("dead_items").show(ui, |ui| {
+                    egui::Grid::new("dead_items_grid").striped(true).num_columns(2).show(ui, |ui| {
+                        for (_, name, last_date) in &self.dead_items {
+                            ui.label(name);
+                            ui.label(format!("last seen {}", last_date));
+                            ui.end_row();
+                        }
+                    });
+                });
+
+                ui.horizontal(|ui| {
+                    if !self.confirm_prune {
+                        if ui.button("🗑 Prune dead item history").clicked() {
+                            self.confirm_prune = true;
+                        }
+                    } else {
+                        ui.label(RichText::new("Permanently delete their history from the database?")
+                            .color(Color32::from_rgb(255, 150, 150)));
+                        if ui.button("Yes, prune").clicked() {
+                            let ids: Vec<i32> = self.dead_items.iter().map(|(id, _, _)| *id).collect();
+                            self.prune_status = Some(match loader::prune_item_history(self.db_path(), &ids) {
+                                Ok(n) => format!("Removed {} snapshots", n),
+                                Err(e) => format!("Prune failed: {}", e),
+                            });
+                            self.confirm_prune = false;
+                            self.stats_version = None;
+                            self.load_data();
+                        }
+                        if ui.button("Cancel").clicked() {
+                            self.confirm_prune = false;
+                        }
+                    }
+                });
+
+                if let Some(status) = &self.prune_status {
+                    ui.label(RichText::new(status).color(Color32::from_rgb(180, 160, 120)));
+                }
+            });
+    }
+
+    fn export_parquet(&mut self) {
+        let query = self.parquet_item.trim();
+        let item = if query.is_empty() {
+            None
+        } else {
+            match loader::find_item(self.db_path(), query) {
+                Ok(Some(item)) => Some(item),
+                Ok(None) => {
+                    self.parquet_error = Some(format!("No item matching '{}'", query));
+                    return;
+                }
+                Err(e) => {
+                    self.parquet_error = Some(e.to_string());
+                    return;
+                }
+            }
+        };
+        self.parquet_error = None;
+        self.start_task(Task::ExportParquet { path: self.parquet_path.trim().to_string(), item });
+    }
+
+    fn start_retention(&mut self) {
+        let retention = self.config.retention;
+        self.start_task(Task::Retention {
+            daily_after_days: retention.daily_after_days,
+            weekly_after_days: retention.weekly_after_days,
+        });
+        self.retention_failed = false;
+    }
+
+    fn export_state(&self) -> Result<(), String> {
+        let state = StateBundle {
+            version: bundle::BUNDLE_VERSION,
+            favorite_ids: self.favorites.iter().copied().collect(),
+            favorites: HashMap::new(),
+            pinned: self.pinned.iter().copied().collect(),
+            blacklist: self.blacklist.iter().copied().collect(),
+            watchlist: self.watchlist.items.clone(),
+            config: bundle::without_secrets(self.config.clone()),
+            holdings: self.holdings.clone(),
+            journal: self.journal.trades.clone(),
+        };
+        bundle::export(&self.bundle_path, &state)
+    }
+
+    fn import_state(&mut self) -> Result<(), String> {
+        let state = bundle::import(&self.bundle_path)?;
+
+        self.favorites = if state.version < 2 {
+            favorites_from_names(state.favorites)
+        } else {
+            state.favorite_ids.into_iter().collect()
+        };
+        self.save_favorites();
+        self.pinned = state.pinned.into_iter().collect();
+        self.save_pinned();
+        if state.version >= 3 {
+            self.blacklist = state.blacklist.into_iter().collect();
+            self.save_blacklist();
+        }
+        self.watchlist.items = state.watchlist;
+        self.watchlist.save();
+        // The lists above went into this game's files, so stay on it
+        let game = self.config.game;
+        self.config = bundle::keep_local(state.config, &self.config);
+        self.config.game = game;
+        self.config.save();
+        self.journal.trades = state.journal;
+        self.journal.save();
+
+        for h in &self.holdings {
+            loader::delete_holding(self.db_path(), h.item_id).map_err(|e| e.to_string())?;
+        }
+        for h in &state.holdings {
+            loader::save_holding(self.db_path(), h).map_err(|e| e.to_string())?;
+        }
+        self.holdings = loader::load_holdings(self.db_path()).unwrap_or_default();
+
+        if self.loaded {
+            self.load_data();
+        }
+        Ok(())
+    }
+
+    fn quick_add_watch(&mut self) {
+        let query = watchlist::item_query(&self.watch_query);
+        self.watch_status = Some(match loader::find_item(self.db_path(), &query) {
+            Ok(Some((id, name))) => {
+                if self.watchlist.add(id, name.clone()) {
+                    self.watchlist.save();
+                    self.watch_query.clear();
+                    format!("Added {}", name)
+                } else {
+                    format!("{} is already on the watchlist", name)
+                }
+            }
+            Ok(None) => format!("No item matches '{}'", query),
+            Err(e) => format!("Lookup failed: {}", e),
+        });
+    }
+
+    fn watchlist_card(&mut self, ui: &mut egui::Ui) {
+        egui::Frame::new()
+            .fill(Color32::from_rgb(35, 28, 18))
+            .stroke(Stroke::new(1.0, Color32::from_rgb(100, 80, 50)))
+            .inner_margin(Margin::same(10))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new("👁 Watchlist").color(Color32::from_rgb(255, 210, 100)).strong().size(16.0));
+                    ui.separator();
+                    let input = ui.add(egui::TextEdit::singleline(&mut self.watch_query)
+                        .hint_text("Item name, ID or GE/Wiki URL")
+                        .desired_width(320.0));
+                    let submitted = input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if (ui.button("➕ Add").clicked() || submitted) && !self.watch_query.trim().is_empty() {
+                        self.quick_add_watch();
+                    }
+                    if let Some(status) = &self.watch_status {
+                        ui.label(RichText::new(status).color(Color32::from_rgb(180, 160, 120)));
+                    }
+                });
+                ui.separator();
+
+                if self.watchlist.items.is_empty() {
+                    ui.label(RichText::new("Nothing watched yet").italics());
+                    return;
+                }
+
+                let mut remove = None;
+                egui::Grid::new("watchlist").striped(true).num_columns(6).show(ui, |ui| {
+                    for w in &self.watchlist.items {
+                        ui.label(RichText::new(&w.name).strong());
+
+                        match self.stats.iter().find(|s| s.item_id == w.item_id) {
+                            Some(s) => {
+                                ui.label(format_gp(s.current_price));
+                                match s.price_1d_ago.filter(|p| *p > 0.0) {
+                                    Some(p) => {
+                                        let pct = (s.current_price - p) / p * 100.0;
+                                        let color = if pct >= 0.0 { Color32::from_rgb(150, 255, 150) } else { Color32::from_rgb(255, 120, 100) };
+                                        ui.label(RichText::new(format!("{:+.1}% 24h", pct)).color(color));
+                                    }
+                                    None => { ui.label("-"); }
+                                }
+                            }
+                            None => {
+                                ui.label("-");
+                                ui.label("-");
+                            }
+                        }
+
+                        match self.items.iter().find(|r| r.item_id == w.item_id) {
+                            Some(r) => {
+                                ui.label(RichText::new(&r.tier).color(self.tier_color(&r.tier)));
+                                ui.label(format!("{} → {}", format_gp(r.buy.as_f64()), format_gp(r.sell.as_f64())));
+                            }
+                            None => {
+                                ui.label("-");
+                                ui.label("-");
+                            }
+                        }
+
+                        if ui.small_button("✖").on_hover_text("Stop watching").clicked() {
+                            remove = Some(w.item_id);
+                        }
+                        ui.end_row();
+                    }
+                });
+
+                if let Some(id) = remove {
+                    self.watchlist.remove(id);
+                    self.watchlist.save();
+                }
+            });
+    }
+
+    fn add_basket_item(&mut self) {
+        let Some(basket) = self.baskets.baskets.get_mut(self.basket_target) else { return };
+        let query = watchlist::item_query(&self.basket_query);
+        self.basket_status = Some(match loader::find_item(self.config.game.db_path(), &query) {
+            Ok(Some((id, name))) => {
+                basket.add(id, name.clone(), self.basket_qty);
+                let status = format!("Added {} × {} to {}", self.basket_qty, name, basket.name);
+                self.baskets.save();
+                self.basket_query.clear();
+                status
+            }
+            Ok(None) => format!("No item matches '{}'", query),
+            Err(e) => format!("Lookup failed: {}", e),
+        });
+    }
+
+    // User-defined item bundles priced as a whole, with their cost over time
+    fn baskets_card(&mut self, ui: &mut egui::Ui) {
+        egui::Frame::new()
+            .fill(Color32::from_rgb(35, 28, 18))
+            .stroke(Stroke::new(1.0, Color32::from_rgb(100, 80, 50)))
+            .inner_margin(Margin::same(10))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new("🧺 Baskets").color(Color32::from_rgb(255, 210, 100)).strong().size(16.0));
+                    ui.separator();
+                    ui.add(egui::TextEdit::singleline(&mut self.basket_name)
+                        .hint_text("New basket, e.g. Full Trimmed Masterwork")
+                        .desired_width(240.0));
+                    if ui.button("➕ Create").clicked() && !self.basket_name.trim().is_empty() {
+                        self.baskets.baskets.push(Basket::new(self.basket_name.trim().to_string()));
+                        self.baskets.save();
+                        self.basket_target = self.baskets.baskets.len() - 1;
+                        self.basket_name.clear();
+                    }
+                });
+
+                if self.baskets.baskets.is_empty() {
+                    ui.label(RichText::new("No baskets yet - create one, then add items with a quantity").italics());
+                    return;
+                }
+
+                ui.horizontal(|ui| {
+                    let input = ui.add(egui::TextEdit::singleline(&mut self.basket_query)
+                        .hint_text("Item name, ID or GE/Wiki URL")
+                        .desired_width(240.0));
+                    ui.label("×");
+                    ui.add(egui::DragValue::new(&mut self.basket_qty).range(1..=i64::MAX));
+                    ui.label("into");
+                    self.basket_target = self.basket_target.min(self.baskets.baskets.len() - 1);
+                    egui::ComboBox::from_id_salt("basket_target")
+                        .selected_text(&self.baskets.baskets[self.basket_target].name)
+                        .show_ui(ui, |ui| {
+                            for (i, b) in self.baskets.baskets.iter().enumerate() {
+                                ui.selectable_value(&mut self.basket_target, i, &b.name);
+                            }
+                        });
+                    let submitted = input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if (ui.button("➕ Add").clicked() || submitted) && !self.basket_query.trim().is_empty() {
+                        self.add_basket_item();
+                    }
+                    if let Some(status) = &self.basket_status {
+                        ui.label(RichText::new(status).color(Color32::from_rgb(180, 160, 120)));
+                    }
+                });
+                ui.separator();
+
+                let money = self.money();
+                let palette = self.config.palette;
+                let mut changed = false;
+                let mut delete = None;
+                for (bi, basket) in self.baskets.baskets.iter_mut().enumerate() {
+                    let value = baskets::value(basket, &self.stats);
+                    let mut title = format!("{} - {}", basket.name, money.format(value.total));
+                    if let Some(pct) = value.change_7d_pct {
+                        title.push_str(&format!(" ({:+.1}% 7d)", pct));
+                    }
+                    if value.is_cheap(basket) {
+                        title.push_str(" 🔔 cheap");
+                    }
+
+                    egui::CollapsingHeader::new(title)
+                        .id_salt(("basket", bi))
+                        .show(ui, |ui| {
+                            let mut remove = None;
+                            egui::Grid::new(("basket_items", bi)).striped(true).num_columns(5).show(ui, |ui| {
+                                for (ii, item) in basket.items.iter().enumerate() {
+                                    let price = self.current_prices.get(&item.item_id).copied();
+                                    ui.label(&item.name);
+                                    ui.label(format!("× {}", item.qty));
+                                    ui.label(price.map_or("-".to_string(), format_gp));
+                                    ui.label(price.map_or("-".to_string(), |p| money.format(p * item.qty as f64)));
+                                    if ui.small_button("✖").on_hover_text("Remove from basket").clicked() {
+                                        remove = Some(ii);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                            if let Some(ii) = remove {
+                                basket.items.remove(ii);
+                                changed = true;
+                            }
+
+                            if !value.missing.is_empty() {
+                                ui.label(RichText::new(format!("No price yet for: {}", value.missing.join(", ")))
+                                    .color(Color32::from_rgb(180, 160, 120)));
+                            }
+
+                            ui.horizontal(|ui| {
+                                if let Some(p) = value.percentile {
+                                    let color = if value.is_cheap(basket) { palette.gain(true) } else { Color32::from_rgb(220, 200, 160) };
+                                    ui.label(RichText::new(format!("Cheaper than {:.0}% of its {} tracked days", 100.0 - p, value.history.len())).color(color));
+                                    ui.separator();
+                                }
+                                ui.label("Alert when in the cheapest");
+                                changed |= ui.add(egui::DragValue::new(&mut basket.cheap_percentile).range(1.0..=50.0).suffix("%")).changed();
+                                ui.separator();
+                                if ui.small_button("🗑 Delete basket").clicked() {
+                                    delete = Some(bi);
+                                }
+                            });
+
+                            if value.history.len() >= 2 {
+                                let points: PlotPoints = value.history.iter().map(|(day, v)| [*day as f64, *v]).collect();
+                                Plot::new(("basket_history", bi))
+                                    .height(140.0)
+                                    .show_x(false)
+                                    .label_formatter(|_name, value| format!("Basket: {}", format_gp(value.y)))
+                                    .show(ui, |plot_ui| {
+                                        plot_ui.line(Line::new("Basket cost", points)
+                                            .color(Color32::from_rgb(255, 210, 100))
+                                            .width(2.0));
+                                    });
+                            }
+                        });
+                }
+
+                if let Some(bi) = delete {
+                    self.baskets.baskets.remove(bi);
+                    changed = true;
+                }
+                if changed {
+                    self.baskets.save();
+                }
+            });
+    }
+
+    // Treemap of the market: tile area = daily traded value, colour = 24h change
+    fn heatmap_tab(&mut self, ui: &mut egui::Ui) {
+        if !self.loaded {
+            ui.label(RichText::new("Click 'Scan Market' to load market data")
+                .color(Color32::from_rgb(180, 160, 120))
+                .italics());
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            ui.heading(RichText::new("🗺 Market Heatmap").color(Color32::from_rgb(255, 210, 100)));
+            ui.separator();
+            ui.label(RichText::new("Size = daily traded value, colour = 24h change. Click a tile to open it in Flips.")
+                .color(Color32::from_rgb(180, 160, 120)));
+        });
+        ui.add_space(4.0);
+
+        let mut groups: HashMap<&str, Vec<(usize, f64)>> = HashMap::new();
+        for (i, s) in self.stats.iter().enumerate() {
+            let value = s.avg_volume * s.current_price;
+            if value > 0.0 {
+                groups.entry(categories::category(&s.name)).or_default().push((i, value));
+            }
+        }
+        let mut groups: Vec<_> = groups
+            .into_iter()
+            .map(|(cat, mut items)| {
+                items.sort_by(|a, b| b.1.total_cmp(&a.1));
+                let total: f64 = items.iter().map(|(_, v)| v).sum();
+                (cat, total, items)
+            })
+            .collect();
+        groups.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        let (response, painter) = ui.allocate_painter(ui.available_size(), egui::Sense::click());
+        let area = response.rect;
+        let hover = response.hover_pos();
+        let mut hovered: Option<(usize, egui::Rect)> = None;
+
+        let group_values: Vec<f64> = groups.iter().map(|g| g.1).collect();
+        let group_rects = treemap::squarify(&group_values, area);
+
+        for ((cat, total, items), cat_rect) in groups.iter().zip(group_rects) {
+            let show_header = cat_rect.height() > 40.0 && cat_rect.width() > 80.0;
+            let mut inner = cat_rect.shrink(2.0);
+            if show_header {
+                inner.min.y += 16.0;
+            }
+
+            let values: Vec<f64> = items.iter().map(|(_, v)| *v).collect();
+            for ((idx, _), tile) in items.iter().zip(treemap::squarify(&values, inner)) {
+                let s = &self.stats[*idx];
+                let change = s.price_1d_ago
+                    .filter(|p| *p > 0.0)
+                    .map(|p| (s.current_price - p) / p * 100.0);
+                painter.rect_filled(tile.shrink(0.5), 0.0, heat_color(change, self.config.palette));
+
+                if tile.width() > 60.0 && tile.height() > 30.0 {
+                    let clip = painter.with_clip_rect(tile.shrink(2.0));
+                    clip.text(tile.min + Vec2::new(4.0, 3.0), egui::Align2::LEFT_TOP, &s.name,
+                        FontId::proportional(12.0), Color32::from_rgb(245, 235, 215));
+                    if let Some(c) = change {
+                        clip.text(tile.min + Vec2::new(4.0, 17.0), egui::Align2::LEFT_TOP, format!("{:+.1}%", c),
+                            FontId::proportional(11.0), Color32::from_rgb(220, 210, 190));
+                    }
+                }
+
+                if hover.is_some_and(|p| tile.contains(p)) {
+                    hovered = Some((*idx, tile));
+                }
+            }
+
+            painter.rect_stroke(cat_rect, 0.0, Stroke::new(1.5, Color32::from_rgb(100, 80, 50)), egui::StrokeKind::Inside);
+            if show_header {
+                painter.with_clip_rect(cat_rect).text(
+                    cat_rect.min + Vec2::new(5.0, 2.0),
+                    egui::Align2::LEFT_TOP,
+                    format!("{} · {}/day", cat, format_gp(*total)),
+                    FontId::proportional(13.0),
+                    Color32::from_rgb(255, 210, 100),
+                );
+            }
+        }
+
+        if let Some((idx, tile)) = hovered {
+            painter.rect_stroke(tile, 0.0, Stroke::new(2.0, Color32::WHITE), egui::StrokeKind::Inside);
+
+            let s = &self.stats[idx];
+            let clicked = response.clicked();
+            response.on_hover_ui_at_pointer(|ui| {
+                ui.label(RichText::new(&s.name).strong());
+                ui.label(format!("Category: {}", categories::category(&s.name)));
+                ui.label(format!("Price: {}", format_gp(s.current_price)));
+                ui.label(format!("Traded/day: {}", format_gp(s.avg_volume * s.current_price)));
+                match s.price_1d_ago.filter(|p| *p > 0.0) {
+                    Some(p) => ui.label(format!("24h: {:+.1}%", (s.current_price - p) / p * 100.0)),
+                    None => ui.label("24h: no data"),
+                };
+            });
+
+            if clicked {
+                self.search = s.name.clone();
+                self.tab = Tab::Flips;
+                self.apply_filters();
+            }
+        }
+    }
+
+    fn allocation_active(&self) -> bool {
+        self.config.allocation.enabled && self.config.bank_size > 0.0
+    }
+
+    // Gp the Investments tab may plan with: its share of the bank, or the
+    // flipping budget when the bank isn't split
+    fn investment_budget(&self) -> f64 {
+        if self.allocation_active() {
+            self.config.allocation.investments(self.config.bank_size)
+        } else {
+            self.budget
+        }
+    }
+
+    fn allocation_settings(&mut self, ui: &mut egui::Ui) {
+        ui.add_space(10.0);
+        ui.label(RichText::new("🏦 Bank Allocation").strong());
+        let mut changed = ui.checkbox(&mut self.config.allocation.enabled, "Split the bank between tabs")
+            .on_hover_text("Give flips, investments and alching separate budgets that add up to your bank")
+            .changed();
+        if self.config.allocation.enabled {
+            ui.horizontal(|ui| {
+                ui.label("Bank:");
+                let mut bank_m = self.config.bank_size / 1_000_000.0;
+                if ui.add(egui::DragValue::new(&mut bank_m).suffix(" M").speed(1.0).range(0.0..=1_000_000.0)).changed() {
+                    self.config.bank_size = bank_m * 1_000_000.0;
+                    changed = true;
+                }
+            });
+            let bank = self.config.bank_size;
+            let allocation = &mut self.config.allocation;
+            egui::Grid::new("allocation").num_columns(3).show(ui, |ui| {
+                ui.label("Flips");
+                if ui.add(egui::DragValue::new(&mut allocation.flips_pct).range(0.0..=100.0).suffix("%")).changed() {
+                    allocation.clamp(true);
+                    changed = true;
+                }
+                ui.label(format_gp(allocation.flips(bank)));
+                ui.end_row();
+
+                ui.label("Investments");
+                if ui.add(egui::DragValue::new(&mut allocation.investments_pct).range(0.0..=100.0).suffix("%")).changed() {
+                    allocation.clamp(false);
+                    changed = true;
+                }
+                ui.label(format_gp(allocation.investments(bank)));
+                ui.end_row();
+
+                ui.label("Alching");
+                ui.label(format!("{:.0}%", allocation.alch_pct()))
+                    .on_hover_text("Whatever the other two don't use");
+                ui.label(format_gp(allocation.alchs(bank)));
+                ui.end_row();
+            });
+        }
+
+        if changed {
+            self.config.save();
+            if self.allocation_active() {
+                self.budget = self.config.allocation.flips(self.config.bank_size).max(100_000.0);
+                if self.loaded {
+                    self.load_data();
+                }
+            }
+        }
+    }
+
+    fn investments_tab(&mut self, ui: &mut egui::Ui) {
+        if !self.loaded {
+            ui.label(RichText::new("Click 'Scan Market' to load market data")
+                .color(Color32::from_rgb(180, 160, 120))
+                .italics());
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            ui.heading(RichText::new("🌱 Investments").color(Color32::from_rgb(255, 210, 100)));
+            ui.separator();
+            ui.label(RichText::new(format!("Items near their low of the last {} days that still trade well - held until they recover, not flipped", stats::COVERAGE_DAYS))
+                .color(Color32::from_rgb(180, 160, 120)));
+        });
+        ui.horizontal(|ui| {
+            let screen = &mut self.investment_screen;
+            ui.label("Low over the last");
+            let mut changed = ui.add(egui::DragValue::new(&mut screen.lookback_days).range(60..=stats::COVERAGE_DAYS).suffix(" days"))
+                .on_hover_text("Scans load the last 90 days of history, so the low can't look further back")
+                .changed();
+            ui.label("Within");
+            changed |= ui.add(egui::DragValue::new(&mut screen.max_above_low_pct).range(0.0..=50.0).suffix("%")).changed();
+            ui.label("of the low, volume at least");
+            changed |= ui.add(egui::DragValue::new(&mut screen.min_volume).range(0.0..=10_000_000.0).speed(100.0).suffix("/day")).changed();
+            if changed {
+                self.investments = investments::screen(&self.stats, &self.investment_screen);
+            }
+        });
+        let budget = self.investment_budget();
+        ui.label(RichText::new(format!(
+            "Investment budget: {}{}",
+            format_gp(budget),
+            if self.allocation_active() { " (bank allocation)" } else { " (flipping budget - split the bank in the side panel to change)" }
+        )).small().color(Color32::from_rgb(180, 160, 120)));
+        ui.add_space(4.0);
+
+        let mut open = None;
+        let mut watch = None;
+        TableBuilder::new(ui)
+            .striped(true)
+            .column(Column::remainder().at_least(200.0))
+            .columns(Column::exact(100.0), 6)
+            .column(Column::exact(70.0))
+            .column(Column::exact(90.0))
+            .column(Column::exact(60.0))
+            .header(24.0, |mut header| {
+                for (h, tip) in [
+                    ("Item", "Click to open in Flips"),
+                    ("Price", "Current price"),
+                    ("Low", "Lowest price in the lookback"),
+                    ("Above low", "How far the price is above that low"),
+                    ("Median", "Typical price in the lookback - the recovery target"),
+                    ("Upside", "Gain if the price returns to the median, before tax"),
+                    ("Volume Δ", "Recent volume against the long-run average; rising usage is a good sign"),
+                    ("Score", "Upside, plus half the volume growth (±50% max), minus distance above the low"),
+                    ("Can buy", "How many the whole investment budget buys"),
+                    ("Watch", "Add to the Market tab watchlist"),
+                ] {
+                    header.col(|ui| {
+                        ui.label(RichText::new(h).strong().color(Color32::from_rgb(200, 180, 140))).on_hover_text(tip);
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(22.0, self.investments.len(), |mut row| {
+                    let inv = &self.investments[row.index()];
+                    row.col(|ui| {
+                        if ui.link(&inv.name).clicked() {
+                            open = Some(inv.name.clone());
+                        }
+                    });
+                    row.col(|ui| { ui.label(format_gp(inv.price)); });
+                    row.col(|ui| { ui.label(format_gp(inv.low)); });
+                    row.col(|ui| { ui.label(format!("{:.1}%", inv.above_low_pct)); });
+                    row.col(|ui| { ui.label(format_gp(inv.median)); });
+                    row.col(|ui| {
+                        ui.label(RichText::new(format!("+{:.1}%", inv.upside_pct)).color(Color32::from_rgb(150, 255, 150)));
+                    });
+                    row.col(|ui| {
+                        let color = if inv.volume_growth_pct >= 0.0 { Color32::from_rgb(150, 255, 150) } else { Color32::from_rgb(255, 150, 150) };
+                        ui.label(RichText::new(format!("{:+.0}%", inv.volume_growth_pct)).color(color))
+                            .on_hover_text(format!("{:.0}/day on average", inv.volume));
+                    });
+                    row.col(|ui| { ui.label(format!("{:.0}", inv.score)); });
+                    row.col(|ui| {
+                        let qty = if inv.price > 0.0 { (budget / inv.price).floor() } else { 0.0 };
+                        ui.label(format!("{:.0}", qty));
+                    });
+                    row.col(|ui| {
+                        let watched = self.watchlist.contains(inv.item_id);
+                        if ui.add_enabled(!watched, egui::Button::new(if watched { "👁" } else { "➕" }).small()).clicked() {
+                            watch = Some((inv.item_id, inv.name.clone()));
+                        }
+                    });
+                });
+            });
+
+        if let Some((item_id, name)) = watch {
+            if self.watchlist.add(item_id, name) {
+                self.watchlist.save();
+            }
+        }
+        if let Some(name) = open {
+            self.search = name;
+            self.tab = Tab::Flips;
+            self.apply_filters();
+        }
+    }
+
+    // Ironman mode's stand-in for the flips table: what prices are doing, with no targets
+    fn prices_tab(&mut self, ui: &mut egui::Ui) {
+        if !self.loaded {
+            ui.label(RichText::new("Click 'Scan Market' to load market data")
+                .color(Color32::from_rgb(180, 160, 120))
+                .italics());
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            ui.heading(RichText::new("📈 Prices").color(Color32::from_rgb(255, 210, 100)));
+            ui.separator();
+            ui.label("Sort:");
+            let mut changed = ui.selectable_value(&mut self.price_sort, PriceSort::Range, "Cheapest in range").clicked();
+            changed |= ui.selectable_value(&mut self.price_sort, PriceSort::Change, "Biggest 7d drop").clicked();
+            changed |= ui.selectable_value(&mut self.price_sort, PriceSort::Volume, "Volume").clicked();
+            changed |= ui.selectable_value(&mut self.price_sort, PriceSort::Name, "Name").clicked();
+            if changed {
+                ironman::sort(&mut self.price_rows, self.price_sort);
+            }
+        });
+        ui.label(RichText::new("Range shows where today's price sits between a cheap (Q10) and an expensive (Q90) day. Buy supplies when it's low or on their cheap weekday.")
+            .small()
+            .color(Color32::from_rgb(180, 160, 120)));
+        ui.add_space(4.0);
+
+        let search = self.search.to_lowercase();
+        let visible: Vec<&PriceRow> = self.price_rows
+            .iter()
+            .filter(|r| search.is_empty() || r.name.to_lowercase().contains(&search))
+            .collect();
+        let change = |pct: Option<f64>| match pct {
+            Some(p) => {
+                let color = if p >= 0.0 { Color32::from_rgb(150, 255, 150) } else { Color32::from_rgb(255, 120, 100) };
+                RichText::new(format!("{:+.1}%", p)).color(color)
+            }
+            None => RichText::new("-").color(Color32::GRAY),
+        };
+
+        TableBuilder::new(ui)
+            .striped(true)
+            .column(Column::remainder().at_least(200.0))
+            .column(Column::exact(110.0))
+            .columns(Column::exact(80.0), 2)
+            .column(Column::exact(60.0))
+            .column(Column::exact(130.0))
+            .column(Column::exact(110.0))
+            .column(Column::exact(130.0))
+            .header(24.0, |mut header| {
+                for (h, tip) in [
+                    ("Item", ""),
+                    ("Price", "Latest guide price"),
+                    ("24h", "Price change over the last day"),
+                    ("7d", "Price change over the last week"),
+                    ("Trend", "Slope of the price over the history window"),
+                    ("Range", "Where the price sits between a cheap (Q10) and expensive (Q90) day"),
+                    ("Vol/Day", "Average traded per day, and the recent rate"),
+                    ("Cheap day", "Weekday the price usually runs lowest"),
+                ] {
+                    header.col(|ui| {
+                        ui.label(RichText::new(h).strong().color(Color32::from_rgb(200, 180, 140))).on_hover_text(tip);
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(22.0, visible.len(), |mut row| {
+                    let r = visible[row.index()];
+                    row.col(|ui| {
+                        ui.label(&r.name);
+                        if let Some(skill) = r.skill {
+                            ui.label(RichText::new(skill).small().color(Color32::from_rgb(180, 160, 120)));
+                        }
+                    });
+                    row.col(|ui| { ui.label(format_gp(r.price)); });
+                    row.col(|ui| { ui.label(change(r.change_1d_pct)); });
+                    row.col(|ui| { ui.label(change(r.change_7d_pct)); });
+                    row.col(|ui| {
+                        let (arrow, color) = if r.trend > 0.0 {
+                            ("↑", Color32::from_rgb(150, 255, 150))
+                        } else if r.trend < 0.0 {
+                            ("↓", Color32::from_rgb(255, 120, 100))
+                        } else {
+                            ("→", Color32::GRAY)
+                        };
+                        ui.label(RichText::new(arrow).color(color));
+                    });
+                    row.col(|ui| {
+                        ui.add(egui::ProgressBar::new((r.range_pct / 100.0) as f32)
+                            .desired_width(90.0)
+                            .text(format!("{:.0}%", r.range_pct)));
+                    });
+                    row.col(|ui| {
+                        ui.label(format_gp(r.avg_volume))
+                            .on_hover_text(format!("Recently {}/day", format_gp(r.recent_volume)));
+                    });
+                    row.col(|ui| match r.cheap_day {
+                        Some((day, discount)) => {
+                            ui.label(format!("{} (-{:.1}%)", day, discount));
+                        }
+                        None => {
+                            ui.label(RichText::new("-").color(Color32::GRAY));
+                        }
+                    });
+                });
+            });
+    }
+
+    fn skilling_tab(&mut self, ui: &mut egui::Ui) {
+        if !self.loaded {
+            ui.label(RichText::new("Click 'Scan Market' to load market data")
+                .color(Color32::from_rgb(180, 160, 120))
+                .italics());
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            ui.heading(RichText::new("⛏ Skilling Supplies").color(Color32::from_rgb(255, 210, 100)));
+            ui.separator();
+            ui.label(RichText::new("Each skill's materials as a group - rising prices on rising volume mean demand is heating up")
+                .color(Color32::from_rgb(180, 160, 120)));
+        });
+        ui.add_space(4.0);
+
+        let change = |pct: f64| {
+            let color = if pct >= 0.0 { Color32::from_rgb(150, 255, 150) } else { Color32::from_rgb(255, 120, 100) };
+            RichText::new(format!("{:+.1}%", pct)).color(color)
+        };
+
+        egui::Grid::new("skill_trends").striped(true).num_columns(7).show(ui, |ui| {
+            for (h, tip) in [
+                ("Skill", ""),
+                ("Items", "Supplies with enough volume to track"),
+                ("24h", "Median price change across the group"),
+                ("7d", "Median price change across the group"),
+                ("Volume", "Recent traded volume against the long-run average"),
+                ("Traded/day", "gp value traded per day across the group"),
+                ("Heat", "7d change plus a quarter of the volume change"),
+            ] {
+                ui.label(RichText::new(h).strong().color(Color32::from_rgb(200, 180, 140))).on_hover_text(tip);
+            }
+            ui.end_row();
+
+            for g in &self.skill_trends {
+                ui.label(RichText::new(g.name).strong());
+                ui.label(g.items.len().to_string());
+                ui.label(change(g.change_1d_pct));
+                ui.label(change(g.change_7d_pct));
+                ui.label(change(g.volume_change_pct));
+                ui.label(format_gp(g.traded_value));
+                let heat = g.heat();
+                let flames = if heat > 10.0 { "🔥🔥" } else if heat > 3.0 { "🔥" } else if heat < -3.0 { "❄" } else { "" };
+                ui.label(format!("{:.1} {}", heat, flames));
+                ui.end_row();
+            }
+        });
+
+        ui.add_space(10.0);
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for g in &self.skill_trends {
+                egui::CollapsingHeader::new(format!("{} ({})", g.name, g.items.len()))
+                    .id_salt(g.name)
+                    .show(ui, |ui| {
+                        egui::Grid::new(format!("skill_items_{}", g.name)).striped(true).num_columns(3).show(ui, |ui| {
+                            for m in &g.items {
+                                ui.label(&m.name);
+                                ui.label(format_gp(m.price));
+                                ui.label(change(m.change_pct));
+                                ui.end_row();
+                            }
+                        });
+                    });
+            }
+        });
+    }
+
+    fn refresh_pairs(&mut self) {
+        self.pairs = pairs::find_pairs(&self.stats, self.pair_min_z);
+        self.selected_pair = None;
+    }
+
+    fn arbitrage_tab(&mut self, ui: &mut egui::Ui) {
+        if !self.loaded {
+            ui.label(RichText::new("Click 'Scan Market' to load market data")
+                .color(Color32::from_rgb(180, 160, 120))
+                .italics());
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            ui.heading(RichText::new("🔀 Pair Trades").color(Color32::from_rgb(255, 210, 100)));
+            ui.separator();
+            ui.label(RichText::new("Items that usually move together but have drifted apart - buy the cheap side, sell (or hold off buying) the dear one")
+                .color(Color32::from_rgb(180, 160, 120)));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Diverged by at least");
+            if ui.add(egui::DragValue::new(&mut self.pair_min_z).range(1.0..=4.0).speed(0.1).suffix(" σ"))
+                .on_hover_text("Standard deviations the price ratio sits from its 90-day average")
+                .changed()
+            {
+                self.refresh_pairs();
+            }
+        });
+        ui.add_space(4.0);
+
+        if self.pairs.is_empty() {
+            ui.label(RichText::new("No correlated pairs have diverged that far right now")
+                .color(Color32::from_rgb(180, 160, 120))
+                .italics());
+            return;
+        }
+
+        let chart_height = 260.0;
+        egui::ScrollArea::vertical()
+            .max_height((ui.available_height() - chart_height - 20.0).max(120.0))
+            .show(ui, |ui| {
+                egui::Grid::new("pair_trades").striped(true).num_columns(6).show(ui, |ui| {
+                    for (h, tip) in [
+                        ("Buy", "Cheap relative to its partner"),
+                        ("Sell / delay", "Dear relative to its partner: sell if you hold it, or put off buying it"),
+                        ("Correlation", "Of daily price changes over the shared history"),
+                        ("Divergence", "How far the price ratio is from its average, in standard deviations"),
+                        ("Reversion", "How much the bought side gains on the other if the ratio returns to average"),
+                        ("", ""),
+                    ] {
+                        ui.label(RichText::new(h).strong().color(Color32::from_rgb(200, 180, 140))).on_hover_text(tip);
+                    }
+                    ui.end_row();
+
+                    for (i, p) in self.pairs.iter().enumerate() {
+                        let (long, short) = p.legs();
+                        ui.label(RichText::new(long).color(Color32::from_rgb(150, 255, 150)));
+                        ui.label(RichText::new(short).color(Color32::from_rgb(255, 150, 150)));
+                        ui.label(format!("{:.2}", p.correlation));
+                        ui.label(format!("{:+.1} σ", p.z));
+                        ui.label(format!("+{:.1}%", p.reversion_pct()));
+                        let selected = self.selected_pair == Some(i);
+                        if ui.selectable_label(selected, "📈 Chart").clicked() {
+                            self.selected_pair = if selected { None } else { Some(i) };
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+
+        let Some(p) = self.selected_pair.and_then(|i| self.pairs.get(i)) else { return };
+        ui.separator();
+        ui.label(RichText::new(format!("{} ÷ {} price ratio", p.a, p.b)).strong());
+        let last_day = p.ratio_history.last().map_or(0, |(d, _)| *d);
+        let points: PlotPoints = p.ratio_history
+            .iter()
+            .map(|(day, ratio)| [(*day - last_day) as f64, *ratio])
+            .collect();
+        let band = self.pair_min_z * p.std_ratio;
+        Plot::new("pair_ratio")
+            .height(chart_height)
+            .x_axis_formatter(|mark, _range| format!("{:.0}d", mark.value))
+            .label_formatter(|_name, value| format!("{:.0} days ago\nRatio {:.4}", -value.x, value.y))
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new("Ratio", points)
+                    .color(Color32::from_rgb(255, 210, 100))
+                    .width(2.0));
+                plot_ui.hline(HLine::new("Average", p.mean_ratio)
+                    .color(Color32::from_rgb(180, 160, 120)));
+                for (label, level) in [("Upper band", p.mean_ratio + band), ("Lower band", p.mean_ratio - band)] {
+                    plot_ui.hline(HLine::new(label, level)
+                        .color(Color32::from_rgb(255, 150, 150))
+                        .style(LineStyle::dashed_loose()));
+                }
+            });
+    }
+
+    // Totals for everything the current filters let through, not just the rows on screen
+    fn table_footer(&self, ui: &mut egui::Ui) {
+        let rows = &self.filtered_items;
+        let profit: f64 = rows.iter().map(|r| r.profit.as_f64()).sum();
+        let capital: f64 = rows.iter().map(|r| r.total_cost.as_f64()).sum();
+        let avg_roi = rows.iter().map(|r| r.roi).sum::<f64>() / rows.len().max(1) as f64;
+        let money = self.money();
+        let label_color = Color32::from_rgb(200, 180, 140);
+
+        ui.horizontal(|ui| {
+            ui.label(RichText::new("Σ").strong().color(label_color));
+            ui.label(RichText::new(format!("{} items", rows.len())).strong());
+            ui.separator();
+            ui.label(RichText::new("Potential profit:").color(label_color));
+            ui.label(RichText::new(money.format(profit)).strong().color(self.config.palette.gain(false)));
+            ui.separator();
+            ui.label(RichText::new("Capital required:").color(label_color));
+            let capital_label = ui.label(RichText::new(money.format(capital)).strong());
+            if self.budget > 0.0 && capital > self.budget {
+                capital_label.on_hover_text(format!(
+                    "{:.1}× your budget - each row is sized to the budget on its own, so pick a subset",
+                    capital / self.budget
+                ));
+            }
+            ui.separator();
+            ui.label(RichText::new("Avg ROI:").color(label_color));
+            ui.label(RichText::new(format!("{:.1}%", avg_roi)).strong());
+            if capital > 0.0 {
+                ui.label(RichText::new(format!("({:.1}% on capital)", profit / capital * 100.0)).color(Color32::GRAY))
+                    .on_hover_text("Total profit over total capital, so bigger flips count for more");
+            }
+        });
+    }
+
+    // Shown above the table while any rows are ticked
+    fn bulk_bar(&mut self, ui: &mut egui::Ui) {
+        let mut action = None;
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(format!("☑ {} selected", self.bulk_selection.len()))
+                .strong()
+                .color(Color32::from_rgb(255, 210, 100)));
+            ui.separator();
+            if ui.button("★ Favorite all").clicked() {
+                action = Some(BulkAction::Favorite);
+            }
+            if ui.button("👁 Watch all").clicked() {
+                action = Some(BulkAction::Watch);
+            }
+            if ui.button("🚫 Blacklist all").on_hover_text("Hide all of these from the table").clicked() {
+                action = Some(BulkAction::Blacklist);
+            }
+            if ui.button("📤 Export").on_hover_text("Write selection.html and selection.md").clicked() {
+                action = Some(BulkAction::Export);
+            }
+            ui.separator();
+            if ui.button("Select all shown").clicked() {
+                self.bulk_selection.extend(self.filtered_items.iter().map(|r| r.item_id));
+            }
+            if ui.button("Clear").clicked() {
+                self.bulk_selection.clear();
+                self.bulk_status = None;
+            }
+            if let Some(status) = &self.bulk_status {
+                ui.label(RichText::new(status).color(Color32::from_rgb(180, 160, 120)));
+            }
+        });
+        if let Some(action) = action {
+            self.run_bulk_action(action);
+        }
+    }
+
+    fn run_bulk_action(&mut self, action: BulkAction) {
+        let selected: Vec<&Row> = self.filtered_items.iter().filter(|r| self.bulk_selection.contains(&r.item_id)).collect();
+        let count = selected.len();
+        self.bulk_status = Some(match action {
+            BulkAction::Favorite => {
+                self.favorites.extend(selected.iter().map(|r| r.item_id));
+                self.save_favorites();
+                format!("Favorited {}", count)
+            }
+            BulkAction::Watch => {
+                let mut added = 0;
+                for r in &selected {
+                    added += self.watchlist.add(r.item_id, r.name.clone()) as usize;
+                }
+                if added > 0 {
+                    self.watchlist.save();
+                }
+                format!("Watching {} more", added)
+            }
+            BulkAction::Blacklist => {
+                self.blacklist.extend(selected.iter().map(|r| r.item_id));
+                self.save_blacklist();
+                self.bulk_selection.clear();
+                self.selected_row = None;
+                self.target_graph_height = 0.0;
+                self.apply_filters();
+                format!("Blacklisted {}", count)
+            }
+            BulkAction::Export => {
+                let rows = self.report_rows_where(|r| self.bulk_selection.contains(&r.item_id));
+                let mut report = self.report(&rows);
+                report.title = format!("{} - selected items", report.title);
+                let written = std::fs::write("selection.html", report::render_html(&report))
+                    .and_then(|_| std::fs::write("selection.md", report::render_markdown(&report)));
+                match written {
+                    Ok(()) => format!("Wrote {} items to selection.html and selection.md", rows.len()),
+                    Err(e) => format!("Export failed: {}", e),
+                }
+            }
+        });
+    }
+
+    fn run_row_action(&mut self, action: RowAction, i: usize) {
+        let Some(r) = self.filtered_items.get(i).cloned() else { return };
+        match action {
+            RowAction::Favorite => {
+                if !self.favorites.remove(&r.item_id) {
+                    self.favorites.insert(r.item_id);
+                }
+                self.save_favorites();
+            }
+            RowAction::Watch => {
+                if self.watchlist.add(r.item_id, r.name) {
+                    self.watchlist.save();
+                }
+            }
+            RowAction::Blacklist => {
+                self.blacklist.insert(r.item_id);
+                self.save_blacklist();
+                self.selected_row = None;
+                self.target_graph_height = 0.0;
+                self.apply_filters();
+            }
+            RowAction::AddToPortfolio => {
+                // Adds to an existing holding at the blended cost
+                let (qty, cost) = match self.holdings.iter().find(|h| h.item_id == r.item_id) {
+                    Some(h) => {
+                        let qty = h.qty + r.qty as i64;
+                        (qty, Gp::from_f64((h.qty as f64 * h.cost_basis.as_f64() + r.qty as f64 * r.buy.as_f64()) / qty as f64))
+                    }
+                    None => (r.qty as i64, r.buy),
+                };
+                let holding = Holding {
+                    item_id: r.item_id,
+                    name: r.name,
+                    qty,
+                    cost_basis: cost,
+                    target_sell: Some(r.sell),
+                };
+                self.holding_error = loader::save_holding(self.db_path(), &holding).err().map(|e| e.to_string());
+                self.holdings = loader::load_holdings(self.db_path()).unwrap_or_default();
+            }
+            RowAction::OpenChart => self.select_row(i),
+        }
+    }
+
+    // Commands and items matching the palette's query, best first
+    fn palette_matches(&self) -> Vec<(PaletteCommand, String)> {
+        const MAX_ITEMS: usize = 12;
+        let query = self.palette_query.trim().to_lowercase();
+        let matches = |label: &str| query.is_empty() || label.to_lowercase().contains(&query);
+
+        let mut commands: Vec<(PaletteCommand, String)> = Tab::ALL
+            .into_iter()
+            .map(|t| (PaletteCommand::Tab(t), format!("Go to {}", t.label())))
+            .collect();
+        commands.push((PaletteCommand::Scan, "🔍 Scan market".to_string()));
+        commands.push((PaletteCommand::Report, "📝 Write report".to_string()));
+        commands.push((PaletteCommand::ExportState, format!("📦 Export app state to {}", self.bundle_path)));
+        if !self.config.alt1_path.is_empty() {
+            commands.push((PaletteCommand::Alt1, format!("🎯 Write Alt1 targets to {}", self.config.alt1_path)));
+        }
+        if let Some(r) = self.selected_row.and_then(|i| self.filtered_items.get(i)) {
+            let verb = if self.favorites.contains(&r.item_id) { "Unfavorite" } else { "Favorite" };
+            commands.push((PaletteCommand::ToggleFavorite(r.item_id), format!("⭐ {} {}", verb, r.name)));
+        }
+        commands.retain(|(_, label)| matches(label));
+
+        // Items by name, or by exact ID, with names that start with the query first
+        if !query.is_empty() {
+            let id: Option<i32> = query.parse().ok();
+            let mut items: Vec<&Row> = self.items
+                .iter()
+                .filter(|r| Some(r.item_id) == id || r.name.to_lowercase().contains(&query))
+                .collect();
+            items.sort_by_key(|r| (Some(r.item_id) != id, !r.name.to_lowercase().starts_with(&query), r.name.len()));
+            commands.extend(items.into_iter().take(MAX_ITEMS).map(|r| {
+                (PaletteCommand::Item(r.item_id), format!("📈 {} (#{})", r.name, r.item_id))
+            }));
+        }
+        commands
+    }
+
+    fn run_palette_command(&mut self, command: PaletteCommand) {
+        self.palette_status = None;
+        match command {
+            PaletteCommand::Tab(tab) => self.tab = tab,
+            PaletteCommand::Scan => self.scan(),
+            PaletteCommand::Report => {
+                self.report_status = Some(match self.generate_report() {
+                    Ok(_) => "Wrote report.html and report.md".to_string(),
+                    Err(e) => format!("Report failed: {}", e),
+                });
+            }
+            PaletteCommand::ExportState => {
+                self.bundle_status = Some(match self.export_state() {
+                    Ok(_) => format!("Exported to {}", self.bundle_path),
+                    Err(e) => format!("Export failed: {}", e),
+                });
+            }
+            PaletteCommand::Alt1 => self.write_alt1_targets(),
+            PaletteCommand::ToggleFavorite(id) => {
+                if !self.favorites.remove(&id) {
+                    self.favorites.insert(id);
+                }
+                self.save_favorites();
+                if self.show_favorites_only {
+                    self.apply_filters();
+                }
+            }
+            PaletteCommand::Item(id) => {
+                // Search for it if the current filters hide it
+                if !self.filtered_items.iter().any(|r| r.item_id == id) {
+                    if let Some(name) = self.items.iter().find(|r| r.item_id == id).map(|r| r.name.clone()) {
+                        self.search = name;
+                        self.apply_filters();
+                    }
+                }
+                match self.filtered_items.iter().position(|r| r.item_id == id) {
+                    Some(i) => self.select_row(i),
+                    None => {
+                        self.palette_status = Some("That item is hidden by the current filters".to_string());
+                        return;
+                    }
+                }
+            }
+        }
+        self.show_palette = false;
+    }
+
+    fn palette_window(&mut self, ctx: &Context) {
+        let mut open = self.show_palette;
+        let mut chosen = None;
+        egui::Window::new("⌨ Command Palette")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .title_bar(false)
+            .anchor(egui::Align2::CENTER_TOP, Vec2::new(0.0, 80.0))
+            .fixed_size(Vec2::new(460.0, 0.0))
+            .show(ctx, |ui| {
+                let input = ui.add(egui::TextEdit::singleline(&mut self.palette_query)
+                    .hint_text("Item name or ID, tab, or command...")
+                    .desired_width(f32::INFINITY));
+                input.request_focus();
+                let enter = input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+
+                let matches = self.palette_matches();
+                if enter {
+                    chosen = matches.first().map(|(c, _)| *c);
+                }
+
+                ui.separator();
+                if matches.is_empty() {
+                    let hint = if self.loaded { "No matches" } else { "No matches - scan the market to search items" };
+                    ui.label(RichText::new(hint).color(Color32::from_rgb(180, 160, 120)).italics());
+                }
+                egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    for (i, (command, label)) in matches.iter().enumerate() {
+                        let text = if i == 0 { RichText::new(label).strong() } else { RichText::new(label) };
+                        if ui.selectable_label(i == 0, text).clicked() {
+                            chosen = Some(*command);
+                        }
+                    }
+                });
+
+                if let Some(status) = &self.palette_status {
+                    ui.label(RichText::new(status).color(Color32::from_rgb(180, 160, 120)));
+                }
+                ui.label(RichText::new("Enter runs the highlighted entry · Esc closes").small().color(Color32::GRAY));
+            });
+        self.show_palette = open;
+
+        if let Some(command) = chosen {
+            self.run_palette_command(command);
+        }
+    }
+
+    // Opens the detail panel on the Flips tab for a row of filtered_items
+    fn select_row(&mut self, i: usize) {
+        let Some(r) = self.filtered_items.get(i) else { return };
+        self.selected_item_history = load_item_history(self.db_path(), r.item_id).unwrap_or_default();
+        self.selected_annotations = loader::load_annotations(self.db_path(), r.item_id).unwrap_or_default();
+        self.selected_row = Some(i);
+        self.detail_history_page = 0;
+        self.target_graph_height = 340.0;
+        self.tab = Tab::Flips;
+    }
+
+    // Any two metrics of the current results against each other, one series per tier
+    fn scatter_tab(&mut self, ui: &mut egui::Ui) {
+        if !self.loaded {
+            ui.label(RichText::new("Click 'Scan Market' to load market data")
+                .color(Color32::from_rgb(180, 160, 120))
+                .italics());
+            return;
+        }
+
+        ui.horizontal(|ui| {
+            ui.heading(RichText::new("✨ Scatter Explorer").color(Color32::from_rgb(255, 210, 100)));
+            ui.separator();
+            for (label, metric, log, id) in [
+                ("X:", &mut self.scatter_x, &mut self.scatter_log_x, "scatter_x"),
+                ("Y:", &mut self.scatter_y, &mut self.scatter_log_y, "scatter_y"),
+            ] {
+                ui.label(RichText::new(label).strong());
+                egui::ComboBox::from_id_salt(id)
+                    .selected_text(metric.label())
+                    .show_ui(ui, |ui| {
+                        for m in Metric::ALL {
+                            ui.selectable_value(metric, m, m.label());
+                        }
+                    });
+                ui.checkbox(log, "log");
+                ui.separator();
+            }
+            ui.label(RichText::new(format!("{} items (current filters). Click a point to open it.", self.filtered_items.len()))
+                .color(Color32::from_rgb(180, 160, 120)));
+        });
+
+        // Log axes drop the non-positive values they can't show
+        let axis = |v: f64, log: bool| if log { (v > 0.0).then(|| v.log10()) } else { Some(v) };
+        let points: Vec<(usize, [f64; 2])> = self.filtered_items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, r)| {
+                let x = axis(self.scatter_x.value(r), self.scatter_log_x)?;
+                let y = axis(self.scatter_y.value(r), self.scatter_log_y)?;
+                Some((i, [x, y]))
+            })
+            .collect();
+
+        let (log_x, log_y) = (self.scatter_log_x, self.scatter_log_y);
+        let x_label = format!("{}{}", self.scatter_x.label(), if log_x { " (log)" } else { "" });
+        let y_label = format!("{}{}", self.scatter_y.label(), if log_y { " (log)" } else { "" });
+
+        let response = Plot::new("scatter_explorer")
+            .legend(egui_plot::Legend::default())
+            .x_axis_label(x_label)
+            .y_axis_label(y_label)
+            .x_axis_formatter(move |mark, _| axis_value(mark.value, log_x))
+            .y_axis_formatter(move |mark, _| axis_value(mark.value, log_y))
+            .show_x(false)
+            .show_y(false)
+            .show(ui, |plot_ui| {
+                for tier in ["DIAMOND", "GOLD", "GREEN", "NORMAL", "CRASH"] {
+                    let series: Vec<[f64; 2]> = points
+                        .iter()
+                        .filter(|(i, _)| self.filtered_items[*i].tier == tier)
+                        .map(|(_, p)| *p)
+                        .collect();
+                    if !series.is_empty() {
+                        plot_ui.points(Points::new(tier, series).radius(3.5).color(self.tier_color(tier)));
+                    }
+                }
+            });
+
+        // Nearest point to the pointer, in screen space
+        let hovered = response.response.hover_pos().and_then(|pos| {
+            points
+                .iter()
+                .map(|(i, p)| (*i, response.transform.position_from_point(&PlotPoint::new(p[0], p[1])).distance(pos)))
+                .filter(|(_, d)| *d < 10.0)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(i, _)| i)
+        });
+
+        if let Some(i) = hovered {
+            let r = &self.filtered_items[i];
+            let clicked = response.response.clicked();
+            response.response.on_hover_ui_at_pointer(|ui| {
+                ui.label(RichText::new(&r.name).strong().color(self.tier_color(&r.tier)));
+                ui.label(format!("{}: {}", self.scatter_x.label(), metric_text(self.scatter_x, r)));
+                ui.label(format!("{}: {}", self.scatter_y.label(), metric_text(self.scatter_y, r)));
+                ui.label(format!("Buy {} → Sell {}", format_gp(r.buy.as_f64()), format_gp(r.sell.as_f64())));
+            });
+            if clicked {
+                self.select_row(i);
+            }
+        }
+    }
+
+    fn database_tab(&mut self, ui: &mut egui::Ui) {
+        if self.maintenance.stats.is_none() && !self.maintenance.is_running() && self.maintenance.log.is_empty() {
+            self.start_task(Task::Stats);
+        }
+
+        ui.heading(RichText::new("🗄 Database").color(Color32::from_rgb(255, 210, 100)));
+        ui.label(RichText::new(format!("{} - {}", self.config.game.label(), self.db_path())).color(Color32::from_rgb(180, 160, 120)));
+        ui.add_space(4.0);
+
+        if self.config.game == Game::Osrs {
+            ui.horizontal(|ui| {
+                if ui.add_enabled(!self.maintenance.is_running(), egui::Button::new("🌐 Fetch today's prices"))
+                    .on_hover_text("Records the OSRS Wiki's 24-hour average price and volume for every traded item. Fetch once a day to build up history.")
+                    .clicked()
+                {
+                    self.start_task(Task::FetchOsrsPrices);
+                }
+            });
+            ui.add_space(4.0);
+        }
+
+        if let Some(stats) = &self.maintenance.stats {
+            egui::Grid::new("db_summary").num_columns(2).show(ui, |ui| {
+                ui.label("File size:");
+                ui.label(format!("{:.1} MB", stats.file_size as f64 / 1_048_576.0));
+                ui.end_row();
+                ui.label("Items:");
+                ui.label(stats.item_count.to_string());
+                ui.end_row();
+                ui.label("Snapshots:");
+                ui.label(stats.history_rows.to_string());
+                ui.end_row();
+                ui.label("Date range:");
+                ui.label(format!("{} → {}", stats.first_date, stats.last_date));
+                ui.end_row();
+            });
+        }
+
+        ui.add_space(6.0);
+
+        let running = self.maintenance.is_running();
+        ui.add_enabled_ui(!running, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("🔄 Refresh Stats").clicked() {
+                    self.start_task(Task::Stats);
+                }
+                if ui.button("🧹 VACUUM").on_hover_text("Reclaim free space and defragment the file").clicked() {
+                    self.start_task(Task::Vacuum);
+                }
+                if ui.button("📇 Reindex").clicked() {
+                    self.start_task(Task::Reindex);
+                }
+                if ui.button("✔ Integrity Check").clicked() {
+                    self.start_task(Task::IntegrityCheck);
+                }
+                ui.separator();
+                if !self.confirm_prune_older {
+                    if ui.button("✂ Prune older than").clicked() {
+                        self.confirm_prune_older = true;
+                    }
+                    ui.add(egui::DragValue::new(&mut self.prune_days).range(30..=3650).suffix(" days"));
+                } else {
+                    ui.label(RichText::new(format!("Permanently delete every snapshot older than {} days?", self.prune_days))
+                        .color(Color32::from_rgb(255, 150, 150)));
+                    if ui.button("Yes, prune").clicked() {
+                        self.confirm_prune_older = false;
+                        self.start_task(Task::PruneOlderThan(self.prune_days));
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.confirm_prune_older = false;
+                    }
+                }
+            });
+
+            ui.horizontal(|ui| {
+                let retention = &mut self.config.retention;
+                let mut changed = ui.checkbox(&mut retention.enabled, "🗜 Compact old snapshots daily")
+                    .on_hover_text("Keeps one row per day for older snapshots and one per week for very old ones, with the open/high/low/close saved alongside")
+                    .changed();
+                ui.label("daily after");
+                changed |= ui.add(egui::DragValue::new(&mut retention.daily_after_days)
+                    .range(Retention::MIN_DAYS..=3650)
+                    .suffix(" days"))
+                    .changed();
+                ui.label("weekly after");
+                changed |= ui.add(egui::DragValue::new(&mut retention.weekly_after_days)
+                    .range(retention.daily_after_days..=3650)
+                    .suffix(" days"))
+                    .changed();
+                if changed {
+                    self.config.save();
+                }
+                if !self.confirm_retention {
+                    if ui.button("Run now").clicked() {
+                        self.confirm_retention = true;
+                    }
+                } else {
+                    ui.label(RichText::new("Permanently merge old snapshots into daily and weekly rows?")
+                        .color(Color32::from_rgb(255, 150, 150)));
+                    if ui.button("Yes, compact").clicked() {
+                        self.confirm_retention = false;
+                        self.start_retention();
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.confirm_retention = false;
+                    }
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("📥 Scan batch size:");
+                if ui.add(egui::DragValue::new(&mut self.config.scan_batch_size)
+                    .range(1_000..=1_000_000)
+                    .speed(1_000.0)
+                    .suffix(" snapshots"))
+                    .on_hover_text("Scans read the database this many rows at a time. Smaller batches update the progress bar more often on large databases.")
+                    .changed()
+                {
+                    self.config.save();
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("📤 Parquet:");
+                ui.add(egui::TextEdit::singleline(&mut self.parquet_path).desired_width(200.0));
+                ui.add(egui::TextEdit::singleline(&mut self.parquet_item)
+                    .hint_text("Item name or ID (blank = all)")
+                    .desired_width(180.0));
+                if ui.button("Export history")
+                    .on_hover_text("item_id, name, date, price and volume for every snapshot, ready for pandas or Polars")
+                    .clicked()
+                {
+                    self.export_parquet();
+                }
+                if let Some(err) = &self.parquet_error {
+                    ui.label(RichText::new(err).color(Color32::from_rgb(255, 100, 100)));
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Merge from:");
+                ui.add(egui::TextEdit::singleline(&mut self.merge_path)
+                    .hint_text("path/to/other/rs3_market.db")
+                    .desired_width(300.0));
+                ui.checkbox(&mut self.merge_prefer_incoming, "Prefer incoming on conflict")
+                    .on_hover_text("When both databases have a snapshot for the same item and date, keep the other file's row");
+                if ui.add_enabled(!self.merge_path.trim().is_empty(), egui::Button::new("⤵ Merge")).clicked() {
+                    self.start_task(Task::Merge {
+                        other_path: self.merge_path.trim().to_string(),
+                        prefer_incoming: self.merge_prefer_incoming,
+                    });
+                }
+            });
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("📦 App state:");
+            ui.add(egui::TextEdit::singleline(&mut self.bundle_path).desired_width(300.0));
+            if ui.button("Export").on_hover_text("Favorites, blacklist, settings, holdings and journal as one JSON file. Passwords, bot tokens and hooks are left out.").clicked() {
+                self.bundle_status = Some(match self.export_state() {
+                    Ok(_) => format!("Exported to {}", self.bundle_path),
+                    Err(e) => format!("Export failed: {}", e),
+                });
+            }
+            if ui.button("Import").on_hover_text("Replaces your current favorites, blacklist, settings, holdings and journal. Your email, Telegram, post-scan hook and Alt1 settings are kept.").clicked() {
+                self.bundle_status = Some(match self.import_state() {
+                    Ok(_) => format!("Imported {}", self.bundle_path),
+                    Err(e) => format!("Import failed: {}", e),
+                });
+            }
+            if let Some(status) = &self.bundle_status {
+                ui.label(RichText::new(status).color(Color32::from_rgb(180, 160, 120)));
+            }
+        });
+
+        if running {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Working...");
+            });
+        }
+
+        egui::ScrollArea::vertical()
+            .id_salt("maintenance_log")
+            .max_height(100.0)
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                for line in &self.maintenance.log {
+                    ui.label(RichText::new(line).monospace().color(Color32::from_rgb(180, 160, 120)));
+                }
+            });
+
+        ui.separator();
+
+        let Some(stats) = &self.maintenance.stats else { return };
+        ui.label(RichText::new("Coverage per item").strong());
+
+        TableBuilder::new(ui)
+            .striped(true)
+            .column(Column::remainder().at_least(200.0))
+            .column(Column::exact(100.0))
+            .column(Column::exact(120.0))
+            .column(Column::exact(120.0))
+            .header(24.0, |mut header| {
+                for h in ["Item", "Snapshots", "First", "Last"] {
+                    header.col(|ui| {
+                        ui.label(RichText::new(h).strong().color(Color32::from_rgb(200, 180, 140)));
+                    });
+                }
+            })
+            .body(|body| {
+                body.rows(22.0, stats.coverage.len(), |mut row| {
+                    let c = &stats.coverage[row.index()];
+                    row.col(|ui| { ui.label(&c.name); });
+                    row.col(|ui| { ui.label(c.rows.to_string()); });
+                    row.col(|ui| { ui.label(&c.first_date); });
+                    row.col(|ui| { ui.label(&c.last_date); });
+                });
+            });
+    }
+
+    fn bank_tab(&mut self, ui: &mut egui::Ui) {
+        ui.heading(RichText::new("🏦 Bank Holdings").color(Color32::from_rgb(255, 210, 100)));
+        ui.add_space(4.0);
+
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.holding_query)
+                .hint_text("Item name or ID")
+                .desired_width(200.0));
+            ui.label("Qty");
+            ui.add(egui::DragValue::new(&mut self.holding_qty).range(1..=i64::MAX));
+            ui.label("Cost each");
+            ui.add(egui::DragValue::new(&mut self.holding_cost).range(0.0..=f64::MAX));
+            ui.label("Target sell");
+            ui.add(egui::DragValue::new(&mut self.holding_target).range(0.0..=f64::MAX))
+                .on_hover_text("Alert when the price reaches this (0 = no target)");
+
+            if ui.button("➕ Add / Update").clicked() {
+                self.holding_error = match loader::find_item(self.db_path(), &self.holding_query) {
+                    Ok(Some((item_id, name))) => {
+                        let holding = Holding {
+                            item_id,
+                            name,
+                            qty: self.holding_qty,
+                            cost_basis: Gp::from_f64(self.holding_cost),
+                            target_sell: (self.holding_target > 0.0).then(|| Gp::from_f64(self.holding_target)),
+                        };
+                        match loader::save_holding(self.db_path(), &holding) {
+                            Ok(_) => {
+                                self.holding_query.clear();
+                                self.holdings = loader::load_holdings(self.db_path()).unwrap_or_default();
+                                None
+                            }
+                            Err(e) => Some(e.to_string()),
+                        }
+                    }
+                    Ok(None) => Some(format!("No item matching '{}'", self.holding_query)),
+                    Err(e) => Some(e.to_string()),
+                };
+            }
+        });
+
+        if let Some(err) = &self.holding_error {
+            ui.label(RichText::new(err).color(Color32::from_rgb(255, 100, 100)));
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("🔔 Alert when a holding drops");
+            if ui.add(egui::DragValue::new(&mut self.config.holding_drop_alert_pct)
+                .suffix("%")
+                .range(1.0..=90.0))
+                .changed()
+            {
+                self.config.save();
+            }
+            ui.label("below cost basis");
+        });
+
+        ui.add_space(6.0);
+
+        let mut remove = None;
+        egui::Grid::new("holdings_grid").striped(true).num_columns(7).show(ui, |ui| {
+            for h in ["", "Item", "Qty", "Price", "Target", "Value", "P&L"] {
+                ui.label(RichText::new(h).strong().color(Color32::from_rgb(200, 180, 140)));
+            }
+            ui.end_row();
+
+            for h in &self.holdings {
+                let price = self.current_prices.get(&h.item_id).copied();
+                let value = h.qty as f64 * price.unwrap_or(h.cost_basis.as_f64());
+                let pnl = value - h.qty as f64 * h.cost_basis.as_f64();
+
+                if ui.small_button("🗑").clicked() {
+                    remove = Some(h.item_id);
+                }
+                ui.label(&h.name);
+                ui.label(h.qty.to_string());
+                ui.label(price.map(format_gp).unwrap_or_else(|| "scan needed".into()));
+                ui.label(h.target_sell.map(|t| format_gp(t.as_f64())).unwrap_or_else(|| "-".into()));
+                ui.label(format_gp(value));
+                ui.label(RichText::new(format_gp(pnl)).color(if pnl >= 0.0 {
+                    Color32::from_rgb(150, 255, 150)
+                } else {
+                    Color32::from_rgb(255, 100, 100)
+                }));
+                ui.end_row();
+            }
+        });
+
+        if let Some(item_id) = remove {
+            let _ = loader::delete_holding(self.db_path(), item_id);
+            self.holdings = loader::load_holdings(self.db_path()).unwrap_or_default();
+        }
+
+        ui.add_space(6.0);
+        ui.label(RichText::new(format!("Total bank value: {}", self.money().format(self.bank_value().as_f64())))
+            .color(Color32::from_rgb(255, 220, 150))
+            .strong()
+            .size(18.0));
+
+        self.crash_exits(ui);
+
+        ui.separator();
+
+        if self.bank_history.is_empty() {
+            ui.label(RichText::new("Bank value is recorded on each market scan")
+                .color(Color32::from_rgb(180, 160, 120))
+                .italics());
+            return;
+        }
+
+        let points: PlotPoints = self.bank_history
+            .iter()
+            .enumerate()
+            .map(|(i, (_, v))| [i as f64, v.as_f64()])
+            .collect();
+        let history = self.bank_history.clone();
+        Plot::new("bank_value")
+            .x_axis_formatter(move |mark, _range| {
+                history.get(mark.value as usize)
+                    .map(|(date, _)| date.get(..10).unwrap_or(date).to_string())
+                    .unwrap_or_default()
+            })
+            .label_formatter(|_name, value| format!("Bank: {}", format_gp(value.y)))
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new("Bank value", points)
+                    .color(Color32::from_rgb(255, 210, 100))
+                    .width(2.0));
+            });
+    }
+
+    // Held items the latest scan rates CRASH, with whether to sell now or wait
+    fn crash_exits(&self, ui: &mut egui::Ui) {
+        let advice: Vec<(&Holding, ExitAdvice)> = self.holdings
+            .iter()
+            .filter(|h| self.items.iter().any(|r| r.item_id == h.item_id && r.tier == "CRASH"))
+            .filter_map(|h| {
+                let s = self.stats.iter().find(|s| s.item_id == h.item_id)?;
+                Some((h, exit::advise(s, &self.tax)?))
+            })
+            .collect();
+        if advice.is_empty() {
+            return;
+        }
+
+        let money = self.money();
+        let palette = self.config.palette;
+        ui.add_space(8.0);
+        ui.label(RichText::new("📉 Crashing holdings").strong().color(palette.loss(true)))
+            .on_hover_text("Compared against this item's earlier drops of at least the same depth: \
+                how many climbed back to their pre-drop price within 30 days, and what waiting returned on average");
+        egui::Grid::new("crash_exits").striped(true).num_columns(7).show(ui, |ui| {
+            for h in ["Item", "Drop", "Sell now", "If held", "Past drops", "Recovery", "Advice"] {
+                ui.label(RichText::new(h).strong().color(Color32::from_rgb(200, 180, 140)));
+            }
+            ui.end_row();
+
+            for (h, a) in &advice {
+                let qty = h.qty as f64;
+                ui.label(&h.name);
+                ui.label(format!("-{:.1}%", a.drop_pct));
+                ui.label(money.format(a.sell_now * qty))
+                    .on_hover_text(format!("After tax. P&L vs cost basis: {}", format_gp((a.sell_now - h.cost_basis.as_f64()) * qty)));
+                ui.label(money.format(a.hold_value * qty))
+                    .on_hover_text("Expected after tax, selling at the pre-drop price if it recovers or after 30 days if not");
+                ui.label(format!("{}/{} recovered ({:.0}%)", a.recovered, a.episodes, a.recovery_rate() * 100.0));
+                ui.label(a.median_recovery_days.map_or("-".to_string(), |d| format!("~{:.0} days", d)));
+                let color = match a.call {
+                    ExitCall::Hold => palette.gain(true),
+                    ExitCall::Sell => palette.loss(true),
+                    ExitCall::Unknown => palette.neutral(),
+                };
+                let diff = (a.hold_value - a.sell_now) * qty;
+                ui.label(RichText::new(a.call.label()).strong().color(color))
+                    .on_hover_text(format!("Waiting is worth {}{} vs selling now", if diff >= 0.0 { "+" } else { "-" }, format_gp(diff.abs())));
+                ui.end_row();
+            }
+        });
+    }
+
+    // Second OS window holding the item detail, for a second monitor.
+    // Closing it puts the detail back under the table.
+    fn detail_viewport(&mut self, ctx: &Context, r: &Row) {
+        let money = self.money();
+        let viewport = egui::ViewportBuilder::default()
+            .with_title(format!("{} - {} Market Analyzer", r.name, self.config.game.short_label()))
+            .with_inner_size([1000.0, 640.0]);
+
+        ctx.show_viewport_immediate(egui::ViewportId::from_hash_of("item_detail"), viewport, |ctx, class| {
+            if class == egui::ViewportClass::Embedded {
+                // No native windows here (web build) - fall back to a floating egui window
+                let mut open = true;
+                egui::Window::new(&r.name)
+                    .open(&mut open)
+                    .default_size([1000.0, 640.0])
+                    .show(ctx, |ui| self.item_detail(ui, r, money, 420.0));
+                if !open {
+                    self.dock_detail();
+                }
+                return;
+            }
+
+            egui::CentralPanel::default().show(ctx, |ui| {
+                let chart_height = (ui.available_height() - 180.0).max(200.0);
+                self.item_detail(ui, r, money, chart_height);
+            });
+            if ctx.input(|i| i.viewport().close_requested()) {
+                self.dock_detail();
+            }
+        });
+    }
+
+    fn dock_detail(&mut self) {
+        self.detail_popped = false;
+        if self.selected_row.is_some() {
+            self.target_graph_height = 340.0;
+        }
+    }
+
+    // Offer planner and summary for one row, over tabs for its chart, stats,
+    // snapshots and journal
+    fn item_detail(&mut self, ui: &mut egui::Ui, r: &Row, money: Money, chart_height: f32) {
+        egui::Frame::new()
+            .fill(Color32::from_rgb(35, 28, 18))
+            .stroke(Stroke::new(2.0, Color32::from_rgb(100, 80, 50)))
+            .inner_margin(Margin::same(12))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new("⎘ Price History:")
+                        .color(Color32::from_rgb(255, 210, 100))
+                        .strong()
+                        .size(16.0));
+
+                    ui.label(RichText::new(&r.name)
+                        .color(Color32::from_rgb(255, 220, 150))
+                        .size(16.0));
+
+                    ui.separator();
+
+                    ui.label(RichText::new(&r.notes)
+                        .color(Color32::from_rgb(180, 160, 120))
+                        .italics());
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button(RichText::new("✖").size(16.0)).clicked() {
+                            self.selected_row = None;
+                            self.target_graph_height = 0.0;
+                            self.selected_item_history.clear();
+                            self.detail_popped = false;
+                        }
+                        let (icon, tip) = if self.detail_popped { ("⬇", "Dock back under the table") } else { ("⧉", "Pop out into its own window") };
+                        if ui.button(RichText::new(icon).size(16.0)).on_hover_text(tip).clicked() {
+                            self.detail_popped = !self.detail_popped;
+                            self.target_graph_height = if self.detail_popped { 0.0 } else { 340.0 };
+                        }
+                    });
+                });
+
+                // Offer planner - prices in the plain format the GE price box accepts
+                ui.horizontal(|ui| {
+                    for (label, price, color) in [
+                        ("🛒 Buy offer:", r.buy.as_f64(), Color32::from_rgb(255, 150, 150)),
+                        ("💰 Sell offer:", r.sell.as_f64(), Color32::from_rgb(150, 255, 150)),
+                    ] {
+                        ui.label(RichText::new(label).color(color).strong());
+                        ui.label(RichText::new(format!("{} × {} gp", r.qty, offer_price(price)))
+                            .color(Color32::from_rgb(220, 200, 160)));
+                        if ui.small_button("Copy price").clicked() {
+                            ui.ctx().copy_text(offer_price(price));
+                        }
+                        if ui.small_button("Copy qty").clicked() {
+                            ui.ctx().copy_text(r.qty.to_string());
+                        }
+                        ui.separator();
+                    }
+                });
+
+                ui.label(RichText::new(format!("💡 {}", r.summary))
+                    .color(Color32::from_rgb(220, 200, 160)));
+
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    for tab in DetailTab::ALL {
+                        ui.selectable_value(&mut self.detail_tab, tab, tab.label());
+                    }
+                });
+                ui.separator();
+
+                match self.detail_tab {
+                    DetailTab::Chart => self.detail_chart(ui, r, chart_height),
+                    DetailTab::Stats => {
+                        egui::ScrollArea::vertical().id_salt("detail_stats").show(ui, |ui| self.detail_stats(ui, r, money));
+                    }
+                    DetailTab::History => self.detail_history(ui, r),
+                    DetailTab::Journal => {
+                        egui::ScrollArea::vertical().id_salt("detail_journal").show(ui, |ui| self.detail_journal(ui, r));
+                    }
+                }
+            });
+    }
+
+    // The price history with the Q10-Q90 band, buy/sell lines and note markers,
+    // over a strip of volume bars
+    fn detail_chart(&mut self, ui: &mut egui::Ui, r: &Row, chart_height: f32) {
+        if !self.selected_item_history.is_empty() {
+            // The whole history, thinned once per item; the 365 days the stats
+            // use are the fallback if the full read fails
+            let key = (r.item_id, self.selected_item_history.len());
+            if self.chart.as_ref().is_none_or(|c| c.key != key) {
+                let history = loader::load_item_history_range(self.db_path(), r.item_id, None, None)
+                    .ok()
+                    .filter(|h| !h.is_empty())
+                    .unwrap_or_else(|| self.selected_item_history.clone());
+                self.chart = Some(ChartData {
+                    key,
+                    thinned: history.len() > downsample::CHART_POINTS,
+                    overview: ChartSeries::new(&history),
+                    window: None,
+                });
+            }
+            let Some(chart) = &self.chart else { return };
+
+            // Inside the zoomed window the full-resolution rows replace the overview's
+            let shown = match &chart.window {
+                Some((from, to, rows)) => {
+                    let outside = |x: f64| x < *from as f64 || x > *to as f64;
+                    let mut points: Vec<[f64; 2]> = chart.overview.prices.iter().filter(|p| outside(p[0])).copied().collect();
+                    points.extend(&rows.prices);
+                    points.sort_by(|a, b| a[0].total_cmp(&b[0]));
+                    let mut volumes: Vec<(f64, f64, f64)> = chart.overview.volumes.iter().filter(|v| outside(v.0)).copied().collect();
+                    volumes.extend(&rows.volumes);
+                    ChartSeries { prices: points, volumes }
+                }
+                None => chart.overview.clone(),
+            };
+            let ChartSeries { prices: points, volumes } = shown;
+            let span = match (chart.overview.prices.first(), chart.overview.prices.last()) {
+                (Some(first), Some(last)) => (first[0], last[0]),
+                _ => return,
+            };
+            let thinned = chart.thinned;
+
+            let line = Line::new("Price", PlotPoints::from(points.clone()))
+                .color(Color32::from_rgb(100, 200, 255))
+                .width(2.0);
+
+            // Calculate Y-axis range to handle negative values
+            let min_price = points.iter().map(|p| p[1]).fold(f64::INFINITY, f64::min);
+            let max_price = points.iter().map(|p| p[1]).fold(f64::NEG_INFINITY, f64::max);
+            let padding = (max_price - min_price) * 0.1;
+
+            // Historical Q10-Q90 band (over the same year as the stats), and the
+            // days price sat in the buy / sell zone
+            let mut sorted: Vec<f64> = self.selected_item_history.iter().map(|(_, p, _)| *p).collect();
+            sorted.sort_by(|a, b| a.total_cmp(b));
+            let (q10, q90) = (stats::quantile(&sorted, 0.10), stats::quantile(&sorted, 0.90));
+            let band = Polygon::new("Q10-Q90", vec![[span.0, q10], [span.1, q10], [span.1, q90], [span.0, q90]])
+                .fill_color(Color32::from_rgba_unmultiplied(255, 210, 100, 18))
+                .stroke(Stroke::new(0.5, Color32::from_rgb(120, 100, 60)));
+
+            let (buy, sell) = (r.buy.as_f64(), r.sell.as_f64());
+            let zone_points = |in_zone: &dyn Fn(f64) -> bool| -> Vec<[f64; 2]> {
+                points.iter().filter(|p| in_zone(p[1])).copied().collect()
+            };
+            let buy_zone = Points::new("In buy zone", zone_points(&|p| p <= buy))
+                .color(Color32::from_rgb(255, 150, 150))
+                .radius(3.0);
+            let sell_zone = Points::new("In sell zone", zone_points(&|p| p >= sell))
+                .color(Color32::from_rgb(150, 255, 150))
+                .radius(3.0);
+            let buy_line = HLine::new(format!("Buy {}", format_gp(buy)), buy)
+                .color(Color32::from_rgb(255, 150, 150))
+                .style(LineStyle::dashed_loose());
+            let sell_line = HLine::new(format!("Sell {}", format_gp(sell)), sell)
+                .color(Color32::from_rgb(150, 255, 150))
+                .style(LineStyle::dashed_loose());
+
+            // Notes sit on their own day
+            let markers: Vec<VLine> = self.selected_annotations
+                .iter()
+                .filter_map(|a| {
+                    let x = stats::day_number(&a.date)?;
+                    Some(VLine::new(a.text.clone(), x as f64)
+                        .color(Color32::from_rgb(255, 180, 100))
+                        .width(1.5))
+                })
+                .collect();
+
+            // Volume gets a strip under the price, sharing its days; the dates
+            // then sit under the volume instead
+            let has_volume = !volumes.is_empty();
+            let price_height = if has_volume { chart_height * 0.75 } else { chart_height };
+            let date_axis = |mark: egui_plot::GridMark, range: &std::ops::RangeInclusive<f64>| {
+                // Years of history read better by month than by day
+                let date = stats::date_from_day(mark.value.round() as i64);
+                if range.end() - range.start() > 180.0 {
+                    date[..7].to_string()
+                } else {
+                    date[5..].to_string()
+                }
+            };
+
+            let mut plot = Plot::new("price_history")
+                .height(price_height)
+                .show_axes([!has_volume, true])
+                .y_axis_min_width(CHART_AXIS_WIDTH)
+                .link_axis("detail_chart", [true, false])
+                .link_cursor("detail_chart", [true, false])
+                .show_grid(true)
+                .allow_zoom(true)
+                .allow_scroll(true)
+                .allow_drag(true)
+                .include_y(min_price - padding)
+                .include_y(max_price + padding)
+                .x_axis_formatter(date_axis)
+                .label_formatter(|_name, value| {
+                    format!("{}\nPrice: {}", stats::date_from_day(value.x.round() as i64), format_gp(value.y))
+                })
+                .legend(egui_plot::Legend::default());
+            let mut volume_plot = Plot::new("volume_history")
+                .height(chart_height - price_height)
+                .y_axis_min_width(CHART_AXIS_WIDTH)
+                .link_axis("detail_chart", [true, false])
+                .link_cursor("detail_chart", [true, false])
+                .allow_zoom([true, false])
+                .allow_scroll([true, false])
+                .allow_drag([true, false])
+                .include_y(0.0)
+                .x_axis_formatter(date_axis)
+                .y_axis_formatter(|mark, _range| format_gp(mark.value))
+                .label_formatter(|_name, value| {
+                    format!("{}\nVolume: {}", stats::date_from_day(value.x.round() as i64), format_gp(value.y))
+                });
+            // Keep the zoom while the same item stays open; a new one starts zoomed out
+            if self.chart_item != Some(r.item_id) {
+                plot = plot.reset();
+                volume_plot = volume_plot.reset();
+                self.chart_item = Some(r.item_id);
+            }
+            let response = plot.show(ui, |plot_ui| {
+                    plot_ui.polygon(band);
+                    plot_ui.hline(buy_line);
+                    plot_ui.hline(sell_line);
+                    plot_ui.line(line);
+                    plot_ui.points(buy_zone);
+                    plot_ui.points(sell_zone);
+                    for marker in markers {
+                        plot_ui.vline(marker);
+                    }
+                });
+            if has_volume {
+                let bars: Vec<Bar> = volumes
+                    .iter()
+                    .map(|&(x, volume, width)| Bar::new(x, volume)
+                        .width(width * 0.8)
+                        .fill(Color32::from_rgb(110, 140, 170)))
+                    .collect();
+                volume_plot.show(ui, |plot_ui| {
+                    plot_ui.bar_chart(BarChart::new("Volume", bars));
+                });
+            }
+
+            // Zoomed in on a thinned history: once the pointer lets go, load
+            // every row around the visible days so nothing inside is skipped
+            let bounds = response.transform.bounds();
+            let (from, to) = (bounds.min()[0].max(span.0), bounds.max()[0].min(span.1));
+            let zoomed = thinned && to > from && to - from < (span.1 - span.0) * 0.9;
+            if !zoomed {
+                if let Some(chart) = &mut self.chart {
+                    chart.window = None;
+                }
+            } else if !ui.input(|i| i.pointer.any_down()) {
+                // Loaded windows are padded, so small pans don't reload; one far
+                // wider than the view is too thinned to keep
+                let covered = self.chart.as_ref().and_then(|c| c.window.as_ref()).is_some_and(|(start, end, _)| {
+                    *start as f64 <= from && *end as f64 >= to && ((end - start) as f64) <= (to - from) * 4.0
+                });
+                if !covered {
+                    let pad = (to - from) / 2.0;
+                    let (start, end) = ((from - pad).floor() as i64, (to + pad).ceil() as i64);
+                    let rows = loader::load_item_history_range(
+                        self.db_path(),
+                        r.item_id,
+                        Some(&stats::date_from_day(start)),
+                        Some(&stats::date_from_day(end)),
+                    )
+                    .unwrap_or_default();
+                    if let Some(chart) = &mut self.chart {
+                        chart.window = Some((start, end, ChartSeries::new(&rows)));
+                    }
+                }
+            }
+        } else {
+            ui.centered_and_justified(|ui| {
+                ui.label(RichText::new("Loading price history...")
+                    .color(Color32::from_rgb(180, 160, 120))
+                    .italics()
+                    .size(18.0));
+            });
+        }
+    }
+
+    // Everything the analysis knows about the item, beyond the table's columns
+    fn detail_stats(&mut self, ui: &mut egui::Ui, r: &Row, money: Money) {
+        if let Some(s) = self.stats.iter().find(|s| s.item_id == r.item_id) {
+            let volatility = if s.q50 > 0.0 { s.std_dev / s.q50 * 100.0 } else { 0.0 };
+            let ago = |price: Option<f64>| price.map(format_gp).unwrap_or_else(|| "-".to_string());
+            egui::Grid::new("detail_stats_grid").striped(true).num_columns(4).show(ui, |ui| {
+                let pair = |ui: &mut egui::Ui, label: &str, value: String| {
+                    ui.label(RichText::new(label).color(Color32::from_rgb(180, 160, 120)));
+                    ui.label(RichText::new(value).color(Color32::from_rgb(220, 200, 160)));
+                };
+                pair(ui, "Price", format_gp(s.current_price));
+                pair(ui, "Previous", format_gp(s.prev_price));
+                ui.end_row();
+                pair(ui, "1 day ago", ago(s.price_1d_ago));
+                pair(ui, "7 days ago", ago(s.price_7d_ago));
+                ui.end_row();
+                pair(ui, "Q10 / Q50 / Q90", format!("{} / {} / {}", format_gp(s.q10), format_gp(s.q50), format_gp(s.q90)));
+                pair(ui, "Std dev", format!("{} ({:.1}%)", format_gp(s.std_dev), volatility));
+                ui.end_row();
+                pair(ui, "Volume (avg / recent)", format!("{} / {}", format_gp(s.avg_volume), format_gp(s.recent_volume)));
+                pair(ui, "Latest volume", format_gp(s.current_volume));
+                ui.end_row();
+                pair(ui, "Trend", format!("{:+.2}", s.price_trend));
+                pair(ui, "GE limit", if s.ge_limit > 0 { s.ge_limit.to_string() } else { "-".to_string() });
+                ui.end_row();
+                pair(ui, "Snapshots", format!("{} ({} outliers removed)", s.data_points, s.outliers_removed));
+                pair(ui, "Coverage", format!("{:.0}% of the last {} days", r.coverage_pct(), stats::COVERAGE_DAYS));
+                ui.end_row();
+                pair(ui, "First / last", format!("{} → {}", s.first_date, s.last_date));
+                pair(ui, "Break-even sell", format_gp(r.break_even));
+                ui.end_row();
+            });
+            ui.add_space(4.0);
+        }
+
+        // Timing from the price's dominant cycle, over the full loaded history
+        let key = (r.item_id, self.selected_item_history.len());
+        if self.cycle.as_ref().is_none_or(|(k, _)| *k != key) {
+            let history: Vec<(i64, f64)> = self.selected_item_history
+                .iter()
+                .filter_map(|(date, price, _)| Some((stats::day_number(date)?, *price)))
+                .collect();
+            self.cycle = Some((key, cycles::dominant_cycle(&history)));
+        }
+        if let Some((_, Some(cycle))) = &self.cycle {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(format!("🔁 ~{}-day cycle", cycle.period))
+                    .color(Color32::from_rgb(170, 200, 230))
+                    .strong())
+                    .on_hover_text(format!(
+                        "The detrended price repeats every {} days (autocorrelation {:.2}), swinging about {:.1}% from low to high",
+                        cycle.period, cycle.strength, cycle.amplitude_pct
+                    ));
+                ui.label(RichText::new(cycle.advice()).color(Color32::from_rgb(220, 200, 160)));
+            });
+        }
+
+        // What a buyer at the recommended price has historically sat through
+        ui.horizontal(|ui| {
+            ui.label(RichText::new("⚠ Risk:").color(Color32::from_rgb(255, 180, 100)).strong());
+            ui.label(RichText::new(format!("Worst case {}/item", format_gp(r.downside)))
+                .color(Color32::from_rgb(220, 200, 160)))
+                .on_hover_text("Loss per item if the flip had to be sold at a bad (5th percentile) day's price, after tax");
+            ui.separator();
+            match r.drawdown {
+                Some(d) => {
+                    let recovery = match d.recovery_days {
+                        Some(days) => format!("back in {}d", days),
+                        None => "still under".to_string(),
+                    };
+                    ui.label(RichText::new(format!("Max drawdown -{:.1}% ({})", d.max_pct, recovery))
+                        .color(Color32::from_rgb(255, 150, 120)))
+                        .on_hover_text(glossary::explain("drawdown"));
+                    ui.separator();
+                    ui.label(RichText::new(format!("Longest under water: {}d", d.longest_underwater_days))
+                        .color(Color32::from_rgb(220, 200, 160)));
+                }
+                None => {
+                    ui.label(RichText::new("Never touched the buy price in the loaded history").weak());
+                }
+            }
+        });
+
+        egui::CollapsingHeader::new(RichText::new("🎲 Outcome simulation").color(Color32::from_rgb(255, 180, 100)).strong())
+            .id_salt("monte_carlo")
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Hold for up to");
+                    ui.add(egui::DragValue::new(&mut self.sim_horizon).range(1..=90).suffix(" days"));
+                });
+
+                let key = (r.item_id, self.sim_horizon, r.qty);
+                if self.simulation.as_ref().is_none_or(|(k, _)| *k != key) {
+                    let sim = self.stats.iter().find(|s| s.item_id == r.item_id).and_then(|s| {
+                        let returns = montecarlo::daily_returns(&s.history);
+                        montecarlo::simulate(&r.name, &returns, r.buy.as_f64(), r.sell.as_f64(), r.qty, &self.tax, self.sim_horizon, r.item_id as u64)
+                    });
+                    self.simulation = Some((key, sim));
+                }
+                let Some((_, Some(sim))) = &self.simulation else {
+                    ui.label(RichText::new("Not enough day-to-day history to simulate").weak());
+                    return;
+                };
+
+                ui.horizontal(|ui| {
+                    for (label, value) in [("P5", sim.p5), ("P50", sim.p50), ("P95", sim.p95)] {
+                        let color = if value >= 0.0 { Color32::from_rgb(150, 255, 150) } else { Color32::from_rgb(255, 120, 100) };
+                        ui.label(RichText::new(format!("{}: {}", label, money.format(value))).color(color).strong());
+                        ui.separator();
+                    }
+                    ui.label(format!("Sold at target in {:.0}% of runs", sim.sold_pct))
+                        .on_hover_text("Resamples this item's historical day-to-day moves; runs that never reach the sell price exit at their last price");
+                });
+
+                let bars: Vec<Bar> = montecarlo::histogram(&sim.profits, 30)
+                    .into_iter()
+                    .map(|(x, w, n)| Bar::new(x, n as f64)
+                        .width(w * 0.9)
+                        .fill(if x >= 0.0 { Color32::from_rgb(100, 200, 120) } else { Color32::from_rgb(220, 100, 90) }))
+                    .collect();
+                Plot::new("monte_carlo_plot")
+                    .height(110.0)
+                    .show_y(false)
+                    .allow_scroll(false)
+                    .x_axis_formatter(|mark, _| axis_value(mark.value, false))
+                    .show(ui, |plot_ui| {
+                        plot_ui.bar_chart(BarChart::new("Profit", bars));
+                        plot_ui.vline(VLine::new("P50", sim.p50).color(Color32::from_rgb(255, 210, 100)));
+                    });
+            });
+
+        egui::CollapsingHeader::new(RichText::new("📚 Offer book / volume profile").color(Color32::from_rgb(255, 180, 100)).strong())
+            .id_salt("offer_book")
+            .show(ui, |ui| self.offer_book(ui, r));
+    }
+
+    // Raw snapshots for the item, newest first, a page at a time. Bad rows
+    // (fat-fingered collector data) can be corrected or deleted; every change
+    // goes into the snapshot_edits audit log.
+    fn detail_history(&mut self, ui: &mut egui::Ui, r: &Row) {
+        const PAGE: usize = 50;
+        // Prices this many times off the item's median are flagged as suspect
+        const SUSPECT_RATIO: f64 = 3.0;
+
+        let item_id = r.item_id;
+        if self.raw_snapshots.as_ref().is_some_and(|(id, _, _)| *id != item_id) {
+            self.snapshot_status = None;
+        }
+        if self.raw_snapshots.as_ref().is_none_or(|(id, _, _)| *id != item_id) {
+            let snaps = loader::load_item_snapshots(self.db_path(), item_id).unwrap_or_default();
+            let edits = loader::load_snapshot_edits(self.db_path(), item_id).unwrap_or_default();
+            self.raw_snapshots = Some((item_id, snaps, edits));
+            self.snapshot_edit = None;
+            self.snapshot_delete = None;
+        }
+        let Some((_, snaps, edits)) = &self.raw_snapshots else { return };
+
+        // A price seen in game that the data source hasn't caught up to
+        let mut add = None;
+        ui.horizontal(|ui| {
+            ui.label(RichText::new("✋ Add price:").color(Color32::from_rgb(255, 180, 100)).strong())
+                .on_hover_text(format!(
+                    "Records a price you saw in game as a manual snapshot. It counts {}× in the recent window (see Manual Price Weight) and never towards volume. Replaces your earlier price for that day; a collected price replaces it.",
+                    self.config.manual_weight
+                ));
+            ui.add(egui::TextEdit::singleline(&mut self.manual_date)
+                .hint_text("YYYY-MM-DD")
+                .desired_width(90.0))
+                .on_hover_text("Leave empty for today");
+            ui.add(egui::TextEdit::singleline(&mut self.manual_price)
+                .hint_text("Price")
+                .desired_width(90.0));
+            ui.add(egui::TextEdit::singleline(&mut self.manual_volume)
+                .hint_text("Volume (optional)")
+                .desired_width(110.0));
+            let price = ge_import::parse_gp(&self.manual_price).map(Gp::from_f64).filter(|p| p.0 > 0);
+            let volume = if self.manual_volume.trim().is_empty() {
+                Some(None)
+            } else {
+                ge_import::parse_gp(&self.manual_volume).map(|v| Some(v.round() as i64))
+            };
+            let date = self.manual_date.trim();
+            let date_ok = date.is_empty() || stats::is_date(date);
+            if !date_ok {
+                ui.label(RichText::new("Date must be YYYY-MM-DD").color(Color32::from_rgb(255, 120, 100)));
+            }
+            if let (Some(price), Some(volume), true) = (price, volume, date_ok) {
+                if ui.small_button("➕").clicked() {
+                    add = Some((price, volume));
+                }
+            }
+        });
+
+        if let Some(status) = &self.snapshot_status {
+            ui.label(RichText::new(status).color(Color32::from_rgb(180, 160, 120)));
+        }
+        if let Some((price, volume)) = add {
+            let date = self.manual_date.trim().to_string();
+            let result = loader::add_manual_snapshot(self.db_path(), item_id, &date, price, volume)
+                .map(|_| format!("Added a manual {} price{}", format_gp(price.as_f64()), if date.is_empty() { String::new() } else { format!(" for {}", date) }));
+            if result.is_ok() {
+                self.manual_price.clear();
+                self.manual_volume.clear();
+            }
+            self.after_snapshot_write(item_id, result);
+            return;
+        }
+        if snaps.is_empty() {
+            ui.label(RichText::new("No snapshots in the last year").weak());
+            return;
+        }
+        let pages = snaps.len().div_ceil(PAGE);
+        self.detail_history_page = self.detail_history_page.min(pages - 1);
+
+        let mut sorted: Vec<f64> = snaps.iter().map(|s| s.price.as_f64()).collect();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let median = stats::quantile(&sorted, 0.5);
+
+        ui.horizontal(|ui| {
+            if ui.add_enabled(self.detail_history_page > 0, egui::Button::new("◀ Newer")).clicked() {
+                self.detail_history_page -= 1;
+            }
+            ui.label(format!("Page {} of {} ({} snapshots)", self.detail_history_page + 1, pages, snaps.len()));
+            if ui.add_enabled(self.detail_history_page + 1 < pages, egui::Button::new("Older ▶")).clicked() {
+                self.detail_history_page += 1;
+            }
+        });
+
+        let end = snaps.len() - (self.detail_history_page * PAGE).min(snaps.len());
+        let start = end.saturating_sub(PAGE);
+        let mut save = None;
+        let mut delete = None;
+        let mut cancel = false;
+        egui::ScrollArea::vertical().id_salt("detail_history").show(ui, |ui| {
+            egui::Grid::new("detail_history_grid").striped(true).num_columns(5).show(ui, |ui| {
+                for header in ["Date", "Price", "Volume", "Change", ""] {
+                    ui.label(RichText::new(header).strong());
+                }
+                ui.end_row();
+
+                for i in (start..end).rev() {
+                    let snap = &snaps[i];
+                    let suspect = median > 0.0 && {
+                        let ratio = snap.price.as_f64() / median;
+                        !(1.0 / SUSPECT_RATIO..=SUSPECT_RATIO).contains(&ratio)
+                    };
+                    if snap.manual {
+                        ui.label(format!("{} ✋", snap.date)).on_hover_text("Added by hand");
+                    } else {
+                        ui.label(&snap.date);
+                    }
+
+                    if let Some((rowid, price, volume)) = self.snapshot_edit.as_mut().filter(|(id, _, _)| *id == snap.rowid) {
+                        ui.add(egui::TextEdit::singleline(price).desired_width(90.0));
+                        ui.add(egui::TextEdit::singleline(volume).hint_text("none").desired_width(70.0));
+                        ui.label("");
+                        ui.horizontal(|ui| {
+                            let parsed = ge_import::parse_gp(price).map(Gp::from_f64);
+                            let parsed_volume = if volume.trim().is_empty() {
+                                Some(None)
+                            } else {
+                                ge_import::parse_gp(volume).map(|v| Some(v.round() as i64))
+                            };
+                            let valid = parsed.is_some_and(|p| p.0 > 0) && parsed_volume.is_some();
+                            if ui.add_enabled(valid, egui::Button::new("✔").small()).on_hover_text("Save the correction").clicked() {
+                                save = Some((*rowid, snap.date.clone(), parsed.unwrap_or_default(), parsed_volume.flatten()));
+                            }
+                            cancel = ui.small_button("✖").on_hover_text("Cancel").clicked();
+                        });
+                        ui.end_row();
+                        continue;
+                    }
+
+                    let mut price = ui.label(RichText::new(format_gp(snap.price.as_f64()))
+                        .color(if suspect { Color32::from_rgb(255, 120, 100) } else { Color32::from_rgb(220, 200, 160) }));
+                    if suspect {
+                        price = price.on_hover_text(format!("Over {:.0}× off the item's median of {} - possibly a bad row", SUSPECT_RATIO, format_gp(median)));
+                    }
+                    ui.label(snap.volume.map(|v| format_gp(v as f64)).unwrap_or_else(|| "-".to_string()));
+                    match i.checked_sub(1).map(|j| snaps[j].price.as_f64()) {
+                        Some(prev) if prev > 0.0 => {
+                            let change = (snap.price.as_f64() - prev) / prev * 100.0;
+                            let color = if change > 0.0 {
+                                Color32::from_rgb(150, 255, 150)
+                            } else if change < 0.0 {
+                                Color32::from_rgb(255, 150, 150)
+                            } else {
+                                Color32::from_rgb(180, 160, 120)
+                            };
+                            ui.label(RichText::new(format!("{:+.1}%", change)).color(color));
+                        }
+                        _ => {
+                            ui.label("");
+                        }
+                    }
+
+                    ui.horizontal(|ui| {
+                        if self.snapshot_delete == Some(snap.rowid) {
+                            ui.label(RichText::new("Delete?").color(Color32::from_rgb(255, 150, 150)));
+                            if ui.small_button("Yes").clicked() {
+                                delete = Some((snap.rowid, snap.date.clone()));
+                            }
+                            if ui.small_button("No").clicked() {
+                                self.snapshot_delete = None;
+                            }
+                        } else {
+                            if accessible(ui.small_button("✏"), egui::WidgetType::Button, "Correct snapshot").on_hover_text("Correct this row").clicked() {
+                                let volume = snap.volume.map(|v| v.to_string()).unwrap_or_default();
+                                self.snapshot_edit = Some((snap.rowid, snap.price.0.to_string(), volume));
+                                self.snapshot_delete = None;
+                            }
+                            if accessible(ui.small_button("🗑"), egui::WidgetType::Button, "Delete snapshot").on_hover_text("Delete this row").clicked() {
+                                self.snapshot_delete = Some(snap.rowid);
+                                self.snapshot_edit = None;
+                            }
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+
+            if !edits.is_empty() {
+                ui.add_space(6.0);
+                egui::CollapsingHeader::new(RichText::new(format!("🧾 Edit log ({})", edits.len())).color(Color32::from_rgb(255, 180, 100)).strong())
+                    .id_salt("snapshot_edits")
+                    .show(ui, |ui| {
+                        let volume = |v: Option<i64>| v.map(|v| format_gp(v as f64)).unwrap_or_else(|| "-".to_string());
+                        for e in edits {
+                            let change = match e.new_price {
+                                Some(price) => format!(
+                                    "{} / vol {} → {} / vol {}",
+                                    format_gp(e.old_price.as_f64()), volume(e.old_volume), format_gp(price.as_f64()), volume(e.new_volume)
+                                ),
+                                None => format!("deleted ({} / vol {})", format_gp(e.old_price.as_f64()), volume(e.old_volume)),
+                            };
+                            ui.label(format!("{}  {} snapshot: {}", e.edited_at, e.record_date, change));
+                        }
+                    });
+            }
+        });
+
+        if cancel {
+            self.snapshot_edit = None;
+        }
+        let result = match (save, delete) {
+            (Some((rowid, date, price, volume)), _) => loader::correct_snapshot(self.db_path(), item_id, rowid, price, volume)
+                .map(|_| format!("Corrected the {} snapshot", date)),
+            (None, Some((rowid, date))) => loader::delete_snapshot(self.db_path(), item_id, rowid)
+                .map(|_| format!("Deleted the {} snapshot", date)),
+            (None, None) => return,
+        };
+        self.after_snapshot_write(item_id, result);
+    }
+
+    // Which fee schedule profit is taxed with: the game's own GE tax or one
+    // from fees.json
+    fn fee_settings(&mut self, ui: &mut egui::Ui) {
+        let game = self.config.game;
+        ui.label(RichText::new("🧾 GE Tax").strong())
+            .on_hover_text(format!("The fee taken from each sale. Schedules for other servers or tax changes go in {}", self.fees.path()));
+
+        let mut selected = self.fees.selected.clone();
+        let builtin = game.tax().name;
+        egui::ComboBox::from_id_salt("fee_schedule")
+            .selected_text(self.tax.name.clone())
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut selected, None, format!("{} (built-in)", builtin));
+                for t in &self.fees.schedules {
+                    ui.selectable_value(&mut selected, Some(t.name.clone()), &t.name);
+                }
+            });
+        ui.label(RichText::new(self.tax.describe()).small().color(Color32::from_rgb(180, 160, 120)));
+
+        let mut reload = false;
+        ui.horizontal(|ui| {
+            if ui.small_button("📝 Customize").on_hover_text("Copy the schedule in use into the fees file to edit by hand").clicked() {
+                let name = self.fees.add_copy(&self.tax);
+                selected = Some(name.clone());
+                self.fee_status = Some(format!("Added \"{}\" to {} - edit it there, then Reload", name, self.fees.path()));
+            }
+            if ui.small_button("🔄 Reload").on_hover_text("Read the fees file again after editing it").clicked() {
+                self.fees = Fees::load(game);
+                selected = self.fees.selected.clone();
+                self.fee_status = Some(format!("{} custom schedules", self.fees.schedules.len()));
+                reload = true;
+            }
+        });
+        if let Some(error) = &self.fees.error {
+            ui.label(RichText::new(format!("Couldn't read {}", error)).small().color(Color32::from_rgb(255, 150, 150)));
+        } else if let Some(status) = &self.fee_status {
+            ui.label(RichText::new(status).small().color(Color32::from_rgb(180, 160, 120)));
+        }
+
+        if selected != self.fees.selected || reload {
+            self.fees.selected = selected;
+            self.fees.save();
+            self.tax = self.fees.active(game);
+            if self.loaded {
+                self.load_data();
+            }
+        }
+    }
+
+    // Reloads the item's rows and the analysis after a change to its history
+    fn after_snapshot_write(&mut self, item_id: i32, result: Result<String, impl std::fmt::Display>) {
+        self.snapshot_status = Some(match result {
+            Ok(done) => {
+                self.raw_snapshots = None;
+                self.selected_item_history = load_item_history(self.db_path(), item_id).unwrap_or_default();
+                // Edits don't move data_version, so the cached stats have to go
+                self.stats_version = None;
+                if self.loaded {
+                    self.load_data();
+                }
+                done
+            }
+            Err(e) => format!("Edit failed: {}", e),
+        });
+    }
+
+    // Dated notes on the item and the flips of it logged in the journal
+    fn detail_journal(&mut self, ui: &mut egui::Ui, r: &Row) {
+        let item_id = r.item_id;
+        ui.label(RichText::new("📌 Notes").color(Color32::from_rgb(255, 180, 100)).strong())
+            .on_hover_text("Also drawn as markers on the chart");
+
+        let mut remove = None;
+        for a in &self.selected_annotations {
+            ui.horizontal(|ui| {
+                if ui.small_button("🗑").clicked() {
+                    remove = Some(a.id);
+                }
+                ui.label(RichText::new(&a.date).color(Color32::from_rgb(180, 160, 120)));
+                ui.label(RichText::new(&a.text).color(Color32::from_rgb(220, 200, 160)));
+            });
+        }
+
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.annotation_date)
+                .hint_text("YYYY-MM-DD")
+                .desired_width(90.0))
+                .on_hover_text("Leave empty for today");
+            ui.add(egui::TextEdit::singleline(&mut self.annotation_text)
+                .hint_text("Add a note...")
+                .desired_width(260.0));
+            if ui.small_button("➕").clicked() && !self.annotation_text.trim().is_empty() {
+                changed = loader::add_annotation(self.db_path(), item_id, &self.annotation_date, &self.annotation_text).is_ok();
+                if changed {
+                    self.annotation_text.clear();
+                    self.annotation_date.clear();
+                }
+            }
+        });
+        if let Some(id) = remove {
+            changed = loader::delete_annotation(self.db_path(), id).is_ok();
+        }
+        if changed {
+            self.selected_annotations = loader::load_annotations(self.db_path(), item_id).unwrap_or_default();
+        }
+
+        ui.add_space(6.0);
+        ui.label(RichText::new("📒 Logged flips").color(Color32::from_rgb(255, 180, 100)).strong());
+        let tax = &self.tax;
+        // Trades only know the item's name, so items sharing one share their trades
+        let trades: Vec<&Trade> = self.journal.trades.iter().filter(|t| t.item.eq_ignore_ascii_case(&r.name)).collect();
+        if trades.is_empty() {
+            ui.label(RichText::new("None yet - log one from the Trade Journal").weak());
+            return;
+        }
+        let total: Gp = trades.iter().map(|t| t.profit(tax)).sum();
+        ui.label(format!("{} flips, {} realized", trades.len(), total));
+        for t in trades.iter().rev() {
+            let profit = t.profit(tax);
+            let color = if profit >= Gp::ZERO { Color32::from_rgb(150, 255, 150) } else { Color32::from_rgb(255, 100, 100) };
+            ui.horizontal(|ui| {
+                ui.label(format!("{} × {} → {}", t.qty, format_gp(t.buy.as_f64()), format_gp(t.sell.as_f64())));
+                ui.label(RichText::new(profit.to_string()).color(color).strong());
+            });
+        }
+    }
+
+    // Past volume piled up by price, to pick offer prices between the Q10/Q90 lines
+    fn offer_book(&mut self, ui: &mut egui::Ui, r: &Row) {
+        const LEVELS: usize = 24;
+
+        let key = (r.item_id, self.selected_item_history.len());
+        if self.offer_book.as_ref().is_none_or(|(k, _)| *k != key) {
+            let trades = loader::load_item_trades(self.db_path(), r.item_id).unwrap_or_default();
+            self.offer_book = Some((key, orderbook::build(&trades, LEVELS)));
+        }
+        let Some((_, Some(book))) = &self.offer_book else {
+            ui.label(RichText::new("Not enough traded volume to build a book").weak());
+            return;
+        };
+
+        let current = self.current_prices.get(&r.item_id).copied();
+        let quantiles = self.stats.iter().find(|s| s.item_id == r.item_id).map(|s| (s.q10, s.q90));
+        let (buy, sell) = (r.buy.as_f64(), r.sell.as_f64());
+
+        ui.horizontal_wrapped(|ui| {
+            ui.label(RichText::new(format!("🛒 Buy at {}: {:.0}% of volume traded at or below", format_gp(buy), book.share_below(buy)))
+                .color(Color32::from_rgb(255, 150, 150)));
+            ui.separator();
+            ui.label(RichText::new(format!("💰 Sell at {}: {:.0}% traded at or above", format_gp(sell), 100.0 - book.share_below(sell)))
+                .color(Color32::from_rgb(150, 255, 150)));
+            if let Some(level) = book.busiest() {
+                ui.separator();
+                ui.label(format!("Busiest: {} - {}", format_gp(level.low), format_gp(level.high)));
+            }
+        })
+        .response
+        .on_hover_text("Fewer trades past your price means a slower fill; more means you may be leaving money on the table");
+
+        // Volume peaks either side of today's price, as targets to weigh against the quantiles
+        let reference = current.unwrap_or((buy + sell) / 2.0);
+        let support = book.support(reference).map(|l| l.mid());
+        let resistance = book.resistance(reference).map(|l| l.mid());
+        ui.horizontal_wrapped(|ui| {
+            let against = |target: f64, quantile_price: f64, name: &str| {
+                let diff = (quantile_price - target) / target * 100.0;
+                if diff.abs() < 0.5 {
+                    format!("{} sits on it", name)
+                } else {
+                    format!("{} is {:.1}% {}", name, diff.abs(), if diff > 0.0 { "above" } else { "below" })
+                }
+            };
+            match support {
+                Some(price) => {
+                    ui.label(RichText::new(format!("🧱 Support ~{}", format_gp(price))).color(Color32::from_rgb(255, 150, 150)).strong())
+                        .on_hover_text(glossary::explain("support"));
+                    ui.label(RichText::new(format!("({})", against(price, buy, "your buy"))).weak());
+                }
+                None => {
+                    ui.label(RichText::new("🧱 No clear support below the price").weak());
+                }
+            }
+            ui.separator();
+            match resistance {
+                Some(price) => {
+                    ui.label(RichText::new(format!("⛰ Resistance ~{}", format_gp(price))).color(Color32::from_rgb(150, 255, 150)).strong())
+                        .on_hover_text(glossary::explain("support"));
+                    ui.label(RichText::new(format!("({})", against(price, sell, "your sell"))).weak());
+                }
+                None => {
+                    ui.label(RichText::new("⛰ No clear resistance above the price").weak());
+                }
+            }
+        });
+
+        let split = current.unwrap_or(f64::INFINITY);
+        let nodes: Vec<f64> = book.nodes().iter().map(|l| l.mid()).collect();
+        let bars: Vec<Bar> = book.levels
+            .iter()
+            .map(|l| {
+                let node = nodes.contains(&l.mid());
+                let fill = match (l.mid() < split, node) {
+                    (true, false) => Color32::from_rgb(200, 110, 110),
+                    (true, true) => Color32::from_rgb(255, 140, 140),
+                    (false, false) => Color32::from_rgb(110, 190, 120),
+                    (false, true) => Color32::from_rgb(140, 240, 150),
+                };
+                Bar::new(l.mid(), l.volume).width((l.high - l.low) * 0.9).fill(fill)
+            })
+            .collect();
+        Plot::new("offer_book_plot")
+            .height(200.0)
+            .allow_scroll(false)
+            .x_axis_formatter(|mark, _| axis_value(mark.value, false))
+            .y_axis_formatter(|mark, _| axis_value(mark.value, false))
+            .label_formatter(|_, value| format!("Price: {}\nVolume: {}", format_gp(value.y), format_gp(value.x)))
+            .legend(egui_plot::Legend::default())
+            .show(ui, |plot_ui| {
+                plot_ui.bar_chart(BarChart::new("Volume", bars).horizontal());
+                if let Some((q10, q90)) = quantiles {
+                    for (name, q) in [("Q10", q10), ("Q90", q90)] {
+                        plot_ui.hline(HLine::new(name, q).color(Color32::from_rgb(120, 100, 60)).style(LineStyle::dotted_loose()));
+                    }
+                }
+                plot_ui.hline(HLine::new(format!("Buy {}", format_gp(buy)), buy)
+                    .color(Color32::from_rgb(255, 150, 150))
+                    .style(LineStyle::dashed_loose()));
+                plot_ui.hline(HLine::new(format!("Sell {}", format_gp(sell)), sell)
+                    .color(Color32::from_rgb(150, 255, 150))
+                    .style(LineStyle::dashed_loose()));
+                if let Some(price) = current {
+                    plot_ui.hline(HLine::new("Now", price).color(Color32::from_rgb(255, 210, 100)));
+                }
+                if let Some(price) = support {
+                    plot_ui.hline(HLine::new(format!("Support {}", format_gp(price)), price)
+                        .color(Color32::from_rgb(255, 110, 110))
+                        .width(1.5));
+                }
+                if let Some(price) = resistance {
+                    plot_ui.hline(HLine::new(format!("Resistance {}", format_gp(price)), price)
+                        .color(Color32::from_rgb(110, 230, 130))
+                        .width(1.5));
+                }
+            });
+    }
+
+    // One clickable bar per tier, sized by its item count in the scan
+    fn tier_distribution(&mut self, ui: &mut egui::Ui) {
+        const TIERS: [(&str, &str); 5] = [
+            ("DIAMOND", "💎 Diamond"),
+            ("GOLD", "⭐ Gold"),
+            ("GREEN", "✅ Good"),
+            ("NORMAL", "⚪ Normal"),
+            ("CRASH", "📉 Crash"),
+        ];
+        const LABEL_WIDTH: f32 = 90.0;
+        const COUNT_WIDTH: f32 = 44.0;
+
+        let count = |tier: &str| self.items.iter().filter(|r| r.tier == tier).count();
+        let counts = TIERS.map(|(tier, _)| count(tier));
+        let most = counts.iter().copied().max().unwrap_or(0).max(1);
+        let total = self.items.len().max(1);
+
+        let mut clicked = None;
+        for ((tier, label), n) in TIERS.into_iter().zip(counts) {
+            let is_selected = self.selected_tier.as_deref() == Some(tier);
+            let color = self.tier_color(tier);
+            let (rect, response) = ui.allocate_exact_size(Vec2::new(ui.available_width(), 20.0), egui::Sense::click());
+            let painter = ui.painter();
+
+            if is_selected {
+                painter.rect_filled(rect, 3.0, ui.visuals().selection.bg_fill);
+            } else if response.hovered() {
+                painter.rect_filled(rect, 3.0, ui.visuals().widgets.hovered.weak_bg_fill);
+            }
+            painter.text(rect.left_center() + Vec2::new(4.0, 0.0), egui::Align2::LEFT_CENTER, label, FontId::proportional(13.0), color);
+
+            let track = egui::Rect::from_min_max(
+                egui::pos2(rect.left() + LABEL_WIDTH, rect.center().y - 5.0),
+                egui::pos2((rect.right() - COUNT_WIDTH).max(rect.left() + LABEL_WIDTH), rect.center().y + 5.0),
+            );
+            let mut bar = track;
+            bar.set_width(track.width() * n as f32 / most as f32);
+            painter.rect_filled(track, 2.0, Color32::from_rgb(45, 38, 28));
+            painter.rect_filled(bar, 2.0, color);
+            painter.text(rect.right_center() - Vec2::new(4.0, 0.0), egui::Align2::RIGHT_CENTER, n.to_string(), FontId::proportional(12.0), Color32::from_rgb(220, 200, 160));
+
+            let action = if is_selected { "click to clear the filter" } else { "click to show only these" };
+            let response = accessible(response, egui::WidgetType::Button, label)
+                .on_hover_text(format!("{} items, {:.0}% of the scan - {}", n, n as f64 / total as f64 * 100.0, action));
+            if response.clicked() {
+                clicked = Some((tier, is_selected));
+            }
+        }
+
+        if let Some((tier, was_selected)) = clicked {
+            self.selected_tier = if was_selected { None } else { Some(tier.to_string()) };
+            self.apply_filters();
+        }
+    }
+
+    fn tier_color(&self, t: &str) -> Color32 {
+        self.config.palette.tier(t)
+    }
+}
+
+impl eframe::App for RS3App {
+    fn update(&mut self, ctx: &Context, _frame: &mut eframe::Frame) {
+        self.handle_undo_shortcuts(ctx);
+        self.handle_palette_shortcut(ctx);
+        self.track_window(ctx);
+        self.handle_tray(ctx);
+        self.handle_telegram(ctx);
+
+        if !self.show_wizard && self.config.schedule.is_due(self.last_scan_at, journal::now_secs()) {
+            self.scan();
+        }
+
+        egui::TopBottomPanel::top("top").show(ctx, |ui| {
+            ui.add_space(4.0);
+            
+            // Title bar with RS3 styling
+            ui.horizontal(|ui| {
+                let title = match self.config.game {
+                    Game::Rs3 => "⚔ RuneScape 3 Grand Exchange Analyzer",
+                    Game::Osrs => "⚔ Old School Grand Exchange Analyzer",
+                };
+                ui.heading(RichText::new(title)
+                    .color(Color32::from_rgb(255, 210, 100))
+                    .strong()
+                    .size(24.0)
+                );
+                
 
             });
 
@@ -353,7 +5794,16 @@ impl eframe::App for RS3App {
                         .color(Color32::from_rgb(255, 220, 100))
                         .strong())
                 ).clicked() {
-                    self.load_data();
+                    self.scan();
+                }
+                if self.snapshot_stream.is_running() {
+                    let text = match self.snapshot_stream.total {
+                        Some(total) => format!("Loading {} / {} snapshots", self.snapshot_stream.loaded, total),
+                        None => "Counting snapshots...".to_string(),
+                    };
+                    ui.add(egui::ProgressBar::new(self.snapshot_stream.fraction())
+                        .text(text)
+                        .desired_width(240.0));
                 }
 
                 ui.separator();
@@ -363,9 +5813,11 @@ impl eframe::App for RS3App {
                     .color(Color32::from_rgb(200, 180, 100)));
                 
                 let mut b = self.budget / 1_000_000.0;
-                if ui.add(egui::DragValue::new(&mut b)
+                let allocated = self.allocation_active();
+                if ui.add_enabled(!allocated, egui::DragValue::new(&mut b)
                     .suffix(" M")
                     .speed(1.0))
+                    .on_disabled_hover_text("Set by the bank allocation in the side panel")
                     .changed() 
                 {
                     self.budget = (b * 1_000_000.0).max(100_000.0);
@@ -383,21 +5835,152 @@ impl eframe::App for RS3App {
                         .hint_text("Search items...")
                         .desired_width(200.0)
                 );
-                if search_response.changed() {
-                    if self.loaded {
-                        self.apply_filters();
-                    }
+                if search_response.changed() && self.loaded {
+                    self.apply_filters();
                 }
+                if !self.search.is_empty() && self.loaded {
+                    let count = self.search_matches.len();
+                    ui.label(RichText::new(format!("{} match{}", count, if count == 1 { "" } else { "es" }))
+                        .small()
+                        .color(Color32::from_rgb(180, 160, 120)))
+                        .on_hover_text("Press Enter in the search box to step through them");
+                }
+                // Enter steps the selection through the matches, keeping focus in the box
+                if search_response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) && !self.search_matches.is_empty() {
+                    let next = self.search_matches
+                        .iter()
+                        .copied()
+                        .find(|&i| self.selected_row.is_none_or(|s| i > s))
+                        .unwrap_or(self.search_matches[0]);
+                    self.select_row(next);
+                    self.scroll_to_row = Some(next);
+                    search_response.request_focus();
+                }
+
+                ui.separator();
 
+                // Shopping list export
+                ui.add_enabled_ui(self.loaded, |ui| {
+                    if ui.button("📋 Copy Top")
+                        .on_hover_text("Copy a Markdown shopping list of the top flips, sized to your budget")
+                        .clicked()
+                    {
+                        let list = self.shopping_list();
+                        ui.ctx().copy_text(list);
+                    }
+                    ui.add(egui::DragValue::new(&mut self.shopping_list_size)
+                        .range(1..=50)
+                        .speed(0.2));
+                    if ui.add(egui::DragValue::new(&mut self.config.max_drawdown_pct)
+                        .range(0.0..=100.0)
+                        .speed(0.5)
+                        .custom_formatter(|v, _| if v <= 0.0 { "no loss cap".to_string() } else { format!("max loss {:.0}%", v) })
+                        .custom_parser(|s| s.trim_matches(|c: char| !c.is_ascii_digit() && c != '.').parse().ok()))
+                        .on_hover_text("Risk budget: trims the shopping list so its worst case (every item sold at its 5th percentile price) loses at most this share of your budget")
+                        .changed()
+                    {
+                        self.config.save();
+                    }
+
+                    if ui.button("⏳ Time Machine")
+                        .on_hover_text("See the table as it looked on two past dates, and what changed between them")
+                        .clicked()
+                    {
+                        self.open_time_machine();
+                    }
+
+                    let report_btn = ui.button("📝 Report")
+                        .on_hover_text(self.report_status.as_deref().unwrap_or("Write report.html and report.md for the current results"));
+                    if report_btn.clicked() {
+                        self.report_status = Some(match self.generate_report() {
+                            Ok(_) => "Wrote report.html and report.md".to_string(),
+                            Err(e) => format!("Report failed: {}", e),
+                        });
+                    }
+                });
+
+                ui.separator();
+
+                self.profit_tracker(ui);
+
+            });
+
+            ui.add_space(2.0);
+
+            ui.horizontal(|ui| {
+                for tab in Tab::ALL {
+                    let label = if tab == Tab::Flips && self.config.ironman_mode { "📈 Prices" } else { tab.label() };
+                    ui.selectable_value(&mut self.tab, tab, label);
+                }
+                ui.separator();
+                let mut game = self.config.game;
+                egui::ComboBox::from_id_salt("game")
+                    .selected_text(game.label())
+                    .show_ui(ui, |ui| {
+                        for g in Game::ALL {
+                            ui.selectable_value(&mut game, g, g.label());
+                        }
+                    });
+                if game != self.config.game && !self.maintenance.is_running() {
+                    self.switch_game(game);
+                }
+                if ui.checkbox(&mut self.config.ironman_mode, "Ironman")
+                    .on_hover_text("Hide flip recommendations and show price trends, cheap buy days and volume instead")
+                    .changed()
+                {
+                    self.config.save();
+                }
             });
 
             ui.add_space(2.0);
         });
 
-        if self.loaded {
-            egui::SidePanel::right("filters")
+        if let Some((task, ok)) = self.maintenance.poll() {
+            self.stats_version = None;
+            // Only a finished compaction counts as the day's run
+            if matches!(task, Task::Retention { .. }) {
+                if ok {
+                    self.config.retention.last_run = journal::now_secs();
+                    self.config.save();
+                } else {
+                    self.retention_failed = true;
+                }
+            }
+        }
+        self.poll_snapshots();
+        if self.config.retention.is_due(journal::now_secs())
+            && !self.retention_failed
+            && !self.maintenance.is_running()
+            && loader::database_ready(self.db_path())
+        {
+            self.start_retention();
+        }
+
+        if self.tab == Tab::Bank {
+            egui::CentralPanel::default().show(ctx, |ui| self.bank_tab(ui));
+        } else if self.tab == Tab::Database {
+            egui::CentralPanel::default().show(ctx, |ui| self.database_tab(ui));
+        } else if self.tab == Tab::Scatter {
+            egui::CentralPanel::default().show(ctx, |ui| self.scatter_tab(ui));
+        } else if self.tab == Tab::Skilling {
+            egui::CentralPanel::default().show(ctx, |ui| self.skilling_tab(ui));
+        } else if self.tab == Tab::Arbitrage {
+            egui::CentralPanel::default().show(ctx, |ui| self.arbitrage_tab(ui));
+        } else if self.tab == Tab::Investments {
+            egui::CentralPanel::default().show(ctx, |ui| self.investments_tab(ui));
+        } else if self.tab == Tab::Heatmap {
+            egui::CentralPanel::default().show(ctx, |ui| self.heatmap_tab(ui));
+        } else if self.config.ironman_mode && self.tab == Tab::Flips {
+            egui::CentralPanel::default().show(ctx, |ui| self.prices_tab(ui));
+        } else if self.tab == Tab::Market {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| self.market_tab(ui));
+            });
+        } else if self.loaded {
+            let side_panel = egui::SidePanel::right("filters")
                 .min_width(250.0)
                 .max_width(350.0)
+                .default_width(self.config.window.side_panel_width.unwrap_or(250.0))
                 .show(ctx, |ui| {
                     ui.heading(RichText::new("⚡ Filters & Settings")
                         .color(Color32::from_rgb(255, 210, 100)));
@@ -405,39 +5988,14 @@ impl eframe::App for RS3App {
                     ui.separator();
                     
                     egui::ScrollArea::vertical().show(ui, |ui| {
-                        // Tier filter
-                        ui.label(RichText::new("🏆 Tier Filter").strong());
-                        ui.horizontal_wrapped(|ui| {
-                            let tiers = [
-                                ("DIAMOND", "💎 Diamond"),
-                                ("GOLD", "⭐ Gold"),
-                                ("GREEN", "✅ Good"),
-                                ("NORMAL", "⚪ Normal"),
-                                ("CRASH", "📉 Crash"),
-                            ];
-                            for (tier, label) in tiers {
-                                let is_selected = self.selected_tier.as_deref() == Some(tier);
-                                let col = self.tier_color(tier);
-                                
-                                if ui.selectable_label(
-                                    is_selected,
-                                    RichText::new(label).color(col)
-                                ).clicked() {
-                                    self.selected_tier = if is_selected {
-                                        None
-                                    } else {
-                                        Some(tier.to_string())
-                                    };
-                                    self.apply_filters();
-                                }
-                            }
-                        });
+                        // Tier filter, drawn as how the scan splits across tiers
+                        ui.label(RichText::new("🏆 Tiers").strong())
+                            .on_hover_text("How many of this scan's items land in each tier. Click a tier to show only it.");
+                        self.tier_distribution(ui);
                         
-                        if self.selected_tier.is_some() {
-                            if ui.button("Clear Tier Filter").clicked() {
-                                self.selected_tier = None;
-                                self.apply_filters();
-                            }
+                        if self.selected_tier.is_some() && ui.button("Clear Tier Filter").clicked() {
+                            self.selected_tier = None;
+                            self.apply_filters();
                         }
                         
                         ui.add_space(10.0);
@@ -467,15 +6025,105 @@ impl eframe::App for RS3App {
                             self.apply_filters();
                         }
                         
+                        ui.add_space(10.0);
+
+                        // Coverage filter
+                        ui.label(RichText::new("📅 Min Data Coverage").strong())
+                            .on_hover_text(glossary::explain("coverage"));
+                        if ui.add(egui::Slider::new(&mut self.min_coverage, 0.0..=100.0)
+                            .suffix("%")
+                            .step_by(5.0))
+                            .changed()
+                        {
+                            self.apply_filters();
+                        }
+
+                        ui.add_space(10.0);
+
+                        // Data quantity and freshness
+                        ui.label(RichText::new("🧮 Min Data Points").strong())
+                            .on_hover_text("Snapshots an item needs before it's worth scoring - a handful of prices can't show a real range");
+                        if ui.add(egui::Slider::new(&mut self.min_data_points, 0..=200)
+                            .logarithmic(true))
+                            .changed()
+                        {
+                            self.apply_filters();
+                        }
+
+                        ui.label(RichText::new("⌛ Max Staleness").strong())
+                            .on_hover_text("Hide items whose last snapshot is this many days older than the newest data (0 = no limit)");
+                        if ui.add(egui::Slider::new(&mut self.max_stale_days, 0..=60)
+                            .suffix(" days"))
+                            .changed()
+                        {
+                            self.apply_filters();
+                        }
+
+                        ui.label(RichText::new("📉 Max Drawdown").strong())
+                            .on_hover_text(format!("{}\n\nHide items that fell further than this (0 = no limit)", glossary::explain("drawdown")));
+                        if ui.add(egui::Slider::new(&mut self.max_drawdown, 0.0..=50.0)
+                            .suffix("%"))
+                            .changed()
+                        {
+                            self.apply_filters();
+                        }
+
+                        ui.add_space(10.0);
+
+                        // Regime filter
+                        ui.label(RichText::new("🌊 Volatility Regimes").strong())
+                            .on_hover_text(glossary::explain("regime"));
+                        ui.horizontal_wrapped(|ui| {
+                            for regime in Regime::ALL {
+                                let shown = !self.hidden_regimes.contains(&regime);
+                                if ui.selectable_label(shown, format!("{} {}", regime.icon(), regime.label()))
+                                    .on_hover_text(regime.describe())
+                                    .clicked()
+                                {
+                                    if shown {
+                                        self.hidden_regimes.insert(regime);
+                                    } else {
+                                        self.hidden_regimes.remove(&regime);
+                                    }
+                                    self.apply_filters();
+                                }
+                            }
+                        });
+
                         ui.add_space(10.0);
                         ui.separator();
                         
+                        if ui.checkbox(&mut self.config.merge_variants, "Merge item variants")
+                            .on_hover_text("Show one row per item family (charged/uncharged, broken, doses...) using its best-scoring variant")
+                            .changed()
+                        {
+                            self.config.save();
+                            self.apply_filters();
+                        }
+
                         // Show bad items toggle
                         if ui.checkbox(&mut self.show_bad, "Show Negative Score Items")
                             .changed() 
                         {
                             self.apply_filters();
                         }
+
+                        if ui.checkbox(&mut self.config.beginner_mode, "🎓 Beginner Mode")
+                            .on_hover_text("Only liquid, affordable, low-risk flips, with fewer columns and explanations")
+                            .changed()
+                        {
+                            self.config.save();
+                            self.apply_filters();
+                        }
+
+                        // Dead items are dropped before analysis, so toggling needs a reload
+                        if ui.checkbox(&mut self.config.include_dead_items, "💀 Include Dead Items")
+                            .on_hover_text("Items with no recent snapshots or ~0 volume")
+                            .changed()
+                        {
+                            self.config.save();
+                            self.load_data();
+                        }
                         
                         ui.add_space(10.0);
                         
@@ -485,6 +6133,19 @@ impl eframe::App for RS3App {
                         {
                             self.apply_filters();
                         }
+
+                        if !self.blacklist.is_empty() {
+                            ui.horizontal(|ui| {
+                                ui.label(RichText::new(format!("🚫 {} blacklisted", self.blacklist.len()))
+                                    .color(Color32::from_rgb(180, 160, 120)))
+                                    .on_hover_text("Hidden from the table with the right-click menu");
+                                if ui.small_button("Clear").clicked() {
+                                    self.blacklist.clear();
+                                    self.save_blacklist();
+                                    self.apply_filters();
+                                }
+                            });
+                        }
                         
                         ui.add_space(10.0);
                         ui.separator();
@@ -523,6 +6184,11 @@ impl eframe::App for RS3App {
                             }
                         });
                         
+                        ui.add_space(10.0);
+                        ui.separator();
+
+                        self.filter_summary(ui);
+
                         ui.add_space(10.0);
                         ui.separator();
                         
@@ -533,484 +6199,1591 @@ impl eframe::App for RS3App {
                         {
                             self.min_profit = 0.0;
                             self.min_roi = 0.0;
+                            self.min_coverage = 0.0;
+                            self.min_data_points = 0;
+                            self.max_stale_days = 0;
+                            self.max_drawdown = 0.0;
+                            self.disabled_filters.clear();
+                            self.hidden_regimes.clear();
                             self.selected_tier = None;
                             self.show_bad = false;
                             self.sort_by = SortBy::Score;
                             self.sort_order = SortOrder::Descending;
                             self.apply_filters();
                         }
-                    });
-                });
-        }
 
-        egui::CentralPanel::default().show(ctx, |ui| {
+                        ui.horizontal(|ui| {
+                            let can_undo = !self.undo_stack.is_empty()
+                                || self.view_committed.as_ref().is_some_and(|c| *c != self.view_state());
+                            if ui.add_enabled(can_undo, egui::Button::new("↶ Undo"))
+                                .on_hover_text("Ctrl+Z")
+                                .clicked()
+                            {
+                                self.undo();
+                            }
+                            if ui.add_enabled(!self.redo_stack.is_empty(), egui::Button::new("↷ Redo"))
+                                .on_hover_text("Ctrl+Y")
+                                .clicked()
+                            {
+                                self.redo();
+                            }
+                        });
 
-            if !self.loaded {
-                ui.centered_and_justified(|ui| {
-                    ui.vertical_centered(|ui| {
-                        ui.add_space(100.0);
-                        ui.label(RichText::new("⚔")
-                            .size(80.0)
-                            .color(Color32::from_rgb(255, 210, 100)));
-                        ui.add_space(20.0);
-                        ui.label(RichText::new("Welcome to the Grand Exchange Analyzer")
-                            .size(24.0)
-                            .color(Color32::from_rgb(200, 180, 140)));
                         ui.add_space(10.0);
-                        ui.label(RichText::new("Click 'Scan Market' to begin analyzing flips")
-                            .color(Color32::from_rgb(180, 160, 120)));
-                    });
-                });
-                return;
-            }
+                        ui.separator();
+
+                        ui.label(RichText::new("⏳ Flip Horizon").strong())
+                            .on_hover_text("How long you plan to hold; changes the history window, target percentiles and trend weighting");
+                        ui.horizontal(|ui| {
+                            let mut changed = false;
+                            for h in Horizon::ALL {
+                                changed |= ui.selectable_value(&mut self.config.horizon, h, h.label())
+                                    .on_hover_text(h.description())
+                                    .clicked();
+                            }
+                            if changed {
+                                self.config.save();
+                                if self.loaded {
+                                    self.load_data();
+                                }
+                            }
+                        });
+                        ui.add_space(10.0);
+
+                        ui.label(RichText::new("🔢 Price Rounding").strong())
+                            .on_hover_text("Round offer prices to steps you'd type into the GE; buys round up and sells down, so profit is never overstated");
+                        ui.horizontal(|ui| {
+                            let mut changed = false;
+                            for r in PriceRounding::ALL {
+                                changed |= ui.selectable_value(&mut self.config.rounding, r, r.label())
+                                    .on_hover_text(r.description())
+                                    .clicked();
+                            }
+                            if changed {
+                                self.config.save();
+                                if self.loaded {
+                                    self.load_data();
+                                }
+                            }
+                        });
+                        ui.add_space(10.0);
 
-            if self.filtered_items.is_empty() {
-                ui.centered_and_justified(|ui| {
-                    ui.vertical_centered(|ui| {
-                        ui.label(RichText::new("🔍")
-                            .size(60.0)
-                            .color(Color32::from_rgb(150, 130, 100)));
+                        self.fee_settings(ui);
                         ui.add_space(10.0);
-                        ui.label(RichText::new("No items match your filters")
-                            .size(20.0)
-                            .color(Color32::from_rgb(180, 160, 120)));
-                        ui.add_space(5.0);
-                        ui.label(RichText::new("Try adjusting your search or filter settings")
-                            .color(Color32::from_rgb(150, 130, 100)));
+
+                        ui.label(RichText::new("✋ Manual Price Weight").strong())
+                            .on_hover_text("How many times each price you added by hand counts in the recent 14-snapshot window, next to one for each collected snapshot");
+                        let mut weight = self.config.manual_weight;
+                        if ui.add(egui::Slider::new(&mut weight, 1..=5).suffix("×")).changed() {
+                            self.config.manual_weight = weight;
+                            self.config.save();
+                            // Weighting happens when the stats are built, so they can't be reused
+                            self.stats_version = None;
+                            if self.loaded {
+                                self.load_data();
+                            }
+                        }
+                        ui.add_space(10.0);
+
+                        self.strategy_picker(ui);
+                        self.allocation_settings(ui);
+
+                        if ui.checkbox(&mut self.config.model_slippage, "Model slippage")
+                            .on_hover_text(glossary::explain("slippage"))
+                            .changed()
+                        {
+                            self.config.save();
+                            self.load_data();
+                        }
+                        if ui.checkbox(&mut self.config.realistic_profit, "Realistic profit")
+                            .on_hover_text(glossary::explain("capture"))
+                            .changed()
+                        {
+                            self.config.save();
+                            self.load_data();
+                        }
+                        ui.horizontal(|ui| {
+                            ui.label("Timeframe columns:").on_hover_text(glossary::explain("timeframes"));
+                            let mut changed = false;
+                            for (shown, days) in self.config.timeframe_columns.iter_mut().zip(stats::TIMEFRAMES) {
+                                changed |= ui.checkbox(shown, format!("{}d", days)).changed();
+                            }
+                            if changed {
+                                self.config.save();
+                            }
+                        });
+
+                        ui.add_space(10.0);
+                        ui.label(RichText::new("📏 Row Density").strong());
+                        ui.horizontal(|ui| {
+                            let mut changed = ui.selectable_value(&mut self.config.row_density, RowDensity::Comfortable, "Comfortable").clicked();
+                            changed |= ui.selectable_value(&mut self.config.row_density, RowDensity::Compact, "Compact")
+                                .on_hover_text("Shorter rows; Risk, Qty, Volume and Total Cost move into the item name tooltip")
+                                .clicked();
+                            if changed {
+                                self.config.save();
+                            }
+                        });
+
+                        ui.add_space(10.0);
+                        ui.label(RichText::new("🎨 Colours").strong());
+                        egui::ComboBox::from_id_salt("palette")
+                            .selected_text(self.config.palette.label())
+                            .show_ui(ui, |ui| {
+                                for p in Palette::ALL {
+                                    if ui.selectable_value(&mut self.config.palette, p, p.label()).clicked() {
+                                        self.config.save();
+                                    }
+                                }
+                            })
+                            .response
+                            .on_hover_text("Blue/orange and high contrast stay readable with colour-blindness and add +/- signs to gains");
+
+                        ui.add_space(10.0);
+                        ui.label(RichText::new("💱 Show Profit In").strong());
+                        ui.horizontal(|ui| {
+                            let mut changed = ui.selectable_value(&mut self.config.currency, Currency::Gp, "gp").clicked();
+                            changed |= ui.selectable_value(&mut self.config.currency, Currency::Bonds, "Bonds").clicked();
+                            changed |= ui.selectable_value(&mut self.config.currency, Currency::Usd, "USD").clicked();
+                            if changed {
+                                self.config.save();
+                            }
+                        });
+                        if self.config.currency == Currency::Usd {
+                            ui.horizontal(|ui| {
+                                ui.label("$ per bond:");
+                                if ui.add(egui::DragValue::new(&mut self.config.usd_per_bond).speed(0.05).range(0.0..=100.0).prefix("$"))
+                                    .changed()
+                                {
+                                    self.config.save();
+                                }
+                            });
+                        }
+                        if self.config.currency != Currency::Gp {
+                            let text = match self.bond_price {
+                                Some(p) => format!("Bond: {}", format_gp(p)),
+                                None => "No Bond price yet - showing gp".to_string(),
+                            };
+                            ui.label(RichText::new(text).small().color(Color32::from_rgb(180, 160, 120)));
+                        }
+
+                        ui.add_space(10.0);
+                        ui.separator();
+                        self.schedule_settings(ui);
                     });
                 });
-                return;
-            }
+            self.config.window.side_panel_width = Some(side_panel.response.rect.width());
+        }
 
-            ui.style_mut().visuals.extreme_bg_color = Color32::from_rgb(45, 38, 28);
-
-            use std::cell::RefCell;
-            use std::rc::Rc;
-            let favorite_toggles = Rc::new(RefCell::new(Vec::new()));
-            let toggles_clone = favorite_toggles.clone();
-
-            TableBuilder::new(ui)
-                .striped(true)
-                .vscroll(true)
-                .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
-                .column(Column::exact(40.0))   // Favorite
-                .column(Column::remainder().at_least(180.0).clip(true))  // Item - takes remaining space
-                .column(Column::exact(80.0))   // Score
-                .column(Column::exact(120.0))  // Tier + Trend
-                .column(Column::exact(130.0))  // Risk Warning
-                .column(Column::exact(110.0))  // Buy
-                .column(Column::exact(110.0))  // Sell
-                .column(Column::exact(70.0))   // Qty                
-                .column(Column::exact(120.0))  // Avg Volume                
-                .column(Column::exact(120.0))  // Total Cost
-                .column(Column::exact(120.0))  // Profit
-                .column(Column::exact(90.0))   // ROI
-                .column(Column::exact(50.0))   // Copy button
-                .header(32.0, |mut header| {
-                    header.col(|ui| { 
-                        ui.heading(RichText::new("★").color(Color32::from_rgb(255, 200, 50)).size(20.0)); 
-                    });
-                    header.col(|ui| { 
-                        ui.heading(RichText::new("Item Name")
-                            .color(Color32::from_rgb(200, 180, 140))); 
-                    });
-                    header.col(|ui| { 
-                        ui.heading(RichText::new("Score")
-                            .color(Color32::from_rgb(200, 180, 140))); 
-                    });
-                    header.col(|ui| { 
-                        ui.heading(RichText::new("Tier/Trend")
-                            .color(Color32::from_rgb(200, 180, 140))); 
-                    });
-                    header.col(|ui| { 
-                        ui.heading(RichText::new("⚠ Risk")
-                            .color(Color32::from_rgb(255, 180, 100))); 
-                    });
-                    header.col(|ui| { 
-                        ui.heading(RichText::new("Buy Price")
-                            .color(Color32::from_rgb(200, 180, 140))); 
-                    });
-                    header.col(|ui| { 
-                        ui.heading(RichText::new("Sell Price")
-                            .color(Color32::from_rgb(200, 180, 140))); 
-                    });
-                    header.col(|ui| { 
-                        ui.heading(RichText::new("Qty")
-                            .color(Color32::from_rgb(200, 180, 140))); 
-                    });
-                    header.col(|ui| { 
-                        ui.heading(RichText::new("Avg Vol/Day")
-                            .color(Color32::from_rgb(200, 180, 140))); 
-                    });
-                    header.col(|ui| { 
-                        ui.heading(RichText::new("Total Cost")
-                            .color(Color32::from_rgb(200, 180, 140))); 
-                    });
-                    header.col(|ui| { 
-                        ui.heading(RichText::new("Profit")
-                            .color(Color32::from_rgb(200, 180, 140))); 
-                    });
-                    header.col(|ui| { 
-                        ui.heading(RichText::new("ROI %")
-                            .color(Color32::from_rgb(200, 180, 140))); 
+        if self.tab == Tab::Flips {
+            egui::CentralPanel::default().show(ctx, |ui| {
+
+                if !self.loaded {
+                    ui.centered_and_justified(|ui| {
+                        ui.vertical_centered(|ui| {
+                            ui.add_space(100.0);
+                            ui.label(RichText::new("⚔")
+                                .size(80.0)
+                                .color(Color32::from_rgb(255, 210, 100)));
+                            ui.add_space(20.0);
+                            ui.label(RichText::new("Welcome to the Grand Exchange Analyzer")
+                                .size(24.0)
+                                .color(Color32::from_rgb(200, 180, 140)));
+                            ui.add_space(10.0);
+                            ui.label(RichText::new("Click 'Scan Market' to begin analyzing flips")
+                                .color(Color32::from_rgb(180, 160, 120)));
+                            if let Some(err) = &self.scan_error {
+                                ui.add_space(10.0);
+                                ui.label(RichText::new(err).color(Color32::from_rgb(255, 120, 100)));
+                                if ui.button("Open setup").clicked() {
+                                    self.wizard_step = 0;
+                                    self.show_wizard = true;
+                                }
+                            }
+                        });
                     });
-                    header.col(|ui| { 
-                        ui.heading(RichText::new("📋").color(Color32::from_rgb(200, 180, 140)).size(18.0)); 
+                    return;
+                }
+
+                if self.filtered_items.is_empty() {
+                    ui.centered_and_justified(|ui| {
+                        ui.vertical_centered(|ui| {
+                            ui.label(RichText::new("🔍")
+                                .size(60.0)
+                                .color(Color32::from_rgb(150, 130, 100)));
+                            ui.add_space(10.0);
+                            ui.label(RichText::new("No items match your filters")
+                                .size(20.0)
+                                .color(Color32::from_rgb(180, 160, 120)));
+                            ui.add_space(5.0);
+                            ui.label(RichText::new("Try adjusting your search or filter settings")
+                                .color(Color32::from_rgb(150, 130, 100)));
+                        });
                     });
-                })
-                .body(|body| {
-                    body.rows(36.0, self.filtered_items.len(), |mut row| {
-                        let i = row.index();
-                        let r = &self.filtered_items[i];
-                        
-                        let is_selected = self.selected_row == Some(i);
-                        let is_favorite = self.favorites.get(&r.name).copied().unwrap_or(false);
-                        let item_name = r.name.clone();
-
-                        // Favorite button
-                        row.col(|ui| {
-                            let toggles = toggles_clone.clone();
-                            if ui.button(RichText::new(if is_favorite { "★" } else { "☆" })
-                                .color(if is_favorite { 
-                                    Color32::from_rgb(255, 200, 50) 
-                                } else { 
-                                    Color32::GRAY 
-                                }))
-                                .clicked() 
-                            {
-                                toggles.borrow_mut().push(item_name);
+                    return;
+                }
+
+                if self.config.beginner_mode {
+                    beginner_help(ui);
+                }
+
+                ui.style_mut().visuals.extreme_bg_color = Color32::from_rgb(45, 38, 28);
+
+                use std::cell::RefCell;
+                use std::rc::Rc;
+                let favorite_toggles = Rc::new(RefCell::new(Vec::new()));
+                let toggles_clone = favorite_toggles.clone();
+                let pin_toggles = Rc::new(RefCell::new(Vec::new()));
+                let select_toggles = Rc::new(RefCell::new(Vec::new()));
+                let mut row_action = None;
+
+                // Compact mode trades the secondary columns for more rows on screen
+                let compact = self.config.row_density == RowDensity::Compact;
+                let row_height = if compact { 22.0 } else { 36.0 };
+                let money = self.money();
+                let palette = self.config.palette;
+                let realistic = self.config.realistic_profit;
+                let flash = self.changes_at
+                    .map(|t| 1.0 - t.elapsed().as_secs_f32() / FLASH_SECS)
+                    .filter(|strength| *strength > 0.0);
+                // Beginner mode hides the columns that need market experience to read
+                let advanced = !self.config.beginner_mode;
+                let timeframe_columns = if advanced { self.config.timeframe_columns } else { [false; 3] };
+
+                egui::TopBottomPanel::bottom("table_footer")
+                    .frame(egui::Frame::NONE.inner_margin(egui::Margin::symmetric(0, 4)))
+                    .show_inside(ui, |ui| self.table_footer(ui));
+                if !self.bulk_selection.is_empty() {
+                    egui::TopBottomPanel::top("bulk_bar")
+                        .frame(egui::Frame::NONE.inner_margin(egui::Margin::symmetric(0, 4)))
+                        .show_inside(ui, |ui| self.bulk_bar(ui));
+                }
+
+                if compact {
+                    let style = ui.style_mut();
+                    style.text_styles.insert(egui::TextStyle::Body, FontId::new(12.0, FontFamily::Proportional));
+                    style.text_styles.insert(egui::TextStyle::Button, FontId::new(12.0, FontFamily::Proportional));
+                    style.text_styles.insert(egui::TextStyle::Heading, FontId::new(14.0, FontFamily::Proportional));
+                    style.spacing.button_padding = Vec2::new(4.0, 0.0);
+                }
+
+                let mut table = TableBuilder::new(ui)
+                    .striped(true)
+                    .vscroll(true)
+                    .sense(egui::Sense::click())  // For the right-click menu
+                    .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                    .column(Column::exact(88.0))   // Bulk select + favorite + pin
+                    .column(Column::remainder().at_least(180.0).clip(true));  // Item - takes remaining space
+                if advanced {
+                    table = table.column(Column::exact(80.0));  // Score
+                }
+                table = table
+                    .column(Column::exact(170.0))  // Tier + Trend + tier change
+                    .column(Column::exact(90.0));  // Difficulty
+                if !compact && advanced {
+                    table = table.column(Column::exact(130.0));  // Risk Warning
+                }
+                table = table
+                    .column(Column::exact(110.0))  // Buy
+                    .column(Column::exact(110.0)); // Sell
+                if advanced {
+                    table = table
+                        .column(Column::exact(110.0))  // Break-even
+                        .column(Column::exact(100.0)); // Floor margin
+                }
+                if !compact {
+                    table = table
+                        .column(Column::exact(70.0))   // Qty
+                        .column(Column::exact(120.0))  // Avg Volume
+                        .column(Column::exact(120.0))  // Total Cost
+                        .column(Column::exact(80.0));  // Data coverage
+                }
+                table = table
+                    .column(Column::exact(120.0))  // Profit
+                    .column(Column::exact(90.0));  // ROI
+                if advanced {
+                    table = table.column(Column::exact(110.0));  // Typical cycle + GP/hr
+                }
+                for _ in timeframe_columns.iter().filter(|shown| **shown) {
+                    table = table.columns(Column::exact(75.0), 3);  // ROI, change, volatility
+                }
+                if let Some(i) = self.scroll_to_row.take() {
+                    table = table.scroll_to_row(i, Some(egui::Align::Center));
+                }
+                let search = self.search.to_lowercase();
+                table
+                    .column(Column::exact(70.0))   // Copy offer buttons
+                    .header(if compact { 24.0 } else { 32.0 }, |mut header| {
+                        header.col(|ui| { 
+                            ui.heading(RichText::new("★").color(Color32::from_rgb(255, 200, 50)).size(20.0)); 
+                        });
+                        header.col(|ui| { 
+                            ui.heading(RichText::new("Item Name")
+                                .color(Color32::from_rgb(200, 180, 140))); 
+                        });
+                        if advanced {
+                            header.col(|ui| {
+                                ui.heading(RichText::new("Score")
+                                    .color(Color32::from_rgb(200, 180, 140)))
+                                    .on_hover_text(glossary::score_breakdown());
+                            });
+                        }
+                        header.col(|ui| { 
+                            ui.heading(RichText::new("Tier/Trend")
+                                .color(Color32::from_rgb(200, 180, 140)))
+                                .on_hover_text(format!("{}\n\n{}", glossary::explain("tier"), glossary::explain("trend"))); 
+                        });
+                        header.col(|ui| {
+                            ui.heading(RichText::new("Difficulty")
+                                .color(Color32::from_rgb(200, 180, 140)))
+                                .on_hover_text(glossary::explain("difficulty"));
+                        });
+                        if !compact && advanced {
+                            header.col(|ui| {
+                                ui.heading(RichText::new("⚠ Risk")
+                                    .color(Color32::from_rgb(255, 180, 100)))
+                                    .on_hover_text(glossary::explain("risk"));
+                            });
+                        }
+                        header.col(|ui| { 
+                            ui.heading(RichText::new("Buy Price")
+                                .color(Color32::from_rgb(200, 180, 140)))
+                                .on_hover_text(glossary::explain("buy")); 
+                        });
+                        header.col(|ui| { 
+                            ui.heading(RichText::new("Sell Price")
+                                .color(Color32::from_rgb(200, 180, 140)))
+                                .on_hover_text(glossary::explain("sell")); 
+                        });
+                        if advanced {
+                            header.col(|ui| {
+                                ui.heading(RichText::new("Break-even")
+                                    .color(Color32::from_rgb(200, 180, 140)))
+                                    .on_hover_text(glossary::explain("break_even"));
+                            });
+                            header.col(|ui| {
+                                ui.heading(RichText::new("Floor")
+                                    .color(Color32::from_rgb(200, 180, 140)))
+                                    .on_hover_text(glossary::explain("floor"));
+                            });
+                        }
+                        if !compact {
+                            header.col(|ui| {
+                                ui.heading(RichText::new("Qty")
+                                    .color(Color32::from_rgb(200, 180, 140)))
+                                    .on_hover_text(glossary::explain("qty"));
+                            });
+                            header.col(|ui| {
+                                ui.heading(RichText::new("Avg Vol/Day")
+                                    .color(Color32::from_rgb(200, 180, 140)))
+                                    .on_hover_text(glossary::explain("volume"));
+                            });
+                            header.col(|ui| {
+                                ui.heading(RichText::new("Total Cost")
+                                    .color(Color32::from_rgb(200, 180, 140)))
+                                    .on_hover_text(glossary::explain("total_cost"));
+                            });
+                            header.col(|ui| {
+                                ui.heading(RichText::new("Data")
+                                    .color(Color32::from_rgb(200, 180, 140)))
+                                    .on_hover_text(glossary::explain("coverage"));
+                            });
+                        }
+                        header.col(|ui| { 
+                            ui.heading(RichText::new("Profit")
+                                .color(Color32::from_rgb(200, 180, 140)))
+                                .on_hover_text(glossary::explain("profit")); 
+                        });
+                        header.col(|ui| { 
+                            ui.heading(RichText::new("ROI %")
+                                .color(Color32::from_rgb(200, 180, 140)))
+                                .on_hover_text(glossary::explain("roi")); 
+                        });
+                        if advanced {
+                            header.col(|ui| {
+                                ui.heading(RichText::new("Cycle")
+                                    .color(Color32::from_rgb(200, 180, 140)))
+                                    .on_hover_text(glossary::explain("cycle"));
+                            });
+                        }
+                        for (shown, days) in timeframe_columns.into_iter().zip(stats::TIMEFRAMES) {
+                            if !shown {
+                                continue;
+                            }
+                            for title in ["ROI", "Chg", "Vol"] {
+                                header.col(|ui| {
+                                    ui.heading(RichText::new(format!("{} {}d", title, days))
+                                        .color(Color32::from_rgb(200, 180, 140)))
+                                        .on_hover_text(glossary::explain("timeframes"));
+                                });
                             }
+                        }
+                        header.col(|ui| { 
+                            ui.heading(RichText::new("📋").color(Color32::from_rgb(200, 180, 140)).size(18.0)); 
                         });
+                    })
+                    .body(|body| {
+                        body.rows(row_height, self.filtered_items.len(), |mut row| {
+                            let i = row.index();
+                            let r = &self.filtered_items[i];
+                        
+                            let is_selected = self.selected_row == Some(i);
+                            let is_favorite = self.favorites.contains(&r.item_id);
+                            let is_pinned = self.pinned.contains(&r.item_id);
+                            let item_id = r.item_id;
+                            let mut name_response = None;  // Right-clicks on the name open the row's menu too
 
-                        row.col(|ui| {
-                            let mut text = RichText::new(&r.name);
-                            if is_selected {
-                                text = text.color(Color32::from_rgb(255, 220, 100)).strong();
-                            } else if is_favorite {
-                                text = text.color(Color32::from_rgb(255, 200, 100));
-                            }
-                            if ui.selectable_label(is_selected, text).clicked() {
+                            // Bulk select, favorite and pin
+                            row.col(|ui| {
+                                let mut ticked = self.bulk_selection.contains(&item_id);
+                                if accessible(ui.checkbox(&mut ticked, ""), egui::WidgetType::Checkbox, "Select for bulk actions")
+                                    .on_hover_text("Select for bulk actions (or Ctrl+click the name)")
+                                    .changed()
+                                {
+                                    select_toggles.borrow_mut().push(item_id);
+                                }
+                                let toggles = toggles_clone.clone();
+                                let fav_btn = ui.button(RichText::new(if is_favorite { "★" } else { "☆" })
+                                    .color(if is_favorite { 
+                                        Color32::from_rgb(255, 200, 50) 
+                                    } else { 
+                                        Color32::GRAY 
+                                    }));
+                                let fav_btn = accessible(fav_btn, egui::WidgetType::Button, if is_favorite { "Remove from favorites" } else { "Add to favorites" });
+                                if fav_btn.clicked() 
+                                {
+                                    toggles.borrow_mut().push(item_id);
+                                }
+                                let pin_btn = ui.button(RichText::new("📌")
+                                    .color(if is_pinned { Color32::from_rgb(255, 120, 100) } else { Color32::from_gray(90) }))
+                                    .on_hover_text(if is_pinned { "Unpin" } else { "Pin to top" });
+                                if accessible(pin_btn, egui::WidgetType::Button, if is_pinned { "Unpin" } else { "Pin to top" })
+                                    .clicked()
+                                {
+                                    pin_toggles.borrow_mut().push(r.item_id);
+                                }
+                            });
+
+                            row.col(|ui| {
+                                let mut text = RichText::new(&r.name);
                                 if is_selected {
-                                    self.selected_row = None;
-                                    self.target_graph_height = 0.0;
+                                    text = text.color(Color32::from_rgb(255, 220, 100)).strong();
+                                } else if is_favorite {
+                                    text = text.color(Color32::from_rgb(255, 200, 100));
+                                }
+                                let color = if is_selected {
+                                    Color32::from_rgb(255, 220, 100)
+                                } else if is_favorite {
+                                    Color32::from_rgb(255, 200, 100)
                                 } else {
-                                    self.selected_row = Some(i);
-                                    self.target_graph_height = 300.0;
-                                    // Always reload history when selecting an item
-                                    self.selected_item_history.clear();
-                                    if let Ok(history) = load_item_history("rs3_market.db", &r.name) {
-                                        self.selected_item_history = history;
+                                    ui.visuals().text_color()
+                                };
+                                let text = match highlight_match(ui, &r.name, &search, color) {
+                                    Some(job) => egui::WidgetText::from(job),
+                                    None => egui::WidgetText::from(text),
+                                };
+                                let mut name_label = ui.selectable_label(is_selected, text);
+                                name_response = Some(name_label.clone());
+                                if compact {
+                                    name_label = name_label.on_hover_text(format!(
+                                        "Qty: {}\nAvg Vol/Day: {}\nTotal Cost: {}\nData: {} of the last {} days\n{}",
+                                        r.qty, format_gp(r.avg_volume), format_gp(r.total_cost.as_f64()), r.coverage_days, stats::COVERAGE_DAYS, r.notes
+                                    ));
+                                }
+                                if let Some(dump) = &r.dump {
+                                    accessible(ui.label(RichText::new("🎁").color(Color32::from_rgb(255, 150, 220))), egui::WidgetType::Label, "Post-promo dump")
+                                        .on_hover_text(format!(
+                                            "Post-promo dump ({}): down {:.1}% this week on {:.1}× normal volume. Dumps usually recover - a buy-the-dump candidate.",
+                                            dump.tag, dump.drop_pct, dump.volume_surge
+                                        ));
+                                }
+                                if let Some(label) = &r.variant {
+                                    ui.label(RichText::new(label).small().color(Color32::from_rgb(160, 150, 130)))
+                                        .on_hover_text(format!("Variant of {} - priced and scored on its own listing", variants::family(&r.name)));
+                                }
+                                if !r.merged_variants.is_empty() {
+                                    ui.label(RichText::new(format!("+{}", r.merged_variants.len())).small().color(Color32::from_rgb(200, 180, 140)))
+                                        .on_hover_text(format!("Also listed as:\n{}", r.merged_variants.join("\n")));
+                                }
+                                if let Some(conflict) = r.timeframe_conflict {
+                                    accessible(ui.label(RichText::new("⇅").color(Color32::from_rgb(230, 190, 120))), egui::WidgetType::Label, conflict)
+                                        .on_hover_text(conflict);
+                                }
+                                if let Some(regime) = r.regime {
+                                    accessible(ui.label(RichText::new(regime.icon()).color(Color32::from_rgb(170, 200, 230))), egui::WidgetType::Label, regime.label())
+                                        .on_hover_text(format!("{}: {}", regime.label(), regime.describe()));
+                                }
+                                if r.reach.is_slow() {
+                                    accessible(ui.label(RichText::new("⏳").color(Color32::from_rgb(255, 200, 100))), egui::WidgetType::Label, "Targets more than a day away")
+                                        .on_hover_text(format!(
+                                            "Targets are out of reach today: the guide price moves about {:.1}% per update, so the buy is {} update(s) away and the sell {} more after that",
+                                            r.reach.daily_cap * 100.0, r.reach.buy_days, r.reach.sell_days
+                                        ));
+                                }
+                                if r.floor.is_some_and(|v| v >= r.buy.as_f64()) {
+                                    accessible(ui.label(RichText::new("🛡").color(Color32::from_rgb(100, 220, 255))), egui::WidgetType::Label, "Under value floor")
+                                        .on_hover_text("Buy is under the alch/component value floor - near-riskless");
+                                }
+                                if name_label.clicked() && ui.input(|i| i.modifiers.command) {
+                                    select_toggles.borrow_mut().push(item_id);
+                                } else if name_label.clicked() {
+                                    if is_selected {
+                                        self.selected_row = None;
+                                        self.target_graph_height = 0.0;
+                                    } else {
+                                        self.selected_row = Some(i);
+                                        self.target_graph_height = 340.0;
+                                        // Always reload history when selecting an item
+                                        self.selected_item_history.clear();
+                                        if let Ok(history) = load_item_history(self.config.game.db_path(), r.item_id) {
+                                            self.selected_item_history = history;
+                                        }
+                                        self.selected_annotations = loader::load_annotations(self.config.game.db_path(), r.item_id).unwrap_or_default();
+                                        self.detail_history_page = 0;
                                     }
                                 }
+                            });
+
+                            if advanced {
+                                // Score with color coding
+                                row.col(|ui| {
+                                    // Coloured by rank in the scan; a score at or under zero means avoid whatever its rank
+                                    let score_color = if r.score <= 0 {
+                                        palette.loss(true)
+                                    } else if r.score_pct >= 90.0 {
+                                        palette.gain(true)
+                                    } else if r.score_pct >= 50.0 {
+                                        Color32::from_rgb(200, 200, 100)
+                                    } else {
+                                        Color32::from_rgb(200, 150, 100)
+                                    };
+                                    let label = ui.horizontal(|ui| {
+                                        ui.label(RichText::new(format!("{:.0}", r.score_pct))
+                                            .color(score_color)
+                                            .strong());
+                                        ui.label(RichText::new(format!("({})", r.score)).small().weak());
+                                    }).response;
+                                    let mut hover = format!("Beats {:.0}% of this scan - raw score {}", r.score_pct, r.score);
+                                    if let Some(reason) = &r.low_confidence {
+                                        hover.push_str(&format!("\n\n{}\n{}", reason, glossary::explain("confidence")));
+                                    }
+                                    label.on_hover_text(hover);
+                                });
+                            }
+
+                            let change = flash.and_then(|strength| Some((strength, *self.cell_changes.get(&r.item_id)?)));
+
+                            // Tier badge with trend indicator
+                            row.col(|ui| {
+                                if let Some((strength, CellChange { tier: Some(up), .. })) = change {
+                                    flash_cell(ui, palette, up, strength);
+                                }
+                                ui.centered_and_justified(|ui| {
+                                    ui.horizontal(|ui| {
+                                        // Tier icon with matching color from tier_color
+                                        let (icon, tier_name) = match r.tier.as_str() {
+                                            "DIAMOND" => ("💎", "Diamond"),
+                                            "GOLD" => ("⭐", "Gold"),
+                                            "GREEN" => ("✅", "Good"),
+                                            "CRASH" => ("📉", "Crash"),
+                                            _ => ("⚪", "Normal"),
+                                        };
+                                        let tier_label = ui.label(RichText::new(icon).size(16.0).color(palette.tier(&r.tier)));
+                                        accessible(tier_label, egui::WidgetType::Label, &format!("{} tier", tier_name))
+                                            .on_hover_text(tier_name);
+                                        if let Some(reason) = &r.low_confidence {
+                                            let label = ui.label(RichText::new("(low confidence)").small().color(Color32::from_rgb(180, 160, 120)));
+                                            accessible(label, egui::WidgetType::Label, "Low confidence")
+                                                .on_hover_text(reason);
+                                        }
+                                    
+                                        // Trend indicator with clear text label
+                                        let (trend_text, trend_name, trend_color) = if r.trend > 5.0 {
+                                            ("↑↑", "Rising fast", palette.gain(true))
+                                        } else if r.trend > 1.0 {
+                                            ("↑", "Rising", palette.gain(false))
+                                        } else if r.trend < -5.0 {
+                                            ("↓↓", "Falling fast", palette.loss(true))
+                                        } else if r.trend < -1.0 {
+                                            ("↓", "Falling", palette.loss(false))
+                                        } else {
+                                            ("→", "Flat", palette.neutral())
+                                        };
+                                        let trend_label = ui.label(RichText::new(trend_text).color(trend_color).strong());
+                                        accessible(trend_label, egui::WidgetType::Label, &format!("Trend: {}", trend_name));
+
+                                        // Tier movement since the previous scan
+                                        if let Some(prev) = r.prev_tier.as_deref() {
+                                            let delta = tier_rank(&r.tier) - tier_rank(prev);
+                                            if delta != 0 {
+                                                let (arrow, color) = if delta > 0 {
+                                                    ("↑", palette.gain(true))
+                                                } else {
+                                                    ("↓", palette.loss(true))
+                                                };
+                                                ui.label(RichText::new(format!("{} from {}", arrow, prev)).small().color(color))
+                                                    .on_hover_text("Tier in the previous scan");
+                                            }
+                                        }
+                                    });
+                                });
+                            });
+
+                            row.col(|ui| {
+                                ui.label(RichText::new(r.difficulty.label())
+                                    .color(difficulty_color(r.difficulty))
+                                    .strong())
+                                    .on_hover_text(r.difficulty_reasons.join("\n"));
+                            });
+
+                            // Risk Warning
+                            if !compact && advanced {
+                                row.col(|ui| {
+                                    // Parse notes to extract risk warning
+                                    let (risk_text, risk_color) = if r.notes.contains("🚨VOLATILE-CRASHING") {
+                                        ("🚨 Crashing", Color32::from_rgb(255, 100, 100))
+                                    } else if r.notes.contains("📉Crashed") {
+                                        ("📉 Crashed", Color32::from_rgb(255, 150, 100))
+                                    } else if r.notes.contains("📈Spiked") {
+                                        ("📈 Spiked", Color32::from_rgb(255, 200, 100))
+                                    } else if r.notes.contains("⚠outliers") {
+                                        ("⚠ Outliers", Color32::from_rgb(200, 180, 100))
+                                    } else {
+                                        ("● Stable", Color32::from_rgb(100, 255, 150))
+                                    };
+                                
+                                    ui.label(RichText::new(risk_text)
+                                        .color(risk_color)
+                                        .strong())
+                                        .on_hover_text(format!(
+                                            "{}\n\nVol: {}\nQ5-Q95: {}",
+                                            r.notes, glossary::explain("volatility"), glossary::explain("q05")
+                                        ));
+                                });
+                            }
+
+                            // Buy price
+                            row.col(|ui| {
+                                if let Some((strength, CellChange { buy: Some(up), .. })) = change {
+                                    flash_cell(ui, palette, up, strength);
+                                }
+                                ui.label(RichText::new(format_gp(r.buy.as_f64()))
+                                    .color(Color32::from_rgb(255, 150, 150)));
+                            });
+
+                            // Sell price
+                            row.col(|ui| {
+                                if let Some((strength, CellChange { sell: Some(up), .. })) = change {
+                                    flash_cell(ui, palette, up, strength);
+                                }
+                                ui.label(RichText::new(format!("{:>10}", format_gp(r.sell.as_f64())))
+                                    .color(Color32::from_rgb(150, 255, 150)));
+                            });
+
+                            if advanced {
+                                // Break-even sell price, with the headroom down to it
+                                row.col(|ui| {
+                                    let headroom = r.sell.as_f64() - r.break_even;
+                                    let pct = if r.sell > Gp::ZERO { headroom / r.sell.as_f64() * 100.0 } else { 0.0 };
+                                    ui.label(RichText::new(format_gp(r.break_even))
+                                        .color(Color32::from_rgb(220, 200, 160)))
+                                        .on_hover_text(format!(
+                                            "{} gp ({:.1}%) of headroom before the flip loses money",
+                                            offer_price(headroom), pct
+                                        ));
+                                });
+
+                                // Floor margin: how far the alch/component floor sits above the buy
+                                row.col(|ui| match r.floor {
+                                    Some(value) => {
+                                        let margin = floor::floor_margin_pct(value, r.buy.as_f64());
+                                        let text = if margin >= 0.0 {
+                                            RichText::new(format!("🛡 +{:.1}%", margin)).color(Color32::from_rgb(100, 220, 255)).strong()
+                                        } else {
+                                            RichText::new(format!("{:.1}%", margin)).color(Color32::from_rgb(160, 160, 160))
+                                        };
+                                        ui.label(text).on_hover_text(format!(
+                                            "Floor {} gp vs buy {} gp{}",
+                                            offer_price(value),
+                                            offer_price(r.buy.as_f64()),
+                                            if margin >= 0.0 { "\nBuying under the floor - near-riskless" } else { "" }
+                                        ));
+                                    }
+                                    None => {
+                                        ui.label(RichText::new("-").color(Color32::GRAY))
+                                            .on_hover_text("No alch value or component value known");
+                                    }
+                                });
                             }
-                        });
 
-                        // Score with color coding
-                        row.col(|ui| {
-                            let score_color = if r.score > 100 {
-                                Color32::from_rgb(100, 255, 100)
-                            } else if r.score > 50 {
-                                Color32::from_rgb(200, 200, 100)
-                            } else if r.score > 0 {
-                                Color32::from_rgb(200, 150, 100)
-                            } else {
-                                Color32::from_rgb(255, 100, 100)
-                            };
-                            ui.label(RichText::new(r.score.to_string())
-                                .color(score_color)
-                                .strong());
-                        });
-
-                        // Tier badge with trend indicator
-                        row.col(|ui| {
-                            ui.centered_and_justified(|ui| {
-                                ui.horizontal(|ui| {
-                                    // Tier icon with matching color from tier_color
-                                    let (icon, tier_name, tier_color) = match r.tier.as_str() {
-                                        "DIAMOND" => ("💎", "Diamond", Color32::from_rgb(0, 255, 255)),
-                                        "GOLD" => ("⭐", "Gold", Color32::from_rgb(255, 200, 50)),
-                                        "GREEN" => ("✅", "Good", Color32::from_rgb(50, 255, 50)),
-                                        "CRASH" => ("📉", "Crash", Color32::RED),
-                                        _ => ("⚪", "Normal", Color32::LIGHT_GRAY),
-                                    };
-                                    ui.label(RichText::new(icon).size(16.0).color(tier_color))
-                                        .on_hover_text(tier_name);
-                                    
-                                    // Trend indicator with clear text label
-                                    let (trend_text, trend_color) = if r.trend > 5.0 {
-                                        ("↑↑", Color32::from_rgb(100, 255, 100))
-                                    } else if r.trend > 1.0 {
-                                        ("↑", Color32::from_rgb(150, 255, 150))
-                                    } else if r.trend < -5.0 {
-                                        ("↓↓", Color32::from_rgb(255, 100, 100))
-                                    } else if r.trend < -1.0 {
-                                        ("↓", Color32::from_rgb(255, 150, 150))
+                            if !compact {
+                                // Quantity
+                                row.col(|ui| {
+                                    ui.label(RichText::new(r.qty.to_string())
+                                        .color(Color32::from_rgb(200, 200, 200)));
+                                });
+
+                                // Avg Volume/Day
+                                row.col(|ui| {
+                                    let vol_text = if r.avg_volume >= 1_000_000.0 {
+                                        format!("{:.1}M", r.avg_volume / 1_000_000.0)
+                                    } else if r.avg_volume >= 1_000.0 {
+                                        format!("{:.1}K", r.avg_volume / 1_000.0)
                                     } else {
-                                        ("→", Color32::from_rgb(200, 200, 200))
+                                        format!("{:.0}", r.avg_volume)
                                     };
-                                    ui.label(RichText::new(trend_text).color(trend_color).strong());
+                                    ui.label(RichText::new(vol_text)
+                                        .color(Color32::from_rgb(180, 200, 255)));
                                 });
-                            });
-                        });
 
-                        // Risk Warning
-                        row.col(|ui| {
-                            // Parse notes to extract risk warning
-                            let (risk_text, risk_color) = if r.notes.contains("🚨VOLATILE-CRASHING") {
-                                ("🚨 Crashing", Color32::from_rgb(255, 100, 100))
-                            } else if r.notes.contains("📉Crashed") {
-                                ("📉 Crashed", Color32::from_rgb(255, 150, 100))
-                            } else if r.notes.contains("📈Spiked") {
-                                ("📈 Spiked", Color32::from_rgb(255, 200, 100))
-                            } else if r.notes.contains("⚠outliers") {
-                                ("⚠ Outliers", Color32::from_rgb(200, 180, 100))
-                            } else {
-                                ("● Stable", Color32::from_rgb(100, 255, 150))
-                            };
-                            
-                            ui.label(RichText::new(risk_text)
-                                .color(risk_color)
-                                .strong())
-                                .on_hover_text(&r.notes);
-                        });
+                                // Total Cost
+                                row.col(|ui| {
+                                    ui.label(RichText::new(format_gp(r.total_cost.as_f64()))
+                                        .color(Color32::from_rgb(200, 180, 255)));
+                                });
 
-                        // Buy price
-                        row.col(|ui| {
-                            ui.label(RichText::new(format_gp(r.buy))
-                                .color(Color32::from_rgb(255, 150, 150)));
-                        });
+                                // Data coverage
+                                row.col(|ui| {
+                                    let pct = r.coverage_pct();
+                                    let fill = if pct >= 75.0 {
+                                        Color32::from_rgb(90, 140, 90)
+                                    } else if pct >= 40.0 {
+                                        Color32::from_rgb(150, 130, 60)
+                                    } else {
+                                        Color32::from_rgb(150, 70, 60)
+                                    };
+                                    ui.add(egui::ProgressBar::new((pct / 100.0) as f32)
+                                        .desired_width(70.0)
+                                        .desired_height(10.0)
+                                        .fill(fill))
+                                        .on_hover_text(format!("Snapshots on {} of the last {} days", r.coverage_days, stats::COVERAGE_DAYS));
+                                });
+                            }
 
-                        // Sell price
-                        row.col(|ui| {
-                            ui.label(RichText::new(format!("{:>10}", format_gp(r.sell as f64)))
-                                .color(Color32::from_rgb(150, 255, 150)));
-                        });
+                            // Profit with highlighting
+                            row.col(|ui| {
+                                let profit = r.profit.as_f64();
+                                let profit_color = if profit > 100_000.0 {
+                                    palette.gain(profit > 1_000_000.0)
+                                } else if profit > 0.0 {
+                                    palette.gain_faint()
+                                } else {
+                                    palette.loss(true)
+                                };
+                                let sign = if profit > 0.0 { palette.plus() } else { "" };
+                                let mut label = ui.label(RichText::new(format!("{}{}", sign, money.format(profit)))
+                                    .color(profit_color)
+                                    .strong());
+                                if money.currency != Currency::Gp {
+                                    label = label.on_hover_text(format!("{} gp", format_gp(profit)));
+                                }
+                                if r.slippage > 0.0 {
+                                    label = label.on_hover_text(format!(
+                                        "{} liquidity: assumes buying {:.1}% above and selling {:.1}% below the targets",
+                                        Liquidity::of(r.avg_volume).label(),
+                                        r.slippage * 100.0,
+                                        r.slippage * 100.0
+                                    ));
+                                }
+                                if let Some(rate) = r.capture {
+                                    let scaled = if realistic && r.unit_profit > Gp::ZERO { " - profit is scaled by it" } else { "" };
+                                    label.on_hover_text(format!("Capture rate {:.0}%{}", rate * 100.0, scaled));
+                                }
+                            });
 
-                        // Quantity
-                        row.col(|ui| {
-                            ui.label(RichText::new(r.qty.to_string())
-                                .color(Color32::from_rgb(200, 200, 200)));
-                        });
+                            // ROI with color coding
+                            row.col(|ui| {
+                                let roi_color = if r.roi > 20.0 {
+                                    palette.gain(true)
+                                } else if r.roi > 10.0 {
+                                    palette.gain(false)
+                                } else if r.roi > 5.0 {
+                                    palette.gain_faint()
+                                } else if r.roi > 0.0 {
+                                    Color32::from_rgb(255, 255, 150)
+                                } else {
+                                    palette.loss(true)
+                                };
+                                let sign = if r.roi > 0.0 { palette.plus() } else { "" };
+                                ui.label(RichText::new(format!("{}{:.1}%", sign, r.roi))
+                                    .color(roi_color));
+                            });
 
-                        // Avg Volume/Day
-                        row.col(|ui| {
-                            let vol_text = if r.avg_volume >= 1_000_000.0 {
-                                format!("{:.1}M", r.avg_volume / 1_000_000.0)
-                            } else if r.avg_volume >= 1_000.0 {
-                                format!("{:.1}K", r.avg_volume / 1_000.0)
-                            } else {
-                                format!("{:.0}", r.avg_volume)
-                            };
-                            ui.label(RichText::new(vol_text)
-                                .color(Color32::from_rgb(180, 200, 255)));
-                        });
+                            if advanced {
+                                // Typical cycle length and the GP/hr estimate it gives
+                                row.col(|ui| {
+                                    match (r.cycle_days, r.gp_per_hour) {
+                                        (Some(days), Some(gph)) => {
+                                            ui.label(RichText::new(format!("{:.1}d", days))
+                                                .color(Color32::from_rgb(200, 200, 200)));
+                                            ui.label(RichText::new(format!("{}/h", format_gp(gph)))
+                                                .small()
+                                                .color(Color32::from_rgb(180, 200, 255)));
+                                        }
+                                        _ => {
+                                            ui.label(RichText::new("-").color(Color32::GRAY))
+                                                .on_hover_text("No complete buy-to-sell cycle in the history");
+                                        }
+                                    }
+                                });
+                            }
 
-                        // Total Cost
-                        row.col(|ui| {
-                            ui.label(RichText::new(format_gp(r.total_cost))
-                                .color(Color32::from_rgb(200, 180, 255)));
-                        });
+                            for (shown, frame) in timeframe_columns.into_iter().zip(&r.timeframes) {
+                                if !shown {
+                                    continue;
+                                }
+                                let Some((t, roi)) = frame else {
+                                    for _ in 0..3 {
+                                        row.col(|ui| {
+                                            ui.label(RichText::new("-").color(Color32::GRAY));
+                                        });
+                                    }
+                                    continue;
+                                };
+                                row.col(|ui| {
+                                    let color = if *roi > 0.0 { palette.gain(false) } else { palette.loss(true) };
+                                    let sign = if *roi > 0.0 { palette.plus() } else { "" };
+                                    ui.label(RichText::new(format!("{}{:.1}%", sign, roi)).color(color))
+                                        .on_hover_text(format!("Buy {} (Q10), sell {} (Q90) over the last {} days", format_gp(t.q10), format_gp(t.q90), t.days));
+                                });
+                                row.col(|ui| {
+                                    let color = if t.change_pct >= 0.0 { palette.gain(false) } else { palette.loss(false) };
+                                    let sign = if t.change_pct > 0.0 { palette.plus() } else { "" };
+                                    ui.label(RichText::new(format!("{}{:.1}%", sign, t.change_pct)).color(color));
+                                });
+                                row.col(|ui| {
+                                    ui.label(RichText::new(format!("{:.0}%", t.volatility)).color(Color32::from_rgb(200, 200, 200)));
+                                });
+                            }
 
-                        // Profit with highlighting
-                        row.col(|ui| {
-                            let profit_color = if r.profit > 1_000_000.0 {
-                                Color32::from_rgb(100, 255, 100)
-                            } else if r.profit > 100_000.0 {
-                                Color32::from_rgb(150, 255, 150)
-                            } else if r.profit > 0.0 {
-                                Color32::from_rgb(200, 255, 200)
-                            } else {
-                                Color32::from_rgb(255, 100, 100)
-                            };
-                            ui.label(RichText::new(format_gp(r.profit))
-                                .color(profit_color)
-                                .strong());
-                        });
+                            // Copy offer prices
+                            row.col(|ui| {
+                                let buy_btn = ui.small_button(RichText::new("B").color(Color32::from_rgb(255, 150, 150)));
+                                let buy_btn = accessible(buy_btn, egui::WidgetType::Button, "Copy buy price")
+                                    .on_hover_text(format!("Copy buy price ({} gp)\nRight-click: copy qty ({})", offer_price(r.buy.as_f64()), r.qty));
+                                if buy_btn.clicked() {
+                                    ui.ctx().copy_text(offer_price(r.buy.as_f64()));
+                                } else if buy_btn.secondary_clicked() {
+                                    ui.ctx().copy_text(r.qty.to_string());
+                                }
 
-                        // ROI with color coding
-                        row.col(|ui| {
-                            let roi_color = if r.roi > 20.0 {
-                                Color32::from_rgb(100, 255, 100)
-                            } else if r.roi > 10.0 {
-                                Color32::from_rgb(150, 255, 150)
-                            } else if r.roi > 5.0 {
-                                Color32::from_rgb(200, 255, 200)
-                            } else if r.roi > 0.0 {
-                                Color32::from_rgb(255, 255, 150)
-                            } else {
-                                Color32::from_rgb(255, 100, 100)
+                                let sell_btn = ui.small_button(RichText::new("S").color(Color32::from_rgb(150, 255, 150)));
+                                let sell_btn = accessible(sell_btn, egui::WidgetType::Button, "Copy sell price")
+                                    .on_hover_text(format!("Copy sell price ({} gp)\nRight-click: copy qty ({})", offer_price(r.sell.as_f64()), r.qty));
+                                if sell_btn.clicked() {
+                                    ui.ctx().copy_text(offer_price(r.sell.as_f64()));
+                                } else if sell_btn.secondary_clicked() {
+                                    ui.ctx().copy_text(r.qty.to_string());
+                                }
+                            });
+
+                            let menu_response = match name_response {
+                                Some(name) => row.response().union(name),
+                                None => row.response(),
                             };
-                            ui.label(RichText::new(format!("{:.1}%", r.roi))
-                                .color(roi_color));
-                        });
-
-                        // Copy button
-                        row.col(|ui| {
-                            if ui.button("📋").on_hover_text("Copy item details").clicked() {
-                                let (trend_text, _) = if r.trend > 5.0 {
-                                    ("Rising++", Color32::from_rgb(100, 255, 100))
-                                } else if r.trend > 1.0 {
-                                    ("Rising+", Color32::from_rgb(150, 255, 150))
-                                } else if r.trend < -5.0 {
-                                    ("Falling--", Color32::from_rgb(255, 100, 100))
-                                } else if r.trend < -1.0 {
-                                    ("Falling-", Color32::from_rgb(255, 150, 150))
-                                } else {
-                                    ("Stable", Color32::from_rgb(200, 200, 200))
-                                };
-                                
-                                let copy_text = format!(
-                                    "{}:\nScore: {}\nTier: {} {}\nBuy: {}\nSell: {}\nQty: {}\nAvg Vol: {}\nTotal cost: {}\nProfit: {}\nROI: {:.1}%",
-                                    r.name,
-                                    r.score,
-                                    r.tier,
-                                    trend_text,
-                                    format_gp(r.buy),
-                                    format_gp(r.sell as f64),
-                                    r.qty,
-                                    r.avg_volume,
-                                    format_gp(r.total_cost),
-                                    format_gp(r.profit),
-                                    r.roi
-                                );
-                                
-                                ui.ctx().copy_text(copy_text);
-                            }
+                            menu_response.context_menu(|ui| {
+                                if let Some(action) = row_menu(ui, r, self.config.game, is_favorite, self.watchlist.contains(r.item_id)) {
+                                    row_action = Some((action, i));
+                                }
+                            });
                         });
                     });
-                });
             
-            // Process favorite toggles
-            let toggles = favorite_toggles.borrow();
-            for item_name in toggles.iter() {
-                let current = self.favorites.get(item_name).copied().unwrap_or(false);
-                self.favorites.insert(item_name.clone(), !current);
-            }
-            if !toggles.is_empty() {
-                self.save_favorites();
-            }
-        });
+                // Process favorite toggles
+                let toggles = favorite_toggles.borrow();
+                for id in toggles.iter() {
+                    if !self.favorites.remove(id) {
+                        self.favorites.insert(*id);
+                    }
+                }
+                if !toggles.is_empty() {
+                    self.save_favorites();
+                }
+
+                let pins = pin_toggles.borrow();
+                for id in pins.iter() {
+                    if !self.pinned.remove(id) {
+                        self.pinned.insert(*id);
+                    }
+                }
+                if !pins.is_empty() {
+                    self.save_pinned();
+                    self.apply_filters();
+                }
+
+                for id in select_toggles.borrow().iter() {
+                    if !self.bulk_selection.remove(id) {
+                        self.bulk_selection.insert(*id);
+                    }
+                }
+
+                if let Some((action, i)) = row_action {
+                    self.run_row_action(action, i);
+                }
+            });
+        }
 
         self.graph_height += (self.target_graph_height - self.graph_height) * 0.2;
         if (self.target_graph_height - self.graph_height).abs() < 0.5 {
             self.graph_height = self.target_graph_height;
         }
 
-        if self.graph_height > 1.0 {
+        let selected = self.selected_row.and_then(|i| self.filtered_items.get(i).cloned());
+        if let (Some(r), true) = (&selected, self.detail_popped) {
+            self.detail_viewport(ctx, r);
+        } else if self.graph_height > 1.0 {
+            let money = self.money();
             egui::TopBottomPanel::bottom("graph_panel")
                 .min_height(self.graph_height)
                 .max_height(self.graph_height)
                 .show(ctx, |ui| {
-                    if let Some(idx) = self.selected_row {
-                        if let Some(r) = self.filtered_items.get(idx) {
-                            egui::Frame::new()
-                                .fill(Color32::from_rgb(35, 28, 18))
-                                .stroke(Stroke::new(2.0, Color32::from_rgb(100, 80, 50)))
-                                .inner_margin(Margin::same(12))
-                                .show(ui, |ui| {
-                                    ui.horizontal(|ui| {
-                                        ui.label(RichText::new("⎘ Price History:")
-                                            .color(Color32::from_rgb(255, 210, 100))
-                                            .strong()
-                                            .size(16.0));
-                                        
-                                        ui.label(RichText::new(&r.name)
-                                            .color(Color32::from_rgb(255, 220, 150))
-                                            .size(16.0));
-                                        
-                                        ui.separator();
-                                        
-                                        ui.label(RichText::new(&r.notes)
-                                            .color(Color32::from_rgb(180, 160, 120))
-                                            .italics());
-
-                                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                                            if ui.button(RichText::new("✖").size(16.0)).clicked() {
-                                                self.selected_row = None;
-                                                self.target_graph_height = 0.0;
-                                                self.selected_item_history.clear();
-                                            }
-                                        });
-                                    });
-                                    
-                                    if !self.selected_item_history.is_empty() {
-                                        ui.add_space(5.0);
-                                        
-                                        let points: PlotPoints = self.selected_item_history
-                                            .iter()
-                                            .enumerate()
-                                            .map(|(i, (_, price))| [i as f64, *price])
-                                            .collect();
-                                        
-                                        let line = Line::new("Price", points)
-                                            .color(Color32::from_rgb(100, 200, 255))
-                                            .width(2.0);
-                                        
-                                        // Calculate Y-axis range to handle negative values
-                                        let prices: Vec<f64> = self.selected_item_history.iter().map(|(_, p)| *p).collect();
-                                        let min_price = prices.iter().cloned().fold(f64::INFINITY, f64::min);
-                                        let max_price = prices.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
-                                        let padding = (max_price - min_price) * 0.1;
-                                        
-                                        let history_clone = self.selected_item_history.clone();
-                                        Plot::new("price_history")
-                                            .height(self.graph_height - 80.0)
-                                            .show_axes(true)
-                                            .show_grid(true)
-                                            .allow_zoom(true)
-                                            .allow_scroll(true)
-                                            .allow_drag(true)
-                                            .reset()  // Reset view when plot is recreated
-                                            .include_y(min_price - padding)
-                                            .include_y(max_price + padding)
-                                            .x_axis_formatter(move |mark, _range| {
-                                                let idx = mark.value as usize;
-                                                if let Some((date, _)) = history_clone.get(idx) {
-                                                    if date.len() >= 10 {
-                                                        date[5..10].to_string()
-                                                    } else {
-                                                        date.clone()
-                                                    }
-                                                } else {
-                                                    String::new()
-                                                }
-                                            })
-                                            .label_formatter(|_name, value| {
-                                                if let Some((date, _)) = self.selected_item_history.get(value.x as usize) {
-                                                    format!("{}\nPrice: {}", date, format_gp(value.y))
-                                                } else {
-                                                    format!("Price: {}", format_gp(value.y))
-                                                }
-                                            })
-                                            .show(ui, |plot_ui| {
-                                                plot_ui.line(line);
-                                            });
-                                    } else {
-                                        ui.centered_and_justified(|ui| {
-                                            ui.label(RichText::new("Loading price history...")
-                                                .color(Color32::from_rgb(180, 160, 120))
-                                                .italics()
-                                                .size(18.0));
-                                        });
-                                    }
-                                });
-                        }
+                    if let Some(r) = &selected {
+                        self.item_detail(ui, r, money, self.graph_height - 140.0);
                     }
                 });
         }
 
+        self.track_view_history(ctx);
+
+        if self.show_wizard {
+            self.wizard_window(ctx);
+        }
+
+        if self.show_journal {
+            self.journal_window(ctx);
+        }
+
+        if self.show_rules {
+            self.rules_window(ctx);
+        }
+
+        if self.show_leaderboard {
+            self.leaderboard_window(ctx);
+        }
+
+        if self.show_palette {
+            self.palette_window(ctx);
+        }
+
+        if self.show_compare {
+            self.compare_window(ctx);
+        }
+
+        if self.show_time_machine {
+            self.time_machine_window(ctx);
+        }
+
+        if self.show_alerts {
+            self.alerts_window(ctx);
+        }
+
         ctx.request_repaint();
     }
 }
 
-fn format_gp(value: f64) -> String {
-    if value >= 1_000_000_000.0 {
-        format!("{:.2}B", value / 1_000_000_000.0)
-    } else if value >= 1_000_000.0 {
-        format!("{:.2}M", value / 1_000_000.0)
-    } else if value >= 1_000.0 {
-        format!("{:.1}K", value / 1_000.0)
+// A time machine date's top rows, best first. With `prev_ranks` each row
+// also shows how far it moved since the previous replay step.
+fn past_table(ui: &mut egui::Ui, id: &str, rows: &[PastRow], palette: Palette, prev_ranks: Option<&HashMap<i32, usize>>) {
+    egui::Grid::new(id).striped(true).num_columns(7).show(ui, |ui| {
+        for h in ["#", "Item", "Tier", "Score", "Buy", "Sell", "ROI"] {
+            ui.label(RichText::new(h).strong());
+        }
+        ui.end_row();
+        for (i, r) in rows.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label((i + 1).to_string());
+                match prev_ranks.map(|p| p.get(&r.item_id)) {
+                    Some(Some(&prev)) if prev > i => { ui.label(RichText::new(format!("▲{}", prev - i)).small().color(palette.gain(true))); }
+                    Some(Some(&prev)) if prev < i => { ui.label(RichText::new(format!("▼{}", i - prev)).small().color(palette.loss(true))); }
+                    Some(None) => { ui.label(RichText::new("new").small().color(Color32::from_rgb(255, 210, 100))); }
+                    _ => {}
+                }
+            });
+            ui.label(&r.name).on_hover_text(format!("Price that day: {}", format_gp(r.price)));
+            ui.label(RichText::new(&r.tier).color(palette.tier(&r.tier)));
+            ui.label(r.score.to_string());
+            ui.label(r.buy.to_string());
+            ui.label(r.sell.to_string());
+            ui.label(format!("{:.1}%", r.roi));
+            ui.end_row();
+        }
+    });
+}
+
+fn save_favorite_ids(game: Game, favorites: &HashSet<i32>) {
+    let mut ids: Vec<i32> = favorites.iter().copied().collect();
+    ids.sort_unstable();
+    if let Ok(json) = serde_json::to_string(&ids) {
+        let _ = std::fs::write(game.file("favorites.json"), json);
+    }
+}
+
+// Migrates name-keyed favorites. Names no longer in the database are dropped;
+// a duplicated name resolves to whichever item the database finds first.
+fn favorites_from_names(by_name: HashMap<String, bool>) -> HashSet<i32> {
+    by_name
+        .into_iter()
+        .filter(|(_, on)| *on)
+        .filter_map(|(name, _)| loader::find_item(loader::DB_PATH, &name).ok().flatten())
+        .map(|(id, _)| id)
+        .collect()
+}
+
+fn hour_drag(value: &mut u32) -> egui::DragValue<'_> {
+    egui::DragValue::new(value).range(0..=23).suffix(":00")
+}
+
+fn metric_text(metric: Metric, r: &Row) -> String {
+    let v = metric.value(r);
+    match metric {
+        Metric::Roi | Metric::Trend => format!("{:.1}%", v),
+        Metric::Score => format!("{:.0}", v),
+        _ => format_gp(v),
+    }
+}
+
+// Tick labels for a scatter axis, undoing the log10 when the axis is logarithmic
+fn axis_value(v: f64, log: bool) -> String {
+    let v = if log { 10f64.powf(v) } else { v };
+    if v.abs() >= 1_000.0 {
+        format!("{}{}", if v < 0.0 { "-" } else { "" }, format_gp(v.abs()))
     } else {
-        format!("{:.0}", value)
+        format!("{:.1}", v)
+    }
+}
+
+// Plain-language guide to the columns shown in beginner mode
+fn beginner_help(ui: &mut egui::Ui) {
+    egui::CollapsingHeader::new(RichText::new("🎓 How to read this table").color(Color32::from_rgb(255, 210, 100)))
+        .default_open(true)
+        .show(ui, |ui| {
+            for key in ["tier", "trend", "difficulty", "buy", "sell", "qty", "volume", "profit", "roi"] {
+                let Some(term) = glossary::term(key) else { continue };
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(RichText::new(term.name).strong().color(Color32::from_rgb(220, 200, 160)));
+                    ui.label(term.text);
+                });
+            }
+        });
+    ui.add_space(4.0);
+}
+
+// Table order: the chosen column, then score, profit, name and item id so
+// rows that tie on the column keep the same order from one refresh to the next
+fn compare_rows(a: &Row, b: &Row, sort_by: SortBy, order: SortOrder) -> std::cmp::Ordering {
+    let primary = match sort_by {
+        SortBy::Score => a.score.cmp(&b.score),
+        SortBy::Profit => a.profit.cmp(&b.profit),
+        SortBy::ROI => a.roi.total_cmp(&b.roi),
+        SortBy::Name => a.name.cmp(&b.name),
+        SortBy::Buy => a.buy.cmp(&b.buy),
+        SortBy::Sell => a.sell.cmp(&b.sell),
+    };
+    let primary = if order == SortOrder::Descending { primary.reverse() } else { primary };
+    primary
+        .then_with(|| b.score.cmp(&a.score))
+        .then_with(|| b.profit.cmp(&a.profit))
+        .then_with(|| a.name.cmp(&b.name))
+        .then_with(|| a.item_id.cmp(&b.item_id))
+}
+
+// ROI after tax buying at a window's Q10 and selling at its Q90
+fn timeframe_roi(name: &str, t: &Timeframe, tax: &Tax) -> f64 {
+    let buy = Gp::from_f64(t.q10);
+    if buy <= Gp::ZERO {
+        return 0.0;
+    }
+    flips::net_profit(name, buy, Gp::from_f64(t.q90), tax).as_f64() / buy.as_f64() * 100.0
+}
+
+fn rule_alert_text(f: &Firing) -> String {
+    format!("📣 {} matched \"{}\": {}", f.item, f.rule, f.reason)
+}
+
+// Settings and conditions for one alert rule. Returns true if anything changed.
+fn rule_editor(ui: &mut egui::Ui, ri: usize, rule: &mut Rule) -> bool {
+    let mut changed = false;
+    egui::Grid::new(("rule_settings", ri)).num_columns(2).show(ui, |ui| {
+        ui.label("Name:");
+        changed |= ui.add(egui::TextEdit::singleline(&mut rule.name).desired_width(220.0)).changed();
+        ui.end_row();
+        ui.label("Enabled:");
+        changed |= ui.checkbox(&mut rule.enabled, "").changed();
+        ui.end_row();
+        ui.label("Items:");
+        changed |= ui.add(egui::TextEdit::singleline(&mut rule.items)
+            .hint_text("Every item (or names, comma-separated)")
+            .desired_width(260.0))
+            .changed();
+        ui.end_row();
+        ui.label("Notify by:");
+        egui::ComboBox::from_id_salt(("rule_channel", ri))
+            .selected_text(rule.channel.label())
+            .show_ui(ui, |ui| {
+                for channel in Channel::ALL {
+                    changed |= ui.selectable_value(&mut rule.channel, channel, channel.label()).clicked();
+                }
+            });
+        ui.end_row();
+        ui.label("Cooldown:");
+        changed |= ui.add(egui::DragValue::new(&mut rule.cooldown_hours).range(0.0..=720.0).speed(1.0).suffix(" h"))
+            .on_hover_text("How long before the same item can fire this rule again")
+            .changed();
+        ui.end_row();
+    });
+
+    let mut remove_group = None;
+    for (gi, group) in rule.groups.iter_mut().enumerate() {
+        if gi > 0 {
+            ui.label(RichText::new("OR").strong().color(Color32::from_rgb(200, 180, 140)));
+        }
+        egui::Frame::group(ui.style()).show(ui, |ui| {
+            let mut remove = None;
+            for (ci, condition) in group.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(if ci == 0 { "When" } else { "and" });
+                    changed |= condition_editor(ui, (ri, gi, ci), condition);
+                    if ui.small_button("✖").clicked() {
+                        remove = Some(ci);
+                    }
+                });
+            }
+            if let Some(ci) = remove {
+                group.remove(ci);
+                changed = true;
+            }
+            ui.horizontal(|ui| {
+                if ui.small_button("➕ AND condition").clicked() {
+                    group.push(Condition::default());
+                    changed = true;
+                }
+                if ui.small_button("Remove group").clicked() {
+                    remove_group = Some(gi);
+                }
+            });
+        });
+    }
+    if let Some(gi) = remove_group {
+        rule.groups.remove(gi);
+        changed = true;
+    }
+    if ui.small_button("➕ OR group").clicked() {
+        rule.groups.push(vec![Condition::default()]);
+        changed = true;
+    }
+    changed
+}
+
+fn condition_editor(ui: &mut egui::Ui, id: (usize, usize, usize), condition: &mut Condition) -> bool {
+    let mut changed = false;
+    let kind = match condition {
+        Condition::Metric { .. } => "Metric",
+        Condition::TierEnters(_) => "Tier enters",
+        Condition::TierLeaves(_) => "Tier leaves",
+    };
+    egui::ComboBox::from_id_salt(("condition_kind", id))
+        .selected_text(kind)
+        .width(100.0)
+        .show_ui(ui, |ui| {
+            if ui.selectable_label(kind == "Metric", "Metric").clicked() && kind != "Metric" {
+                *condition = Condition::default();
+                changed = true;
+            }
+            if ui.selectable_label(kind == "Tier enters", "Tier enters").clicked() && kind != "Tier enters" {
+                *condition = Condition::TierEnters("DIAMOND".into());
+                changed = true;
+            }
+            if ui.selectable_label(kind == "Tier leaves", "Tier leaves").clicked() && kind != "Tier leaves" {
+                *condition = Condition::TierLeaves("DIAMOND".into());
+                changed = true;
+            }
+        });
+
+    match condition {
+        Condition::Metric { field, compare, value } => {
+            egui::ComboBox::from_id_salt(("condition_field", id))
+                .selected_text(field.label())
+                .width(100.0)
+                .show_ui(ui, |ui| {
+                    for f in RuleField::ALL {
+                        changed |= ui.selectable_value(field, f, f.label()).clicked();
+                    }
+                });
+            egui::ComboBox::from_id_salt(("condition_compare", id))
+                .selected_text(compare.label())
+                .width(70.0)
+                .show_ui(ui, |ui| {
+                    for c in [Compare::Above, Compare::Below] {
+                        changed |= ui.selectable_value(compare, c, c.label()).clicked();
+                    }
+                });
+            changed |= ui.add(egui::DragValue::new(value).speed(0.1)).changed();
+        }
+        Condition::TierEnters(tier) | Condition::TierLeaves(tier) => {
+            egui::ComboBox::from_id_salt(("condition_tier", id))
+                .selected_text(tier.as_str())
+                .show_ui(ui, |ui| {
+                    for t in rules::TIERS {
+                        if ui.selectable_label(tier == t, t).clicked() {
+                            *tier = t.to_string();
+                            changed = true;
+                        }
+                    }
+                });
+        }
+    }
+    changed
+}
+
+// Fading background behind a table cell that changed in the last refresh
+fn flash_cell(ui: &mut egui::Ui, palette: Palette, up: bool, strength: f32) {
+    let color = if up { palette.gain(true) } else { palette.loss(true) };
+    ui.painter().rect_filled(ui.max_rect(), 2.0, color.gamma_multiply(0.35 * strength));
+}
+
+// Right-click menu for a table row. Copying and the wiki link happen here;
+// anything that changes app state is returned for the caller to apply.
+fn row_menu(ui: &mut egui::Ui, r: &Row, game: Game, is_favorite: bool, watched: bool) -> Option<RowAction> {
+    let mut action = None;
+    ui.label(RichText::new(&r.name).strong().color(Color32::from_rgb(255, 210, 100)));
+    ui.separator();
+    if ui.button(if is_favorite { "☆ Unfavorite" } else { "★ Favorite" }).clicked() {
+        action = Some(RowAction::Favorite);
+    }
+    if ui.add_enabled(!watched, egui::Button::new(if watched { "👁 Watching" } else { "👁 Watch" })).clicked() {
+        action = Some(RowAction::Watch);
+    }
+    if ui.button("🚫 Blacklist").on_hover_text("Hide this item from the table").clicked() {
+        action = Some(RowAction::Blacklist);
+    }
+    if ui.button("🏦 Add to portfolio")
+        .on_hover_text(format!("Record {} bought at {} gp, targeting {} gp", r.qty, offer_price(r.buy.as_f64()), offer_price(r.sell.as_f64())))
+        .clicked()
+    {
+        action = Some(RowAction::AddToPortfolio);
+    }
+    if ui.button("📈 Open chart").clicked() {
+        action = Some(RowAction::OpenChart);
+    }
+    ui.separator();
+    if ui.button(format!("Copy buy price ({})", offer_price(r.buy.as_f64()))).clicked() {
+        ui.ctx().copy_text(offer_price(r.buy.as_f64()));
+        ui.close();
+    }
+    if ui.button(format!("Copy sell price ({})", offer_price(r.sell.as_f64()))).clicked() {
+        ui.ctx().copy_text(offer_price(r.sell.as_f64()));
+        ui.close();
+    }
+    if ui.button("📖 Open wiki").clicked() {
+        ui.ctx().open_url(egui::OpenUrl::new_tab(game.wiki_url(&r.name)));
+        ui.close();
+    }
+    if action.is_some() {
+        ui.close();
+    }
+    action
+}
+
+// An item name with the part matching the search picked out, in the name's
+// usual colour. None when there's no search or no match.
+fn highlight_match(ui: &egui::Ui, name: &str, query: &str, color: Color32) -> Option<egui::text::LayoutJob> {
+    if query.is_empty() {
+        return None;
+    }
+    let lower = name.to_lowercase();
+    // Lowercasing can change byte lengths outside ASCII; skip those rather than split a character
+    if lower.len() != name.len() {
+        return None;
+    }
+    let start = lower.find(query)?;
+    let end = start + query.len();
+
+    let format = egui::TextFormat {
+        font_id: egui::TextStyle::Body.resolve(ui.style()),
+        color,
+        ..Default::default()
+    };
+    let highlighted = egui::TextFormat {
+        background: Color32::from_rgba_unmultiplied(255, 210, 100, 60),
+        color: Color32::from_rgb(255, 235, 170),
+        ..format.clone()
+    };
+
+    let mut job = egui::text::LayoutJob::default();
+    job.append(&name[..start], 0.0, format.clone());
+    job.append(&name[start..end], 0.0, highlighted);
+    job.append(&name[end..], 0.0, format);
+    Some(job)
+}
+
+// Names an icon-only widget for screen readers (egui's AccessKit backend)
+fn accessible(response: egui::Response, typ: egui::WidgetType, label: &str) -> egui::Response {
+    let enabled = response.enabled();
+    response.widget_info(|| egui::WidgetInfo::labeled(typ, enabled, label));
+    response
+}
+
+fn difficulty_color(d: Difficulty) -> Color32 {
+    match d {
+        Difficulty::Easy => Color32::from_rgb(100, 255, 150),
+        Difficulty::Moderate => Color32::from_rgb(200, 220, 120),
+        Difficulty::Hard => Color32::from_rgb(255, 180, 100),
+        Difficulty::Expert => Color32::from_rgb(255, 100, 100),
+    }
+}
+
+// Red for falling, green for rising (or the palette's pair), saturating at ±10%
+fn heat_color(change_pct: Option<f64>, palette: Palette) -> Color32 {
+    let neutral = (70.0, 60.0, 48.0);
+    let Some(pct) = change_pct else {
+        return Color32::from_rgb(55, 50, 45);
+    };
+    let t = (pct / 10.0).clamp(-1.0, 1.0) as f32;
+    let target = palette.heat(t >= 0.0);
+    let t = t.abs();
+    let lerp = |a: f32, b: f32| (a + (b - a) * t) as u8;
+    Color32::from_rgb(lerp(neutral.0, target.0), lerp(neutral.1, target.1), lerp(neutral.2, target.2))
+}
+
+fn mover_card(ui: &mut egui::Ui, title: &str, movers: &[Mover], color: Color32) {
+    egui::Frame::new()
+        .fill(Color32::from_rgb(35, 28, 18))
+        .stroke(Stroke::new(1.0, Color32::from_rgb(100, 80, 50)))
+        .inner_margin(Margin::same(10))
+        .show(ui, |ui| {
+            ui.label(RichText::new(title).color(color).strong().size(16.0));
+            ui.separator();
+
+            if movers.is_empty() {
+                ui.label(RichText::new("No movers").italics());
+                return;
+            }
+
+            egui::Grid::new(title).striped(true).num_columns(4).show(ui, |ui| {
+                for m in movers {
+                    ui.label(&m.name);
+                    ui.label(format_gp(m.price));
+                    let sign = if m.change >= 0.0 { "+" } else { "-" };
+                    ui.label(RichText::new(format!("{}{}", sign, format_gp(m.change.abs()))).color(color));
+                    ui.label(RichText::new(format!("{:+.1}%", m.change_pct)).color(color).strong());
+                    ui.end_row();
+                }
+            });
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(item_id: i32, name: &str) -> Row {
+        Row {
+            item_id,
+            name: name.to_string(),
+            score: 50,
+            score_pct: 50.0,
+            tier: "GOOD".to_string(),
+            buy: Gp(1_000),
+            sell: Gp(1_200),
+            qty: 10,
+            unit_profit: Gp(176),
+            profit: Gp(1_760),
+            roi: 17.6,
+            notes: String::new(),
+            trend: 0.0,
+            total_cost: Gp(10_000),
+            avg_volume: 20_000.0,
+            prev_tier: None,
+            cycle_days: None,
+            gp_per_hour: None,
+            break_even: 1_021.0,
+            downside: 0.0,
+            slippage: 0.0,
+            capture: None,
+            timeframes: [None, None, None],
+            timeframe_conflict: None,
+            drawdown: None,
+            low_confidence: None,
+            summary: String::new(),
+            dump: None,
+            floor: None,
+            regime: None,
+            reach: flips::Reach { daily_cap: 0.05, buy_days: 0, sell_days: 0 },
+            coverage_days: stats::COVERAGE_DAYS as usize,
+            data_points: 100,
+            stale_days: 0,
+            variant: None,
+            merged_variants: vec![],
+            difficulty: Difficulty::Easy,
+            difficulty_reasons: vec![],
+        }
+    }
+
+    // Every optional filter off; only Affordable and Sanity are always in use
+    fn settings(disabled: &HashSet<FilterKind>, blacklist: &HashSet<i32>) -> FilterSettings<'static> {
+        FilterSettings {
+            disabled: Box::leak(Box::new(disabled.clone())),
+            blacklist: Box::leak(Box::new(blacklist.clone())),
+            favorites: Box::leak(Box::default()),
+            favorites_only: false,
+            search: String::new(),
+            tier: None,
+            min_profit: 0.0,
+            min_roi: 0.0,
+            min_coverage: 0.0,
+            min_data_points: 0,
+            max_stale_days: 0,
+            max_drawdown: 0.0,
+            hidden_regimes: Box::leak(Box::default()),
+            show_bad: true,
+            beginner_mode: false,
+        }
+    }
+
+    fn names(rows: &[Row]) -> Vec<&str> {
+        rows.iter().map(|r| r.name.as_str()).collect()
+    }
+
+    fn filter_test_rows() -> Vec<Row> {
+        let mut rows = vec![
+            row(1, "Rune bar"),
+            row(2, "Rune ore"),
+            row(3, "Dragon bones"),
+            row(4, "Rune sword"),
+            row(5, "Rune axe"),
+            row(6, "Yew logs"),
+        ];
+        rows[1].qty = 0;
+        rows[2].avg_volume = 100.0;
+        rows[3].score = 0;
+        rows
+    }
+
+    #[test]
+    fn filters_count_what_each_removed_in_order() {
+        let mut settings = settings(&HashSet::new(), &HashSet::from([5]));
+        settings.search = "RUNE".to_lowercase();
+        settings.show_bad = false;
+
+        let (kept, removed) = filter_rows(&filter_test_rows(), &settings);
+        assert_eq!(names(&kept), ["Rune bar"]);
+        assert_eq!(
+            removed,
+            [
+                (FilterKind::Affordable, 1),
+                (FilterKind::Sanity, 1),
+                (FilterKind::Blacklist, 1),
+                (FilterKind::Search, 1),
+                (FilterKind::BadScore, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn filters_not_in_use_or_switched_off_are_skipped() {
+        let mut settings = settings(&HashSet::from([FilterKind::Sanity, FilterKind::BadScore]), &HashSet::new());
+        settings.show_bad = false;
+        settings.min_profit = 1_000.0;
+
+        let (kept, removed) = filter_rows(&filter_test_rows(), &settings);
+        assert_eq!(names(&kept), ["Rune bar", "Dragon bones", "Rune sword", "Rune axe", "Yew logs"]);
+        assert_eq!(removed, [(FilterKind::Affordable, 1), (FilterKind::MinProfit, 0)]);
+    }
+
+    #[test]
+    fn search_ignores_case() {
+        let mut settings = settings(&HashSet::new(), &HashSet::new());
+        settings.search = "dragon".to_string();
+        let mut rows = filter_test_rows();
+        rows[2].avg_volume = 20_000.0;
+        rows[5].name = "DRAGON hide".to_string();
+
+        let (kept, removed) = filter_rows(&rows, &settings);
+        assert_eq!(names(&kept), ["Dragon bones", "DRAGON hide"]);
+        assert_eq!(removed, [(FilterKind::Affordable, 1), (FilterKind::Sanity, 0), (FilterKind::Search, 3)]);
+    }
+
+    const SORTS: [SortBy; 6] = [SortBy::Score, SortBy::Profit, SortBy::ROI, SortBy::Name, SortBy::Buy, SortBy::Sell];
+    const ORDERS: [SortOrder; 2] = [SortOrder::Ascending, SortOrder::Descending];
+
+    // Item IDs of `rows` sorted with compare_rows
+    fn sorted(mut rows: Vec<Row>, sort_by: SortBy, order: SortOrder) -> Vec<i32> {
+        rows.sort_by(|a, b| compare_rows(a, b, sort_by, order));
+        rows.iter().map(|r| r.item_id).collect()
+    }
+
+    #[test]
+    fn sort_follows_the_chosen_column_and_order() {
+        let (mut low, mut high) = (row(1, "Air rune"), row(2, "Body rune"));
+        high.profit = Gp(5_000);
+        high.roi = 40.0;
+        high.buy = Gp(2_000);
+        high.sell = Gp(3_000);
+        low.score = 90;  // Only the Score column has these the other way round
+        for sort_by in SORTS {
+            let (first, last) = match sort_by {
+                SortBy::Score => (1, 2),
+                _ => (2, 1),
+            };
+            let rows = vec![low.clone(), high.clone()];
+            assert_eq!(sorted(rows.clone(), sort_by, SortOrder::Descending), [first, last], "{:?}", sort_by);
+            assert_eq!(sorted(rows, sort_by, SortOrder::Ascending), [last, first], "{:?}", sort_by);
+        }
+    }
+
+    #[test]
+    fn sort_ties_fall_back_to_score_profit_name_then_id() {
+        for sort_by in SORTS {
+            for order in ORDERS {
+                // Same everything but ID
+                assert_eq!(sorted(vec![row(2, "Rune bar"), row(1, "Rune bar")], sort_by, order), [1, 2], "{:?} {:?}", sort_by, order);
+
+                // Name breaks the tie, A-Z in either order, unless it's the column
+                if sort_by != SortBy::Name {
+                    assert_eq!(sorted(vec![row(1, "Rune bar"), row(2, "Iron bar")], sort_by, order), [2, 1], "{:?} {:?}", sort_by, order);
+                }
+
+                // Higher profit first, even ascending, when profit isn't the column
+                if sort_by != SortBy::Profit {
+                    let mut richer = row(2, "Rune bar");
+                    richer.profit = Gp(9_000);
+                    assert_eq!(sorted(vec![row(1, "Rune bar"), richer], sort_by, order), [2, 1], "{:?} {:?}", sort_by, order);
+                }
+
+                // Higher score before profit
+                if sort_by != SortBy::Score {
+                    let (mut better, mut richer) = (row(1, "Rune bar"), row(2, "Rune bar"));
+                    better.score = 70;
+                    richer.profit = Gp(9_000);
+                    let expected = if sort_by == SortBy::Profit {
+                        if order == SortOrder::Descending { [2, 1] } else { [1, 2] }
+                    } else {
+                        [1, 2]
+                    };
+                    assert_eq!(sorted(vec![richer, better], sort_by, order), expected, "{:?} {:?}", sort_by, order);
+                }
+            }
+        }
+    }
+
+    // A zero buy price gives a NaN ROI once the sanity filter is switched off
+    #[test]
+    fn sort_handles_nan_roi() {
+        let mut rows: Vec<Row> = (1..=6).map(|id| row(id, "Rune bar")).collect();
+        rows[1].roi = f64::NAN;
+        rows[3].buy = Gp::ZERO;
+        rows[4].roi = f64::NAN;
+        rows[4].buy = Gp::ZERO;
+        rows[5].roi = 99.0;
+        for sort_by in SORTS {
+            for order in ORDERS {
+                let ids = sorted(rows.clone(), sort_by, order);
+                assert_eq!(ids.len(), rows.len());
+                // Reversed input sorts the same, so NaN has a fixed place rather than tying with everything
+                assert_eq!(sorted(rows.iter().rev().cloned().collect(), sort_by, order), ids, "{:?} {:?}", sort_by, order);
+            }
+        }
+        assert_eq!(sorted(rows.clone(), SortBy::ROI, SortOrder::Descending)[..3], [2, 5, 6]);
+        assert_eq!(sorted(rows, SortBy::Buy, SortOrder::Ascending)[..2], [4, 5]);
     }
 }
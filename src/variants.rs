@@ -0,0 +1,41 @@
+// Item variants: the same item in a different state (charged/uncharged,
+// broken, beach cosmetics, dose or charge counts). Each variant trades on its
+// own GE listing, so the stats stay separate; this only lets the results group
+// a family together instead of listing near-duplicates side by side.
+
+// Name suffixes that mark a variant, and how to describe it
+const VARIANT_SUFFIXES: &[(&str, &str)] = &[
+    (" (uncharged)", "uncharged"),
+    (" (charged)", "charged"),
+    (" (broken)", "broken"),
+    (" (damaged)", "damaged"),
+    (" (degraded)", "degraded"),
+    (" (beach)", "beach"),
+    (" (unf)", "unfinished"),
+    (" (i)", "imbued"),
+    (" (or)", "ornament"),
+    (" (t)", "trimmed"),
+    (" (g)", "gold-trimmed"),
+];
+
+// Family name and variant label, e.g. "Amulet of glory (4)" -> ("Amulet of glory", "4 charges")
+pub fn variant_of(name: &str) -> Option<(&str, String)> {
+    for (suffix, label) in VARIANT_SUFFIXES {
+        if let Some(base) = name.strip_suffix(suffix) {
+            return Some((base, label.to_string()));
+        }
+    }
+
+    // Doses and charges: "Prayer potion (3)", "Ring of wealth (5)"
+    let base = name.strip_suffix(')')?;
+    let (base, count) = base.rsplit_once(" (")?;
+    if !count.is_empty() && count.len() <= 2 && count.chars().all(|c| c.is_ascii_digit()) {
+        return Some((base, format!("({})", count)));
+    }
+    None
+}
+
+// The family an item groups under: its base name, or its own name when it isn't a variant
+pub fn family(name: &str) -> &str {
+    variant_of(name).map_or(name, |(base, _)| base)
+}
@@ -0,0 +1,127 @@
+use crate::game::Game;
+use serde::{Serialize, Deserialize};
+
+const WATCHLIST_PATH: &str = "watchlist.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchedItem {
+    pub item_id: i32,
+    pub name: String,
+}
+
+// Items the user keeps an eye on, whether or not they pass the flip filters
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Watchlist {
+    pub items: Vec<WatchedItem>,
+    #[serde(skip)]
+    path: String,  // Per-game file it was loaded from
+}
+
+impl Watchlist {
+    pub fn load(game: Game) -> Self {
+        let path = game.file(WATCHLIST_PATH);
+        let mut loaded: Watchlist = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default();
+        loaded.path = path;
+        loaded
+    }
+
+    pub fn save(&self) {
+        if let Ok(json) = serde_json::to_string_pretty(self) {
+            let _ = std::fs::write(&self.path, json);
+        }
+    }
+
+    // Returns false if the item was already on the list
+    pub fn add(&mut self, item_id: i32, name: String) -> bool {
+        if self.contains(item_id) {
+            return false;
+        }
+        self.items.push(WatchedItem { item_id, name });
+        true
+    }
+
+    pub fn remove(&mut self, item_id: i32) {
+        self.items.retain(|w| w.item_id != item_id);
+    }
+
+    pub fn contains(&self, item_id: i32) -> bool {
+        self.items.iter().any(|w| w.item_id == item_id)
+    }
+}
+
+// Turns quick-add input into something `find_item` can resolve: an item id
+// pulled out of a GE / tracker URL, an item name from a wiki-style URL, or
+// the input unchanged when it is already a name or id.
+//
+//   https://secure.runescape.com/m=itemdb_rs/Abyssal+whip/viewitem?obj=4151  -> "4151"
+//   https://runescape.wiki/w/Exchange:Abyssal_whip                            -> "Abyssal whip"
+pub fn item_query(input: &str) -> String {
+    let input = input.trim();
+    if !input.contains("://") {
+        return input.to_string();
+    }
+
+    let (path, query) = input.split_once('?').unwrap_or((input, ""));
+
+    // Query parameters such as ?obj=4151 or ?id=4151
+    for pair in query.split('&') {
+        if let Some((key, value)) = pair.split_once('=') {
+            if matches!(key, "obj" | "id" | "item" | "itemId") && value.parse::<i32>().is_ok() {
+                return value.to_string();
+            }
+        }
+    }
+
+    let segments: Vec<&str> = path
+        .split('#')
+        .next()
+        .unwrap_or(path)
+        .split('/')
+        .skip(3)  // scheme, empty, host
+        .filter(|s| !s.is_empty() && !s.starts_with("m="))
+        .collect();
+
+    // Paths like /item/4151 or /viewitem/4151
+    if let Some(id) = segments.iter().rev().find(|s| s.parse::<i32>().is_ok()) {
+        return id.to_string();
+    }
+
+    // Otherwise the name is the last meaningful segment, as a slug
+    let slug = segments
+        .iter()
+        .rev()
+        .find(|s| !matches!(**s, "viewitem" | "results" | "w" | "item" | "items"))
+        .copied()
+        .unwrap_or("");
+    let slug = slug.strip_prefix("Exchange:").unwrap_or(slug);
+
+    // Wiki and GE URLs use '_' / '+' for spaces; tracker slugs use '-'
+    let name = percent_decode(slug);
+    if name.contains(['_', '+']) {
+        name.replace(['_', '+'], " ")
+    } else {
+        name.replace('-', " ")
+    }
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+            if let Ok(b) = u8::from_str_radix(hex, 16) {
+                out.push(b);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
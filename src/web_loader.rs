@@ -0,0 +1,277 @@
+// Web replacement for the SQLite loader.
+//
+// Browsers can't open rs3_market.db directly, so the web build fetches a
+// JSON dump of the snapshot window (see `--export-web` in main.rs) and serves
+// every query from that in-memory copy.
+use crate::model::{Annotation, DailyPrice, DataVersion, DbStats, Gp, Holding, ItemSnapshot, RawSnapshot, ScanEntry, SnapshotEdit, SnapshotExport};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+pub const DB_PATH: &str = "rs3_market.db";
+pub const DB_ARCHIVE_PATH: &str = "rs3_market.db.gz";
+pub const SNAPSHOT_URL: &str = "snapshots.json";
+
+pub type Result<T> = std::result::Result<T, String>;
+
+// Features that write to the database have nowhere to persist in the browser
+fn unsupported<T>() -> Result<T> {
+    Err("not available in the web build".to_string())
+}
+
+// None until the download finishes; a failed download keeps its error so
+// every query reports it instead of waiting forever
+static SNAPSHOTS: Mutex<Option<Result<Vec<ItemSnapshot>>>> = Mutex::new(None);
+
+pub fn start_fetch(ctx: egui::Context) {
+    let request = ehttp::Request::get(SNAPSHOT_URL);
+    ehttp::fetch(request, move |response| {
+        let parsed = response
+            .map_err(|e| e.to_string())
+            .and_then(|r| SnapshotExport::parse(&r.bytes).map_err(|e| e.to_string()));
+
+        *SNAPSHOTS.lock().unwrap() = Some(parsed.map_err(|e| format!("failed to fetch {}: {}", SNAPSHOT_URL, e)));
+        ctx.request_repaint();
+    });
+}
+
+fn downloaded(snapshots: &Option<Result<Vec<ItemSnapshot>>>) -> Result<&[ItemSnapshot]> {
+    match snapshots {
+        Some(Ok(snaps)) => Ok(snaps),
+        Some(Err(e)) => Err(e.clone()),
+        None => Err("snapshot data is still downloading".to_string()),
+    }
+}
+
+pub fn load_snapshots(_db_path: &str) -> Result<Vec<ItemSnapshot>> {
+    downloaded(&SNAPSHOTS.lock().unwrap()).map(|snaps| snaps.to_vec())
+}
+
+// The download never changes once it's in, so its size is version enough
+pub fn data_version(db_path: &str) -> Result<DataVersion> {
+    let rows = count_snapshots(db_path)?;
+    Ok(DataVersion { last_rowid: rows as i64, last_date: String::new(), today: String::new() })
+}
+
+pub fn count_snapshots(db_path: &str) -> Result<usize> {
+    load_snapshots(db_path).map(|snaps| snaps.len())
+}
+
+// The download is already all in memory, so it goes over as a single batch,
+// sorted by item like the native stream
+pub fn stream_snapshots(db_path: &str, _batch_size: usize, mut on_batch: impl FnMut(Vec<ItemSnapshot>) -> bool) -> Result<usize> {
+    let mut snaps = load_snapshots(db_path)?;
+    snaps.sort_by(|a, b| (a.item_id, &a.record_date).cmp(&(b.item_id, &b.record_date)));
+    let read = snaps.len();
+    on_batch(snaps);
+    Ok(read)
+}
+
+pub fn load_item_history(_db_path: &str, item_id: i32) -> Result<Vec<(String, f64, Option<f64>)>> {
+    let guard = SNAPSHOTS.lock().unwrap();
+    let snaps = downloaded(&guard)?;
+
+    Ok(snaps
+        .iter()
+        .filter(|s| s.item_id == item_id)
+        .map(history_row)
+        .collect())
+}
+
+pub fn load_item_history_range(_db_path: &str, item_id: i32, from: Option<&str>, to: Option<&str>) -> Result<Vec<(String, f64, Option<f64>)>> {
+    let guard = SNAPSHOTS.lock().unwrap();
+    let snaps = downloaded(&guard)?;
+
+    Ok(snaps
+        .iter()
+        .filter(|s| s.item_id == item_id)
+        .filter(|s| from.is_none_or(|from| s.record_date.as_str() >= from))
+        .filter(|s| to.is_none_or(|to| s.record_date.get(..10).unwrap_or(&s.record_date) <= to))
+        .map(history_row)
+        .collect())
+}
+
+fn history_row(s: &ItemSnapshot) -> (String, f64, Option<f64>) {
+    let volume = if s.manual { None } else { Some(s.volume as f64) };
+    (s.record_date.clone(), s.price.as_f64(), volume)
+}
+
+// Positions stand in for rowids; nothing here can be edited anyway
+pub fn load_item_snapshots(_db_path: &str, item_id: i32) -> Result<Vec<RawSnapshot>> {
+    let guard = SNAPSHOTS.lock().unwrap();
+    let snaps = downloaded(&guard)?;
+
+    Ok(snaps
+        .iter()
+        .filter(|s| s.item_id == item_id)
+        .enumerate()
+        .map(|(i, s)| RawSnapshot {
+            rowid: i as i64,
+            date: s.record_date.clone(),
+            price: s.price,
+            volume: (!s.manual).then_some(s.volume as i64),
+            manual: s.manual,
+        })
+        .collect())
+}
+
+pub fn add_manual_snapshot(_db_path: &str, _item_id: i32, _date: &str, _price: Gp, _volume: Option<i64>) -> Result<()> {
+    unsupported()
+}
+
+pub fn correct_snapshot(_db_path: &str, _item_id: i32, _rowid: i64, _price: Gp, _volume: Option<i64>) -> Result<()> {
+    unsupported()
+}
+
+pub fn delete_snapshot(_db_path: &str, _item_id: i32, _rowid: i64) -> Result<()> {
+    unsupported()
+}
+
+pub fn load_snapshot_edits(_db_path: &str, _item_id: i32) -> Result<Vec<SnapshotEdit>> {
+    Ok(Vec::new())
+}
+
+pub fn load_item_trades(_db_path: &str, item_id: i32) -> Result<Vec<(f64, f64)>> {
+    let guard = SNAPSHOTS.lock().unwrap();
+    let snaps = downloaded(&guard)?;
+
+    Ok(snaps
+        .iter()
+        .filter(|s| s.item_id == item_id && !s.manual)
+        .map(|s| (s.price.as_f64(), s.volume as f64))
+        .collect())
+}
+
+pub fn load_alch_values(_db_path: &str) -> Result<HashMap<i32, Gp>> {
+    // The exported window only carries prices and volumes
+    Ok(HashMap::new())
+}
+
+pub fn load_new_items(_db_path: &str, _days: u32) -> Result<Vec<(i32, String, String)>> {
+    // The exported window doesn't know when an item was first seen
+    Ok(vec![])
+}
+
+pub fn prune_item_history(_db_path: &str, _item_ids: &[i32]) -> Result<usize> {
+    unsupported()
+}
+
+pub fn find_item(_db_path: &str, query: &str) -> Result<Option<(i32, String)>> {
+    let guard = SNAPSHOTS.lock().unwrap();
+    let snaps = downloaded(&guard)?;
+    let query = query.trim();
+    let id = query.parse::<i32>().ok();
+
+    Ok(snaps
+        .iter()
+        .find(|s| Some(s.item_id) == id || s.name.eq_ignore_ascii_case(query))
+        .map(|s| (s.item_id, s.name.clone())))
+}
+
+pub fn load_holdings(_db_path: &str) -> Result<Vec<Holding>> {
+    Ok(vec![])
+}
+
+pub fn save_holding(_db_path: &str, _holding: &Holding) -> Result<()> {
+    unsupported()
+}
+
+pub fn delete_holding(_db_path: &str, _item_id: i32) -> Result<()> {
+    unsupported()
+}
+
+pub fn record_bank_value(_db_path: &str, _value: Gp) -> Result<()> {
+    unsupported()
+}
+
+pub fn load_bank_history(_db_path: &str) -> Result<Vec<(String, Gp)>> {
+    Ok(vec![])
+}
+
+pub fn load_db_stats(_db_path: &str) -> Result<DbStats> {
+    unsupported()
+}
+
+pub fn vacuum(_db_path: &str) -> Result<()> {
+    unsupported()
+}
+
+pub fn reindex(_db_path: &str) -> Result<()> {
+    unsupported()
+}
+
+pub fn prune_older_than(_db_path: &str, _days: u32) -> Result<usize> {
+    unsupported()
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RetentionReport {
+    pub days: usize,
+    pub weeks: usize,
+    pub removed: usize,
+}
+
+pub fn apply_retention(_db_path: &str, _daily_after_days: u32, _weekly_after_days: u32) -> Result<RetentionReport> {
+    unsupported()
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MergeReport {
+    pub new_items: usize,
+    pub inserted: usize,
+    pub replaced: usize,
+}
+
+pub fn merge_database(_db_path: &str, _other_path: &str, _prefer_incoming: bool) -> Result<MergeReport> {
+    unsupported()
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExportReport {
+    pub written: usize,
+    pub skipped: usize,
+}
+
+pub fn export_parquet(_db_path: &str, _out_path: &str, _item_id: Option<i32>) -> Result<ExportReport> {
+    unsupported()
+}
+
+pub fn record_daily_prices(_db_path: &str, _prices: &[DailyPrice]) -> Result<usize> {
+    unsupported()
+}
+
+pub fn integrity_check(_db_path: &str) -> Result<String> {
+    unsupported()
+}
+
+// The snapshot export stands in for the database, so there's nothing to set up
+pub fn database_ready(_db_path: &str) -> bool {
+    true
+}
+
+pub fn is_lfs_pointer(_path: &str) -> bool {
+    false
+}
+
+pub fn install_database(_source: &str, _db_path: &str) -> Result<u64> {
+    unsupported()
+}
+
+pub fn load_annotations(_db_path: &str, _item_id: i32) -> Result<Vec<Annotation>> {
+    Ok(vec![])
+}
+
+pub fn add_annotation(_db_path: &str, _item_id: i32, _date: &str, _text: &str) -> Result<()> {
+    unsupported()
+}
+
+pub fn delete_annotation(_db_path: &str, _id: i64) -> Result<()> {
+    unsupported()
+}
+
+pub fn load_last_scan(_db_path: &str) -> Result<HashMap<i32, ScanEntry>> {
+    Ok(HashMap::new())
+}
+
+pub fn record_scan(_db_path: &str, _entries: &[ScanEntry]) -> Result<()> {
+    unsupported()
+}